@@ -31,7 +31,7 @@ fn main() -> ! {
 
     log::warn!(
         "This station has address #{}.  No other station with this address shall be present.",
-        fdl.parameters().address
+        fdl.address()
     );
 
     log::info!("Connecting to the bus...");
@@ -74,7 +74,8 @@ fn main() {
             // can induce large delays at times.
             .slot_bits(4000)
             .max_retry_limit(3)
-            .build_verified(&dp_master),
+            .build_verified(&dp_master)
+            .unwrap(),
     );
     // Read more about timing considerations in the SerialPortPhy documentation.
     let sleep_time = std::time::Duration::from_micros(3500);
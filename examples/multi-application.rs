@@ -14,8 +14,6 @@ fn main() -> ! {
 
     log::info!("PROFIBUS Multi-Application Example");
 
-    let mut dp_scanner = dp::scan::DpScanner::new();
-
     let mut dp_master = dp::DpMaster::new(vec![]);
 
     // Options generated by `gsdtool` using "wagob757.gsd"
@@ -78,6 +76,13 @@ fn main() -> ! {
             .with_diag_buffer(&mut buffer_diagnostics[..]),
     );
 
+    // The applications are stored in an ApplicationSet.  The DP scanner only uses the token hold
+    // time which is left over after the DP master completed its cycle.
+    let mut apps = fdl::ApplicationSet::new(vec![]);
+    let master_handle = apps.add(dp_master);
+    let scanner_handle = apps.add(dp::scan::DpScanner::new());
+    apps.set_weight(scanner_handle, 0);
+
     let mut fdl = fdl::FdlActiveStation::new(
         fdl::ParametersBuilder::new(MASTER_ADDRESS, BAUDRATE)
             // We use a rather large T_slot time because USB-RS485 converters
@@ -94,14 +99,11 @@ fn main() -> ! {
     let mut phy = phy::SerialPortPhy::new(BUS_DEVICE, fdl.parameters().baudrate);
 
     fdl.set_online();
-    dp_master.enter_operate();
+    apps.get_mut::<dp::DpMaster>(master_handle).enter_operate();
     loop {
-        fdl.poll_multi(
-            profirust::time::Instant::now(),
-            &mut phy,
-            &mut [&mut dp_master, &mut dp_scanner],
-        );
+        fdl.poll_applications(profirust::time::Instant::now(), &mut phy, &mut apps);
 
+        let dp_master = apps.get_mut::<dp::DpMaster>(master_handle);
         let dp_events = dp_master.take_last_events();
         if dp_events.cycle_completed {
             let io = dp_master.get_mut(handle_io_wago);
@@ -115,7 +117,9 @@ fn main() -> ! {
             }
         }
 
-        let scanner_event = dp_scanner.take_last_event();
+        let scanner_event = apps
+            .get_mut::<dp::scan::DpScanner>(scanner_handle)
+            .take_last_event();
         match scanner_event {
             Some(dp::scan::DpScanEvent::PeripheralFound(desc)) => {
                 log::info!("Discovered peripheral #{}:", desc.address);
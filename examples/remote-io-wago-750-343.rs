@@ -98,7 +98,8 @@ fn main() {
             .slot_bits(4000)
             .max_retry_limit(3)
             .watchdog_timeout(profirust::time::Duration::from_secs(2))
-            .build_verified(&dp_master),
+            .build_verified(&dp_master)
+            .unwrap(),
     );
     // Read more about timing considerations in the SerialPortPhy documentation.
     let sleep_time = std::time::Duration::from_micros(3500);
@@ -5,6 +5,7 @@ use rp_pico as bsp;
 use bsp::hal::{
     self,
     clocks::init_clocks_and_plls,
+    dma::DMAExt,
     pac,
     sio::Sio,
     watchdog::Watchdog,
@@ -92,10 +93,12 @@ fn main() -> ! {
     );
     let uart = hal::uart::UartPeripheral::new(pac.UART0, uart_pins, &mut pac.RESETS);
     let dir_pin = pins.gpio15.into_push_pull_output();
+    let dma = pac.DMA.split(&mut pac.RESETS);
     let mut phy_buffer = [0u8; 256];
     let mut phy = phy::Rp2040Phy::new(
         uart,
         dir_pin,
+        dma.ch0,
         &clocks.peripheral_clock,
         &mut phy_buffer[..],
         BAUDRATE,
@@ -146,7 +149,8 @@ fn main() -> ! {
         fdl::ParametersBuilder::new(MASTER_ADDRESS, BAUDRATE)
             .watchdog_timeout(profirust::time::Duration::from_secs(1))
             .slot_bits(1920)
-            .build_verified(&dp_master),
+            .build_verified(&dp_master)
+            .unwrap(),
     );
 
     let mut init = false;
@@ -167,7 +167,8 @@ fn vfd_controller(param: sync::Arc<sync::Mutex<VfdParameters>>) {
             // The MICROMASTER 4 also includes its own watchdog mechanism via parameter
             // P2040 (Telegramm Ausfallzeit CB).
             .watchdog_timeout(profirust::time::Duration::from_millis(100))
-            .build_verified(&dp_master),
+            .build_verified(&dp_master)
+            .unwrap(),
     );
     // Read more about timing considerations in the SerialPortPhy documentation.
     let sleep_time = std::time::Duration::from_micros(3500);
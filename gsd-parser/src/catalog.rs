@@ -0,0 +1,77 @@
+//! Collection of GSD files indexed by ident number
+use crate::{GenericStationDescription, GsdError};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// A collection of parsed GSD files, indexed by their `Ident_Number`
+///
+/// This is useful for finding the matching GSD file for each station discovered on a bus.
+#[derive(Debug, Default, Clone)]
+pub struct GsdCatalog {
+    entries: BTreeMap<u16, GenericStationDescription>,
+}
+
+impl GsdCatalog {
+    /// Create an empty catalog.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse all GSD files in a directory and add them to a new catalog.
+    ///
+    /// All files with a `.gsd` extension or one of the language-specific extensions (`.gse`,
+    /// `.gsg`, ...) are loaded, subdirectories are ignored.  Files are loaded in alphabetical
+    /// order and when multiple files have the same ident number, the first one is kept.
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> Result<Self, GsdError> {
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_file() && is_gsd_path(&path) {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut catalog = Self::new();
+        for path in paths {
+            let gsd = crate::parse_from_file(&path)?;
+            catalog.entries.entry(gsd.ident_number).or_insert(gsd);
+        }
+        Ok(catalog)
+    }
+
+    /// Add a GSD to the catalog.
+    ///
+    /// Returns the GSD which was previously stored for the same ident number, if any.
+    pub fn insert(&mut self, gsd: GenericStationDescription) -> Option<GenericStationDescription> {
+        self.entries.insert(gsd.ident_number, gsd)
+    }
+
+    /// Find the GSD for a station with the given ident number.
+    pub fn lookup(&self, ident: u16) -> Option<&GenericStationDescription> {
+        self.entries.get(&ident)
+    }
+
+    /// Iterate over all GSDs in the catalog, ordered by ident number.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, &GenericStationDescription)> {
+        self.entries.iter().map(|(ident, gsd)| (*ident, gsd))
+    }
+
+    /// Number of GSDs in the catalog.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the catalog contains no GSDs at all.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Whether the path has the extension of a GSD file (`.gs?`, case-insensitive).
+fn is_gsd_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.len() == 3 && ext[..2].eq_ignore_ascii_case("gs"))
+        .unwrap_or(false)
+}
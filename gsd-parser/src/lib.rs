@@ -2,9 +2,15 @@ use std::collections::BTreeMap;
 use std::path::Path;
 use std::sync::Arc;
 
+pub use catalog::GsdCatalog;
+
+mod catalog;
 pub mod parser;
+#[cfg(feature = "serde")]
+mod serde_support;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ProtocolIdent {
     #[default]
     ProfibusDp,
@@ -12,6 +18,7 @@ pub enum ProtocolIdent {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StationType {
     #[default]
     DpSlave,
@@ -19,6 +26,7 @@ pub enum StationType {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RepeaterControlSignal {
     #[default]
     NotConnected,
@@ -27,6 +35,7 @@ pub enum RepeaterControlSignal {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Pins24V {
     #[default]
     NotConnected,
@@ -35,6 +44,7 @@ pub enum Pins24V {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum MainSlaveFamily {
     #[default]
@@ -54,14 +64,40 @@ pub enum MainSlaveFamily {
     Reserved(u8),
 }
 
+impl From<u8> for MainSlaveFamily {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => MainSlaveFamily::General,
+            1 => MainSlaveFamily::Drives,
+            2 => MainSlaveFamily::SwitchingDevices,
+            3 => MainSlaveFamily::IOs,
+            4 => MainSlaveFamily::Valves,
+            5 => MainSlaveFamily::Controllers,
+            6 => MainSlaveFamily::Hmis,
+            7 => MainSlaveFamily::Encoders,
+            8 => MainSlaveFamily::NcRc,
+            9 => MainSlaveFamily::Gateways,
+            10 => MainSlaveFamily::PLCs,
+            11 => MainSlaveFamily::IdentSystems,
+            12 => MainSlaveFamily::PA,
+            v => MainSlaveFamily::Reserved(v),
+        }
+    }
+}
+
+/// Device classification from the `Slave_Family` keyword
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SlaveFamily {
-    main: MainSlaveFamily,
-    sub: Vec<String>,
+    /// Main family of the device
+    pub main: MainSlaveFamily,
+    /// Manufacturer-specific sub-families, from the most general to the most specific one
+    pub sub: Vec<String>,
 }
 
 bitflags::bitflags! {
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct SupportedSpeeds: u16 {
         const B9600 = 1 << 1;
         const B19200 = 1 << 2;
@@ -84,6 +120,7 @@ impl Default for SupportedSpeeds {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MaxTsdr {
     /// Maximum response time (in bits) at 9.6 kbit/s
     pub b9600: u16,
@@ -128,6 +165,7 @@ impl Default for MaxTsdr {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UserPrmDataType {
     Unsigned8,
     Unsigned16,
@@ -181,18 +219,39 @@ impl UserPrmDataType {
             }
             UserPrmDataType::Bit(b) => {
                 assert!(value == 0 || value == 1);
+                s[0] &= !(1 << b);
                 s[0] |= (value as u8) << b;
             }
             UserPrmDataType::BitArea(first, last) => {
                 let bit_size = last - first + 1;
                 assert!(value >= 0 && value < 2i64.pow(bit_size as u32));
-                s[0] = (value as u8) << first;
+                let mask = (((1u16 << bit_size) - 1) << first) as u8;
+                s[0] &= !mask;
+                s[0] |= (value as u8) << first;
+            }
+        }
+    }
+
+    pub fn read_value_from_slice(self, s: &[u8]) -> i64 {
+        match self {
+            UserPrmDataType::Unsigned8 => u8::from_be_bytes(s[..1].try_into().unwrap()).into(),
+            UserPrmDataType::Unsigned16 => u16::from_be_bytes(s[..2].try_into().unwrap()).into(),
+            UserPrmDataType::Unsigned32 => u32::from_be_bytes(s[..4].try_into().unwrap()).into(),
+            UserPrmDataType::Signed8 => i8::from_be_bytes(s[..1].try_into().unwrap()).into(),
+            UserPrmDataType::Signed16 => i16::from_be_bytes(s[..2].try_into().unwrap()).into(),
+            UserPrmDataType::Signed32 => i32::from_be_bytes(s[..4].try_into().unwrap()).into(),
+            UserPrmDataType::Bit(b) => ((s[0] >> b) & 1).into(),
+            UserPrmDataType::BitArea(first, last) => {
+                let bit_size = last - first + 1;
+                let mask = (1u16 << bit_size) - 1;
+                ((u16::from(s[0]) >> first) & mask).into()
             }
         }
     }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PrmValueConstraint {
     MinMax(i64, i64),
     Enum(Vec<i64>),
@@ -208,6 +267,17 @@ impl PrmValueConstraint {
         }
     }
 
+    pub fn check(&self, value: i64) -> Result<(), PrmError> {
+        if self.is_valid(value) {
+            Ok(())
+        } else {
+            Err(PrmError::InvalidValue {
+                value,
+                constraint: self.clone(),
+            })
+        }
+    }
+
     pub fn assert_valid(&self, value: i64) {
         match self {
             PrmValueConstraint::MinMax(min, max) => {
@@ -227,7 +297,42 @@ impl PrmValueConstraint {
     }
 }
 
+/// Error while setting a user parameter
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PrmError {
+    /// No parameter with this name exists
+    UnknownParameter(String),
+    /// The parameter has no text with this name
+    UnknownText(String),
+    /// The value violates the constraint of the parameter
+    InvalidValue {
+        value: i64,
+        constraint: PrmValueConstraint,
+    },
+}
+
+impl std::fmt::Display for PrmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrmError::UnknownParameter(prm) => write!(f, "unknown parameter {prm:?}"),
+            PrmError::UnknownText(text) => write!(f, "unknown parameter text {text:?}"),
+            PrmError::InvalidValue {
+                value,
+                constraint: PrmValueConstraint::MinMax(min, max),
+            } => write!(f, "value {value} not in range {min}..={max}"),
+            PrmError::InvalidValue {
+                value,
+                constraint: PrmValueConstraint::Enum(values),
+            } => write!(f, "value {value} not in set {values:?}"),
+            PrmError::InvalidValue { value, .. } => write!(f, "value {value} is invalid"),
+        }
+    }
+}
+
+impl std::error::Error for PrmError {}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UserPrmDataDefinition {
     pub name: String,
     pub data_type: UserPrmDataType,
@@ -239,6 +344,7 @@ pub struct UserPrmDataDefinition {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UserPrmData {
     pub length: u8,
     pub data_const: Vec<(usize, Vec<u8>)>,
@@ -246,6 +352,7 @@ pub struct UserPrmData {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Module {
     pub name: String,
     pub config: Vec<u8>,
@@ -254,6 +361,7 @@ pub struct Module {
 }
 
 #[derive(PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Slot {
     pub name: String,
     pub number: u8,
@@ -278,12 +386,14 @@ impl std::fmt::Debug for Slot {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnitDiagBitInfo {
     pub text: String,
     pub help: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnitDiagArea {
     pub first: u16,
     pub last: u16,
@@ -291,6 +401,7 @@ pub struct UnitDiagArea {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnitDiag {
     pub bits: BTreeMap<u32, UnitDiagBitInfo>,
     pub not_bits: BTreeMap<u32, UnitDiagBitInfo>,
@@ -298,6 +409,8 @@ pub struct UnitDiag {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(remote = "Self"))]
 pub struct GenericStationDescription {
     pub gsd_revision: u8,
     pub vendor: String,
@@ -310,9 +423,9 @@ pub struct GenericStationDescription {
     // pub fms_supported: bool,
     pub hardware_release: String,
     pub software_release: String,
-    // pub redundancy_supported: bool,
-    // pub repeater_control_signal: RepeaterControlSignal,
-    // pub pins_24v: Pins24V,
+    pub redundancy_supported: bool,
+    pub repeater_control_signal: RepeaterControlSignal,
+    pub pins_24v: Pins24V,
     pub implementation_type: String,
     // pub bitmap_device: String,
     // pub bitmap_diag: String,
@@ -325,10 +438,11 @@ pub struct GenericStationDescription {
     pub max_diag_data_length: u8,
     // pub max_user_prm_data_length: u8,
     // pub module_offset: u8,
-    // pub slave_family: SlaveFamily,
+    pub slave_family: SlaveFamily,
     // pub user_prm_data_length: u8,
     // pub default_usr_prm_data: Vec<u8>,
-    // pub min_slave_intervall_us: u16,
+    /// Minimum time between two data exchange cycles with this station (`Min_Slave_Intervall`)
+    pub min_slave_interval: std::time::Duration,
     pub modular_station: bool,
     pub max_modules: u8,
     pub max_input_length: u8,
@@ -342,6 +456,8 @@ pub struct GenericStationDescription {
     pub user_prm_data: UserPrmData,
     //
     pub unit_diag: UnitDiag,
+    /// Texts for the error types of channel-related diagnostics (`Channel_Diag`)
+    pub channel_diag: BTreeMap<u8, UnitDiagBitInfo>,
 }
 
 pub struct PrmBuilder<'a> {
@@ -385,6 +501,16 @@ impl<'a> PrmBuilder<'a> {
         }
     }
 
+    /// Find the offset and definition of a parameter by its name
+    fn find_prm(&self, prm: &str) -> Result<(usize, &'a UserPrmDataDefinition), PrmError> {
+        self.desc
+            .data_ref
+            .iter()
+            .find(|(_, r)| r.name == prm)
+            .map(|(offset, data_ref)| (*offset, &**data_ref))
+            .ok_or_else(|| PrmError::UnknownParameter(prm.to_owned()))
+    }
+
     pub fn set_prm(&mut self, prm: &str, value: i64) -> &mut Self {
         let (offset, data_ref) = self
             .desc
@@ -415,6 +541,49 @@ impl<'a> PrmBuilder<'a> {
         self
     }
 
+    /// Set a parameter from its text, without panicking on invalid input.
+    ///
+    /// Some GSD files contain texts whose values violate the constraint of the parameter.  Unlike
+    /// [`set_prm_from_text()`][`PrmBuilder::set_prm_from_text`], this method returns an error in
+    /// such cases and leaves the parameter unchanged.
+    pub fn try_set_prm_from_text(&mut self, prm: &str, value: &str) -> Result<&mut Self, PrmError> {
+        let (offset, data_ref) = self.find_prm(prm)?;
+        let value = data_ref
+            .text_ref
+            .as_ref()
+            .and_then(|text_ref| text_ref.get(value))
+            .copied()
+            .ok_or_else(|| PrmError::UnknownText(value.to_owned()))?;
+        data_ref.constraint.check(value)?;
+        data_ref
+            .data_type
+            .write_value_to_slice(value, &mut self.prm[offset..]);
+        Ok(self)
+    }
+
+    /// Set a parameter to a numeric value, without panicking on invalid input.
+    ///
+    /// Unlike [`set_prm()`][`PrmBuilder::set_prm`], this method returns an error when the
+    /// parameter does not exist or the value violates its constraint.  The parameter is left
+    /// unchanged in this case.
+    pub fn try_set_prm(&mut self, prm: &str, value: i64) -> Result<&mut Self, PrmError> {
+        let (offset, data_ref) = self.find_prm(prm)?;
+        data_ref.constraint.check(value)?;
+        data_ref
+            .data_type
+            .write_value_to_slice(value, &mut self.prm[offset..]);
+        Ok(self)
+    }
+
+    pub fn get_prm(&self, prm: &str) -> Option<i64> {
+        let (offset, data_ref) = self.desc.data_ref.iter().find(|(_, r)| r.name == prm)?;
+        Some(
+            data_ref
+                .data_type
+                .read_value_from_slice(&self.prm[*offset..]),
+        )
+    }
+
     pub fn as_bytes(&self) -> &[u8] {
         &self.prm
     }
@@ -424,16 +593,56 @@ impl<'a> PrmBuilder<'a> {
     }
 }
 
-pub fn parse_from_file<P: AsRef<Path>>(file: P) -> GenericStationDescription {
-    use std::io::Read;
+/// Error while reading a GSD file
+#[derive(Debug)]
+pub enum GsdError {
+    /// The file could not be read
+    Io(std::io::Error),
+    /// The file contents are not a valid GSD file
+    Parse(parser::ParseError),
+}
 
-    let mut f = std::fs::File::open(file.as_ref()).unwrap();
-    let mut source_bytes = Vec::new();
-    f.read_to_end(&mut source_bytes).unwrap();
-    let source = String::from_utf8_lossy(&source_bytes);
+impl std::fmt::Display for GsdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GsdError::Io(e) => write!(f, "failed reading GSD file: {e}"),
+            GsdError::Parse(e) => write!(f, "failed parsing GSD file:\n{e}"),
+        }
+    }
+}
+
+impl std::error::Error for GsdError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GsdError::Io(e) => Some(e),
+            GsdError::Parse(e) => Some(e),
+        }
+    }
+}
 
-    match parser::parse(file.as_ref(), &source) {
-        Ok(gsd) => gsd,
-        Err(e) => panic!("{}", e),
+impl From<std::io::Error> for GsdError {
+    fn from(e: std::io::Error) -> Self {
+        GsdError::Io(e)
     }
 }
+
+impl From<parser::ParseError> for GsdError {
+    fn from(e: parser::ParseError) -> Self {
+        GsdError::Parse(e)
+    }
+}
+
+/// Read and parse the GSD file at the given path.
+pub fn parse_from_file<P: AsRef<Path>>(file: P) -> Result<GenericStationDescription, GsdError> {
+    parse_from_file_with_warnings(file).map(|(gsd, _warnings)| gsd)
+}
+
+/// Read and parse the GSD file at the given path, also returning warnings about ignored keywords.
+pub fn parse_from_file_with_warnings<P: AsRef<Path>>(
+    file: P,
+) -> Result<(GenericStationDescription, Vec<parser::ParseWarning>), GsdError> {
+    let source_bytes = std::fs::read(file.as_ref())?;
+    let source = String::from_utf8_lossy(&source_bytes);
+
+    Ok(parser::parse_with_warnings(file.as_ref(), &source)?)
+}
@@ -10,6 +10,38 @@ mod gsd_parser {
 pub type ParseError = pest::error::Error<gsd_parser::Rule>;
 pub type ParseResult<T> = Result<T, ParseError>;
 
+/// Keyword in a GSD file which was ignored by the parser
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseWarning {
+    /// The keyword as written in the GSD file
+    pub keyword: String,
+    /// Line of the keyword (starting at 1)
+    pub line: usize,
+    /// Column of the keyword (starting at 1)
+    pub column: usize,
+}
+
+impl ParseWarning {
+    fn unknown_keyword(pair: &pest::iterators::Pair<'_, gsd_parser::Rule>) -> Self {
+        let (line, column) = pair.as_span().start_pos().line_col();
+        Self {
+            keyword: pair.as_str().to_owned(),
+            line,
+            column,
+        }
+    }
+}
+
+impl std::fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}: unknown keyword {:?} was ignored",
+            self.line, self.column, self.keyword
+        )
+    }
+}
+
 fn parse_error(e: impl std::fmt::Display, span: pest::Span<'_>) -> ParseError {
     let message = format!("{}", e);
     pest::error::Error::new_from_span(pest::error::ErrorVariant::CustomError { message }, span)
@@ -70,6 +102,23 @@ where
     })
 }
 
+/// Parse the offset and data ID of an `Ext_User_Prm_Data_Ref` and look up the referenced
+/// parameter definition.
+fn parse_prm_data_ref(
+    offset_pair: pest::iterators::Pair<'_, gsd_parser::Rule>,
+    data_id_pair: pest::iterators::Pair<'_, gsd_parser::Rule>,
+    definitions: &BTreeMap<u32, Arc<crate::UserPrmDataDefinition>>,
+) -> ParseResult<(usize, Arc<crate::UserPrmDataDefinition>)> {
+    let offset = parse_number(offset_pair)?;
+    let span = data_id_pair.as_span();
+    let data_id = parse_number(data_id_pair)?;
+    let data_ref = definitions
+        .get(&data_id)
+        .cloned()
+        .ok_or_else(|| parse_error(format!("undefined Ext_User_Prm_Data_Def {data_id}"), span))?;
+    Ok((offset, data_ref))
+}
+
 fn parse_bool(pair: pest::iterators::Pair<'_, gsd_parser::Rule>) -> ParseResult<bool> {
     Ok(parse_number::<u32>(pair)? != 0)
 }
@@ -83,14 +132,61 @@ fn parse_string_literal(pair: pest::iterators::Pair<'_, gsd_parser::Rule>) -> St
     chars.as_str().to_owned()
 }
 
+fn parse_slave_family(
+    pair: pest::iterators::Pair<'_, gsd_parser::Rule>,
+) -> ParseResult<crate::SlaveFamily> {
+    if pair.as_rule() != gsd_parser::Rule::family_ident {
+        // Only the main family without any sub-families
+        return Ok(crate::SlaveFamily {
+            main: parse_number::<u8>(pair)?.into(),
+            sub: Vec::new(),
+        });
+    }
+
+    // The family_ident rule extends to the end of the line so strip a trailing comment
+    let text = pair.as_str().split(';').next().unwrap();
+    let mut parts = text.split('@');
+    let main_str = parts.next().unwrap().trim();
+    let main: u8 = if let Some(hex) = main_str.strip_prefix("0x") {
+        u8::from_str_radix(hex, 16)
+    } else {
+        main_str.parse()
+    }
+    .map_err(|e| parse_error(e, pair.as_span()))?;
+    let sub = parts
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_owned())
+        .collect();
+
+    Ok(crate::SlaveFamily {
+        main: main.into(),
+        sub,
+    })
+}
+
 pub fn parse(
     file: &std::path::Path,
     source: &str,
 ) -> ParseResult<crate::GenericStationDescription> {
-    parse_inner(source).map_err(|e| e.with_path(&file.to_string_lossy()))
+    parse_with_warnings(file, source).map(|(gsd, _)| gsd)
 }
 
-fn parse_inner(source: &str) -> ParseResult<crate::GenericStationDescription> {
+/// Parse a GSD file and also return warnings about all keywords which were ignored.
+pub fn parse_with_warnings(
+    file: &std::path::Path,
+    source: &str,
+) -> ParseResult<(crate::GenericStationDescription, Vec<ParseWarning>)> {
+    let mut warnings = Vec::new();
+    let gsd =
+        parse_inner(source, &mut warnings).map_err(|e| e.with_path(&file.to_string_lossy()))?;
+    Ok((gsd, warnings))
+}
+
+fn parse_inner(
+    source: &str,
+    warnings: &mut Vec<ParseWarning>,
+) -> ParseResult<crate::GenericStationDescription> {
     use pest::Parser;
 
     let gsd_pairs = gsd_parser::GsdParser::parse(gsd_parser::Rule::gsd, &source)?
@@ -263,25 +359,26 @@ fn parse_inner(source: &str) -> ParseResult<crate::GenericStationDescription> {
                         }
                         gsd_parser::Rule::setting => {
                             let mut pairs = rule.into_inner();
-                            let key = pairs.next().unwrap().as_str();
+                            let key_pair = pairs.next().unwrap();
+                            let key = key_pair.as_str();
                             let value_pair = pairs.next().unwrap();
                             match key.to_lowercase().as_str() {
                                 "ext_module_prm_data_len" => {
                                     module_prm_data.length = parse_number(value_pair)?;
                                 }
                                 "ext_user_prm_data_ref" => {
-                                    let offset = parse_number(value_pair)?;
-                                    let data_id = parse_number(pairs.next().unwrap())?;
-                                    let data_ref =
-                                        user_prm_data_definitions.get(&data_id).unwrap().clone();
-                                    module_prm_data.data_ref.push((offset, data_ref));
+                                    module_prm_data.data_ref.push(parse_prm_data_ref(
+                                        value_pair,
+                                        pairs.next().unwrap(),
+                                        &user_prm_data_definitions,
+                                    )?);
                                 }
                                 "ext_user_prm_data_const" => {
                                     let offset = parse_number(value_pair)?;
                                     let values: Vec<u8> = parse_number_list(pairs.next().unwrap())?;
                                     module_prm_data.data_const.push((offset, values));
                                 }
-                                _ => (),
+                                _ => warnings.push(ParseWarning::unknown_keyword(&key_pair)),
                             }
                         }
                         gsd_parser::Rule::data_area => (),
@@ -377,7 +474,8 @@ fn parse_inner(source: &str) -> ParseResult<crate::GenericStationDescription> {
             }
             gsd_parser::Rule::setting => {
                 let mut pairs = statement.into_inner();
-                let key = pairs.next().unwrap().as_str();
+                let key_pair = pairs.next().unwrap();
+                let key = key_pair.as_str();
                 let value_pair = pairs.next().unwrap();
                 match key.to_lowercase().as_str() {
                     "gsd_revision" => gsd.gsd_revision = parse_number(value_pair)?,
@@ -391,6 +489,7 @@ fn parse_inner(source: &str) -> ParseResult<crate::GenericStationDescription> {
                     "software_release" => gsd.software_release = parse_string_literal(value_pair),
                     //
                     "fail_safe" => gsd.fail_safe = parse_bool(value_pair)?,
+                    "slave_family" => gsd.slave_family = parse_slave_family(value_pair)?,
                     //
                     "9.6_supp" => {
                         if parse_bool(value_pair)? {
@@ -458,6 +557,35 @@ fn parse_inner(source: &str) -> ParseResult<crate::GenericStationDescription> {
                     "maxtsdr_3m" => gsd.max_tsdr.b3000000 = parse_number(value_pair)?,
                     "maxtsdr_6m" => gsd.max_tsdr.b6000000 = parse_number(value_pair)?,
                     "maxtsdr_12m" => gsd.max_tsdr.b12000000 = parse_number(value_pair)?,
+                    "redundancy" => gsd.redundancy_supported = parse_bool(value_pair)?,
+                    "repeater_ctrl_sig" => {
+                        let span = value_pair.as_span();
+                        gsd.repeater_control_signal = match parse_number::<u8>(value_pair)? {
+                            0 => crate::RepeaterControlSignal::NotConnected,
+                            1 => crate::RepeaterControlSignal::Rs485,
+                            2 => crate::RepeaterControlSignal::Ttl,
+                            v => {
+                                return Err(parse_error(
+                                    format!("invalid Repeater_Ctrl_Sig value {v}"),
+                                    span,
+                                ))
+                            }
+                        };
+                    }
+                    "24v_pins" => {
+                        let span = value_pair.as_span();
+                        gsd.pins_24v = match parse_number::<u8>(value_pair)? {
+                            0 => crate::Pins24V::NotConnected,
+                            1 => crate::Pins24V::Input,
+                            2 => crate::Pins24V::Output,
+                            v => {
+                                return Err(parse_error(
+                                    format!("invalid 24V_Pins value {v}"),
+                                    span,
+                                ))
+                            }
+                        };
+                    }
                     "implementation_type" => {
                         gsd.implementation_type = parse_string_literal(value_pair)
                     }
@@ -468,15 +596,22 @@ fn parse_inner(source: &str) -> ParseResult<crate::GenericStationDescription> {
                     "max_output_len" => gsd.max_output_length = parse_number(value_pair)?,
                     "max_data_len" => gsd.max_data_length = parse_number(value_pair)?,
                     "max_diag_data_len" => gsd.max_diag_data_length = parse_number(value_pair)?,
+                    "min_slave_intervall" => {
+                        // Given in units of 100 µs
+                        let interval: u16 = parse_number(value_pair)?;
+                        gsd.min_slave_interval =
+                            std::time::Duration::from_micros(u64::from(interval) * 100);
+                    }
                     "freeze_mode_supp" => gsd.freeze_mode_supported = parse_bool(value_pair)?,
                     "sync_mode_supp" => gsd.sync_mode_supported = parse_bool(value_pair)?,
                     "auto_baud_supp" => gsd.auto_baud_supported = parse_bool(value_pair)?,
                     "set_slave_add_supp" => gsd.set_slave_addr_supported = parse_bool(value_pair)?,
                     "ext_user_prm_data_ref" => {
-                        let offset = parse_number(value_pair)?;
-                        let data_id = parse_number(pairs.next().unwrap())?;
-                        let data_ref = user_prm_data_definitions.get(&data_id).unwrap().clone();
-                        gsd.user_prm_data.data_ref.push((offset, data_ref));
+                        gsd.user_prm_data.data_ref.push(parse_prm_data_ref(
+                            value_pair,
+                            pairs.next().unwrap(),
+                            &user_prm_data_definitions,
+                        )?);
                         // The presence of this keywords means `User_Prm_Data` and
                         // `User_Prm_Data_Len` should be ignored.
                         legacy_prm = None;
@@ -563,7 +698,17 @@ fn parse_inner(source: &str) -> ParseResult<crate::GenericStationDescription> {
                         let text = parse_string_literal(pairs.next().unwrap());
                         gsd.unit_diag.not_bits.entry(bit).or_default().help = Some(text);
                     }
-                    _ => (),
+                    "channel_diag" => {
+                        let error_type = parse_number(value_pair)?;
+                        let text = parse_string_literal(pairs.next().unwrap());
+                        gsd.channel_diag.entry(error_type).or_default().text = text;
+                    }
+                    "channel_diag_help" => {
+                        let error_type = parse_number(value_pair)?;
+                        let text = parse_string_literal(pairs.next().unwrap());
+                        gsd.channel_diag.entry(error_type).or_default().help = Some(text);
+                    }
+                    _ => warnings.push(ParseWarning::unknown_keyword(&key_pair)),
                 }
             }
             _ => (),
@@ -0,0 +1,74 @@
+//! Serialization support for [`GenericStationDescription`]
+//!
+//! All types are serialized by value, so a [`Module`] referenced from multiple slots or a
+//! parameter text list used by multiple parameters appears multiple times in the serialized data.
+//! After deserialization, equal values are merged again so they share the same `Arc` like in a
+//! freshly parsed GSD file.
+use crate::{GenericStationDescription, Module, UserPrmData, UserPrmDataDefinition};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+impl serde::Serialize for GenericStationDescription {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GenericStationDescription::serialize(self, serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for GenericStationDescription {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut gsd = GenericStationDescription::deserialize(deserializer)?;
+        ArcPool::default().share(&mut gsd);
+        Ok(gsd)
+    }
+}
+
+/// Collection of already seen values for merging equal `Arc`s
+#[derive(Default)]
+struct ArcPool {
+    texts: Vec<Arc<BTreeMap<String, i64>>>,
+    definitions: Vec<Arc<UserPrmDataDefinition>>,
+    modules: Vec<Arc<Module>>,
+}
+
+/// Replace `arc` with an equal one from the `pool` or add it to the pool if there is none.
+fn share_arc<T: PartialEq>(pool: &mut Vec<Arc<T>>, arc: &mut Arc<T>) {
+    if let Some(existing) = pool.iter().find(|a| **a == *arc) {
+        *arc = existing.clone();
+    } else {
+        pool.push(arc.clone());
+    }
+}
+
+impl ArcPool {
+    fn share(&mut self, gsd: &mut GenericStationDescription) {
+        self.share_prm_data(&mut gsd.user_prm_data);
+        // Available modules come first so slots reference them instead of their own copies.
+        for module in gsd.available_modules.iter_mut() {
+            self.share_module(module);
+        }
+        for slot in gsd.slots.iter_mut() {
+            self.share_module(&mut slot.default);
+            for module in slot.allowed_modules.iter_mut() {
+                self.share_module(module);
+            }
+        }
+    }
+
+    fn share_module(&mut self, module: &mut Arc<Module>) {
+        if let Some(existing) = self.modules.iter().find(|m| **m == *module) {
+            *module = existing.clone();
+            return;
+        }
+        self.share_prm_data(&mut Arc::make_mut(module).module_prm_data);
+        self.modules.push(module.clone());
+    }
+
+    fn share_prm_data(&mut self, prm_data: &mut UserPrmData) {
+        for (_, definition) in prm_data.data_ref.iter_mut() {
+            if let Some(text_ref) = Arc::make_mut(definition).text_ref.as_mut() {
+                share_arc(&mut self.texts, text_ref);
+            }
+            share_arc(&mut self.definitions, definition);
+        }
+    }
+}
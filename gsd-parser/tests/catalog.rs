@@ -0,0 +1,45 @@
+#[test]
+fn catalog_from_dir() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+    let catalog = gsd_parser::GsdCatalog::from_dir(dir).unwrap();
+
+    assert_eq!(catalog.len(), 1);
+    let gsd = catalog.lookup(0x1337).unwrap();
+    assert_eq!(gsd.ident_number, 0x1337);
+    assert!(catalog.lookup(0x4242).is_none());
+}
+
+#[test]
+fn catalog_insert() {
+    let path = std::path::PathBuf::from(file!());
+    let mut catalog = gsd_parser::GsdCatalog::new();
+    assert!(catalog.is_empty());
+
+    let gsd_a = gsd_parser::parser::parse(
+        &path,
+        "#Profibus_DP\nIdent_Number=0x4242\nModel_Name=\"A\"\n",
+    )
+    .unwrap();
+    let gsd_b = gsd_parser::parser::parse(
+        &path,
+        "#Profibus_DP\nIdent_Number=0x4242\nModel_Name=\"B\"\n",
+    )
+    .unwrap();
+
+    assert!(catalog.insert(gsd_a).is_none());
+    assert_eq!(catalog.lookup(0x4242).unwrap().model, "A");
+    assert_eq!(catalog.insert(gsd_b).unwrap().model, "A");
+    assert_eq!(catalog.lookup(0x4242).unwrap().model, "B");
+    assert_eq!(
+        catalog.iter().map(|(ident, _)| ident).collect::<Vec<_>>(),
+        vec![0x4242]
+    );
+}
+
+#[test]
+fn catalog_missing_dir() {
+    assert!(matches!(
+        gsd_parser::GsdCatalog::from_dir("this/directory/does/not/exist"),
+        Err(gsd_parser::GsdError::Io(_))
+    ));
+}
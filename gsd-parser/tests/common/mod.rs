@@ -0,0 +1,7 @@
+/// Parse GSD source from a test, using the test file as the path for error messages.
+pub fn parse(
+    source: &str,
+) -> Result<gsd_parser::GenericStationDescription, gsd_parser::parser::ParseError> {
+    let path = std::path::PathBuf::from(file!());
+    gsd_parser::parser::parse(&path, source)
+}
@@ -0,0 +1,23 @@
+mod common;
+use common::parse;
+
+#[test]
+fn channel_diag() {
+    let gsd = parse(
+        r#"
+#Profibus_DP
+Channel_Diag(16) = "Valve stuck"
+Channel_Diag_Help(16) = "Check the valve for debris"
+Channel_Diag(17) = "Pressure too low"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(gsd.channel_diag.len(), 2);
+    let info = &gsd.channel_diag[&16];
+    assert_eq!(info.text, "Valve stuck");
+    assert_eq!(info.help.as_deref(), Some("Check the valve for debris"));
+    let info = &gsd.channel_diag[&17];
+    assert_eq!(info.text, "Pressure too low");
+    assert_eq!(info.help, None);
+}
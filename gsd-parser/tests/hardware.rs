@@ -0,0 +1,34 @@
+mod common;
+use common::parse;
+
+#[test]
+fn hardware_capabilities() {
+    let gsd = parse(
+        r#"
+#Profibus_DP
+Redundancy=1
+Repeater_Ctrl_Sig=2
+24V_Pins=1 ; Signal M24V and P24V connected
+Implementation_Type="SPC3"
+"#,
+    )
+    .unwrap();
+    assert!(gsd.redundancy_supported);
+    assert_eq!(
+        gsd.repeater_control_signal,
+        gsd_parser::RepeaterControlSignal::Ttl
+    );
+    assert_eq!(gsd.pins_24v, gsd_parser::Pins24V::Input);
+    assert_eq!(gsd.implementation_type, "SPC3");
+
+    let gsd = parse("#Profibus_DP\nGSD_Revision=1\n").unwrap();
+    assert!(!gsd.redundancy_supported);
+    assert_eq!(
+        gsd.repeater_control_signal,
+        gsd_parser::RepeaterControlSignal::NotConnected
+    );
+    assert_eq!(gsd.pins_24v, gsd_parser::Pins24V::NotConnected);
+
+    assert!(parse("#Profibus_DP\n24V_Pins=3\n").is_err());
+    assert!(parse("#Profibus_DP\nRepeater_Ctrl_Sig=7\n").is_err());
+}
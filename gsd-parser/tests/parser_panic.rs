@@ -71,3 +71,11 @@ User_Prm_Data_Len = 3
     let path = std::path::PathBuf::from(format!("{}", file!()));
     println!("{}", gsd_parser::parser::parse(&path, source).unwrap_err());
 }
+
+#[test]
+fn parse_from_missing_file() {
+    let path = std::path::PathBuf::from("tests/data/does-not-exist.gsd");
+    let err = gsd_parser::parse_from_file(&path).unwrap_err();
+    assert!(matches!(err, gsd_parser::GsdError::Io(_)));
+    println!("{}", err);
+}
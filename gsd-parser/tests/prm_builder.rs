@@ -0,0 +1,140 @@
+fn parse(source: &str) -> gsd_parser::GenericStationDescription {
+    let path = std::path::PathBuf::from(file!());
+    gsd_parser::parser::parse(&path, source).unwrap()
+}
+
+#[test]
+fn try_set_prm_from_text_out_of_range() {
+    let gsd = parse(
+        r#"
+#Profibus_DP
+PrmText=1
+Text(0)="Slow"
+Text(1)="Fast"
+Text(5)="Broken"
+EndPrmText
+
+ExtUserPrmData=1 "Speed"
+Unsigned8 0 0-1
+Prm_Text_Ref=1
+EndExtUserPrmData
+
+Max_User_Prm_Data_Len=1
+Ext_User_Prm_Data_Ref(0)=1
+"#,
+    );
+
+    let mut prm = gsd_parser::PrmBuilder::new(&gsd.user_prm_data);
+    prm.try_set_prm_from_text("Speed", "Fast").unwrap();
+    assert_eq!(prm.get_prm("Speed"), Some(1));
+
+    assert_eq!(
+        prm.try_set_prm_from_text("Speed", "Broken").err(),
+        Some(gsd_parser::PrmError::InvalidValue {
+            value: 5,
+            constraint: gsd_parser::PrmValueConstraint::MinMax(0, 1),
+        })
+    );
+    // The previous value must be retained
+    assert_eq!(prm.get_prm("Speed"), Some(1));
+
+    assert_eq!(
+        prm.try_set_prm_from_text("Speed", "Medium").err(),
+        Some(gsd_parser::PrmError::UnknownText("Medium".to_owned()))
+    );
+    assert_eq!(
+        prm.try_set_prm_from_text("Direction", "Fast").err(),
+        Some(gsd_parser::PrmError::UnknownParameter(
+            "Direction".to_owned()
+        ))
+    );
+}
+
+#[test]
+fn try_set_prm_out_of_range() {
+    let gsd = parse(
+        r#"
+#Profibus_DP
+ExtUserPrmData=1 "Filter"
+Unsigned8 3 1-10
+EndExtUserPrmData
+
+Max_User_Prm_Data_Len=1
+Ext_User_Prm_Data_Ref(0)=1
+"#,
+    );
+
+    let mut prm = gsd_parser::PrmBuilder::new(&gsd.user_prm_data);
+    prm.try_set_prm("Filter", 7).unwrap();
+    assert_eq!(prm.as_bytes(), &[7]);
+
+    assert_eq!(
+        prm.try_set_prm("Filter", 11).err(),
+        Some(gsd_parser::PrmError::InvalidValue {
+            value: 11,
+            constraint: gsd_parser::PrmValueConstraint::MinMax(1, 10),
+        })
+    );
+    // The previous value must be retained
+    assert_eq!(prm.get_prm("Filter"), Some(7));
+
+    assert_eq!(
+        prm.try_set_prm("Delay", 7).err(),
+        Some(gsd_parser::PrmError::UnknownParameter("Delay".to_owned()))
+    );
+}
+
+#[test]
+fn bit_areas_sharing_a_byte() {
+    let gsd = parse(
+        r#"
+#Profibus_DP
+PrmText=1
+Text(0)="Off"
+Text(1)="On"
+EndPrmText
+
+PrmText=2
+Text(0)="Mode A"
+Text(1)="Mode B"
+Text(2)="Mode C"
+Text(3)="Mode D"
+EndPrmText
+
+ExtUserPrmData=1 "Enable"
+Bit(0) 0 0-1
+Prm_Text_Ref=1
+EndExtUserPrmData
+
+ExtUserPrmData=2 "Mode"
+BitArea(1-2) 0 0-3
+Prm_Text_Ref=2
+EndExtUserPrmData
+
+ExtUserPrmData=3 "Filter"
+BitArea(4-6) 0 0-7
+EndExtUserPrmData
+
+Max_User_Prm_Data_Len=1
+Ext_User_Prm_Data_Const(0)=0x80
+Ext_User_Prm_Data_Ref(0)=1
+Ext_User_Prm_Data_Ref(0)=2
+Ext_User_Prm_Data_Ref(0)=3
+"#,
+    );
+
+    let mut prm = gsd_parser::PrmBuilder::new(&gsd.user_prm_data);
+    prm.set_prm_from_text("Enable", "On");
+    prm.set_prm_from_text("Mode", "Mode D");
+    prm.set_prm("Filter", 5);
+    assert_eq!(prm.as_bytes(), &[0b1101_0111]);
+
+    prm.set_prm_from_text("Mode", "Mode B");
+    prm.set_prm("Filter", 2);
+    prm.set_prm_from_text("Enable", "Off");
+    assert_eq!(prm.as_bytes(), &[0b1010_0010]);
+
+    assert_eq!(prm.get_prm("Enable"), Some(0));
+    assert_eq!(prm.get_prm("Mode"), Some(1));
+    assert_eq!(prm.get_prm("Filter"), Some(2));
+}
@@ -3,14 +3,14 @@ use std::path::PathBuf;
 #[rstest::rstest]
 fn regress(#[files("tests/data/*.[gG][sS][dD]")] gsd_file: PathBuf) {
     let name = gsd_file.file_stem().unwrap().to_string_lossy().to_string();
-    let gsd = gsd_parser::parse_from_file(gsd_file);
+    let gsd = gsd_parser::parse_from_file(gsd_file).unwrap();
     insta::assert_debug_snapshot!(name.as_ref(), gsd);
 }
 
 #[rstest::rstest]
 fn regress_prm(#[files("tests/data/*.[gG][sS][dD]")] gsd_file: PathBuf) {
     let name = gsd_file.file_stem().unwrap().to_string_lossy().to_string();
-    let gsd = gsd_parser::parse_from_file(gsd_file);
+    let gsd = gsd_parser::parse_from_file(gsd_file).unwrap();
     let mut prm = gsd_parser::PrmBuilder::new(&gsd.user_prm_data);
 
     // Try setting all the available parameters to some reasonable values.
@@ -23,6 +23,7 @@ fn regress_prm(#[files("tests/data/*.[gG][sS][dD]")] gsd_file: PathBuf) {
                 texts.keys().next().unwrap()
             };
             prm.set_prm_from_text(&prm_ref.name, text);
+            assert_eq!(prm.get_prm(&prm_ref.name), texts.get(text).copied());
         } else {
             let v = match &prm_ref.constraint {
                 gsd_parser::PrmValueConstraint::MinMax(_, max) => *max,
@@ -30,8 +31,11 @@ fn regress_prm(#[files("tests/data/*.[gG][sS][dD]")] gsd_file: PathBuf) {
                 gsd_parser::PrmValueConstraint::Unconstrained => 1,
             };
             prm.set_prm(&prm_ref.name, v);
+            assert_eq!(prm.get_prm(&prm_ref.name), Some(v));
         }
     }
 
+    assert_eq!(prm.get_prm("this parameter does not exist"), None);
+
     insta::assert_debug_snapshot!(format!("{}-PRM", name).as_ref(), prm.as_bytes());
 }
@@ -0,0 +1,83 @@
+#![cfg(feature = "serde")]
+use std::path::PathBuf;
+use std::sync::Arc;
+
+#[rstest::rstest]
+fn serde_roundtrip(#[files("tests/data/*.[gG][sS][dD]")] gsd_file: PathBuf) {
+    let gsd = gsd_parser::parse_from_file(gsd_file).unwrap();
+    let json = serde_json::to_string(&gsd).unwrap();
+    let deserialized: gsd_parser::GenericStationDescription = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, gsd);
+}
+
+#[test]
+fn serde_shared_references() {
+    let path = PathBuf::from(file!());
+    let gsd = gsd_parser::parser::parse(
+        &path,
+        r#"
+#Profibus_DP
+PrmText=1
+Text(0)="Off"
+Text(1)="On"
+EndPrmText
+
+ExtUserPrmData=1 "Enable"
+Bit(0) 0 0-1
+Prm_Text_Ref=1
+EndExtUserPrmData
+
+ExtUserPrmData=2 "Diagnostics"
+Bit(1) 0 0-1
+Prm_Text_Ref=1
+EndExtUserPrmData
+
+Modular_Station=1
+Max_Module=2
+
+Module="Module A" 0x10
+1
+Ext_Module_Prm_Data_Len=1
+Ext_User_Prm_Data_Ref(0)=1
+EndModule
+
+Module="Module B" 0x20
+2
+Ext_Module_Prm_Data_Len=1
+Ext_User_Prm_Data_Ref(0)=1
+Ext_User_Prm_Data_Ref(0)=2
+EndModule
+
+SlotDefinition
+Slot(1) = "First" 1 1-2
+Slot(2) = "Second" 2 1-2
+EndSlotDefinition
+"#,
+    )
+    .unwrap();
+
+    let json = serde_json::to_string(&gsd).unwrap();
+    let gsd2: gsd_parser::GenericStationDescription = serde_json::from_str(&json).unwrap();
+    assert_eq!(gsd2, gsd);
+
+    // Slots must reference the available modules
+    assert_eq!(gsd2.slots.len(), 2);
+    for slot in gsd2.slots.iter() {
+        assert!(Arc::ptr_eq(
+            &slot.default,
+            &gsd2.available_modules[slot.number as usize - 1]
+        ));
+        for (module, available) in slot.allowed_modules.iter().zip(&gsd2.available_modules) {
+            assert!(Arc::ptr_eq(module, available));
+        }
+    }
+
+    // Parameter definitions and texts must be shared between modules
+    let prm_a = &gsd2.available_modules[0].module_prm_data.data_ref;
+    let prm_b = &gsd2.available_modules[1].module_prm_data.data_ref;
+    assert!(Arc::ptr_eq(&prm_a[0].1, &prm_b[0].1));
+    assert!(Arc::ptr_eq(
+        prm_b[0].1.text_ref.as_ref().unwrap(),
+        prm_b[1].1.text_ref.as_ref().unwrap()
+    ));
+}
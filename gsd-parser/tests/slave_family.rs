@@ -0,0 +1,45 @@
+mod common;
+use common::parse;
+
+#[test]
+fn slave_family_with_sub_families() {
+    let gsd = parse(
+        r#"
+#Profibus_DP
+Slave_Family=3@TdF@Digital ; Digital I/O
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        gsd.slave_family,
+        gsd_parser::SlaveFamily {
+            main: gsd_parser::MainSlaveFamily::IOs,
+            sub: vec!["TdF".to_owned(), "Digital".to_owned()],
+        }
+    );
+}
+
+#[test]
+fn slave_family_main_only() {
+    let gsd = parse(
+        r#"
+#Profibus_DP
+Slave_Family=7
+"#,
+    )
+    .unwrap();
+    assert_eq!(gsd.slave_family.main, gsd_parser::MainSlaveFamily::Encoders);
+    assert!(gsd.slave_family.sub.is_empty());
+
+    let gsd = parse(
+        r#"
+#Profibus_DP
+Slave_Family=14@Custom
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        gsd.slave_family.main,
+        gsd_parser::MainSlaveFamily::Reserved(14)
+    );
+}
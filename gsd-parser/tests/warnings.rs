@@ -0,0 +1,38 @@
+#[test]
+fn unknown_keywords() {
+    let path = std::path::PathBuf::from(file!());
+    let source = r#"
+#Profibus_DP
+GSD_Revision=1
+Vendor_Specific_Thing=1
+Module="4 DI" 0x10
+Ext_Module_Foo=2
+EndModule
+"#;
+    let (gsd, warnings) = gsd_parser::parser::parse_with_warnings(&path, source).unwrap();
+    assert_eq!(gsd.gsd_revision, 1);
+    assert_eq!(gsd.available_modules.len(), 1);
+    assert_eq!(
+        warnings,
+        [
+            gsd_parser::parser::ParseWarning {
+                keyword: "Vendor_Specific_Thing".to_owned(),
+                line: 4,
+                column: 1,
+            },
+            gsd_parser::parser::ParseWarning {
+                keyword: "Ext_Module_Foo".to_owned(),
+                line: 6,
+                column: 1,
+            },
+        ]
+    );
+    assert_eq!(
+        warnings[0].to_string(),
+        "4:1: unknown keyword \"Vendor_Specific_Thing\" was ignored"
+    );
+
+    let (_, warnings) =
+        gsd_parser::parser::parse_with_warnings(&path, "#Profibus_DP\nGSD_Revision=1\n").unwrap();
+    assert!(warnings.is_empty());
+}
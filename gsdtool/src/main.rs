@@ -1,5 +1,6 @@
 use console::style;
 use gumdrop::Options;
+use std::collections::BTreeMap;
 
 #[derive(Debug, Options)]
 struct GsdToolOptions {
@@ -15,6 +16,8 @@ enum GsdToolCommand {
     Dump(DumpOptions),
     /// Run the interactive configuration wizard.
     ConfigWizard(ConfigWizardOptions),
+    /// Generate the configuration from a TOML or JSON file.
+    Config(ConfigOptions),
     /// Interpret extended diagnostics device-based blocks.
     Diagnostics(InterpDiagOptions),
 }
@@ -23,6 +26,9 @@ enum GsdToolCommand {
 struct DumpOptions {
     help: bool,
 
+    /// Print warnings about keywords which were ignored by the parser.
+    warnings: bool,
+
     /// Path to the GSD file.
     #[options(free, required)]
     gsd_path: std::path::PathBuf,
@@ -32,11 +38,55 @@ struct DumpOptions {
 struct ConfigWizardOptions {
     help: bool,
 
+    /// Output format, either "rust" (default) or "json".
+    #[options(default = "rust")]
+    format: OutputFormat,
+
     /// Path to the GSD file.
     #[options(free, required)]
     gsd_path: std::path::PathBuf,
 }
 
+#[derive(Debug, Options)]
+struct ConfigOptions {
+    help: bool,
+
+    /// Path to the TOML or JSON file with module selections and parameter values.
+    #[options(required)]
+    input: std::path::PathBuf,
+
+    /// Output format, either "rust" (default) or "json".
+    #[options(default = "rust")]
+    format: OutputFormat,
+
+    /// Path to the GSD file.
+    #[options(free, required)]
+    gsd_path: std::path::PathBuf,
+}
+
+/// Format of the generated peripheral configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    /// Rust code constructing `profirust::dp::PeripheralOptions`
+    Rust,
+    /// JSON data which deserializes into `profirust::dp::PeripheralConfig`
+    Json,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rust" => Ok(OutputFormat::Rust),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!(
+                "unknown output format {s:?}, expected \"rust\" or \"json\""
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Options)]
 struct InterpDiagOptions {
     help: bool,
@@ -50,12 +100,25 @@ fn main() {
     let args = GsdToolOptions::parse_args_default_or_exit();
     match args.command {
         Some(GsdToolCommand::Dump(args)) => {
-            let gsd = gsd_parser::parse_from_file(args.gsd_path);
+            let (gsd, warnings) = load_gsd_with_warnings(&args.gsd_path);
+            if args.warnings {
+                for warning in warnings {
+                    eprintln!(
+                        "{} {}:{}",
+                        style("Warning:").yellow().bold(),
+                        args.gsd_path.display(),
+                        warning
+                    );
+                }
+            }
             println!("{:#?}", gsd);
         }
         Some(GsdToolCommand::ConfigWizard(args)) => {
             run_config_wizard(&args);
         }
+        Some(GsdToolCommand::Config(args)) => {
+            run_config(&args);
+        }
         Some(GsdToolCommand::Diagnostics(args)) => {
             run_interp_diag(&args);
         }
@@ -66,18 +129,62 @@ fn main() {
     }
 }
 
+fn load_gsd(path: &std::path::Path) -> gsd_parser::GenericStationDescription {
+    load_gsd_with_warnings(path).0
+}
+
+fn load_gsd_with_warnings(
+    path: &std::path::Path,
+) -> (
+    gsd_parser::GenericStationDescription,
+    Vec<gsd_parser::parser::ParseWarning>,
+) {
+    match gsd_parser::parse_from_file_with_warnings(path) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{} {}: {}", style("Error:").red().bold(), path.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Find the modules which are allowed in a slot and the slot definition, if there is one
+fn slot_modules(
+    gsd: &gsd_parser::GenericStationDescription,
+    slot_number: u8,
+) -> (
+    &[std::sync::Arc<gsd_parser::Module>],
+    Option<&gsd_parser::Slot>,
+) {
+    if let Some(slot) = gsd.slots.iter().find(|s| s.number == slot_number) {
+        (&slot.allowed_modules, Some(slot))
+    } else {
+        // Without a slot definition, all available modules are allowed
+        (&gsd.available_modules, None)
+    }
+}
+
+fn print_prm_warning(name: &str, e: &gsd_parser::PrmError) {
+    eprintln!(
+        "{} Cannot set parameter {:?}: {}.  Keeping the default value.",
+        style("Warning:").yellow().bold(),
+        name,
+        e
+    );
+}
+
 fn run_config_wizard(args: &ConfigWizardOptions) {
-    let gsd = gsd_parser::parse_from_file(&args.gsd_path);
+    let gsd = load_gsd(&args.gsd_path);
 
-    println!(
+    eprintln!(
         "{}",
         style("Welcome to the station configuration wizard!").bold()
     );
-    println!("Station: {:?} from {:?}", gsd.model, gsd.vendor);
-    println!("Ident:   0x{:04x}", gsd.ident_number);
-    println!();
+    eprintln!("Station: {:?} from {:?}", gsd.model, gsd.vendor);
+    eprintln!("Ident:   0x{:04x}", gsd.ident_number);
+    eprintln!();
 
-    println!("{}", style("Global parameters:").bold());
+    eprintln!("{}", style("Global parameters:").bold());
     let mut prm = gsd_parser::PrmBuilder::new(&gsd.user_prm_data);
     let mut global_parameters = vec![];
     let mut had_parameters = false;
@@ -104,7 +211,10 @@ fn run_config_wizard(args: &ConfigWizardOptions) {
                 .unwrap();
 
             let sel_text = &texts_list[selection];
-            prm.set_prm_from_text(&prm_ref.name, sel_text);
+            if let Err(e) = prm.try_set_prm_from_text(&prm_ref.name, sel_text) {
+                print_prm_warning(&prm_ref.name, &e);
+                continue;
+            }
 
             global_parameters.push((prm_ref.name.to_owned(), sel_text.to_string()));
         } else if let gsd_parser::PrmValueConstraint::MinMax(min, max) = prm_ref.constraint {
@@ -168,16 +278,16 @@ fn run_config_wizard(args: &ConfigWizardOptions) {
         had_parameters = true;
     }
     if !had_parameters {
-        println!("No global parameters available.");
+        eprintln!("No global parameters available.");
     }
-    println!();
+    eprintln!();
 
     let mut user_prm_data = Vec::new();
     user_prm_data.append(&mut prm.into_bytes());
 
     let mut module_config = Vec::new();
 
-    println!(
+    eprintln!(
         "{}",
         style(format!("Selecting modules (maximum {}):", gsd.max_modules)).bold()
     );
@@ -192,13 +302,7 @@ fn run_config_wizard(args: &ConfigWizardOptions) {
     let mut module_selection_list = vec![];
     for i in 0..max_modules {
         let slot_number = i + 1;
-        let (allowed_modules, slot) =
-            if let Some(slot) = gsd.slots.iter().find(|s| s.number == slot_number) {
-                (&slot.allowed_modules, Some(&*slot))
-            } else {
-                // Without a slot definition, all available modules are allowed
-                (&gsd.available_modules, None)
-            };
+        let (allowed_modules, slot) = slot_modules(&gsd, slot_number);
 
         let module_names: Vec<String> =
             allowed_modules.iter().map(|m| m.name.to_string()).collect();
@@ -239,7 +343,7 @@ fn run_config_wizard(args: &ConfigWizardOptions) {
         } else {
             // For non-modular (=compact) stations, always select the first and only module
             debug_assert_eq!(allowed_modules.len(), 1);
-            println!(
+            eprintln!(
                 "Auto-selecting module \"{}\" for this compact station.",
                 module_names[0]
             );
@@ -279,7 +383,10 @@ fn run_config_wizard(args: &ConfigWizardOptions) {
                         .unwrap();
 
                     let sel_text = &texts_list[selection];
-                    prm.set_prm_from_text(&prm_ref.name, sel_text);
+                    if let Err(e) = prm.try_set_prm_from_text(&prm_ref.name, sel_text) {
+                        print_prm_warning(&prm_ref.name, &e);
+                        continue;
+                    }
 
                     module_parameters.push((prm_ref.name.to_owned(), sel_text.to_string()));
                 } else if let gsd_parser::PrmValueConstraint::MinMax(min, max) = prm_ref.constraint
@@ -349,57 +456,326 @@ fn run_config_wizard(args: &ConfigWizardOptions) {
             break;
         }
     }
-    println!();
+    eprintln!();
+
+    eprintln!();
+    eprintln!("{}", style("Peripheral Configuration:").bold());
+    eprintln!();
+    let config = GeneratedConfig {
+        global_parameters,
+        modules: module_selection_list,
+        user_prm_data,
+        module_config,
+    };
+    match args.format {
+        OutputFormat::Rust => print_peripheral_options(&gsd, &args.gsd_path, config),
+        OutputFormat::Json => print_peripheral_config_json(&gsd, config),
+    }
+}
 
-    let mut bytes_input = 0;
-    let mut bytes_output = 0;
-    for cfg_byte in module_config.iter().copied() {
-        let factor = if cfg_byte & 0x40 != 0 {
-            // length in words
-            2
-        } else {
-            // length in bytes
-            1
-        };
-        let length = ((cfg_byte & 0x0f) + 1) * factor;
-        if cfg_byte & 0x20 != 0 {
-            bytes_output += length;
+/// Module selections and parameter values for the `config` subcommand
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigSpec {
+    #[serde(default)]
+    parameters: BTreeMap<String, PrmValue>,
+    #[serde(default)]
+    modules: Vec<ModuleSpec>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ModuleSpec {
+    name: String,
+    #[serde(default)]
+    parameters: BTreeMap<String, PrmValue>,
+}
+
+/// Parameter value, either numeric or one of the parameter texts
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum PrmValue {
+    Number(i64),
+    Text(String),
+}
+
+fn load_config_spec(path: &std::path::Path) -> ConfigSpec {
+    let result = std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|source| {
+            if path.extension().is_some_and(|ext| ext == "json") {
+                serde_json::from_str(&source).map_err(|e| e.to_string())
+            } else {
+                toml::from_str(&source).map_err(|e| e.to_string())
+            }
+        });
+    match result {
+        Ok(spec) => spec,
+        Err(e) => {
+            eprintln!("{} {}: {}", style("Error:").red().bold(), path.display(), e);
+            std::process::exit(1);
         }
-        if cfg_byte & 0x10 != 0 {
-            bytes_input += length;
+    }
+}
+
+/// Build the parameter data from the given values, using defaults for all other parameters
+///
+/// Returns the parameter bytes and the list of parameters with their values for display.
+fn build_prm_data(
+    prm_data: &gsd_parser::UserPrmData,
+    values: &BTreeMap<String, PrmValue>,
+) -> Result<(Vec<u8>, ParameterList), String> {
+    for name in values.keys() {
+        match prm_data.data_ref.iter().find(|(_, r)| &r.name == name) {
+            None => return Err(format!("unknown parameter {name:?}")),
+            Some((_, r)) if !r.visible || !r.changeable => {
+                return Err(format!("parameter {name:?} is not changeable"))
+            }
+            Some(_) => (),
         }
-        if cfg_byte != 0 && cfg_byte & 0x30 == 0 {
-            bytes_input = 0;
-            bytes_output = 0;
-            println!(
-                "{}: Special module format not yet supported, I/O lengths are unknown.",
-                style("Warning").yellow().bold()
+    }
+
+    let mut prm = gsd_parser::PrmBuilder::new(prm_data);
+    let mut parameters = vec![];
+    for (_, prm_ref) in prm_data.data_ref.iter() {
+        if !prm_ref.visible || !prm_ref.changeable {
+            // Skip invisible or read-only...
+            continue;
+        }
+
+        let value_str = match values.get(&prm_ref.name) {
+            Some(PrmValue::Text(text)) => {
+                prm.try_set_prm_from_text(&prm_ref.name, text)
+                    .map_err(|e| format!("parameter {:?}: {}", prm_ref.name, e))?;
+                text.to_owned()
+            }
+            Some(PrmValue::Number(value)) => {
+                prm.try_set_prm(&prm_ref.name, *value)
+                    .map_err(|e| format!("parameter {:?}: {}", prm_ref.name, e))?;
+                value.to_string()
+            }
+            None => prm_ref
+                .text_ref
+                .as_ref()
+                .and_then(|texts| texts.iter().find(|(_, v)| **v == prm_ref.default_value))
+                .map(|(text, _)| text.to_owned())
+                .unwrap_or_else(|| prm_ref.default_value.to_string()),
+        };
+        parameters.push((prm_ref.name.to_owned(), value_str));
+    }
+
+    Ok((prm.into_bytes(), parameters))
+}
+
+/// Assemble the peripheral configuration from a spec file
+fn build_config(
+    gsd: &gsd_parser::GenericStationDescription,
+    spec: &ConfigSpec,
+) -> Result<GeneratedConfig, String> {
+    let (mut user_prm_data, global_parameters) =
+        build_prm_data(&gsd.user_prm_data, &spec.parameters)
+            .map_err(|e| format!("global parameters: {e}"))?;
+
+    // Dirty way to support compact stations for now
+    let max_modules = if gsd.modular_station {
+        gsd.max_modules
+    } else {
+        1
+    };
+
+    let mut module_specs: Vec<&ModuleSpec> = spec.modules.iter().collect();
+    let auto_module;
+    if module_specs.is_empty() && !gsd.modular_station {
+        // For compact stations, select the only module automatically like the wizard does
+        if let ([module], _) = slot_modules(gsd, 1) {
+            auto_module = ModuleSpec {
+                name: module.name.to_owned(),
+                parameters: BTreeMap::new(),
+            };
+            module_specs.push(&auto_module);
+        }
+    }
+
+    if module_specs.len() > usize::from(max_modules) {
+        return Err(format!(
+            "{} modules selected but the station supports at most {}",
+            module_specs.len(),
+            max_modules
+        ));
+    }
+
+    let mut module_config = Vec::new();
+    let mut modules = vec![];
+    for (i, module_spec) in module_specs.into_iter().enumerate() {
+        let slot_number = u8::try_from(i + 1).unwrap();
+        let (allowed_modules, slot) = slot_modules(gsd, slot_number);
+        let module = allowed_modules
+            .iter()
+            .find(|m| m.name == module_spec.name)
+            .ok_or_else(|| match slot {
+                Some(slot)
+                    if gsd
+                        .available_modules
+                        .iter()
+                        .any(|m| m.name == module_spec.name) =>
+                {
+                    format!(
+                        "module {:?} is not allowed in slot \"{}\" {}",
+                        module_spec.name, slot.name, slot_number
+                    )
+                }
+                _ => format!("unknown module {:?}", module_spec.name),
+            })?;
+
+        let (mut prm_data, parameters) =
+            build_prm_data(&module.module_prm_data, &module_spec.parameters)
+                .map_err(|e| format!("module {:?} in slot {}: {}", module.name, slot_number, e))?;
+
+        module_config.extend_from_slice(&module.config);
+        user_prm_data.append(&mut prm_data);
+        modules.push((module.name.to_owned(), parameters));
+    }
+
+    Ok(GeneratedConfig {
+        global_parameters,
+        modules,
+        user_prm_data,
+        module_config,
+    })
+}
+
+fn run_config(args: &ConfigOptions) {
+    let gsd = load_gsd(&args.gsd_path);
+    let spec = load_config_spec(&args.input);
+
+    match build_config(&gsd, &spec) {
+        Ok(config) => match args.format {
+            OutputFormat::Rust => print_peripheral_options(&gsd, &args.gsd_path, config),
+            OutputFormat::Json => print_peripheral_config_json(&gsd, config),
+        },
+        Err(e) => {
+            eprintln!(
+                "{} {}: {}",
+                style("Error:").red().bold(),
+                args.input.display(),
+                e
             );
-            break;
+            std::process::exit(1);
         }
     }
+}
+
+/// Names and values of parameters for display
+type ParameterList = Vec<(String, String)>;
+
+/// Peripheral configuration assembled from module selections and parameter values
+struct GeneratedConfig {
+    global_parameters: ParameterList,
+    modules: Vec<(String, ParameterList)>,
+    user_prm_data: Vec<u8>,
+    module_config: Vec<u8>,
+}
+
+/// List the maximum response times (Tsdr) for all baudrates supported by the station
+fn max_tsdr_table(gsd: &gsd_parser::GenericStationDescription) -> Vec<(profirust::Baudrate, u16)> {
+    gsd.supported_speeds
+        .iter()
+        .map(|speed| match speed {
+            gsd_parser::SupportedSpeeds::B9600 => (profirust::Baudrate::B9600, gsd.max_tsdr.b9600),
+            gsd_parser::SupportedSpeeds::B19200 => {
+                (profirust::Baudrate::B19200, gsd.max_tsdr.b19200)
+            }
+            gsd_parser::SupportedSpeeds::B31250 => {
+                (profirust::Baudrate::B31250, gsd.max_tsdr.b31250)
+            }
+            gsd_parser::SupportedSpeeds::B45450 => {
+                (profirust::Baudrate::B45450, gsd.max_tsdr.b45450)
+            }
+            gsd_parser::SupportedSpeeds::B93750 => {
+                (profirust::Baudrate::B93750, gsd.max_tsdr.b93750)
+            }
+            gsd_parser::SupportedSpeeds::B187500 => {
+                (profirust::Baudrate::B187500, gsd.max_tsdr.b187500)
+            }
+            gsd_parser::SupportedSpeeds::B500000 => {
+                (profirust::Baudrate::B500000, gsd.max_tsdr.b500000)
+            }
+            gsd_parser::SupportedSpeeds::B1500000 => {
+                (profirust::Baudrate::B1500000, gsd.max_tsdr.b1500000)
+            }
+            gsd_parser::SupportedSpeeds::B3000000 => {
+                (profirust::Baudrate::B3000000, gsd.max_tsdr.b3000000)
+            }
+            gsd_parser::SupportedSpeeds::B6000000 => {
+                (profirust::Baudrate::B6000000, gsd.max_tsdr.b6000000)
+            }
+            gsd_parser::SupportedSpeeds::B12000000 => {
+                (profirust::Baudrate::B12000000, gsd.max_tsdr.b12000000)
+            }
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Print the configuration as JSON which deserializes into `profirust::dp::PeripheralConfig`
+fn print_peripheral_config_json(
+    gsd: &gsd_parser::GenericStationDescription,
+    config: GeneratedConfig,
+) {
+    let peripheral_config = profirust::dp::PeripheralConfig {
+        ident_number: gsd.ident_number,
+        user_parameters: config.user_prm_data,
+        config: config.module_config,
+        max_tsdr: max_tsdr_table(gsd).into_iter().collect(),
+        fail_safe: gsd.fail_safe,
+        min_slave_interval: profirust::time::Duration::from_micros(
+            gsd.min_slave_interval.as_micros().try_into().unwrap(),
+        ),
+    };
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&peripheral_config).unwrap()
+    );
+}
+
+/// Print the `PeripheralOptions` code block for a configuration
+fn print_peripheral_options(
+    gsd: &gsd_parser::GenericStationDescription,
+    gsd_path: &std::path::Path,
+    config: GeneratedConfig,
+) {
+    let (bytes_input, bytes_output) = profirust::dp::PeripheralOptions {
+        config: Some(&config.module_config),
+        ..Default::default()
+    }
+    .io_lengths()
+    .unwrap_or_else(|| {
+        eprintln!(
+            "{}: Truncated special module format, I/O lengths are unknown.",
+            style("Warning").yellow().bold()
+        );
+        (0, 0)
+    });
 
-    println!();
-    println!("{}", style("Peripheral Configuration:").bold());
-    println!();
     println!(
         "    // Options generated by `gsdtool` using \"{}\"",
-        args.gsd_path.file_name().unwrap().to_string_lossy()
+        gsd_path.file_name().unwrap().to_string_lossy()
     );
     println!("    let options = profirust::dp::PeripheralOptions {{");
     println!("        // \"{}\" by \"{}\"", gsd.model, gsd.vendor);
     println!("        ident_number: 0x{:04x},", gsd.ident_number);
     println!();
     println!("        // Global Parameters:");
-    if global_parameters.len() == 0 {
+    if config.global_parameters.len() == 0 {
         println!("        //   (none)");
     } else {
-        let longest_name = global_parameters
+        let longest_name = config
+            .global_parameters
             .iter()
             .map(|(n, _)| n.len())
             .max()
             .unwrap_or(0);
-        for (name, value) in global_parameters.into_iter() {
+        for (name, value) in config.global_parameters.into_iter() {
             println!(
                 "        //   - {:.<width$}: {}",
                 name,
@@ -408,11 +784,11 @@ fn run_config_wizard(args: &ConfigWizardOptions) {
             );
         }
     }
-    if module_selection_list.len() > 0 {
+    if config.modules.len() > 0 {
         println!("        //");
         println!("        // Selected Modules:");
-        let modid_width = usize::try_from(module_selection_list.len().ilog10()).unwrap() + 1;
-        for (i, (module, param)) in module_selection_list.into_iter().enumerate() {
+        let modid_width = usize::try_from(config.modules.len().ilog10()).unwrap() + 1;
+        for (i, (module, param)) in config.modules.into_iter().enumerate() {
             let slot_number = i + 1;
             println!(
                 "        //   [{slot_number:width$}] {}",
@@ -433,12 +809,12 @@ fn run_config_wizard(args: &ConfigWizardOptions) {
         }
     }
     print!("        user_parameters: Some(&[");
-    for b in user_prm_data.into_iter() {
+    for b in config.user_prm_data.into_iter() {
         print!("0x{b:02x}, ");
     }
     println!("]),");
     print!("        config: Some(&[");
-    for b in module_config.into_iter() {
+    for b in config.module_config.into_iter() {
         print!("0x{b:02x}, ");
     }
     println!("]),");
@@ -446,76 +822,8 @@ fn run_config_wizard(args: &ConfigWizardOptions) {
     println!("        // Set max_tsdr depending on baudrate and assert");
     println!("        // that a supported baudrate is used.");
     println!("        max_tsdr: match BAUDRATE {{");
-    for (_, speed) in gsd.supported_speeds.iter_names() {
-        match speed {
-            gsd_parser::SupportedSpeeds::B9600 => {
-                println!(
-                    "            profirust::Baudrate::B9600 => {},",
-                    gsd.max_tsdr.b9600
-                );
-            }
-            gsd_parser::SupportedSpeeds::B19200 => {
-                println!(
-                    "            profirust::Baudrate::B19200 => {},",
-                    gsd.max_tsdr.b19200
-                );
-            }
-            gsd_parser::SupportedSpeeds::B31250 => {
-                println!(
-                    "            profirust::Baudrate::B31250 => {},",
-                    gsd.max_tsdr.b31250
-                );
-            }
-            gsd_parser::SupportedSpeeds::B45450 => {
-                println!(
-                    "            profirust::Baudrate::B45450 => {},",
-                    gsd.max_tsdr.b45450
-                );
-            }
-            gsd_parser::SupportedSpeeds::B93750 => {
-                println!(
-                    "            profirust::Baudrate::B93750 => {},",
-                    gsd.max_tsdr.b93750
-                );
-            }
-            gsd_parser::SupportedSpeeds::B187500 => {
-                println!(
-                    "            profirust::Baudrate::B187500 => {},",
-                    gsd.max_tsdr.b187500
-                );
-            }
-            gsd_parser::SupportedSpeeds::B500000 => {
-                println!(
-                    "            profirust::Baudrate::B500000 => {},",
-                    gsd.max_tsdr.b500000
-                );
-            }
-            gsd_parser::SupportedSpeeds::B1500000 => {
-                println!(
-                    "            profirust::Baudrate::B1500000 => {},",
-                    gsd.max_tsdr.b1500000
-                );
-            }
-            gsd_parser::SupportedSpeeds::B3000000 => {
-                println!(
-                    "            profirust::Baudrate::B3000000 => {},",
-                    gsd.max_tsdr.b3000000
-                );
-            }
-            gsd_parser::SupportedSpeeds::B6000000 => {
-                println!(
-                    "            profirust::Baudrate::B6000000 => {},",
-                    gsd.max_tsdr.b6000000
-                );
-            }
-            gsd_parser::SupportedSpeeds::B12000000 => {
-                println!(
-                    "            profirust::Baudrate::B12000000 => {},",
-                    gsd.max_tsdr.b12000000
-                );
-            }
-            _ => unreachable!(),
-        }
+    for (baudrate, max_tsdr) in max_tsdr_table(gsd) {
+        println!("            profirust::Baudrate::{baudrate:?} => {max_tsdr},");
     }
     println!(
         "            b => panic!(\"Peripheral \\\"{}\\\" does not support baudrate {{b:?}}!\"),",
@@ -524,6 +832,12 @@ fn run_config_wizard(args: &ConfigWizardOptions) {
     println!("        }},");
     println!();
     println!("        fail_safe: {},", gsd.fail_safe);
+    if !gsd.min_slave_interval.is_zero() {
+        println!(
+            "        min_slave_interval: profirust::time::Duration::from_micros({}),",
+            gsd.min_slave_interval.as_micros()
+        );
+    }
     println!("        ..Default::default()");
     println!("    }};");
     if bytes_input != 0 || bytes_output != 0 {
@@ -540,27 +854,92 @@ fn run_config_wizard(args: &ConfigWizardOptions) {
 }
 
 fn run_interp_diag(args: &InterpDiagOptions) {
-    let gsd = gsd_parser::parse_from_file(&args.gsd_path);
+    let gsd = load_gsd(&args.gsd_path);
 
+    /// Parse a `fmt::Debug` slice like `[1, 2, 3]`.
     fn parse_slice(text: &str) -> Option<Vec<u8>> {
         let mut buffer = Vec::new();
-        let text = text.trim();
         let text = text.strip_prefix("[")?;
+        let text = text.strip_suffix("]").unwrap_or(text);
         for number_str in text.split(",") {
-            let number_str = number_str.trim().trim_end_matches("]");
-            buffer.push(str::parse::<u8>(number_str).ok()?);
+            buffer.push(str::parse::<u8>(number_str.trim()).ok()?);
+        }
+        Some(buffer)
+    }
+
+    /// Parse hex bytes separated by whitespace and/or commas, like `01 02 03` or `0x01,0x02`.
+    fn parse_hex(text: &str) -> Option<Vec<u8>> {
+        let mut buffer = Vec::new();
+        for byte_str in text
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+        {
+            let byte_str = byte_str
+                .strip_prefix("0x")
+                .or_else(|| byte_str.strip_prefix("0X"))
+                .unwrap_or(byte_str);
+            if byte_str.is_empty() || byte_str.len() > 2 {
+                return None;
+            }
+            buffer.push(u8::from_str_radix(byte_str, 16).ok()?);
+        }
+        if buffer.is_empty() {
+            return None;
+        }
+        Some(buffer)
+    }
+
+    /// Parse standard base64 (with optional padding).
+    fn parse_base64(text: &str) -> Option<Vec<u8>> {
+        fn sextet(c: u8) -> Option<u32> {
+            match c {
+                b'A'..=b'Z' => Some((c - b'A') as u32),
+                b'a'..=b'z' => Some((c - b'a' + 26) as u32),
+                b'0'..=b'9' => Some((c - b'0' + 52) as u32),
+                b'+' => Some(62),
+                b'/' => Some(63),
+                _ => None,
+            }
+        }
+
+        let text = text.trim_end_matches('=').as_bytes();
+        if text.is_empty() || text.len() % 4 == 1 {
+            return None;
+        }
+        let mut buffer = Vec::new();
+        for chunk in text.chunks(4) {
+            let mut acc = 0u32;
+            for c in chunk {
+                acc = (acc << 6) | sextet(*c)?;
+            }
+            // Left-align a partial chunk to a full 24 bit group.
+            acc <<= 6 * (4 - chunk.len() as u32);
+            let bytes = acc.to_be_bytes();
+            buffer.extend_from_slice(&bytes[1..chunk.len()]);
         }
         Some(buffer)
     }
 
+    /// Auto-detect the input format and parse it.
+    fn parse_diag_data(text: &str) -> Option<Vec<u8>> {
+        let text = text.trim();
+        if text.starts_with("[") {
+            parse_slice(text)
+        } else {
+            // Short tokens like `01 02` are always hex, everything else is tried as base64 as
+            // well.
+            parse_hex(text).or_else(|| parse_base64(text))
+        }
+    }
+
     let value = dialoguer::Input::new()
-        .with_prompt("Diagnostics Data (as fmt::Debug slice)")
+        .with_prompt("Diagnostics Data (as fmt::Debug slice, hex, or base64)")
         .validate_with(|inp: &String| -> Result<(), &str> {
-            parse_slice(&inp).map(|_| ()).ok_or("not a valid value")
+            parse_diag_data(&inp).map(|_| ()).ok_or("not a valid value")
         })
         .interact()
         .unwrap();
-    let diag = parse_slice(&value).unwrap();
+    let diag = parse_diag_data(&value).unwrap();
     let diag_bits = bitvec::slice::BitSlice::<u8>::from_slice(&diag);
 
     for (bit, info) in gsd.unit_diag.bits.iter() {
@@ -598,4 +977,46 @@ fn run_interp_diag(args: &InterpDiagOptions) {
             // println!("Area {}-{}: {} = Unknown!!", area.first, area.last, value);
         }
     }
+
+    let mut diag_buffer = diag.clone();
+    let ext_diag = profirust::dp::ExtendedDiagnostics::from_raw(&mut diag_buffer[..]);
+    for block in ext_diag.iter_diag_blocks() {
+        let profirust::dp::ExtDiagBlock::Channel(channel) = block else {
+            continue;
+        };
+
+        let direction = match (channel.input, channel.output) {
+            (true, true) => "input/output",
+            (true, false) => "input",
+            (false, true) => "output",
+            (false, false) => "unknown direction",
+        };
+        let error_type = channel.error.error_type();
+        let info = gsd.channel_diag.get(&error_type);
+        let text = match (info, channel.error) {
+            (Some(info), _) => info.text.as_str(),
+            (None, profirust::dp::ChannelError::ShortCircuit) => "Short circuit",
+            (None, profirust::dp::ChannelError::UnderVoltage) => "Undervoltage",
+            (None, profirust::dp::ChannelError::OverVoltage) => "Overvoltage",
+            (None, profirust::dp::ChannelError::OverLoad) => "Overload",
+            (None, profirust::dp::ChannelError::OverTemperature) => "Overtemperature",
+            (None, profirust::dp::ChannelError::LineBreak) => "Line break",
+            (None, profirust::dp::ChannelError::UpperLimitOvershoot) => {
+                "Upper limit value exceeded"
+            }
+            (None, profirust::dp::ChannelError::LowerLimitUndershoot) => {
+                "Lower limit value exceeded"
+            }
+            (None, profirust::dp::ChannelError::Error) => "Error",
+            (None, profirust::dp::ChannelError::Vendor(_)) => "Unknown vendor-specific error",
+            (None, profirust::dp::ChannelError::Reserved(_)) => "Unknown reserved error",
+        };
+        println!(
+            "Module {}, Channel {} ({direction}): Error {error_type} = {text}",
+            channel.module, channel.channel,
+        );
+        if let Some(help) = info.and_then(|info| info.help.as_ref()) {
+            println!("  Help: {help}");
+        }
+    }
 }
@@ -26,6 +26,8 @@ pub const SAP_MASTER_MS0: Option<u8> = Some(62);
 
 /// SAP (Service Access Point) of a DP slave for **Data Exchange**
 pub const SAP_SLAVE_DATA_EXCHANGE: Option<u8> = None;
+/// SAP (Service Access Point) of a DP slave for **DPV1 MSAC_C1: Acyclic master class 1**
+pub const SAP_SLAVE_DPV1_MS1: Option<u8> = Some(51);
 /// SAP (Service Access Point) of a DP slave for **Set Address**
 pub const SAP_SLAVE_SET_ADDRESS: Option<u8> = Some(55);
 /// SAP (Service Access Point) of a DP slave for **Read Inputs**
@@ -42,3 +44,14 @@ pub const SAP_SLAVE_DIAGNOSIS: Option<u8> = Some(60);
 pub const SAP_SLAVE_SET_PRM: Option<u8> = Some(61);
 /// SAP (Service Access Point) of a DP slave for **Check Configuration**
 pub const SAP_SLAVE_CHK_CFG: Option<u8> = Some(62);
+
+/// Global Control: Clear_Data
+pub const GC_CLEAR_DATA: u8 = 0x02;
+/// Global Control: Unfreeze
+pub const GC_UNFREEZE: u8 = 0x04;
+/// Global Control: Freeze
+pub const GC_FREEZE: u8 = 0x08;
+/// Global Control: Unsync
+pub const GC_UNSYNC: u8 = 0x10;
+/// Global Control: Sync
+pub const GC_SYNC: u8 = 0x20;
@@ -28,7 +28,6 @@ impl<'a> ExtendedDiagnostics<'a> {
     ///
     /// The iterator yields an [`ExtDiagBlock`] for each diagnostics block.
     pub fn iter_diag_blocks(&self) -> ExtDiagBlockIter<'_> {
-        // TODO: is_available() guard?
         ExtDiagBlockIter {
             ext_diag: self,
             cursor: 0,
@@ -50,6 +49,34 @@ impl<'a> ExtendedDiagnostics<'a> {
         }
     }
 
+    /// The raw extended diagnostics data, as reported by the peripheral.
+    ///
+    /// This is useful for interpreting vendor-specific diagnostics or for blocks that
+    /// [`iter_diag_blocks()`][`ExtendedDiagnostics::iter_diag_blocks`] does not understand.  The
+    /// data can also be passed to `gsdtool diagnostics`.
+    ///
+    /// Returns an empty slice when no extended diagnostics were reported or no buffer was
+    /// prepared for them.
+    #[inline]
+    pub fn raw(&self) -> &[u8] {
+        &self.buffer[..self.length]
+    }
+
+    /// Wrap raw extended diagnostics data, for example as previously returned by
+    /// [`raw()`][`ExtendedDiagnostics::raw`].
+    ///
+    /// The whole buffer is treated as valid diagnostics data.
+    pub fn from_raw<S>(data: S) -> Self
+    where
+        S: Into<managed::ManagedSlice<'a, u8>>,
+    {
+        let buffer = data.into();
+        Self {
+            length: buffer.len(),
+            buffer,
+        }
+    }
+
     pub(crate) fn from_buffer(buffer: managed::ManagedSlice<'a, u8>) -> Self {
         Self { buffer, length: 0 }
     }
@@ -156,7 +183,10 @@ impl ChannelError {
         }
     }
 
-    fn into_diag_byte2(self) -> u8 {
+    /// Numeric error type code as reported in the diagnostics.
+    ///
+    /// This is the code which `Channel_Diag` entries in a GSD file refer to.
+    pub fn error_type(self) -> u8 {
         match self {
             ChannelError::ShortCircuit => 1,
             ChannelError::UnderVoltage => 2,
@@ -171,6 +201,10 @@ impl ChannelError {
             ChannelError::Reserved(r) => r,
         }
     }
+
+    fn into_diag_byte2(self) -> u8 {
+        self.error_type()
+    }
 }
 
 /// Diagnostic information for a module channel
@@ -192,6 +226,82 @@ pub struct ChannelDiagnostics {
     pub error: ChannelError,
 }
 
+/// Status of a single module as reported in a [`ModuleStatusBlock`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum ModuleStatus {
+    /// Module is okay, its data is valid
+    Ok = 0b00,
+    /// Module has an error, its data is invalid
+    Error = 0b01,
+    /// Wrong module is plugged, its data is invalid
+    WrongModule = 0b10,
+    /// No module is plugged, there is no valid data
+    NoModule = 0b11,
+}
+
+impl ModuleStatus {
+    fn from_bits(b: u8) -> Self {
+        match b & 0b11 {
+            0b00 => ModuleStatus::Ok,
+            0b01 => ModuleStatus::Error,
+            0b10 => ModuleStatus::WrongModule,
+            0b11 => ModuleStatus::NoModule,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Module status diagnostics (DP-V1 `Module_Status` status block)
+///
+/// Contains the status of each module slot, with 2 bits per module.  The module indices match
+/// the ones from the configuration telegram.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ModuleStatusBlock<'a> {
+    /// Slot number this status block belongs to
+    pub slot_number: u8,
+    /// Status specifier of this block
+    pub specifier: u8,
+    data: &'a [u8],
+}
+
+impl<'a> ModuleStatusBlock<'a> {
+    /// Iterate over the status of all modules in this block.
+    ///
+    /// Yields `(module_index, status)` tuples.  Note that the block is padded to full bytes, so
+    /// there may be trailing entries for module slots which do not exist.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, ModuleStatus)> + 'a {
+        self.data
+            .iter()
+            .flat_map(|b| (0..4).map(move |i| ModuleStatus::from_bits(b >> (i * 2))))
+            .enumerate()
+    }
+}
+
+impl<'a> core::fmt::Debug for ModuleStatusBlock<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ModuleStatusBlock")
+            .field("slot_number", &self.slot_number)
+            .field("specifier", &self.specifier)
+            .field("modules", &ModuleStatusDebug(self))
+            .finish()
+    }
+}
+
+struct ModuleStatusDebug<'a, 'b>(&'b ModuleStatusBlock<'a>);
+
+impl<'a, 'b> core::fmt::Debug for ModuleStatusDebug<'a, 'b> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut dbg_map = f.debug_map();
+        for (i, status) in self.0.iter() {
+            if status != ModuleStatus::Ok {
+                dbg_map.entry(&i, &status);
+            }
+        }
+        dbg_map.finish()
+    }
+}
+
 /// One extended diagnostics block
 #[derive(Clone, PartialEq, Eq)]
 pub enum ExtDiagBlock<'a> {
@@ -211,6 +321,10 @@ pub enum ExtDiagBlock<'a> {
     /// file.  `gsdtool` has a `diagnostics` subcommand which can dissect a device-based
     /// diagnostics buffer and print human-readable information about the diagnostics it encodes.
     Device(&'a [u8]),
+    /// Module status diagnostics
+    ///
+    /// Reports the status of each module.  See [`ModuleStatusBlock`] for details.
+    ModuleStatus(ModuleStatusBlock<'a>),
 }
 
 struct IdentifierDebug<'a>(&'a bitvec::slice::BitSlice<u8>);
@@ -234,6 +348,7 @@ impl<'a> core::fmt::Debug for ExtDiagBlock<'a> {
                 .finish(),
             ExtDiagBlock::Channel(c) => f.debug_tuple("Channel").field(c).finish(),
             ExtDiagBlock::Device(d) => f.debug_tuple("Device").field(d).finish(),
+            ExtDiagBlock::ModuleStatus(m) => f.debug_tuple("ModuleStatus").field(m).finish(),
         }
     }
 }
@@ -248,7 +363,7 @@ impl<'a> Iterator for ExtDiagBlockIter<'a> {
     type Item = ExtDiagBlock<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let raw_buffer = self.ext_diag.raw_diag_buffer().unwrap();
+        let raw_buffer = self.ext_diag.raw();
         if self.cursor >= raw_buffer.len() {
             return None;
         }
@@ -298,7 +413,16 @@ impl<'a> Iterator for ExtDiagBlockIter<'a> {
                 }
 
                 self.cursor += length;
-                Some(ExtDiagBlock::Device(&remainder[1..length]))
+                if length >= 4 && remainder[1] == 0x82 {
+                    // DP-V1 status block with status type `Module_Status`
+                    Some(ExtDiagBlock::ModuleStatus(ModuleStatusBlock {
+                        slot_number: remainder[2],
+                        specifier: remainder[3],
+                        data: &remainder[4..length],
+                    }))
+                } else {
+                    Some(ExtDiagBlock::Device(&remainder[1..length]))
+                }
             }
             // Reserved
             0b11 => {
@@ -376,6 +500,55 @@ mod tests {
         assert_eq!(blocks.len(), 3);
     }
 
+    #[test]
+    fn test_diag_iter_module_status() {
+        let mut buffer = [0x06, 0x82, 0x00, 0x00, 0b11_00_01_00, 0b00_00_00_10];
+        let ext_diag = ExtendedDiagnostics {
+            length: buffer.len(),
+            buffer: (&mut buffer[..]).into(),
+        };
+
+        let blocks: Vec<ExtDiagBlock> = ext_diag.iter_diag_blocks().collect();
+        assert_eq!(blocks.len(), 1);
+
+        if let ExtDiagBlock::ModuleStatus(m) = &blocks[0] {
+            let modules: Vec<_> = m.iter().collect();
+            assert_eq!(
+                modules,
+                &[
+                    (0, ModuleStatus::Ok),
+                    (1, ModuleStatus::Error),
+                    (2, ModuleStatus::Ok),
+                    (3, ModuleStatus::NoModule),
+                    (4, ModuleStatus::WrongModule),
+                    (5, ModuleStatus::Ok),
+                    (6, ModuleStatus::Ok),
+                    (7, ModuleStatus::Ok),
+                ]
+            );
+        } else {
+            panic!("wrong diag block 0 {:?}", blocks[0]);
+        }
+    }
+
+    #[test]
+    fn test_raw() {
+        let ext_diag = ExtendedDiagnostics::default();
+        assert_eq!(ext_diag.raw(), &[]);
+        assert_eq!(ext_diag.iter_diag_blocks().count(), 0);
+
+        let mut ext_diag = ExtendedDiagnostics::from_buffer(vec![0u8; 8].into());
+        assert_eq!(ext_diag.raw(), &[]);
+        assert!(ext_diag.fill(&[0x44, 0x00, 0x01, 0x00, 0xff, 0x12]));
+        assert_eq!(ext_diag.raw(), &[0x44, 0x00, 0x01, 0x00, 0xff, 0x12]);
+        assert_eq!(ext_diag.raw_diag_buffer(), Some(ext_diag.raw()));
+
+        let mut buffer = [0x81, 0x43, 0x25];
+        let ext_diag = ExtendedDiagnostics::from_raw(&mut buffer[..]);
+        assert_eq!(ext_diag.raw(), &[0x81, 0x43, 0x25]);
+        assert_eq!(ext_diag.iter_diag_blocks().count(), 1);
+    }
+
     #[test]
     fn test_diag_iter_invalid() {
         let mut buffer = [0x44, 0x00, 0x01, 0x00, 0xff, 0x12, 0x34];
@@ -0,0 +1,105 @@
+//! DPV1 acyclic communication (MSAC_C1)
+
+/// DPV1 function number: Read
+pub(crate) const FUNCTION_READ: u8 = 0x5e;
+/// DPV1 function number: Write
+pub(crate) const FUNCTION_WRITE: u8 = 0x5f;
+/// Bit set in the function number of error responses
+const FUNCTION_ERROR: u8 = 0x80;
+
+/// Error from a DPV1 acyclic read or write request
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Dpv1Error {
+    /// The peripheral does not support DPV1 acyclic communication.
+    ///
+    /// This is also reported when DPV1 was not enabled in the parameters of the peripheral.
+    NotSupported,
+    /// The peripheral responded with an error.
+    ///
+    /// The meaning of the error codes depends on `error_decode`.  For `0x80` (DPV1), the error
+    /// codes are defined by the DPV1 specification.  Other values denote a profile-specific error.
+    Response {
+        error_decode: u8,
+        error_code_1: u8,
+        error_code_2: u8,
+    },
+    /// The peripheral sent a response which does not match the request.
+    InvalidResponse,
+}
+
+impl core::fmt::Display for Dpv1Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Dpv1Error::NotSupported => write!(f, "DPV1 is not supported by the peripheral"),
+            Dpv1Error::Response {
+                error_decode,
+                error_code_1,
+                error_code_2,
+            } => write!(
+                f,
+                "DPV1 error response (decode 0x{error_decode:02x}, code 0x{error_code_1:02x} 0x{error_code_2:02x})"
+            ),
+            Dpv1Error::InvalidResponse => write!(f, "invalid DPV1 response"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum Dpv1Step {
+    /// The request needs to be sent
+    Request,
+    /// Waiting for the peripheral to acknowledge the request
+    AwaitAck,
+    /// The peripheral needs to be polled for the response
+    Poll,
+    /// Waiting for the reply to a poll
+    AwaitResponse,
+}
+
+/// DPV1 request which was issued by the application but is not completed yet
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) struct Dpv1Request {
+    pub function: u8,
+    pub slot: u8,
+    pub index: u8,
+    pub length: u8,
+    pub step: Dpv1Step,
+}
+
+impl Dpv1Request {
+    /// Whether a DPV1 telegram was sent and the reply is still outstanding.
+    pub fn is_awaiting_reply(&self) -> bool {
+        matches!(self.step, Dpv1Step::AwaitAck | Dpv1Step::AwaitResponse)
+    }
+
+    /// Parse a response PDU and return the received data.
+    pub fn parse_response<'a>(&self, pdu: &'a [u8]) -> Result<&'a [u8], Dpv1Error> {
+        match *pdu {
+            [function, error_decode, error_code_1, error_code_2, ..]
+                if function == self.function | FUNCTION_ERROR =>
+            {
+                Err(Dpv1Error::Response {
+                    error_decode,
+                    error_code_1,
+                    error_code_2,
+                })
+            }
+            [function, slot, index, length, ref data @ ..]
+                if function == self.function && slot == self.slot && index == self.index =>
+            {
+                let length = usize::from(length);
+                if self.function == FUNCTION_READ
+                    && length <= usize::from(self.length)
+                    && data.len() >= length
+                {
+                    Ok(&data[..length])
+                } else if self.function == FUNCTION_WRITE {
+                    Ok(&[])
+                } else {
+                    Err(Dpv1Error::InvalidResponse)
+                }
+            }
+            _ => Err(Dpv1Error::InvalidResponse),
+        }
+    }
+}
@@ -31,6 +31,31 @@ impl OperatingState {
     }
 }
 
+/// Reaction of the DP master to peripherals going offline
+///
+/// See [`DpMaster::set_fault_policy()`].
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum FaultPolicy {
+    /// Stay in [`Operate`][`OperatingState::Operate`] and leave it to the application to react.
+    #[default]
+    StayInOperate,
+    /// Enter [`Clear`][`OperatingState::Clear`] when any peripheral goes offline.
+    ClearOnAnyOffline,
+    /// Enter [`Clear`][`OperatingState::Clear`] when a
+    /// [critical][`crate::dp::Peripheral::set_critical`] peripheral goes offline.
+    ClearOnCriticalOffline,
+}
+
+/// Operating state change made by the [`FaultPolicy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultEvent {
+    /// The peripheral went offline and the DP master entered [`Clear`][`OperatingState::Clear`].
+    EnteredClear(crate::dp::PeripheralHandle),
+    /// All faulted peripherals exchange data again and the DP master re-entered
+    /// [`Operate`][`OperatingState::Operate`].
+    Recovered,
+}
+
 /// Events from the last poll cycle
 ///
 /// These events have occurred during the last poll cycle and likely need to be handled by the
@@ -43,6 +68,133 @@ pub struct DpEvents {
     ///
     /// The handle of the perpheral is included to identify it.
     pub peripheral: Option<(crate::dp::PeripheralHandle, crate::dp::PeripheralEvent)>,
+    /// A [`DpMaster::set_slave_address()`] request was completed.
+    pub set_slave_address: Option<SetSlaveAddressEvent>,
+    /// The [`FaultPolicy`] changed the operating state.
+    pub fault: Option<FaultEvent>,
+}
+
+/// Summary of the state of a DP master and its peripherals
+///
+/// Returned by [`DpMaster::summary()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DpSummary {
+    /// Operating state of the DP master
+    pub operating_state: OperatingState,
+    /// Number of configured peripherals
+    pub peripherals: usize,
+    /// Number of peripherals which are exchanging data
+    pub running: usize,
+    /// Whether all critical peripherals are exchanging data
+    pub all_critical_running: bool,
+}
+
+/// Outcome of a [`DpMaster::set_slave_address()`] request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetSlaveAddressEvent {
+    /// The peripheral acknowledged its new address.
+    Success { old_address: u8, new_address: u8 },
+    /// No station responded at the current address so the request was not sent.
+    NotLive { address: u8 },
+    /// The peripheral did not acknowledge the request.
+    Failed { address: u8 },
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum SetSlaveAddressStep {
+    /// Check whether the station is live using an FDL status request
+    CheckLive,
+    AwaitLive,
+    /// Send the Set_Slave_Address telegram
+    SetAddress,
+    AwaitConfirmation,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct SetSlaveAddressRequest {
+    current: u8,
+    new: u8,
+    ident: u16,
+    no_add_chg: bool,
+    step: SetSlaveAddressStep,
+}
+
+/// SYNC/FREEZE mode flags affected by a Global Control command, and their new values.
+fn global_control_modes(command: u8) -> (crate::dp::DiagnosticFlags, crate::dp::DiagnosticFlags) {
+    use crate::dp::DiagnosticFlags;
+
+    let mut mask = DiagnosticFlags::empty();
+    let mut modes = DiagnosticFlags::empty();
+    if command & (crate::consts::GC_SYNC | crate::consts::GC_UNSYNC) != 0 {
+        mask |= DiagnosticFlags::SYNC_MODE;
+    }
+    if command & crate::consts::GC_SYNC != 0 {
+        modes |= DiagnosticFlags::SYNC_MODE;
+    }
+    if command & (crate::consts::GC_FREEZE | crate::consts::GC_UNFREEZE) != 0 {
+        mask |= DiagnosticFlags::FREEZE_MODE;
+    }
+    if command & crate::consts::GC_FREEZE != 0 {
+        modes |= DiagnosticFlags::FREEZE_MODE;
+    }
+    (mask, modes)
+}
+
+/// Ongoing output transaction, see [`DpMaster::begin_output_transaction()`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct OutputTransaction {
+    /// Groups of peripherals taking part in the transaction
+    groups: u8,
+    /// Whether the outputs are latched as soon as all peripherals received them
+    committed: bool,
+}
+
+/// Queue of Global Control commands that were requested by the application but not yet sent.
+///
+/// Entries are stored as `(control_command, group_select)`.
+#[derive(Debug, Clone, Default)]
+struct GlobalControlQueue {
+    entries: [(u8, u8); 4],
+    len: usize,
+}
+
+impl GlobalControlQueue {
+    /// Queue a command, returns `false` when the queue is full.
+    fn push(&mut self, command: u8, opposite: u8, groups: u8) -> bool {
+        if let Some((pending, g)) = self.entries[..self.len].last_mut() {
+            // Commands for the same groups can be combined into one telegram, as long as this does
+            // not conflict with the already pending command (e.g. SYNC followed by UNSYNC).
+            if *g == groups && *pending & opposite == 0 {
+                *pending |= command;
+                return true;
+            }
+        }
+        if self.len == self.entries.len() {
+            return false;
+        }
+        self.entries[self.len] = (command, groups);
+        self.len += 1;
+        true
+    }
+
+    fn pop(&mut self) -> Option<(u8, u8)> {
+        if self.len == 0 {
+            return None;
+        }
+        let first = self.entries[0];
+        self.entries.copy_within(1..self.len, 0);
+        self.len -= 1;
+        Some(first)
+    }
+
+    fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Number of commands that can still be queued.
+    fn free(&self) -> usize {
+        self.entries.len() - self.len
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -112,9 +264,33 @@ pub struct DpMasterState {
     /// Last time we sent a "Global Control" telegram to advertise our operating state.
     last_global_control: Option<crate::time::Instant>,
 
+    /// Whether a final Clear_Data "Global Control" telegram must be sent after entering `Stop`.
+    announce_stop: bool,
+
     /// Cycle State, tracking progress of the data exchange cycle
     cycle_state: CycleState,
 
+    /// Global Control commands that were requested by the application and are yet to be sent.
+    pending_global_control: GlobalControlQueue,
+
+    /// Ongoing output transaction
+    output_transaction: Option<OutputTransaction>,
+
+    /// Ongoing Set_Slave_Address request
+    set_slave_address: Option<SetSlaveAddressRequest>,
+
+    /// Outcome of the last Set_Slave_Address request, until it is reported
+    set_slave_address_event: Option<SetSlaveAddressEvent>,
+
+    /// Reaction to peripherals going offline
+    fault_policy: FaultPolicy,
+
+    /// Addresses of the peripherals which made the fault policy enter `Clear`
+    faulted: bitvec::BitArr!(for 128),
+
+    /// Last operating state change by the fault policy, until it is reported
+    fault_event: Option<FaultEvent>,
+
     /// Last set of events that occurred
     last_events: DpEvents,
 
@@ -136,6 +312,14 @@ impl<'a> DpMaster<'a> {
             state: DpMasterState {
                 operating_state: OperatingState::Stop,
                 last_global_control: None,
+                announce_stop: false,
+                pending_global_control: Default::default(),
+                output_transaction: None,
+                set_slave_address: None,
+                set_slave_address_event: None,
+                fault_policy: FaultPolicy::StayInOperate,
+                faulted: bitvec::array::BitArray::ZERO,
+                fault_event: None,
                 cycle_state: CycleState::DataExchange(0),
                 last_events: Default::default(),
                 #[cfg(feature = "debug-measure-dp-cycle")]
@@ -146,36 +330,141 @@ impl<'a> DpMaster<'a> {
 
     /// Add a peripheral to the set, and return its handle.
     ///
+    /// A warning is logged when the lengths of the process images do not match the
+    /// [`io_lengths()`][`crate::dp::PeripheralOptions::io_lengths`] of the peripheral's
+    /// configuration.
+    ///
     /// # Panics
     /// This function panics if the storage is fixed-size (not a `Vec`) and is full.
+    ///
+    /// It also panics if a peripheral with the same address was already added.  Use
+    /// [`ParametersBuilder::build_verified()`][`crate::fdl::ParametersBuilder::build_verified`] to
+    /// check that no peripheral has the address of the FDL active station.
     pub fn add(&mut self, peripheral: Peripheral<'a>) -> crate::dp::PeripheralHandle {
-        self.peripherals.add(peripheral)
+        match self.try_add(peripheral) {
+            Ok(handle) => handle,
+            Err(_) => panic!("Adding peripheral to full PeripheralSet"),
+        }
+    }
+
+    /// Add a peripheral to the set, and return its handle.
+    ///
+    /// Like [`DpMaster::add()`], but returns a [`CapacityError`][`crate::dp::CapacityError`]
+    /// instead of panicking when the storage is fixed-size (not a `Vec`) and is full.  The
+    /// peripheral is dropped in this case.
+    ///
+    /// # Panics
+    /// This function panics if a peripheral with the same address was already added.
+    pub fn try_add(
+        &mut self,
+        peripheral: Peripheral<'a>,
+    ) -> Result<crate::dp::PeripheralHandle, crate::dp::CapacityError> {
+        assert!(
+            self.peripherals
+                .iter()
+                .all(|(_, p)| p.address() != peripheral.address()),
+            "Peripheral #{} was already added to the DP master!",
+            peripheral.address(),
+        );
+        if let Some(wdg) = peripheral.options().watchdog {
+            if let Some(Err(())) = crate::fdl::watchdog_factors(wdg) {
+                log::warn!(
+                    "Watchdog timeout {} of peripheral #{} is out of range, clamping to 10ms..650s!",
+                    wdg,
+                    peripheral.address(),
+                );
+            }
+        }
+        if let Some((inputs, outputs)) = peripheral.options().io_lengths() {
+            if inputs != peripheral.pi_i().len() || outputs != peripheral.pi_q().len() {
+                log::warn!(
+                    "Peripheral #{} expects {} input and {} output bytes but process images have {} and {} bytes!",
+                    peripheral.address(),
+                    inputs,
+                    outputs,
+                    peripheral.pi_i().len(),
+                    peripheral.pi_q().len(),
+                );
+            }
+        }
+        self.peripherals.try_add(peripheral)
+    }
+
+    /// Remove a peripheral from the DP master and return it.
+    ///
+    /// The DP master stops communicating with the peripheral immediately.  Its storage slot is
+    /// freed for reuse and the handle becomes invalid: Calling [`DpMaster::get_mut()`] with it
+    /// will panic.  Returns `None` if the peripheral was already removed.
+    pub fn remove(&mut self, handle: crate::dp::PeripheralHandle) -> Option<Peripheral<'a>> {
+        self.peripherals.remove(handle)
     }
 
     /// Get a peripheral from the set by its handle, as mutable.
     ///
     /// # Panics
-    /// This function may panic if the handle does not belong to this peripheral set.
+    /// This function may panic if the handle does not belong to this peripheral set.  It also
+    /// panics if the peripheral was [removed][`DpMaster::remove`].
     pub fn get_mut(&mut self, handle: crate::dp::PeripheralHandle) -> &mut Peripheral<'a> {
         self.peripherals.get_mut(handle)
     }
 
+    /// Iterate over all peripherals of this DP master, as mutable.
+    ///
+    /// The peripherals are yielded in a stable order which does not change when more peripherals
+    /// are added.  See [`DpMaster::iter()`].
     pub fn iter_mut(
         &mut self,
     ) -> impl Iterator<Item = (crate::dp::PeripheralHandle, &mut Peripheral<'a>)> {
         self.peripherals.iter_mut()
     }
 
+    /// Iterate over all peripherals of this DP master.
+    ///
+    /// Each peripheral is yielded along with its handle.  The iteration order is the order of the
+    /// storage slots.  Adding more peripherals never changes the position of existing ones, so
+    /// the order (and all handles) remain stable as long as no peripheral is removed.
     pub fn iter(&self) -> impl Iterator<Item = (crate::dp::PeripheralHandle, &Peripheral<'a>)> {
         self.peripherals.iter()
     }
 
+    /// Call `f` for each peripheral which exchanged data since the last call.
+    ///
+    /// This is an alternative to checking [`DpEvents::cycle_completed`] and then inspecting every
+    /// peripheral.  Only peripherals which emitted a [`PeripheralEvent::DataExchanged`] are
+    /// passed to `f`, so their process images are known to be current.  Call this after each
+    /// poll.
+    ///
+    /// [`PeripheralEvent::DataExchanged`]: crate::dp::PeripheralEvent::DataExchanged
+    ///
+    /// # Example
+    /// ```
+    /// # let mut dp_master = profirust::dp::DpMaster::new(Vec::new());
+    /// dp_master.on_data_exchanged(|handle, peripheral| {
+    ///     log::debug!("Inputs of #{}: {:?}", peripheral.address(), peripheral.pi_i());
+    ///     peripheral.pi_q_mut().copy_from_slice(&[0x55]);
+    /// });
+    /// ```
+    pub fn on_data_exchanged<F>(&mut self, mut f: F)
+    where
+        F: FnMut(crate::dp::PeripheralHandle, &mut Peripheral<'a>),
+    {
+        for (handle, peripheral) in self.peripherals.iter_mut() {
+            if peripheral.take_data_exchanged() {
+                f(handle, peripheral);
+            }
+        }
+    }
+
     /// Return the last events set once.
     ///
     /// On consecutive calls, an empty events set it returned.  If events are not retrieved using
     /// this function, they may be overridden by newer events on the next poll cycle.
     pub fn take_last_events(&mut self) -> DpEvents {
-        core::mem::take(&mut self.state.last_events)
+        DpEvents {
+            set_slave_address: self.state.set_slave_address_event.take(),
+            fault: self.state.fault_event.take(),
+            ..core::mem::take(&mut self.state.last_events)
+        }
     }
 
     #[inline(always)]
@@ -183,15 +472,27 @@ impl<'a> DpMaster<'a> {
         self.state.operating_state
     }
 
+    /// Enter a new operating state.
+    ///
+    /// The new state is broadcast to all peripherals using a Global Control telegram as soon as
+    /// possible.  When entering [`Stop`][`OperatingState::Stop`], a final Clear_Data command is
+    /// sent before the DP master goes silent.
     #[inline]
     pub fn enter_state(&mut self, state: OperatingState) {
         log::info!("DP master entering state \"{:?}\"", state);
+        let previous = self.state.operating_state;
         self.state.operating_state = state;
+        // An explicit state change ends any fault reaction.
+        self.state.faulted = bitvec::array::BitArray::ZERO;
         // Ensure we will send a new global control telegram ASAP:
         self.state.last_global_control = None;
-
-        if state != OperatingState::Operate {
-            todo!("OperatingState {:?} is not yet supported properly!", state);
+        if state == OperatingState::Stop {
+            // Don't send stale commands when leaving the `Stop` state later on.
+            self.state.pending_global_control.clear();
+            self.state.output_transaction = None;
+            self.state.announce_stop |= previous != OperatingState::Stop;
+        } else {
+            self.state.announce_stop = false;
         }
     }
 
@@ -219,6 +520,316 @@ impl<'a> DpMaster<'a> {
         self.enter_state(OperatingState::Operate)
     }
 
+    /// Summarize the state of the DP master and its peripherals.
+    ///
+    /// Bus level health information is available from
+    /// [`FdlActiveStation::summary()`][`crate::fdl::FdlActiveStation::summary`].
+    pub fn summary(&self) -> DpSummary {
+        DpSummary {
+            operating_state: self.state.operating_state,
+            peripherals: self.peripherals.iter().count(),
+            running: self
+                .peripherals
+                .iter()
+                .filter(|(_, peripheral)| peripheral.is_running())
+                .count(),
+            all_critical_running: self.all_critical_running(),
+        }
+    }
+
+    /// Whether all [critical][`crate::dp::Peripheral::set_critical`] peripherals exchange data.
+    ///
+    /// This is useful for deciding between [`Operate`][`OperatingState::Operate`] and
+    /// [`Clear`][`OperatingState::Clear`] in the application.
+    pub fn all_critical_running(&self) -> bool {
+        self.peripherals
+            .iter()
+            .all(|(_, peripheral)| !peripheral.is_critical() || peripheral.is_running())
+    }
+
+    /// Set how the DP master reacts to peripherals going offline.
+    ///
+    /// With a policy other than [`FaultPolicy::StayInOperate`], the DP master automatically drops
+    /// from [`Operate`][`OperatingState::Operate`] to [`Clear`][`OperatingState::Clear`] when a
+    /// peripheral goes offline.  Once all peripherals which caused this exchange data again, it
+    /// re-enters `Operate`.  Both transitions are reported in [`DpEvents::fault`].
+    ///
+    /// Explicitly entering an operating state ends the fault reaction.
+    pub fn set_fault_policy(&mut self, policy: FaultPolicy) {
+        self.state.fault_policy = policy;
+    }
+
+    /// The current reaction to peripherals going offline.
+    #[inline(always)]
+    pub fn fault_policy(&self) -> FaultPolicy {
+        self.state.fault_policy
+    }
+
+    /// Send a SYNC Global Control command to the selected groups of peripherals.
+    ///
+    /// Peripherals that were parameterized with [`sync_mode`][`crate::dp::PeripheralOptions`]
+    /// and which are members of one of the selected groups will only apply their outputs on each
+    /// SYNC command.  The process image of outputs is still exchanged cyclically, but the
+    /// peripheral latches the data it received last when the next SYNC command arrives.  To
+    /// release the peripherals from SYNC mode again, use
+    /// [`global_control_unsync()`][`DpMaster::global_control_unsync`].
+    ///
+    /// The `group_mask` selects which peripherals react to the command: A peripheral is addressed
+    /// when any bit of its [`groups`][`crate::dp::PeripheralOptions`] is also set in `group_mask`.
+    /// A `group_mask` of `0` addresses all peripherals.
+    ///
+    /// The command is queued and broadcast the next time the DP master gets to transmit.  Up to
+    /// four commands can be pending at once, one of them is sent per transmission.  Returns
+    /// `false` when the queue is full and the command was **not** queued.
+    ///
+    /// No commands are sent while the DP master is in the [`Stop`][`OperatingState::Stop`]
+    /// state.  Entering the `Stop` state discards all pending commands.
+    pub fn global_control_sync(&mut self, group_mask: u8) -> bool {
+        self.queue_global_control(crate::consts::GC_SYNC, crate::consts::GC_UNSYNC, group_mask)
+    }
+
+    /// Send an UNSYNC Global Control command to the selected groups of peripherals.
+    ///
+    /// This ends SYNC mode such that outputs are applied immediately again.  See
+    /// [`global_control_sync()`][`DpMaster::global_control_sync`] for the meaning of
+    /// `group_mask` and the return value.
+    pub fn global_control_unsync(&mut self, group_mask: u8) -> bool {
+        self.queue_global_control(crate::consts::GC_UNSYNC, crate::consts::GC_SYNC, group_mask)
+    }
+
+    /// Send a FREEZE Global Control command to the selected groups of peripherals.
+    ///
+    /// Peripherals that were parameterized with [`freeze_mode`][`crate::dp::PeripheralOptions`]
+    /// sample their inputs on each FREEZE command and keep reporting this frozen state until the
+    /// next FREEZE command.  To release the peripherals from FREEZE mode again, use
+    /// [`global_control_unfreeze()`][`DpMaster::global_control_unfreeze`].
+    ///
+    /// See [`global_control_sync()`][`DpMaster::global_control_sync`] for the meaning of
+    /// `group_mask` and the return value.
+    pub fn global_control_freeze(&mut self, group_mask: u8) -> bool {
+        self.queue_global_control(
+            crate::consts::GC_FREEZE,
+            crate::consts::GC_UNFREEZE,
+            group_mask,
+        )
+    }
+
+    /// Send an UNFREEZE Global Control command to the selected groups of peripherals.
+    ///
+    /// This ends FREEZE mode such that inputs are reported continuously again.  See
+    /// [`global_control_sync()`][`DpMaster::global_control_sync`] for the meaning of
+    /// `group_mask` and the return value.
+    pub fn global_control_unfreeze(&mut self, group_mask: u8) -> bool {
+        self.queue_global_control(
+            crate::consts::GC_UNFREEZE,
+            crate::consts::GC_FREEZE,
+            group_mask,
+        )
+    }
+
+    /// Start a transaction for changing the outputs of several peripherals at the same time.
+    ///
+    /// This sends a SYNC Global Control command to the selected groups (see
+    /// [`global_control_sync()`][`DpMaster::global_control_sync`] for the meaning of
+    /// `group_mask`).  The peripherals then hold their outputs while the application updates the
+    /// process images.  Once all outputs are written, call
+    /// [`commit_output_transaction()`][`DpMaster::commit_output_transaction`] to apply them
+    /// together.  The peripherals must be parameterized with
+    /// [`sync_mode`][`crate::dp::PeripheralOptions`].
+    ///
+    /// Returns `false` when another transaction is still ongoing or the Global Control queue is
+    /// full.  In this case, no transaction was started.
+    pub fn begin_output_transaction(&mut self, group_mask: u8) -> bool {
+        if self.state.output_transaction.is_some()
+            || !self.queue_global_control(
+                crate::consts::GC_SYNC,
+                crate::consts::GC_UNSYNC,
+                group_mask,
+            )
+        {
+            return false;
+        }
+        self.state.output_transaction = Some(OutputTransaction {
+            groups: group_mask,
+            committed: false,
+        });
+        true
+    }
+
+    /// Apply all outputs written since [`begin_output_transaction()`] at the same time.
+    ///
+    /// The DP master waits until each running peripheral of the transaction received its current
+    /// outputs with a data exchange.  It then sends a SYNC command to make all peripherals apply
+    /// the outputs at once, followed by an UNSYNC command to end the transaction.  Use
+    /// [`is_output_transaction_open()`][`DpMaster::is_output_transaction_open`] to find out when
+    /// this has happened.
+    ///
+    /// Returns `false` when no transaction was started or it was already committed.
+    ///
+    /// [`begin_output_transaction()`]: DpMaster::begin_output_transaction
+    pub fn commit_output_transaction(&mut self) -> bool {
+        let Some(transaction) = self.state.output_transaction.as_mut() else {
+            return false;
+        };
+        if transaction.committed {
+            return false;
+        }
+        transaction.committed = true;
+        let groups = transaction.groups;
+        for (_, peripheral) in self.peripherals.iter_mut() {
+            if groups == 0 || peripheral.options().groups & groups != 0 {
+                peripheral.request_output_commit();
+            }
+        }
+        true
+    }
+
+    /// Whether an output transaction was started and its outputs were not applied yet.
+    #[inline]
+    pub fn is_output_transaction_open(&self) -> bool {
+        self.state.output_transaction.is_some()
+    }
+
+    /// Latch the outputs of a committed transaction once all peripherals received them.
+    fn finish_output_transaction(&mut self) {
+        let Some(transaction) = self.state.output_transaction else {
+            return;
+        };
+        if !transaction.committed
+            || self
+                .peripherals
+                .iter()
+                .any(|(_, p)| p.is_running() && p.output_commit_pending())
+        {
+            return;
+        }
+        // SYNC and UNSYNC are never combined into one telegram, so two free entries are needed.
+        if self.state.pending_global_control.free() < 2 {
+            return;
+        }
+        log::debug!(
+            "Applying outputs of transaction for groups 0x{:02x}",
+            transaction.groups
+        );
+        self.queue_global_control(
+            crate::consts::GC_SYNC,
+            crate::consts::GC_UNSYNC,
+            transaction.groups,
+        );
+        self.queue_global_control(
+            crate::consts::GC_UNSYNC,
+            crate::consts::GC_SYNC,
+            transaction.groups,
+        );
+        self.state.output_transaction = None;
+    }
+
+    /// Assign a new address to the peripheral at address `current`.
+    ///
+    /// This sends a Set_Slave_Address telegram (SAP 55) which is only supported by some
+    /// peripherals (check for `Set_Slave_Add_supp` in the GSD file).  `ident` must be the ident
+    /// number of the peripheral.  When `no_add_chg` is `true`, the peripheral will refuse any
+    /// further address changes until it is reset.
+    ///
+    /// Before sending the request, the DP master checks whether a station responds at the
+    /// `current` address.  The outcome is reported in
+    /// [`DpEvents::set_slave_address`][`DpEvents`].  No peripheral at the `current` address
+    /// should be added to the DP master at the same time.
+    ///
+    /// Returns `false` when another request is still ongoing and this one was **not** started.
+    ///
+    /// # Panics
+    /// This function panics if `new` is not a valid peripheral address (0 to 125).
+    pub fn set_slave_address(
+        &mut self,
+        current: u8,
+        new: u8,
+        ident: u16,
+        no_add_chg: bool,
+    ) -> bool {
+        assert!(new <= 125, "invalid peripheral address {new}");
+        crate::debug_assert_address(current);
+        if self.state.set_slave_address.is_some() {
+            return false;
+        }
+        log::info!("Changing address of peripheral #{} to #{}", current, new);
+        self.state.set_slave_address = Some(SetSlaveAddressRequest {
+            current,
+            new,
+            ident,
+            no_add_chg,
+            step: SetSlaveAddressStep::CheckLive,
+        });
+        true
+    }
+
+    fn finish_set_slave_address(&mut self, event: SetSlaveAddressEvent) {
+        match event {
+            SetSlaveAddressEvent::Success { .. } => log::info!("{:?}", event),
+            _ => log::debug!("{:?}", event),
+        }
+        self.state.set_slave_address = None;
+        self.state.set_slave_address_event = Some(event);
+    }
+
+    fn queue_global_control(&mut self, command: u8, opposite: u8, group_mask: u8) -> bool {
+        let queued = self
+            .state
+            .pending_global_control
+            .push(command, opposite, group_mask);
+        if !queued {
+            log::debug!(
+                "Global Control queue is full, dropping command 0x{:02x} for groups 0x{:02x}",
+                command,
+                group_mask
+            );
+        }
+        queued
+    }
+
+    /// React to a peripheral going offline according to the fault policy.
+    fn handle_peripheral_offline(&mut self, handle: crate::dp::PeripheralHandle, critical: bool) {
+        let applies = match self.state.fault_policy {
+            FaultPolicy::StayInOperate => false,
+            FaultPolicy::ClearOnAnyOffline => true,
+            FaultPolicy::ClearOnCriticalOffline => critical,
+        };
+        let faulted = self.state.faulted.any();
+        if !applies || !(self.state.operating_state.is_operate() || faulted) {
+            return;
+        }
+
+        if !faulted {
+            log::warn!(
+                "Peripheral #{} went offline, DP master entering Clear.",
+                handle.address()
+            );
+            self.enter_clear();
+            self.state.fault_event = Some(FaultEvent::EnteredClear(handle));
+        }
+        self.state.faulted.set(usize::from(handle.address()), true);
+    }
+
+    /// Re-enter `Operate` once all faulted peripherals exchange data again.
+    fn check_fault_recovery(&mut self) {
+        if self.state.faulted.not_any() {
+            return;
+        }
+        let recovered = self.state.faulted.iter_ones().all(|address| {
+            self.peripherals
+                .iter()
+                .find(|(handle, _)| usize::from(handle.address()) == address)
+                .map(|(_, peripheral)| peripheral.is_running())
+                // Removed peripherals cannot recover.
+                .unwrap_or(true)
+        });
+        if recovered {
+            log::info!("All faulted peripherals recovered, DP master re-entering Operate.");
+            self.enter_operate();
+            self.state.fault_event = Some(FaultEvent::Recovered);
+        }
+    }
+
     fn increment_cycle_state(&mut self, index: u8, now: crate::time::Instant) -> bool {
         if let Some(next) = self.peripherals.get_next_index(index) {
             self.state.cycle_state = CycleState::DataExchange(next);
@@ -233,11 +844,49 @@ impl<'a> DpMaster<'a> {
             }
 
             self.state.cycle_state = CycleState::CycleCompleted;
+            self.check_fault_recovery();
             true
         }
     }
 }
 
+impl DpMaster<'_> {
+    fn send_global_control(
+        &self,
+        fdl: &crate::fdl::FdlActiveStation,
+        tx: crate::fdl::TelegramTx,
+        command: u8,
+        groups: u8,
+    ) -> crate::fdl::TelegramTxResponse {
+        tx.send_data_telegram(
+            crate::fdl::DataTelegramHeader {
+                da: 0x7f,
+                sa: fdl.address(),
+                dsap: crate::consts::SAP_SLAVE_GLOBAL_CONTROL,
+                ssap: crate::consts::SAP_MASTER_MS0,
+                fc: crate::fdl::FunctionCode::Request {
+                    // TODO: Do we need an FCB for GC telegrams?
+                    fcb: crate::fdl::FrameCountBit::Inactive,
+                    req: crate::fdl::RequestType::SdnLow,
+                },
+            },
+            2,
+            |buf| {
+                buf[0] = command
+                    | match self.state.operating_state {
+                        OperatingState::Clear | OperatingState::Stop => {
+                            crate::consts::GC_CLEAR_DATA
+                        }
+                        OperatingState::Operate => 0x00,
+                    };
+                buf[1] = groups;
+            },
+        )
+    }
+}
+
+crate::fdl::impl_any_application!(DpMaster, DpMaster<'a>);
+
 impl<'a> crate::fdl::FdlApplication for DpMaster<'a> {
     fn transmit_telegram(
         &mut self,
@@ -246,13 +895,47 @@ impl<'a> crate::fdl::FdlApplication for DpMaster<'a> {
         mut tx: crate::fdl::TelegramTx,
         high_prio_only: bool,
     ) -> Option<crate::fdl::TelegramTxResponse> {
-        // In STOP state, never send anything
+        // In STOP state, never send anything except for telling the peripherals to clear their
+        // outputs once.
         if self.state.operating_state.is_stop() {
             // TODO: Is overwriting the last events here the best course of action?
             self.state.last_events = DpEvents::default();
+            if self.state.announce_stop && !high_prio_only {
+                self.state.announce_stop = false;
+                log::trace!("DP master sending global control for state Stop");
+                return Some(self.send_global_control(fdl, tx, 0x00, 0x00));
+            }
             return None;
         }
 
+        // Commands requested by the application take precedence over the periodic global control
+        // telegram.
+        if !high_prio_only {
+            self.finish_output_transaction();
+
+            if let Some((command, groups)) = self.state.pending_global_control.pop() {
+                log::trace!(
+                    "DP master sending global control command 0x{:02x} to groups 0x{:02x}",
+                    command,
+                    groups
+                );
+                // Verify with the next diagnostics that the addressed peripherals entered the
+                // requested modes.
+                let (mask, modes) = global_control_modes(command);
+                for (_, peripheral) in self.peripherals.iter_mut() {
+                    if groups == 0 || peripheral.options().groups & groups != 0 {
+                        peripheral.expect_global_control_modes(mask, modes);
+                    }
+                }
+
+                // The command also advertises our operating state so the periodic telegram can be
+                // postponed.
+                self.state.last_global_control = Some(now);
+                self.state.last_events = DpEvents::default();
+                return Some(self.send_global_control(fdl, tx, command, groups));
+            }
+        }
+
         // First check whether it is time for another global control telegram
         //
         // TODO: 50 Tsl is an arbitrary interval.  Documentation talks about 3 times the watchdog
@@ -271,28 +954,42 @@ impl<'a> crate::fdl::FdlApplication for DpMaster<'a> {
             );
             // TODO: Is overwriting the last events here the best course of action?
             self.state.last_events = DpEvents::default();
-            return Some(tx.send_data_telegram(
-                crate::fdl::DataTelegramHeader {
-                    da: 0x7f,
-                    sa: fdl.parameters().address,
-                    dsap: crate::consts::SAP_SLAVE_GLOBAL_CONTROL,
-                    ssap: crate::consts::SAP_MASTER_MS0,
-                    fc: crate::fdl::FunctionCode::Request {
-                        // TODO: Do we need an FCB for GC telegrams?
-                        fcb: crate::fdl::FrameCountBit::Inactive,
-                        req: crate::fdl::RequestType::SdnLow,
-                    },
-                },
-                2,
-                |buf| {
-                    buf[0] = match self.state.operating_state {
-                        OperatingState::Clear => 0x02,
-                        OperatingState::Operate => 0x00,
-                        OperatingState::Stop => unreachable!(),
-                    };
-                    buf[1] = 0x00;
-                },
-            ));
+            return Some(self.send_global_control(fdl, tx, 0x00, 0x00));
+        }
+
+        if !high_prio_only {
+            if let Some(req) = self.state.set_slave_address.as_mut() {
+                // In the `Await*` steps, the FDL layer asks us to retry the request.
+                match req.step {
+                    SetSlaveAddressStep::CheckLive | SetSlaveAddressStep::AwaitLive => {
+                        req.step = SetSlaveAddressStep::AwaitLive;
+                        self.state.last_events = DpEvents::default();
+                        return Some(tx.send_fdl_status_request(req.current, fdl.address()));
+                    }
+                    SetSlaveAddressStep::SetAddress | SetSlaveAddressStep::AwaitConfirmation => {
+                        req.step = SetSlaveAddressStep::AwaitConfirmation;
+                        let req = *req;
+                        self.state.last_events = DpEvents::default();
+                        return Some(tx.send_data_telegram(
+                            crate::fdl::DataTelegramHeader {
+                                da: req.current,
+                                sa: fdl.address(),
+                                dsap: crate::consts::SAP_SLAVE_SET_ADDRESS,
+                                ssap: crate::consts::SAP_MASTER_MS0,
+                                fc: crate::fdl::FunctionCode::new_srd_low(
+                                    crate::fdl::FrameCountBit::First,
+                                ),
+                            },
+                            4,
+                            |buf| {
+                                buf[0] = req.new;
+                                buf[1..3].copy_from_slice(&req.ident.to_be_bytes());
+                                buf[3] = u8::from(req.no_add_chg);
+                            },
+                        ));
+                    }
+                }
+            }
         }
 
         let mut peripheral_event = None;
@@ -335,6 +1032,10 @@ impl<'a> crate::fdl::FdlApplication for DpMaster<'a> {
                             // there can never be a situation where multiple peripherals go offline
                             // in the same poll cycle.
                             assert!(peripheral_event.is_none());
+                            if event == crate::dp::PeripheralEvent::Offline {
+                                let critical = peripheral.is_critical();
+                                self.handle_peripheral_offline(handle, critical);
+                            }
                             peripheral_event = Some((handle, event));
                         }
 
@@ -348,11 +1049,21 @@ impl<'a> crate::fdl::FdlApplication for DpMaster<'a> {
                             self.state.last_events = DpEvents {
                                 cycle_completed: true,
                                 peripheral: peripheral_event,
+                                ..Default::default()
                             };
                             return None;
                         }
                     }
                 }
+            } else {
+                // There are no (more) peripherals, so the cycle is completed.
+                self.state.cycle_state = CycleState::DataExchange(0);
+                self.state.last_events = DpEvents {
+                    cycle_completed: true,
+                    peripheral: peripheral_event,
+                    ..Default::default()
+                };
+                return None;
             }
         }
     }
@@ -364,25 +1075,69 @@ impl<'a> crate::fdl::FdlApplication for DpMaster<'a> {
         addr: u8,
         telegram: crate::fdl::Telegram,
     ) {
+        if let Some(req) = self.state.set_slave_address.as_mut() {
+            if req.current == addr {
+                match req.step {
+                    SetSlaveAddressStep::AwaitLive => {
+                        // Any response means the station is live.
+                        req.step = SetSlaveAddressStep::SetAddress;
+                        return;
+                    }
+                    SetSlaveAddressStep::AwaitConfirmation => {
+                        let req = *req;
+                        let acknowledged = match telegram {
+                            crate::fdl::Telegram::ShortConfirmation(_) => true,
+                            crate::fdl::Telegram::Data(t) => matches!(
+                                t.is_response(),
+                                Some(
+                                    crate::fdl::ResponseStatus::Ok
+                                        | crate::fdl::ResponseStatus::DataLow
+                                )
+                            ),
+                            crate::fdl::Telegram::Token(_) => false,
+                        };
+                        self.finish_set_slave_address(if acknowledged {
+                            SetSlaveAddressEvent::Success {
+                                old_address: req.current,
+                                new_address: req.new,
+                            }
+                        } else {
+                            SetSlaveAddressEvent::Failed {
+                                address: req.current,
+                            }
+                        });
+                        return;
+                    }
+                    SetSlaveAddressStep::CheckLive | SetSlaveAddressStep::SetAddress => (),
+                }
+            }
+        }
+
         let index = match self.state.cycle_state {
             CycleState::DataExchange(i) => i,
             CycleState::CycleCompleted => {
-                unreachable!("impossible to get a reply when the cycle was completed!");
+                log::debug!(
+                    "Ignoring reply from #{addr} after the cycle was completed: {telegram:?}"
+                );
+                return;
             }
         };
         match self.peripherals.get_at_index_mut(index) {
             Some((handle, peripheral)) if addr == peripheral.address() => {
                 let event = peripheral.receive_reply(now, &self.state, fdl, telegram);
+                if event == Some(crate::dp::PeripheralEvent::DataExchanged) {
+                    peripheral.mark_data_exchanged();
+                }
                 let cycle_completed = self.increment_cycle_state(index, now);
                 self.state.last_events = DpEvents {
                     cycle_completed,
                     peripheral: event.map(|ev| (handle, ev)),
+                    ..Default::default()
                 };
             }
             _ => {
-                unreachable!(
-                    "Received reply for unknown/unexpected peripheral #{addr}: {telegram:?}"
-                );
+                // The peripheral may have been removed while we were waiting for its reply.
+                log::debug!("Ignoring reply from removed peripheral #{addr}: {telegram:?}");
             }
         }
     }
@@ -393,9 +1148,1453 @@ impl<'a> crate::fdl::FdlApplication for DpMaster<'a> {
         fdl: &crate::fdl::FdlActiveStation,
         addr: u8,
     ) {
-        // At this time, there is no meaningful action to take in response to this.  Timeout
-        // handling is actually done as part of the transmit_telegram() code.
-        //
-        // log::warn!("Timeout while waiting for response from #{}!", addr);
+        if let Some(req) = self.state.set_slave_address {
+            if req.current == addr {
+                match req.step {
+                    SetSlaveAddressStep::AwaitLive => {
+                        self.finish_set_slave_address(SetSlaveAddressEvent::NotLive {
+                            address: addr,
+                        });
+                        return;
+                    }
+                    SetSlaveAddressStep::AwaitConfirmation => {
+                        self.finish_set_slave_address(SetSlaveAddressEvent::Failed {
+                            address: addr,
+                        });
+                        return;
+                    }
+                    SetSlaveAddressStep::CheckLive | SetSlaveAddressStep::SetAddress => (),
+                }
+            }
+        }
+
+        let index = match self.state.cycle_state {
+            CycleState::DataExchange(i) => i,
+            CycleState::CycleCompleted => return,
+        };
+        match self.peripherals.get_at_index_mut(index) {
+            Some((handle, peripheral)) if addr == peripheral.address() => {
+                let event = peripheral.handle_timeout(now, fdl);
+                if event == Some(crate::dp::PeripheralEvent::Offline) {
+                    let critical = peripheral.is_critical();
+                    self.handle_peripheral_offline(handle, critical);
+                }
+                // Move on to the next peripheral.
+                let cycle_completed = self.increment_cycle_state(index, now);
+                self.state.last_events = DpEvents {
+                    cycle_completed,
+                    peripheral: event.map(|ev| (handle, ev)),
+                    ..Default::default()
+                };
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::dp::*;
+
+    #[test]
+    fn global_control_sync_freeze() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        dp_master.add(Peripheral::new(
+            8,
+            Default::default(),
+            Vec::new(),
+            Vec::new(),
+        ));
+        let mut slave = FakeSlave {
+            address: 8,
+            ..Default::default()
+        };
+        dp_master.enter_operate();
+
+        // The periodic global control telegram comes first
+        assert_eq!(
+            run_dp_master(&mut dp_master, &mut slave),
+            Some((0x00, 0x00))
+        );
+
+        assert!(dp_master.global_control_sync(0x02));
+        assert_eq!(
+            run_dp_master(&mut dp_master, &mut slave),
+            Some((0x20, 0x02))
+        );
+
+        // Commands for the same groups are combined when they don't conflict
+        assert!(dp_master.global_control_freeze(0x05));
+        assert!(dp_master.global_control_unsync(0x05));
+        assert!(dp_master.global_control_unfreeze(0x05));
+        // Commands for other groups are queued separately
+        assert!(dp_master.global_control_sync(0x01));
+        assert!(dp_master.global_control_sync(0x02));
+        // The queue is full now
+        assert!(!dp_master.global_control_sync(0x04));
+
+        assert_eq!(
+            run_dp_master(&mut dp_master, &mut slave),
+            Some((0x18, 0x05))
+        );
+        assert_eq!(
+            run_dp_master(&mut dp_master, &mut slave),
+            Some((0x04, 0x05))
+        );
+        assert_eq!(
+            run_dp_master(&mut dp_master, &mut slave),
+            Some((0x20, 0x01))
+        );
+        assert_eq!(
+            run_dp_master(&mut dp_master, &mut slave),
+            Some((0x20, 0x02))
+        );
+
+        // Nothing pending anymore and the periodic telegram was postponed by the queued commands.
+        // Regular communication with the peripheral follows.
+        assert_eq!(run_dp_master(&mut dp_master, &mut slave), None);
+    }
+
+    #[test]
+    fn sync_mode_data_exchange() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            ident_number: 0x1337,
+            sync_mode: true,
+            groups: 0x02,
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        dp_master.enter_operate();
+
+        for _ in 0..20 {
+            run_dp_master(&mut dp_master, &mut slave);
+            if dp_master.get_mut(handle).is_running() {
+                break;
+            }
+        }
+        assert!(dp_master.get_mut(handle).is_running());
+
+        // Sync_Req must be set in the station status and the groups are passed along
+        let (station_status, groups) = slave.parameters.unwrap();
+        assert_eq!(station_status & 0x20, 0x20);
+        assert_eq!(groups, 0x02);
+
+        dp_master.get_mut(handle).pi_q_mut()[0] = 0xa5;
+        assert!(dp_master.global_control_sync(0x02));
+        assert_eq!(
+            run_dp_master(&mut dp_master, &mut slave),
+            Some((0x20, 0x02))
+        );
+
+        // Cyclic data exchange continues in SYNC mode and the outputs are sent unchanged.
+        // Latching them is up to the peripheral.
+        let count = slave.data_exchange_count;
+        for _ in 0..4 {
+            assert_eq!(run_dp_master(&mut dp_master, &mut slave), None);
+        }
+        assert!(slave.data_exchange_count > count);
+        assert_eq!(slave.outputs, [0xa5]);
+        assert_eq!(dp_master.get_mut(handle).pi_i(), [0x42]);
+
+        // The peripheral confirmed SYNC mode in its diagnostics
+        assert!(dp_master.get_mut(handle).in_sync_mode());
+        assert!(!dp_master.get_mut(handle).in_freeze_mode());
+
+        assert!(dp_master.global_control_unsync(0x02));
+        assert!(dp_master.global_control_freeze(0x02));
+        run_dp_master_n(&mut dp_master, &mut slave, 4);
+        assert!(!dp_master.get_mut(handle).in_sync_mode());
+        assert!(dp_master.get_mut(handle).in_freeze_mode());
+    }
+
+    #[test]
+    fn global_control_other_group() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            ident_number: 0x1337,
+            freeze_mode: true,
+            groups: 0x02,
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        dp_master.enter_operate();
+
+        for _ in 0..20 {
+            run_dp_master(&mut dp_master, &mut slave);
+            if dp_master.get_mut(handle).is_running() {
+                break;
+            }
+        }
+        assert!(dp_master.get_mut(handle).is_running());
+
+        // A peripheral outside the selected groups ignores the command and the DP master does
+        // not expect it to enter FREEZE mode.
+        assert!(dp_master.global_control_freeze(0x05));
+        assert_eq!(
+            run_dp_master(&mut dp_master, &mut slave),
+            Some((0x08, 0x05))
+        );
+        run_dp_master_n(&mut dp_master, &mut slave, 4);
+        assert!(!slave.freeze_mode);
+        assert!(!dp_master.get_mut(handle).in_freeze_mode());
+
+        // Selecting its group reaches the peripheral.
+        assert!(dp_master.global_control_freeze(0x06));
+        run_dp_master_n(&mut dp_master, &mut slave, 4);
+        assert!(slave.freeze_mode);
+        assert!(dp_master.get_mut(handle).in_freeze_mode());
+    }
+
+    #[test]
+    fn output_transaction() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            sync_mode: true,
+            groups: 0x02,
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        dp_master.enter_operate();
+        run_dp_master_n(&mut dp_master, &mut slave, 20);
+        assert!(dp_master.get_mut(handle).is_running());
+
+        assert!(!dp_master.commit_output_transaction());
+        assert!(dp_master.begin_output_transaction(0x02));
+        assert!(!dp_master.begin_output_transaction(0x02));
+        assert!(dp_master.is_output_transaction_open());
+        assert_eq!(
+            run_dp_master(&mut dp_master, &mut slave),
+            Some((0x20, 0x02))
+        );
+
+        // Outputs are sent to the peripheral but not applied yet
+        dp_master.get_mut(handle).pi_q_mut()[0] = 0xa5;
+        for _ in 0..4 {
+            assert_eq!(run_dp_master(&mut dp_master, &mut slave), None);
+        }
+        assert_eq!(slave.outputs, [0xa5]);
+        assert_eq!(slave.applied_outputs, [0x00]);
+
+        // On commit, the latest outputs are sent before they are applied using SYNC
+        dp_master.get_mut(handle).pi_q_mut()[0] = 0x5a;
+        assert!(dp_master.commit_output_transaction());
+        assert!(!dp_master.commit_output_transaction());
+        let mut commands = Vec::new();
+        for _ in 0..6 {
+            if let Some((command, groups)) = run_dp_master(&mut dp_master, &mut slave) {
+                assert_eq!(groups, 0x02);
+                if commands.is_empty() {
+                    assert_eq!(slave.outputs, [0x5a]);
+                }
+                commands.push(command);
+            }
+        }
+        assert_eq!(commands, [0x20, 0x10]);
+        assert_eq!(slave.applied_outputs, [0x5a]);
+        assert!(!slave.sync_mode);
+        assert!(!dp_master.is_output_transaction_open());
+    }
+
+    #[test]
+    fn sync_mode_not_confirmed() {
+        crate::test_utils::prepare_test_logger_with_warnings(vec![
+            "Peripheral #8 did not confirm SYNC command!",
+        ]);
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            sync_mode: true,
+            groups: 0x02,
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        slave.ignore_global_control = true;
+        dp_master.enter_operate();
+        run_dp_master_n(&mut dp_master, &mut slave, 20);
+        assert!(dp_master.get_mut(handle).is_running());
+
+        // Peripherals of other groups are not expected to react
+        assert!(dp_master.global_control_sync(0x01));
+        let count = slave.diagnostics_count;
+        run_dp_master_n(&mut dp_master, &mut slave, 4);
+        assert_eq!(slave.diagnostics_count, count);
+
+        assert!(dp_master.global_control_sync(0x02));
+        run_dp_master_n(&mut dp_master, &mut slave, 4);
+        assert_eq!(slave.diagnostics_count, count + 1);
+        assert!(!dp_master.get_mut(handle).in_sync_mode());
+        assert!(dp_master.get_mut(handle).is_running());
+    }
+
+    #[test]
+    fn ident_mismatch_blocks_data_exchange() {
+        crate::test_utils::prepare_test_logger_with_warnings(vec![
+            "Peripheral #8 reports ident number 0x4242, expected 0x1337!",
+        ]);
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            ident_number: 0x1337,
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let handle = dp_master.add(Peripheral::new(8, options, vec![0u8; 1], vec![0u8; 1]));
+        let mut slave = FakeSlave {
+            address: 8,
+            ident_number: 0x4242,
+            inputs: vec![0x42],
+            ..Default::default()
+        };
+        dp_master.enter_operate();
+
+        let mut events = vec![];
+        for _ in 0..20 {
+            run_dp_master(&mut dp_master, &mut slave);
+            events.extend(dp_master.take_last_events().peripheral);
+        }
+        assert_eq!(
+            events,
+            [(
+                handle,
+                crate::dp::PeripheralEvent::IdentMismatch {
+                    expected: 0x1337,
+                    got: 0x4242
+                }
+            )]
+        );
+        assert!(!dp_master.get_mut(handle).is_running());
+        assert!(slave.parameters.is_none());
+
+        // Once the ident number matches, the peripheral is brought up as usual
+        slave.ident_number = 0x1337;
+        run_dp_master_n(&mut dp_master, &mut slave, 20);
+        assert!(dp_master.get_mut(handle).is_running());
+    }
+
+    #[test]
+    fn missing_configuration() {
+        crate::test_utils::prepare_test_logger_with_warnings(vec![
+            "Peripheral #8 has no config and will not enter data exchange!",
+        ]);
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            ident_number: 0x1337,
+            user_parameters: Some(&[]),
+            config: None,
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        dp_master.enter_operate();
+
+        let mut events = vec![];
+        for _ in 0..20 {
+            run_dp_master(&mut dp_master, &mut slave);
+            events.extend(dp_master.take_last_events().peripheral);
+        }
+        assert_eq!(
+            events,
+            [
+                (handle, crate::dp::PeripheralEvent::Online),
+                (handle, crate::dp::PeripheralEvent::MissingConfiguration),
+            ]
+        );
+        assert!(!dp_master.get_mut(handle).is_running());
+        assert_eq!(
+            dp_master.get_mut(handle).lifecycle(),
+            crate::dp::PeripheralLifecycle::Discovered
+        );
+        assert!(slave.parameters.is_some());
+    }
+
+    #[test]
+    fn peripheral_lifecycle() {
+        crate::test_utils::prepare_test_logger_with_warnings(vec![
+            "Peripheral #8 stopped responding!",
+        ]);
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            ident_number: 0x1337,
+            user_parameters: Some(&[0x01]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        dp_master.enter_operate();
+
+        let mut lifecycles = vec![dp_master.get_mut(handle).lifecycle()];
+        for _ in 0..20 {
+            run_dp_master(&mut dp_master, &mut slave);
+            let lifecycle = dp_master.get_mut(handle).lifecycle();
+            if lifecycles.last() != Some(&lifecycle) {
+                lifecycles.push(lifecycle);
+            }
+        }
+        assert_eq!(
+            lifecycles,
+            [
+                crate::dp::PeripheralLifecycle::Offline,
+                crate::dp::PeripheralLifecycle::Parameterizing,
+                crate::dp::PeripheralLifecycle::Configuring,
+                crate::dp::PeripheralLifecycle::Running,
+            ]
+        );
+
+        slave.offline = true;
+        run_dp_master_n(&mut dp_master, &mut slave, 20);
+        assert_eq!(
+            dp_master.get_mut(handle).lifecycle(),
+            crate::dp::PeripheralLifecycle::Offline
+        );
+    }
+
+    #[test]
+    fn fault_policy() {
+        crate::test_utils::prepare_test_logger_with_warnings(vec![
+            "Peripheral #8 stopped responding!",
+            "Peripheral #8 went offline, DP master entering Clear.",
+        ]);
+        for critical in [false, true] {
+            let mut dp_master = DpMaster::new(Vec::new());
+            dp_master.set_fault_policy(FaultPolicy::ClearOnCriticalOffline);
+            let options = crate::dp::PeripheralOptions {
+                user_parameters: Some(&[]),
+                config: Some(&[0x10, 0x20]),
+                ..Default::default()
+            };
+            let handle = dp_master.add(Peripheral::new(8, options, vec![0u8; 1], vec![0u8; 1]));
+            // Peripherals are critical by default
+            assert!(dp_master.get_mut(handle).is_critical());
+            if !critical {
+                dp_master.get_mut(handle).set_critical(false);
+            }
+            let mut slave = FakeSlave {
+                address: 8,
+                inputs: vec![0x42],
+                ..Default::default()
+            };
+            dp_master.enter_operate();
+            dp_master.get_mut(handle).pi_q_mut()[0] = 0x55;
+            run_dp_master_n(&mut dp_master, &mut slave, 20);
+            assert!(dp_master.get_mut(handle).is_running());
+            assert!(dp_master.all_critical_running());
+            assert_eq!(slave.outputs, &[0x55]);
+            assert_eq!(
+                dp_master.summary(),
+                DpSummary {
+                    operating_state: OperatingState::Operate,
+                    peripherals: 1,
+                    running: 1,
+                    all_critical_running: true,
+                }
+            );
+
+            let mut events = vec![];
+            slave.offline = true;
+            for _ in 0..20 {
+                run_dp_master(&mut dp_master, &mut slave);
+                events.extend(dp_master.take_last_events().fault);
+            }
+            assert!(!dp_master.get_mut(handle).is_running());
+            assert_eq!(dp_master.all_critical_running(), !critical);
+
+            if !critical {
+                assert_eq!(dp_master.operating_state(), OperatingState::Operate);
+                assert_eq!(events, &[]);
+                continue;
+            }
+            assert_eq!(dp_master.operating_state(), OperatingState::Clear);
+            assert_eq!(events, &[FaultEvent::EnteredClear(handle)]);
+            assert_eq!(
+                dp_master.summary(),
+                DpSummary {
+                    operating_state: OperatingState::Clear,
+                    peripherals: 1,
+                    running: 0,
+                    all_critical_running: false,
+                }
+            );
+
+            // Once the peripheral is back, the DP master re-enters Operate.
+            events.clear();
+            slave.offline = false;
+            for _ in 0..20 {
+                run_dp_master(&mut dp_master, &mut slave);
+                events.extend(dp_master.take_last_events().fault);
+            }
+            assert!(dp_master.get_mut(handle).is_running());
+            assert_eq!(dp_master.operating_state(), OperatingState::Operate);
+            assert_eq!(events, &[FaultEvent::Recovered]);
+            assert_eq!(slave.outputs, &[0x55]);
+        }
+    }
+
+    #[test]
+    fn set_user_parameters() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            ident_number: 0x1337,
+            user_parameters: Some(&[0x01]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        dp_master.enter_operate();
+
+        run_dp_master_n(&mut dp_master, &mut slave, 20);
+        assert!(dp_master.get_mut(handle).is_running());
+        assert_eq!(slave.user_parameters, [0x01]);
+        dp_master.take_last_events();
+
+        dp_master.get_mut(handle).pi_q_mut()[0] = 0x55;
+        dp_master.get_mut(handle).set_user_parameters(&[0x02]);
+        let mut events = vec![];
+        for _ in 0..20 {
+            run_dp_master(&mut dp_master, &mut slave);
+            events.extend(
+                dp_master
+                    .take_last_events()
+                    .peripheral
+                    .filter(|(_, e)| *e != crate::dp::PeripheralEvent::DataExchanged),
+            );
+        }
+        assert_eq!(
+            events,
+            [
+                (handle, crate::dp::PeripheralEvent::Reparameterizing),
+                (handle, crate::dp::PeripheralEvent::Configured),
+            ]
+        );
+        assert_eq!(slave.set_prm_count, 2);
+        assert_eq!(slave.user_parameters, [0x02]);
+
+        // Data exchange continues with the same process images
+        let peripheral = dp_master.get_mut(handle);
+        assert!(peripheral.is_running());
+        assert_eq!(peripheral.pi_i(), [0x42]);
+        assert_eq!(peripheral.pi_q(), [0x55]);
+        assert_eq!(slave.outputs, [0x55]);
+    }
+
+    #[test]
+    fn inputs_updated_in_clear() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let handle = dp_master.add(Peripheral::new(8, options, vec![0u8; 1], vec![0xffu8; 1]));
+        let mut slave = FakeSlave {
+            address: 8,
+            inputs: vec![0x42],
+            ..Default::default()
+        };
+        dp_master.enter_clear();
+        assert_eq!(dp_master.get_mut(handle).last_data_exchange(), None);
+
+        let mut now = crate::time::Instant::ZERO;
+        for _ in 0..20 {
+            now += crate::time::Duration::from_millis(1);
+            run_dp_master_at(&mut dp_master, &mut slave, now);
+        }
+        let peripheral = dp_master.get_mut(handle);
+        assert!(peripheral.is_running());
+        assert_eq!(peripheral.last_data_exchange(), Some(now));
+        assert_eq!(peripheral.pi_i(), [0x42]);
+        // Outputs are not written in the Clear state
+        assert_eq!(slave.outputs, [0x00]);
+    }
+
+    #[test]
+    fn operating_state_global_control() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+
+        // Nothing is sent before leaving the initial `Stop` state
+        assert_eq!(run_dp_master(&mut dp_master, &mut slave), None);
+        assert_eq!(slave.diagnostics_count, 0);
+
+        dp_master.enter_operate();
+        assert_eq!(
+            run_dp_master(&mut dp_master, &mut slave),
+            Some((0x00, 0x00))
+        );
+        run_dp_master_n(&mut dp_master, &mut slave, 20);
+        assert!(dp_master.get_mut(handle).is_running());
+
+        dp_master.enter_clear();
+        assert_eq!(
+            run_dp_master(&mut dp_master, &mut slave),
+            Some((crate::consts::GC_CLEAR_DATA, 0x00))
+        );
+
+        // Pending commands are discarded and a final Clear_Data command is sent on `Stop`
+        assert!(dp_master.global_control_sync(0x01));
+        dp_master.enter_stop();
+        assert_eq!(
+            run_dp_master(&mut dp_master, &mut slave),
+            Some((crate::consts::GC_CLEAR_DATA, 0x00))
+        );
+        let count = slave.data_exchange_count;
+        for _ in 0..4 {
+            assert_eq!(run_dp_master(&mut dp_master, &mut slave), None);
+        }
+        assert_eq!(slave.data_exchange_count, count);
+    }
+
+    #[test]
+    fn fail_safe_clear() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            fail_safe: true,
+            ..Default::default()
+        };
+        let handle = dp_master.add(Peripheral::new(8, options, vec![0u8; 1], vec![0xffu8; 1]));
+        let mut slave = FakeSlave {
+            address: 8,
+            inputs: vec![0x42],
+            ..Default::default()
+        };
+        dp_master.enter_operate();
+        run_dp_master_n(&mut dp_master, &mut slave, 20);
+        assert!(dp_master.get_mut(handle).is_running());
+        assert_eq!(slave.outputs, [0xff]);
+
+        dp_master.enter_clear();
+        run_dp_master_n(&mut dp_master, &mut slave, 4);
+        // Fail-safe peripherals get no output data at all in the Clear state
+        assert_eq!(slave.outputs, []);
+        assert_eq!(dp_master.get_mut(handle).pi_i(), [0x42]);
+    }
+
+    #[test]
+    fn min_slave_interval() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            min_slave_interval: crate::time::Duration::from_millis(10),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        dp_master.enter_operate();
+        run_dp_master_n(&mut dp_master, &mut slave, 20);
+        assert!(dp_master.get_mut(handle).is_running());
+        assert_eq!(slave.data_exchange_count, 1);
+
+        // Before the interval has passed, the peripheral is skipped
+        for _ in 0..20 {
+            run_dp_master_at(
+                &mut dp_master,
+                &mut slave,
+                crate::time::Instant::from_millis(9),
+            );
+        }
+        assert_eq!(slave.data_exchange_count, 1);
+
+        // Afterwards, exactly one more data exchange cycle happens
+        for _ in 0..20 {
+            run_dp_master_at(
+                &mut dp_master,
+                &mut slave,
+                crate::time::Instant::from_millis(10),
+            );
+        }
+        assert_eq!(slave.data_exchange_count, 2);
+        assert!(dp_master.get_mut(handle).is_running());
+    }
+
+    #[test]
+    fn soft_retry_window() {
+        crate::test_utils::prepare_test_logger_with_warnings(vec![
+            "Resending a telegram to #8...",
+            "Peripheral #8 did not respond, tolerating intermittent failure.",
+            "Peripheral #8 stopped responding!",
+        ]);
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        let fdl = crate::fdl::FdlActiveStation::new(
+            crate::fdl::ParametersBuilder::new(2, crate::Baudrate::B19200)
+                .soft_retry_window(crate::time::Duration::from_secs(1))
+                .build(),
+        );
+        let run_at = |dp_master: &mut DpMaster, slave: &mut FakeSlave, millis| {
+            for _ in 0..20 {
+                run_dp_master_with_fdl(
+                    dp_master,
+                    slave,
+                    crate::time::Instant::from_millis(millis),
+                    &fdl,
+                );
+            }
+        };
+        dp_master.enter_operate();
+        run_at(&mut dp_master, &mut slave, 0);
+        assert!(dp_master.get_mut(handle).is_running());
+
+        // A single failure is tolerated
+        slave.offline = true;
+        for _ in 0..3 {
+            run_dp_master_with_fdl(
+                &mut dp_master,
+                &mut slave,
+                crate::time::Instant::from_millis(100),
+                &fdl,
+            );
+        }
+        slave.offline = false;
+        run_at(&mut dp_master, &mut slave, 200);
+        assert!(dp_master.get_mut(handle).is_running());
+        assert_eq!(slave.set_prm_count, 1);
+
+        // A second failure within the window makes the peripheral go offline
+        slave.offline = true;
+        run_at(&mut dp_master, &mut slave, 500);
+        assert!(!dp_master.get_mut(handle).is_running());
+        slave.offline = false;
+        run_at(&mut dp_master, &mut slave, 600);
+        assert!(dp_master.get_mut(handle).is_running());
+        assert_eq!(slave.set_prm_count, 2);
+
+        // After the window has passed, a failure is tolerated again
+        slave.offline = true;
+        for _ in 0..3 {
+            run_dp_master_with_fdl(
+                &mut dp_master,
+                &mut slave,
+                crate::time::Instant::from_millis(1600),
+                &fdl,
+            );
+        }
+        slave.offline = false;
+        run_at(&mut dp_master, &mut slave, 1700);
+        assert!(dp_master.get_mut(handle).is_running());
+        assert_eq!(slave.set_prm_count, 2);
+    }
+
+    #[test]
+    fn peripheral_cycle_time() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        dp_master.enter_operate();
+        run_dp_master_n(&mut dp_master, &mut slave, 20);
+        assert!(dp_master.get_mut(handle).is_running());
+        // All telegrams were answered immediately in the loop above
+        assert_eq!(
+            dp_master.get_mut(handle).last_cycle_time(),
+            Some(crate::time::Duration::ZERO)
+        );
+
+        // Skip over the end of the current cycle and any Global Control telegrams
+        let fdl = crate::fdl::FdlActiveStation::new(Default::default());
+        let mut buf = [0u8; 256];
+        let sent = crate::time::Instant::from_millis(100);
+        let telegram = loop {
+            let sent_telegram = crate::fdl::FdlApplication::transmit_telegram(
+                &mut dp_master,
+                sent,
+                &fdl,
+                crate::fdl::TelegramTx::new(&mut buf),
+                false,
+            );
+            if sent_telegram.is_none() {
+                continue;
+            }
+            match crate::fdl::Telegram::deserialize(&buf) {
+                Some(Ok((crate::fdl::Telegram::Data(t), _)))
+                    if t.h.dsap == crate::consts::SAP_SLAVE_DATA_EXCHANGE =>
+                {
+                    break t
+                }
+                Some(Ok((crate::fdl::Telegram::Data(t), _)))
+                    if t.h.dsap == crate::consts::SAP_SLAVE_GLOBAL_CONTROL => {}
+                t => panic!("DP master sent unexpected telegram: {t:?}"),
+            }
+        };
+        let mut pdu_buffer = [0u8; 256];
+        let reply = slave.respond(&telegram, &mut pdu_buffer);
+        crate::fdl::FdlApplication::receive_reply(
+            &mut dp_master,
+            sent + crate::time::Duration::from_micros(1500),
+            &fdl,
+            8,
+            reply,
+        );
+
+        assert_eq!(
+            dp_master.get_mut(handle).last_cycle_time(),
+            Some(crate::time::Duration::from_micros(1500))
+        );
+    }
+
+    #[test]
+    fn dpv1_read_write() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let handle = dp_master.add(
+            Peripheral::new(8, options, vec![0u8; 1], vec![0u8; 1]).with_dpv1_buffer(vec![0u8; 8]),
+        );
+        let mut slave = FakeSlave {
+            address: 8,
+            inputs: vec![0x42],
+            ..Default::default()
+        };
+        dp_master.enter_operate();
+        run_dp_master_n(&mut dp_master, &mut slave, 20);
+        assert!(dp_master.get_mut(handle).is_running());
+
+        let wait_for_response = |dp_master: &mut DpMaster, slave: &mut FakeSlave| {
+            for _ in 0..20 {
+                run_dp_master(dp_master, slave);
+                if let Some((h, crate::dp::PeripheralEvent::Dpv1Response)) =
+                    dp_master.take_last_events().peripheral
+                {
+                    assert_eq!(h, handle);
+                    return;
+                }
+            }
+            panic!("No DPV1 response received!");
+        };
+
+        let peripheral = dp_master.get_mut(handle);
+        assert!(peripheral.dpv1_write(1, 2, &[0xde, 0xad, 0xbe, 0xef]));
+        // Only one request can be pending at a time
+        assert!(!peripheral.dpv1_read(1, 2, 4));
+        assert_eq!(peripheral.dpv1_response(), None);
+        let count = slave.data_exchange_count;
+        wait_for_response(&mut dp_master, &mut slave);
+        assert_eq!(dp_master.get_mut(handle).dpv1_response(), Some(Ok(&[][..])));
+        assert_eq!(slave.dpv1_record, [0xde, 0xad, 0xbe, 0xef]);
+        // Cyclic data exchange continues while the request is pending
+        assert!(slave.data_exchange_count > count);
+
+        assert!(dp_master.get_mut(handle).dpv1_read(1, 2, 3));
+        wait_for_response(&mut dp_master, &mut slave);
+        assert_eq!(
+            dp_master.get_mut(handle).dpv1_response(),
+            Some(Ok(&[0xde, 0xad, 0xbe][..]))
+        );
+
+        assert!(dp_master.get_mut(handle).dpv1_read(1, 3, 4));
+        wait_for_response(&mut dp_master, &mut slave);
+        assert_eq!(
+            dp_master.get_mut(handle).dpv1_response(),
+            Some(Err(crate::dp::Dpv1Error::Response {
+                error_decode: 0x80,
+                error_code_1: 0xb0,
+                error_code_2: 0x00,
+            }))
+        );
+    }
+
+    #[test]
+    fn on_data_exchanged() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = || crate::dp::PeripheralOptions {
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let handle = dp_master.add(Peripheral::new(8, options(), vec![0u8; 1], vec![0u8; 1]));
+        // A peripheral which never responds
+        dp_master.add(Peripheral::new(9, options(), vec![0u8; 1], vec![0u8; 1]));
+        let mut slave = FakeSlave {
+            address: 8,
+            inputs: vec![0x42],
+            ..Default::default()
+        };
+        dp_master.enter_operate();
+        run_dp_master_n(&mut dp_master, &mut slave, 20);
+
+        let mut exchanged = Vec::new();
+        dp_master.on_data_exchanged(|h, peripheral| {
+            assert_eq!(peripheral.pi_i(), [0x42]);
+            peripheral.pi_q_mut()[0] = 0x55;
+            exchanged.push(h);
+        });
+        assert_eq!(exchanged, [handle]);
+
+        // Nothing new without another data exchange
+        dp_master.on_data_exchanged(|_, _| panic!("no data was exchanged"));
+
+        run_dp_master_n(&mut dp_master, &mut slave, 4);
+        assert_eq!(slave.outputs, [0x55]);
+        let mut count = 0;
+        dp_master.on_data_exchanged(|h, _| {
+            assert_eq!(h, handle);
+            count += 1;
+        });
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn config_readback() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let handle = dp_master.add(
+            Peripheral::new(8, options, vec![0u8; 1], vec![0u8; 1])
+                .with_config_readback_buffer(vec![0u8; 4]),
+        );
+        let mut slave = FakeSlave {
+            address: 8,
+            inputs: vec![0x42],
+            ..Default::default()
+        };
+        dp_master.enter_operate();
+        run_dp_master_n(&mut dp_master, &mut slave, 20);
+        assert!(dp_master.get_mut(handle).is_running());
+        assert_eq!(dp_master.get_mut(handle).config_readback(), None);
+
+        // The peripheral now reports a different module population
+        slave.config = vec![0x10, 0x20, 0x30];
+        dp_master.get_mut(handle).request_config_readback();
+        let mut received = false;
+        for _ in 0..20 {
+            run_dp_master(&mut dp_master, &mut slave);
+            if let Some((h, crate::dp::PeripheralEvent::ConfigReadback)) =
+                dp_master.take_last_events().peripheral
+            {
+                assert_eq!(h, handle);
+                received = true;
+                break;
+            }
+        }
+        assert!(received, "No config readback received!");
+        assert_eq!(
+            dp_master.get_mut(handle).config_readback(),
+            Some(&[0x10, 0x20, 0x30][..])
+        );
+
+        // Data exchange continues afterwards
+        let count = slave.data_exchange_count;
+        run_dp_master(&mut dp_master, &mut slave);
+        run_dp_master(&mut dp_master, &mut slave);
+        assert!(slave.data_exchange_count > count);
+        assert_eq!(dp_master.get_mut(handle).pi_i(), &[0x42]);
+    }
+
+    #[test]
+    fn config_readback_without_buffer() {
+        crate::test_utils::prepare_test_logger_with_warnings(vec![
+            "Peripheral #8 has no config readback buffer, ignoring readback request!",
+        ]);
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        dp_master.enter_operate();
+        run_dp_master_n(&mut dp_master, &mut slave, 20);
+        assert!(dp_master.get_mut(handle).is_running());
+
+        dp_master.get_mut(handle).request_config_readback();
+        for _ in 0..20 {
+            run_dp_master(&mut dp_master, &mut slave);
+            assert_ne!(
+                dp_master.take_last_events().peripheral,
+                Some((handle, crate::dp::PeripheralEvent::ConfigReadback))
+            );
+        }
+        assert_eq!(dp_master.get_mut(handle).config_readback(), None);
+    }
+
+    #[test]
+    fn peripheral_debug_state() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        dp_master.enter_operate();
+
+        let debug = dp_master.get_mut(handle).debug_state();
+        assert_eq!(debug.state, crate::dp::PeripheralState::Offline);
+        assert_eq!(debug.fcb, crate::fdl::FrameCountBit::First);
+
+        run_dp_master_n(&mut dp_master, &mut slave, 20);
+        let debug = dp_master.get_mut(handle).debug_state();
+        assert_eq!(debug.state, crate::dp::PeripheralState::DataExchange);
+        assert_eq!(debug.retry_count, 0);
+
+        // The FCB toggles with each acknowledged telegram
+        let fcb = debug.fcb;
+        while dp_master.get_mut(handle).debug_state().fcb == fcb {
+            run_dp_master(&mut dp_master, &mut slave);
+        }
+        assert_ne!(fcb, crate::fdl::FrameCountBit::First);
+    }
+
+    #[test]
+    fn set_slave_address() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let mut slave = FakeSlave {
+            address: 126,
+            ident_number: 0x1337,
+            ..Default::default()
+        };
+        dp_master.enter_operate();
+        // Skip the periodic global control telegram
+        assert!(run_dp_master(&mut dp_master, &mut slave).is_some());
+
+        assert!(dp_master.set_slave_address(126, 12, 0x1337, false));
+        // Only one request at a time
+        assert!(!dp_master.set_slave_address(126, 13, 0x1337, false));
+
+        run_dp_master(&mut dp_master, &mut slave);
+        assert_eq!(dp_master.take_last_events().set_slave_address, None);
+        run_dp_master(&mut dp_master, &mut slave);
+        assert_eq!(
+            dp_master.take_last_events().set_slave_address,
+            Some(SetSlaveAddressEvent::Success {
+                old_address: 126,
+                new_address: 12
+            })
+        );
+        assert_eq!(slave.address, 12);
+
+        // Now the old address is not live anymore
+        assert!(dp_master.set_slave_address(126, 13, 0x1337, false));
+        run_dp_master(&mut dp_master, &mut slave);
+        assert_eq!(
+            dp_master.take_last_events().set_slave_address,
+            Some(SetSlaveAddressEvent::NotLive { address: 126 })
+        );
+        assert_eq!(slave.address, 12);
+
+        // Regular operation continues without any peripherals
+        assert_eq!(run_dp_master(&mut dp_master, &mut slave), None);
+        assert!(dp_master.take_last_events().cycle_completed);
+    }
+
+    #[test]
+    fn set_slave_address_retry() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let mut slave = FakeSlave {
+            address: 126,
+            ident_number: 0x1337,
+            ..Default::default()
+        };
+        dp_master.enter_operate();
+        // Skip the periodic global control telegram
+        assert!(run_dp_master(&mut dp_master, &mut slave).is_some());
+
+        assert!(dp_master.set_slave_address(126, 12, 0x1337, false));
+        run_dp_master(&mut dp_master, &mut slave);
+
+        // The first Set_Slave_Add telegram goes unanswered and the FDL layer asks for a retry
+        let fdl = crate::fdl::FdlActiveStation::new(Default::default());
+        let mut buf = [0u8; 256];
+        crate::fdl::FdlApplication::transmit_telegram(
+            &mut dp_master,
+            crate::time::Instant::ZERO,
+            &fdl,
+            crate::fdl::TelegramTx::new(&mut buf),
+            false,
+        )
+        .unwrap();
+        assert_eq!(dp_master.take_last_events().set_slave_address, None);
+
+        run_dp_master(&mut dp_master, &mut slave);
+        assert_eq!(
+            dp_master.take_last_events().set_slave_address,
+            Some(SetSlaveAddressEvent::Success {
+                old_address: 126,
+                new_address: 12
+            })
+        );
+        assert_eq!(slave.address, 12);
+    }
+
+    #[test]
+    fn peripheral_watchdog() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            ident_number: 0x1337,
+            watchdog: Some(crate::time::Duration::from_secs(2)),
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        dp_master.enter_operate();
+
+        for _ in 0..20 {
+            run_dp_master(&mut dp_master, &mut slave);
+            if dp_master.get_mut(handle).is_running() {
+                break;
+            }
+        }
+        assert!(dp_master.get_mut(handle).is_running());
+
+        // The FDL parameters have no watchdog but the peripheral override enables it
+        let (station_status, _) = slave.parameters.unwrap();
+        assert_eq!(station_status & 0x08, 0x08);
+        assert_eq!(slave.watchdog_factors, (1, 200));
+    }
+
+    #[test]
+    fn peripheral_min_tsdr() {
+        crate::test_utils::prepare_test_logger();
+        for (min_tsdr_bits, expected) in [(None, 11), (Some(42), 42)] {
+            let mut dp_master = DpMaster::new(Vec::new());
+            let options = crate::dp::PeripheralOptions {
+                ident_number: 0x1337,
+                min_tsdr_bits,
+                user_parameters: Some(&[]),
+                config: Some(&[0x10, 0x20]),
+                ..Default::default()
+            };
+            let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+            dp_master.enter_operate();
+
+            for _ in 0..20 {
+                run_dp_master(&mut dp_master, &mut slave);
+                if dp_master.get_mut(handle).is_running() {
+                    break;
+                }
+            }
+            assert!(dp_master.get_mut(handle).is_running());
+            assert_eq!(slave.min_tsdr_bits, expected);
+        }
+    }
+
+    #[test]
+    fn peripheral_watchdog_out_of_range() {
+        crate::test_utils::prepare_test_logger_with_warnings(vec![
+            "Watchdog timeout 1000.000s of peripheral #8 is out of range, clamping to 10ms..650s!",
+        ]);
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            ident_number: 0x1337,
+            watchdog: Some(crate::time::Duration::from_secs(1000)),
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        dp_master.enter_operate();
+
+        run_dp_master_n(&mut dp_master, &mut slave, 20);
+        assert!(dp_master.get_mut(handle).is_running());
+        assert_eq!(slave.watchdog_factors, (255, 255));
+    }
+
+    #[test]
+    fn process_image_length_mismatch() {
+        crate::test_utils::prepare_test_logger_with_warnings(vec![
+            "Peripheral #8 expects 3 input and 1 output bytes but process images have 2 and 1 bytes!",
+        ]);
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            config: Some(&[0x11, 0x20]),
+            ..Default::default()
+        };
+        dp_master.add(Peripheral::new(8, options, vec![0u8; 2], vec![0u8; 1]));
+    }
+
+    #[test]
+    fn remove_peripheral() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            ident_number: 0x1337,
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        dp_master.enter_operate();
+
+        for _ in 0..20 {
+            run_dp_master(&mut dp_master, &mut slave);
+            if dp_master.get_mut(handle).is_running() {
+                break;
+            }
+        }
+        assert!(dp_master.get_mut(handle).is_running());
+
+        let peripheral = dp_master.remove(handle).unwrap();
+        assert_eq!(peripheral.address(), 8);
+        assert!(dp_master.remove(handle).is_none());
+        assert_eq!(dp_master.iter().count(), 0);
+
+        // No more communication with the removed peripheral
+        let count = slave.data_exchange_count;
+        for _ in 0..5 {
+            assert_eq!(run_dp_master(&mut dp_master, &mut slave), None);
+        }
+        assert_eq!(slave.data_exchange_count, count);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            dp_master.get_mut(handle);
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn try_add_full_storage() {
+        crate::test_utils::prepare_test_logger();
+        let mut storage: [crate::dp::PeripheralStorage; 2] = Default::default();
+        let mut dp_master = DpMaster::new(&mut storage[..]);
+        let peripheral = |address| {
+            Peripheral::new(
+                address,
+                crate::dp::PeripheralOptions {
+                    ident_number: 0x1337,
+                    ..Default::default()
+                },
+                &mut [][..],
+                &mut [][..],
+            )
+        };
+
+        let handle = dp_master.try_add(peripheral(8)).unwrap();
+        dp_master.try_add(peripheral(9)).unwrap();
+        assert_eq!(
+            dp_master.try_add(peripheral(10)),
+            Err(crate::dp::CapacityError { capacity: 2 })
+        );
+        assert_eq!(dp_master.iter().count(), 2);
+
+        // Removing a peripheral frees its slot again
+        dp_master.remove(handle).unwrap();
+        assert_eq!(dp_master.try_add(peripheral(10)).unwrap().address(), 10);
+    }
+
+    #[test]
+    fn periodic_diagnostics() {
+        crate::test_utils::prepare_test_logger();
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            ident_number: 0x1337,
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        dp_master.enter_operate();
+
+        for _ in 0..20 {
+            run_dp_master(&mut dp_master, &mut slave);
+            if dp_master.get_mut(handle).is_running() {
+                break;
+            }
+        }
+        assert!(dp_master.get_mut(handle).is_running());
+
+        // Without an interval, no more diagnostics are requested
+        let count = slave.diagnostics_count;
+        for _ in 0..10 {
+            run_dp_master_at(
+                &mut dp_master,
+                &mut slave,
+                crate::time::Instant::from_secs(10),
+            );
+        }
+        assert_eq!(slave.diagnostics_count, count);
+
+        dp_master
+            .get_mut(handle)
+            .set_diagnostics_interval(crate::time::Duration::from_secs(1));
+
+        for (time, expected) in [(10, 1), (10, 1), (11, 2), (11, 2), (12, 3)] {
+            for _ in 0..5 {
+                let now = crate::time::Instant::from_secs(time);
+                run_dp_master_at(&mut dp_master, &mut slave, now);
+            }
+            assert_eq!(slave.diagnostics_count, count + expected, "at {time}s");
+        }
+
+        // Data exchange continues in between
+        assert!(dp_master.get_mut(handle).is_running());
+    }
+
+    #[test]
+    fn parameter_fault_backoff() {
+        crate::test_utils::prepare_test_logger_with_warnings(vec![
+            "Peripheral #8 reports a parameter fault!",
+        ]);
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            ident_number: 0x1337,
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        slave.prm_fault = true;
+        dp_master.enter_operate();
+
+        let run = |dp_master: &mut DpMaster, slave: &mut FakeSlave, millis: i64| {
+            let mut errors = 0;
+            for _ in 0..50 {
+                let now = crate::time::Instant::from_millis(millis);
+                run_dp_master_at(dp_master, slave, now);
+                if let Some((_, crate::dp::PeripheralEvent::ParameterError)) =
+                    dp_master.take_last_events().peripheral
+                {
+                    errors += 1;
+                }
+            }
+            errors
+        };
+
+        // After a few rejections, the DP master backs off and only reports a single event.
+        assert_eq!(run(&mut dp_master, &mut slave, 0), 1);
+        assert_eq!(slave.set_prm_count, 3);
+
+        // The backoff grows with each rejection.
+        assert_eq!(run(&mut dp_master, &mut slave, 100), 0);
+        assert_eq!(slave.set_prm_count, 4);
+        assert_eq!(run(&mut dp_master, &mut slave, 150), 0);
+        assert_eq!(slave.set_prm_count, 4);
+        assert_eq!(run(&mut dp_master, &mut slave, 300), 0);
+        assert_eq!(slave.set_prm_count, 5);
+
+        // Once the peripheral accepts the parameters, the backoff is reset.
+        slave.prm_fault = false;
+        assert_eq!(run(&mut dp_master, &mut slave, 1000), 0);
+        assert_eq!(slave.set_prm_count, 6);
+        assert!(dp_master.get_mut(handle).is_running());
+
+        slave.prm_fault = true;
+        dp_master.get_mut(handle).reset_address(8);
+        assert_eq!(run(&mut dp_master, &mut slave, 2000), 1);
+        assert_eq!(slave.set_prm_count, 9);
+    }
+
+    #[test]
+    fn parameter_loop() {
+        crate::test_utils::prepare_test_logger_with_warnings(vec![
+            "Got \"SAP not enabled\" response from #8, revalidating config...",
+            "Peripheral #8 wants parameters after completing setup?! Retrying...",
+            "Peripheral #8 requested parameters 5 times after setup, giving up until reset!",
+        ]);
+        let mut dp_master = DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            ident_number: 0x1337,
+            user_parameters: Some(&[]),
+            config: Some(&[0x10, 0x20]),
+            ..Default::default()
+        };
+        let (handle, mut slave) = add_fake_peripheral(&mut dp_master, options);
+        slave.lose_parameters = true;
+        dp_master.enter_operate();
+
+        let mut events = vec![];
+        for _ in 0..200 {
+            run_dp_master(&mut dp_master, &mut slave);
+            if let Some((_, event)) = dp_master.take_last_events().peripheral {
+                events.push(event);
+            }
+        }
+
+        // The loop is detected and the peripheral is no longer parameterized.
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| **e == crate::dp::PeripheralEvent::ParameterLoop)
+                .count(),
+            1
+        );
+        assert_eq!(slave.set_prm_count, 5);
+        let peripheral = dp_master.get_mut(handle);
+        assert_eq!(
+            peripheral.lifecycle(),
+            crate::dp::PeripheralLifecycle::Error
+        );
+        assert_eq!(
+            peripheral.debug_state().state,
+            crate::dp::PeripheralState::ParameterError
+        );
+
+        // Communication only resumes after a reset.
+        slave.lose_parameters = false;
+        dp_master.get_mut(handle).reset();
+        run_dp_master_n(&mut dp_master, &mut slave, 20);
+        assert!(dp_master.get_mut(handle).is_running());
+    }
+
+    #[test]
+    #[should_panic = "Peripheral #8 was already added"]
+    fn add_duplicate_address() {
+        let mut dp_master = DpMaster::new(Vec::new());
+        let handle = dp_master.add(Peripheral::new(
+            8,
+            Default::default(),
+            &mut [][..],
+            &mut [][..],
+        ));
+
+        // Adding a peripheral at the address of a removed one is fine.
+        dp_master.remove(handle);
+        dp_master.add(Peripheral::new(
+            8,
+            Default::default(),
+            &mut [][..],
+            &mut [][..],
+        ));
+
+        dp_master.add(Peripheral::new(
+            8,
+            Default::default(),
+            &mut [][..],
+            &mut [][..],
+        ));
     }
 }
@@ -7,20 +7,31 @@
 //! [`PeripheralOptions`].  These options are best generated from the peripheral's GSD file using
 //! the `gsdtool` that is part of the `profirust` project.
 mod diagnostics;
+mod dpv1;
 mod master;
 mod peripheral;
+#[cfg(feature = "std")]
+mod peripheral_config;
 mod peripheral_set;
 pub mod scan;
+#[cfg(feature = "phy-simulator")]
+pub mod sim;
 
 pub use diagnostics::{
     ChannelDataType, ChannelDiagnostics, ChannelError, ExtDiagBlock, ExtDiagBlockIter,
-    ExtendedDiagnostics,
+    ExtendedDiagnostics, ModuleStatus, ModuleStatusBlock,
 };
+pub use dpv1::Dpv1Error;
 pub(crate) use master::DpMasterState;
-pub use master::{DpEvents, DpMaster, OperatingState};
+pub use master::{
+    DpEvents, DpMaster, DpSummary, FaultEvent, FaultPolicy, OperatingState, SetSlaveAddressEvent,
+};
 pub(crate) use peripheral::DiagnosticsInfo;
 pub use peripheral::{
-    DiagnosticFlags, Peripheral, PeripheralDiagnostics, PeripheralEvent, PeripheralOptions,
+    DiagnosticFlags, Peripheral, PeripheralDebug, PeripheralDiagnostics, PeripheralEvent,
+    PeripheralLifecycle, PeripheralOptions, PeripheralState,
 };
+#[cfg(feature = "std")]
+pub use peripheral_config::PeripheralConfig;
 pub(crate) use peripheral_set::PeripheralSet;
-pub use peripheral_set::{PeripheralHandle, PeripheralStorage};
+pub use peripheral_set::{CapacityError, PeripheralHandle, PeripheralStorage};
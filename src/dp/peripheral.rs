@@ -6,20 +6,50 @@ pub struct PeripheralOptions<'a> {
 
     /// Whether SYNC mode should be enabled
     ///
-    /// (SYNC mode is not yet supported in profirust)
+    /// SYNC mode is entered using
+    /// [`DpMaster::global_control_sync()`][`crate::dp::DpMaster::global_control_sync`].
+    ///
+    /// The DP master keeps exchanging the process image of outputs cyclically in SYNC mode.
+    /// Latching the outputs on each SYNC command is done entirely by the peripheral itself.
     pub sync_mode: bool,
     /// Whether FREEZE mode should be enabled
     ///
-    /// (FREEZE mode is not yet supported in profirust)
+    /// FREEZE mode is entered using
+    /// [`DpMaster::global_control_freeze()`][`crate::dp::DpMaster::global_control_freeze`].
     pub freeze_mode: bool,
     /// Global control groups this peripheral should be a part of
+    ///
+    /// Each bit represents one of the 8 possible groups.  Global Control commands are sent with a
+    /// group mask and only peripherals which are part of one of the selected groups react to them.
     pub groups: u8,
     /// Maximum response time (Tsdr) of this peripheral per the GSD file
     pub max_tsdr: u16,
+    /// Minimum response time (min T<sub>SDR</sub>) to request from this peripheral
+    ///
+    /// When set, this overrides the global [`min_tsdr`][`crate::fdl::ParametersBuilder::min_tsdr`]
+    /// of the FDL parameters in the Set_Prm telegram for this peripheral.  Some peripherals need a
+    /// larger value to keep up with the DP master.
+    pub min_tsdr_bits: Option<u8>,
     /// Whether this peripheral supports fail-safe mode
     ///
-    /// This is used when the DP master enters "clear" state.
+    /// This is used when the DP master enters "clear" state.  Fail-safe peripherals then receive
+    /// data exchange telegrams without output data instead of all-zero outputs.
     pub fail_safe: bool,
+    /// Watchdog timeout for this peripheral
+    ///
+    /// When set, this overrides the global
+    /// [`watchdog_timeout`][`crate::fdl::ParametersBuilder::watchdog_timeout`] of the FDL
+    /// parameters for this peripheral.  A timeout of zero disables the watchdog of this
+    /// peripheral.
+    ///
+    /// The timeout must be between 10 ms and 650 s.  Timeouts outside this range are clamped and
+    /// a warning is logged when adding the peripheral.
+    pub watchdog: Option<crate::time::Duration>,
+    /// Minimum time between two data exchange cycles with this peripheral per the GSD file
+    ///
+    /// This is the `Min_Slave_Intervall` from the GSD file.  When the DP cycle is faster, the
+    /// peripheral is skipped until the interval has passed.
+    pub min_slave_interval: crate::time::Duration,
 
     /// UserPrm constructed from the GSD file
     pub user_parameters: Option<&'a [u8]>,
@@ -27,6 +57,140 @@ pub struct PeripheralOptions<'a> {
     pub config: Option<&'a [u8]>,
 }
 
+impl PeripheralOptions<'_> {
+    /// Calculate the number of input and output bytes from the configuration.
+    ///
+    /// Returns `(input_bytes, output_bytes)` as expected by the peripheral, according to the
+    /// identifier bytes in [`config`][`PeripheralOptions::config`].  These should match the
+    /// lengths of the process images.  Both the compact and the special identifier format are
+    /// supported.
+    ///
+    /// Returns `None` when no configuration is set or when the configuration is truncated.
+    pub fn io_lengths(&self) -> Option<(usize, usize)> {
+        let mut inputs = 0;
+        let mut outputs = 0;
+        for lengths in config_identifiers(self.config?) {
+            let (i, o) = lengths?;
+            inputs += i;
+            outputs += o;
+        }
+        Some((inputs, outputs))
+    }
+
+    /// Find the location of a module's data in the process images.
+    ///
+    /// Each identifier in [`config`][`PeripheralOptions::config`] is treated as one module.
+    /// Returns the byte ranges of the module's `(inputs, outputs)` in the process images.
+    ///
+    /// Returns `None` when no configuration is set, when the configuration is truncated, or when
+    /// there is no module with the given index.
+    pub fn module_ranges(
+        &self,
+        index: usize,
+    ) -> Option<(core::ops::Range<usize>, core::ops::Range<usize>)> {
+        let mut input_offset = 0;
+        let mut output_offset = 0;
+        for (i, lengths) in config_identifiers(self.config?).enumerate() {
+            let (inputs, outputs) = lengths?;
+            if i == index {
+                return Some((
+                    input_offset..input_offset + inputs,
+                    output_offset..output_offset + outputs,
+                ));
+            }
+            input_offset += inputs;
+            output_offset += outputs;
+        }
+        None
+    }
+}
+
+/// Iterate over the `(input_bytes, output_bytes)` of each identifier in a configuration.
+///
+/// Yields `None` when the configuration is truncated.
+fn config_identifiers(config: &[u8]) -> impl Iterator<Item = Option<(usize, usize)>> + '_ {
+    let mut config = config.iter().copied();
+    core::iter::from_fn(move || {
+        let cfg_byte = config.next()?;
+        Some(identifier_lengths(cfg_byte, &mut config))
+    })
+}
+
+/// Decode the lengths of a single identifier, consuming its additional bytes from `config`
+fn identifier_lengths(
+    cfg_byte: u8,
+    config: &mut impl Iterator<Item = u8>,
+) -> Option<(usize, usize)> {
+    let mut inputs = 0;
+    let mut outputs = 0;
+    if cfg_byte & 0x30 == 0 {
+        // Special identifier format: Length bytes for outputs and inputs follow, then
+        // manufacturer specific data.
+        if cfg_byte & 0x40 != 0 {
+            outputs += special_format_length(config.next()?);
+        }
+        if cfg_byte & 0x80 != 0 {
+            inputs += special_format_length(config.next()?);
+        }
+        for _ in 0..(cfg_byte & 0x0f) {
+            config.next()?;
+        }
+        return Some((inputs, outputs));
+    }
+    let factor = if cfg_byte & 0x40 != 0 {
+        // length in words
+        2
+    } else {
+        // length in bytes
+        1
+    };
+    let length = usize::from((cfg_byte & 0x0f) + 1) * factor;
+    if cfg_byte & 0x10 != 0 {
+        inputs += length;
+    }
+    if cfg_byte & 0x20 != 0 {
+        outputs += length;
+    }
+    Some((inputs, outputs))
+}
+
+/// Read `N` bytes from a process image, with a helpful panic message when out of range
+fn read_image<const N: usize>(image: &[u8], offset: usize) -> [u8; N] {
+    match offset.checked_add(N).and_then(|end| image.get(offset..end)) {
+        Some(bytes) => bytes.try_into().unwrap(),
+        None => panic!(
+            "{N} bytes at offset {offset} are out of range for a process image of {} bytes",
+            image.len()
+        ),
+    }
+}
+
+/// Write `N` bytes into a process image, with a helpful panic message when out of range
+fn write_image<const N: usize>(image: &mut [u8], offset: usize, bytes: [u8; N]) {
+    let len = image.len();
+    match offset
+        .checked_add(N)
+        .and_then(|end| image.get_mut(offset..end))
+    {
+        Some(target) => target.copy_from_slice(&bytes),
+        None => panic!(
+            "{N} bytes at offset {offset} are out of range for a process image of {len} bytes"
+        ),
+    }
+}
+
+/// Decode a length byte of the special identifier format
+fn special_format_length(length_byte: u8) -> usize {
+    let factor = if length_byte & 0x40 != 0 {
+        // length in words
+        2
+    } else {
+        // length in bytes
+        1
+    };
+    usize::from((length_byte & 0x3f) + 1) * factor
+}
+
 bitflags::bitflags! {
     /// Diagnostic flags reported by a peripheral
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -81,6 +245,34 @@ pub enum PeripheralEvent {
     DataExchanged,
     /// Peripheral has new diagnostic data available.
     Diagnostics,
+    /// A DPV1 read or write request was completed.
+    ///
+    /// The result is available from [`Peripheral::dpv1_response()`].
+    Dpv1Response,
+    /// The configuration read back from the peripheral is available.
+    ///
+    /// The configuration bytes can be retrieved using [`Peripheral::config_readback()`].
+    ConfigReadback,
+    /// Peripheral reported an ident number that does not match the configured one.
+    ///
+    /// The peripheral is not parameterized and will not enter data exchange until the reported
+    /// ident number matches [`PeripheralOptions::ident_number`].
+    IdentMismatch { expected: u16, got: u16 },
+    /// Peripheral cannot be parameterized or configured because
+    /// [`PeripheralOptions::user_parameters`] or [`PeripheralOptions::config`] is `None`.
+    ///
+    /// The peripheral will not enter data exchange.  This event is only reported once.
+    MissingConfiguration,
+    /// Peripheral is parameterized again after
+    /// [`Peripheral::set_user_parameters()`].
+    ///
+    /// Data exchange pauses until the peripheral reports [`PeripheralEvent::Configured`] again.
+    Reparameterizing,
+    /// Peripheral requested parameters again and again after completing its setup.
+    ///
+    /// This often hints at a watchdog mismatch.  Communication with the peripheral is stopped
+    /// until [`Peripheral::reset()`] is called.
+    ParameterLoop,
     /// Peripheral stopped responding to messages.
     Offline,
 }
@@ -100,6 +292,37 @@ pub struct PeripheralDiagnostics<'a> {
     pub extended_diagnostics: &'a crate::dp::ExtendedDiagnostics<'a>,
 }
 
+impl PeripheralDiagnostics<'_> {
+    /// Whether the peripheral requests (new) parameters.
+    ///
+    /// This is the case when the peripheral reports [`DiagnosticFlags::PARAMETER_REQUIRED`] or
+    /// rejected the last parameters with [`DiagnosticFlags::PARAMETER_FAULT`].
+    #[inline]
+    pub fn needs_parameterization(&self) -> bool {
+        self.flags
+            .intersects(DiagnosticFlags::PARAMETER_REQUIRED | DiagnosticFlags::PARAMETER_FAULT)
+    }
+
+    /// Whether the peripheral reported extended diagnostics.
+    ///
+    /// The blocks are available in [`extended_diagnostics`][`PeripheralDiagnostics::extended_diagnostics`].
+    #[inline]
+    pub fn has_extended_diagnostics(&self) -> bool {
+        self.flags.contains(DiagnosticFlags::EXT_DIAG)
+    }
+}
+
+/// Number of consecutive parameter rejections before re-parameterization is slowed down
+const PRM_FAULT_BACKOFF_THRESHOLD: u8 = 3;
+/// Initial delay between re-parameterization attempts once the backoff kicks in
+const PRM_FAULT_BACKOFF_MIN: crate::time::Duration = crate::time::Duration::from_millis(100);
+/// Maximum delay between re-parameterization attempts
+const PRM_FAULT_BACKOFF_MAX: crate::time::Duration = crate::time::Duration::from_secs(10);
+/// Number of parameter requests after setup within the window which is treated as a loop
+const PRM_LOOP_THRESHOLD: u8 = 5;
+/// Time window for detecting a parameterization loop
+const PRM_LOOP_WINDOW: crate::time::Duration = crate::time::Duration::from_secs(60);
+
 /// Internal storage for diagnostics information
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) struct DiagnosticsInfo {
@@ -108,16 +331,65 @@ pub(crate) struct DiagnosticsInfo {
     pub master_address: Option<u8>,
 }
 
+/// Internal state of the communication with a peripheral
+///
+/// This is only exposed for debugging purposes through [`Peripheral::debug_state()`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[repr(u8)]
-enum PeripheralState {
+#[non_exhaustive]
+pub enum PeripheralState {
+    /// Peripheral is not responding, waiting for it to answer a diagnostics request
     #[default]
     Offline,
+    /// Sending parameters (Set_Prm)
     WaitForParam,
+    /// Sending configuration (Chk_Cfg)
     WaitForConfig,
+    /// Checking diagnostics to see whether parameters and configuration were accepted
     ValidateConfig,
+    /// Ready for data exchange, but no data was exchanged yet
     PreDataExchange,
+    /// Cyclic data exchange
     DataExchange,
+    /// Stopped because the peripheral is stuck requesting parameters, waiting for a reset
+    ParameterError,
+}
+
+/// Lifecycle of a peripheral, as seen by the DP master
+///
+/// This is a coarse view of the communication state which is meant for displaying the status of
+/// a peripheral, e.g. using a status LED.  See [`Peripheral::lifecycle()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PeripheralLifecycle {
+    /// The peripheral does not respond.
+    Offline,
+    /// The peripheral responds, but it cannot be set up because its user parameters or its
+    /// configuration are missing.
+    Discovered,
+    /// The peripheral responds and parameters are being sent.
+    Parameterizing,
+    /// The peripheral was parameterized and its configuration is being sent and checked.
+    Configuring,
+    /// The peripheral is in cyclic data exchange.
+    Running,
+    /// Communication with the peripheral was stopped due to an error.
+    ///
+    /// The peripheral is only set up again after [`Peripheral::reset()`].
+    Error,
+}
+
+/// Snapshot of the internal communication state of a peripheral
+///
+/// See [`Peripheral::debug_state()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeripheralDebug {
+    /// Current state of the communication with this peripheral
+    pub state: PeripheralState,
+    /// Number of retries of the last telegram that was not answered
+    pub retry_count: u8,
+    /// Frame count bit (FCB/FCV) which will be used for the next telegram
+    pub fcb: crate::fdl::FrameCountBit,
 }
 
 /// A PROFIBUS peripheral that is connected to the bus
@@ -178,6 +450,8 @@ pub struct Peripheral<'a> {
     state: PeripheralState,
     /// Retry count when messages don't receive a valid response.
     retry_count: u8,
+    /// Time when the retry limit was last exceeded without going offline
+    last_soft_failure: Option<crate::time::Instant>,
     /// FCB/FCV tracking for this peripheral
     ///
     /// The "Frame Count Bit" is used to detect lost messages and prevent duplication on either
@@ -193,9 +467,54 @@ pub struct Peripheral<'a> {
     ext_diag: crate::dp::ExtendedDiagnostics<'a>,
     /// Flag to indicate necessity of polling diagnostics ASAP
     diag_needed: bool,
-
-    #[cfg(feature = "debug-measure-roundtrip")]
+    /// Interval for periodically polling diagnostics (if enabled)
+    diag_interval: Option<crate::time::Duration>,
+    /// Time of the last received diagnostics
+    last_diag: Option<crate::time::Instant>,
+    /// Flag to remember that an ident number mismatch was already reported
+    ident_mismatch: bool,
+    /// Flag to remember that missing parameters or configuration were already reported
+    missing_configuration: bool,
+    /// Flag to indicate that new user parameters must be sent
+    reparameterize: bool,
+    /// Time of the last successful data exchange
+    last_data_exchange: Option<crate::time::Instant>,
+    /// Number of consecutive parameter rejections
+    prm_fault_count: u8,
+    /// Number of parameter requests after setup and the start of their detection window
+    prm_requests: Option<(u8, crate::time::Instant)>,
+    /// Next re-parameterization attempt is delayed until this time
+    prm_backoff_until: Option<crate::time::Instant>,
+    /// Time when the last data exchange request was sent
     tx_time: Option<crate::time::Instant>,
+    /// Time when the last data exchange cycle was started, for enforcing the minimum interval
+    dx_start: Option<crate::time::Instant>,
+    /// Duration of the last data exchange message cycle
+    last_cycle_time: Option<crate::time::Duration>,
+    /// Pending DPV1 acyclic request
+    dpv1: Option<crate::dp::dpv1::Dpv1Request>,
+    /// Whether the next message cycle should be used for the pending DPV1 request
+    dpv1_turn: bool,
+    /// Buffer for DPV1 write data and read responses
+    dpv1_buffer: managed::ManagedSlice<'a, u8>,
+    /// Result of the last DPV1 request (length of the data in `dpv1_buffer`)
+    dpv1_response: Option<Result<usize, crate::dp::Dpv1Error>>,
+    /// Whether the configuration should be read back from the peripheral (Get_Cfg)
+    cfg_readback_needed: bool,
+    /// Buffer for the configuration read back from the peripheral
+    cfg_readback_buffer: managed::ManagedSlice<'a, u8>,
+    /// Length of the last configuration read back from the peripheral
+    cfg_readback: Option<usize>,
+    /// Whether data was exchanged since the last call to `DpMaster::on_data_exchanged()`
+    data_exchanged: bool,
+    /// Whether the outputs of a committed output transaction still need to be sent
+    output_commit_pending: bool,
+    /// SYNC/FREEZE mode flags to verify with the next diagnostics
+    gc_mode_check: DiagnosticFlags,
+    /// SYNC/FREEZE modes requested using Global Control
+    gc_modes: DiagnosticFlags,
+    /// Whether the DP master depends on this peripheral
+    critical: bool,
 
     options: PeripheralOptions<'a>,
 }
@@ -206,14 +525,37 @@ impl Default for Peripheral<'_> {
             address: Default::default(),
             state: Default::default(),
             retry_count: Default::default(),
+            last_soft_failure: Default::default(),
             fcb: Default::default(),
             pi_i: [].into(),
             pi_q: [].into(),
             diag: Default::default(),
             ext_diag: Default::default(),
             diag_needed: Default::default(),
-            #[cfg(feature = "debug-measure-roundtrip")]
+            diag_interval: Default::default(),
+            last_diag: Default::default(),
+            ident_mismatch: Default::default(),
+            missing_configuration: Default::default(),
+            reparameterize: Default::default(),
+            last_data_exchange: Default::default(),
+            prm_fault_count: Default::default(),
+            prm_requests: Default::default(),
+            prm_backoff_until: Default::default(),
             tx_time: Default::default(),
+            dx_start: Default::default(),
+            last_cycle_time: Default::default(),
+            dpv1: Default::default(),
+            dpv1_turn: Default::default(),
+            dpv1_buffer: [].into(),
+            dpv1_response: Default::default(),
+            cfg_readback_needed: Default::default(),
+            cfg_readback_buffer: [].into(),
+            cfg_readback: Default::default(),
+            data_exchanged: Default::default(),
+            output_commit_pending: Default::default(),
+            gc_mode_check: DiagnosticFlags::empty(),
+            gc_modes: DiagnosticFlags::empty(),
+            critical: true,
             options: Default::default(),
         }
     }
@@ -222,6 +564,12 @@ impl Default for Peripheral<'_> {
 impl<'a> Peripheral<'a> {
     /// Construct a new peripheral from its address, options, and buffers for the process image of
     /// inputs (`pi_i`) and process image of outputs (`pi_q`).
+    ///
+    /// The buffers must be exactly as long as the input and output data of the configured modules.
+    /// For peripherals which only have inputs or only have outputs, pass an empty buffer (e.g.
+    /// `&mut []` or `vec![]`) for the other direction.  Data exchange then sends a request without
+    /// data to input-only peripherals and accepts a short confirmation from output-only
+    /// peripherals.
     pub fn new<PII, PIQ>(address: u8, options: PeripheralOptions<'a>, pi_i: PII, pi_q: PIQ) -> Self
     where
         PII: Into<managed::ManagedSlice<'a, u8>>,
@@ -253,6 +601,34 @@ impl<'a> Peripheral<'a> {
         self
     }
 
+    /// Attach a buffer for DPV1 acyclic communication to this peripheral.
+    ///
+    /// The buffer holds the data of [`dpv1_write()`][`Peripheral::dpv1_write`] requests and the
+    /// data received for [`dpv1_read()`][`Peripheral::dpv1_read`] requests.  DPV1 data is at most
+    /// 240 bytes long.
+    ///
+    /// Note that DPV1 must also be enabled in the parameters of the peripheral (`DPV1_Status_1`).
+    pub fn with_dpv1_buffer<S>(mut self, buffer: S) -> Self
+    where
+        S: Into<managed::ManagedSlice<'a, u8>>,
+    {
+        self.dpv1_buffer = buffer.into();
+        self
+    }
+
+    /// Attach a buffer for the configuration read back from the peripheral.
+    ///
+    /// The buffer must be large enough to fit the configuration of the peripheral, which has the
+    /// same length as the [`PeripheralOptions::config`] for a correctly configured peripheral.
+    /// See [`request_config_readback()`][`Peripheral::request_config_readback`].
+    pub fn with_config_readback_buffer<S>(mut self, buffer: S) -> Self
+    where
+        S: Into<managed::ManagedSlice<'a, u8>>,
+    {
+        self.cfg_readback_buffer = buffer.into();
+        self
+    }
+
     /// Completely reset this peripheral to a new address.
     ///
     /// The process images are not changed by this operation.  A new DP parameterization will take
@@ -262,8 +638,25 @@ impl<'a> Peripheral<'a> {
         let pi_i = core::mem::replace(&mut self.pi_i, [].into());
         let pi_q = core::mem::replace(&mut self.pi_q, [].into());
         let diag_buffer = self.ext_diag.take_buffer();
+        let dpv1_buffer = core::mem::replace(&mut self.dpv1_buffer, [].into());
+        let cfg_readback_buffer = core::mem::replace(&mut self.cfg_readback_buffer, [].into());
+        let diag_interval = self.diag_interval;
+        let critical = self.critical;
 
-        *self = Self::new(new_address, options, pi_i, pi_q).with_diag_buffer(diag_buffer);
+        *self = Self::new(new_address, options, pi_i, pi_q)
+            .with_diag_buffer(diag_buffer)
+            .with_dpv1_buffer(dpv1_buffer)
+            .with_config_readback_buffer(cfg_readback_buffer);
+        self.diag_interval = diag_interval;
+        self.critical = critical;
+    }
+
+    /// Completely reset this peripheral.
+    ///
+    /// This is needed to resume communication after a [`PeripheralEvent::ParameterLoop`].  A new
+    /// DP parameterization will take place once the device responds.
+    pub fn reset(&mut self) {
+        self.reset_address(self.address);
     }
 
     /// Address of this peripheral.
@@ -278,6 +671,12 @@ impl<'a> Peripheral<'a> {
     }
 
     /// Access to the full process image of inputs.
+    ///
+    /// The process image of inputs is updated on each data exchange, both in the
+    /// [`Operate`][`crate::dp::OperatingState::Operate`] and the
+    /// [`Clear`][`crate::dp::OperatingState::Clear`] state.  Use
+    /// [`last_data_exchange()`][`Peripheral::last_data_exchange`] to find out whether the inputs
+    /// are current.
     #[inline(always)]
     pub fn pi_i(&self) -> &[u8] {
         &self.pi_i
@@ -300,6 +699,110 @@ impl<'a> Peripheral<'a> {
         (&self.pi_i, &mut self.pi_q)
     }
 
+    /// Read a big-endian `u16` from the process image of inputs at byte `offset`.
+    ///
+    /// # Panics
+    /// This function panics if the value does not fit into the process image at `offset`.
+    pub fn pi_i_u16(&self, offset: usize) -> u16 {
+        u16::from_be_bytes(read_image(&self.pi_i, offset))
+    }
+
+    /// Read a big-endian `i16` from the process image of inputs at byte `offset`.
+    ///
+    /// # Panics
+    /// This function panics if the value does not fit into the process image at `offset`.
+    pub fn pi_i_i16(&self, offset: usize) -> i16 {
+        i16::from_be_bytes(read_image(&self.pi_i, offset))
+    }
+
+    /// Read a big-endian `u32` from the process image of inputs at byte `offset`.
+    ///
+    /// # Panics
+    /// This function panics if the value does not fit into the process image at `offset`.
+    pub fn pi_i_u32(&self, offset: usize) -> u32 {
+        u32::from_be_bytes(read_image(&self.pi_i, offset))
+    }
+
+    /// Read a big-endian `i32` from the process image of inputs at byte `offset`.
+    ///
+    /// # Panics
+    /// This function panics if the value does not fit into the process image at `offset`.
+    pub fn pi_i_i32(&self, offset: usize) -> i32 {
+        i32::from_be_bytes(read_image(&self.pi_i, offset))
+    }
+
+    /// Write a big-endian `u16` into the process image of outputs at byte `offset`.
+    ///
+    /// # Panics
+    /// This function panics if the value does not fit into the process image at `offset`.
+    pub fn pi_q_set_u16(&mut self, offset: usize, value: u16) {
+        write_image(&mut self.pi_q, offset, value.to_be_bytes());
+    }
+
+    /// Write a big-endian `i16` into the process image of outputs at byte `offset`.
+    ///
+    /// # Panics
+    /// This function panics if the value does not fit into the process image at `offset`.
+    pub fn pi_q_set_i16(&mut self, offset: usize, value: i16) {
+        write_image(&mut self.pi_q, offset, value.to_be_bytes());
+    }
+
+    /// Write a big-endian `u32` into the process image of outputs at byte `offset`.
+    ///
+    /// # Panics
+    /// This function panics if the value does not fit into the process image at `offset`.
+    pub fn pi_q_set_u32(&mut self, offset: usize, value: u32) {
+        write_image(&mut self.pi_q, offset, value.to_be_bytes());
+    }
+
+    /// Write a big-endian `i32` into the process image of outputs at byte `offset`.
+    ///
+    /// # Panics
+    /// This function panics if the value does not fit into the process image at `offset`.
+    pub fn pi_q_set_i32(&mut self, offset: usize, value: i32) {
+        write_image(&mut self.pi_q, offset, value.to_be_bytes());
+    }
+
+    /// Access to the inputs of a single module.
+    ///
+    /// The module boundaries are derived from the configuration, see
+    /// [`PeripheralOptions::module_ranges()`].
+    ///
+    /// # Panics
+    /// This function panics if the peripheral has no module with the given index or if the
+    /// process image is too short.
+    pub fn module_input(&self, index: usize) -> &[u8] {
+        let (inputs, _) = self.module_ranges(index);
+        &self.pi_i[inputs]
+    }
+
+    /// Access to the outputs of a single module.
+    ///
+    /// # Panics
+    /// This function panics if the peripheral has no module with the given index or if the
+    /// process image is too short.
+    pub fn module_output(&self, index: usize) -> &[u8] {
+        let (_, outputs) = self.module_ranges(index);
+        &self.pi_q[outputs]
+    }
+
+    /// Mutable access to the outputs of a single module.
+    ///
+    /// # Panics
+    /// This function panics if the peripheral has no module with the given index or if the
+    /// process image is too short.
+    pub fn module_output_mut(&mut self, index: usize) -> &mut [u8] {
+        let (_, outputs) = self.module_ranges(index);
+        &mut self.pi_q[outputs]
+    }
+
+    fn module_ranges(&self, index: usize) -> (core::ops::Range<usize>, core::ops::Range<usize>) {
+        match self.options.module_ranges(index) {
+            Some(ranges) => ranges,
+            None => panic!("Peripheral #{} has no module {}", self.address, index),
+        }
+    }
+
     /// Whether this peripheral is live and responds on the bus.
     #[inline(always)]
     pub fn is_live(&self) -> bool {
@@ -312,6 +815,57 @@ impl<'a> Peripheral<'a> {
         self.state == PeripheralState::DataExchange
     }
 
+    /// Current lifecycle of this peripheral.
+    ///
+    /// This is a stable projection of the internal communication state which distinguishes a
+    /// peripheral that is still being set up from one that is running.
+    pub fn lifecycle(&self) -> PeripheralLifecycle {
+        match self.state {
+            PeripheralState::Offline => PeripheralLifecycle::Offline,
+            PeripheralState::WaitForParam | PeripheralState::WaitForConfig
+                if self.options.user_parameters.is_none() || self.options.config.is_none() =>
+            {
+                PeripheralLifecycle::Discovered
+            }
+            PeripheralState::WaitForParam => PeripheralLifecycle::Parameterizing,
+            PeripheralState::WaitForConfig
+            | PeripheralState::ValidateConfig
+            | PeripheralState::PreDataExchange => PeripheralLifecycle::Configuring,
+            PeripheralState::DataExchange => PeripheralLifecycle::Running,
+            PeripheralState::ParameterError => PeripheralLifecycle::Error,
+        }
+    }
+
+    /// Internal communication state of this peripheral for debugging purposes.
+    ///
+    /// This is useful for diagnosing peripherals that are stuck, e.g. in a parameterization loop
+    /// or due to duplicate frame detection.  The details of the returned state are not stable.
+    #[inline]
+    pub fn debug_state(&self) -> PeripheralDebug {
+        PeripheralDebug {
+            state: self.state,
+            retry_count: self.retry_count,
+            fcb: self.fcb,
+        }
+    }
+
+    /// Time of the last successful data exchange with this peripheral.
+    ///
+    /// This is `None` if no data was exchanged yet.
+    #[inline(always)]
+    pub fn last_data_exchange(&self) -> Option<crate::time::Instant> {
+        self.last_data_exchange
+    }
+
+    /// Duration of the last data exchange message cycle with this peripheral.
+    ///
+    /// This is the time between sending the data exchange request and receiving the response.
+    /// It is `None` if no data was exchanged yet.
+    #[inline(always)]
+    pub fn last_cycle_time(&self) -> Option<crate::time::Duration> {
+        self.last_cycle_time
+    }
+
     /// Get the last diagnostics information received from this peripheral.
     #[inline]
     pub fn last_diagnostics(&self) -> Option<PeripheralDiagnostics> {
@@ -323,6 +877,26 @@ impl<'a> Peripheral<'a> {
         })
     }
 
+    /// Whether the peripheral reported to be in SYNC mode with its last diagnostics.
+    ///
+    /// See [`DpMaster::global_control_sync()`][`crate::dp::DpMaster::global_control_sync`].
+    #[inline]
+    pub fn in_sync_mode(&self) -> bool {
+        self.diag
+            .as_ref()
+            .is_some_and(|diag| diag.flags.contains(DiagnosticFlags::SYNC_MODE))
+    }
+
+    /// Whether the peripheral reported to be in FREEZE mode with its last diagnostics.
+    ///
+    /// See [`DpMaster::global_control_freeze()`][`crate::dp::DpMaster::global_control_freeze`].
+    #[inline]
+    pub fn in_freeze_mode(&self) -> bool {
+        self.diag
+            .as_ref()
+            .is_some_and(|diag| diag.flags.contains(DiagnosticFlags::FREEZE_MODE))
+    }
+
     /// Request retrieval of diagnostic information at the next possible time.
     ///
     /// When new diagnostics are available, a [`PeripheralEvent::Diagnostics`] is emitted.
@@ -330,9 +904,229 @@ impl<'a> Peripheral<'a> {
     pub fn request_diagnostics(&mut self) {
         self.diag_needed = true;
     }
+
+    /// Read a data record from the peripheral using DPV1 acyclic communication (MSAC_C1).
+    ///
+    /// The request is issued during the next message cycles with this peripheral, alternating
+    /// with cyclic data exchange.  Once completed, a [`PeripheralEvent::Dpv1Response`] is emitted
+    /// and the data can be retrieved using [`dpv1_response()`][`Peripheral::dpv1_response`].
+    ///
+    /// Returns `false` when another DPV1 request is still pending.
+    ///
+    /// # Panics
+    /// Panics if `length` exceeds the size of the buffer passed to
+    /// [`with_dpv1_buffer()`][`Peripheral::with_dpv1_buffer`] or 240 bytes.
+    pub fn dpv1_read(&mut self, slot: u8, index: u8, length: u8) -> bool {
+        assert!(
+            usize::from(length) <= self.dpv1_buffer.len().min(240),
+            "DPV1 read length exceeds the DPV1 buffer"
+        );
+        self.queue_dpv1(crate::dp::dpv1::FUNCTION_READ, slot, index, length)
+    }
+
+    /// Write a data record to the peripheral using DPV1 acyclic communication (MSAC_C1).
+    ///
+    /// See [`dpv1_read()`][`Peripheral::dpv1_read`] for how the request is processed.
+    ///
+    /// Returns `false` when another DPV1 request is still pending.
+    ///
+    /// # Panics
+    /// Panics if `data` exceeds the size of the buffer passed to
+    /// [`with_dpv1_buffer()`][`Peripheral::with_dpv1_buffer`] or 240 bytes.
+    pub fn dpv1_write(&mut self, slot: u8, index: u8, data: &[u8]) -> bool {
+        assert!(
+            data.len() <= self.dpv1_buffer.len().min(240),
+            "DPV1 write data exceeds the DPV1 buffer"
+        );
+        if self.dpv1.is_some() {
+            return false;
+        }
+        self.dpv1_buffer[..data.len()].copy_from_slice(data);
+        self.queue_dpv1(
+            crate::dp::dpv1::FUNCTION_WRITE,
+            slot,
+            index,
+            u8::try_from(data.len()).unwrap(),
+        )
+    }
+
+    /// Result of the last completed DPV1 request.
+    ///
+    /// For read requests, this is the data received from the peripheral.  For write requests, the
+    /// data is empty.  Returns `None` while a request is pending or when no request was made yet.
+    pub fn dpv1_response(&self) -> Option<Result<&[u8], crate::dp::Dpv1Error>> {
+        if self.dpv1.is_some() {
+            return None;
+        }
+        self.dpv1_response
+            .map(|res| res.map(|length| &self.dpv1_buffer[..length]))
+    }
+
+    fn queue_dpv1(&mut self, function: u8, slot: u8, index: u8, length: u8) -> bool {
+        if self.dpv1.is_some() {
+            return false;
+        }
+        self.dpv1 = Some(crate::dp::dpv1::Dpv1Request {
+            function,
+            slot,
+            index,
+            length,
+            step: crate::dp::dpv1::Dpv1Step::Request,
+        });
+        self.dpv1_response = None;
+        self.dpv1_turn = true;
+        true
+    }
+
+    /// Set the interval for periodically requesting diagnostics during data exchange.
+    ///
+    /// By default, diagnostics are only requested when the peripheral signals new diagnostics or
+    /// when [`Peripheral::request_diagnostics()`] is called.  A short interval is useful for
+    /// peripherals with frequently changing extended diagnostics while a long interval saves bus
+    /// bandwidth.  An interval of zero disables periodic diagnostics again.
+    ///
+    /// When new diagnostics are available, a [`PeripheralEvent::Diagnostics`] is emitted.
+    #[inline]
+    pub fn set_diagnostics_interval(&mut self, interval: crate::time::Duration) {
+        self.diag_interval = Some(interval).filter(|i| *i != crate::time::Duration::ZERO);
+    }
+
+    /// Mark this peripheral as critical or optional for operation.
+    ///
+    /// Peripherals are critical by default.  Optional peripherals going offline do not trip the
+    /// DP master into Clear with
+    /// [`FaultPolicy::ClearOnCriticalOffline`][`crate::dp::FaultPolicy::ClearOnCriticalOffline`]
+    /// and are not considered by
+    /// [`DpMaster::all_critical_running()`][`crate::dp::DpMaster::all_critical_running`].
+    #[inline]
+    pub fn set_critical(&mut self, critical: bool) {
+        self.critical = critical;
+    }
+
+    /// Whether this peripheral is critical for operation.
+    #[inline(always)]
+    pub fn is_critical(&self) -> bool {
+        self.critical
+    }
+
+    /// Request reading back the configuration of the peripheral (Get_Cfg) at the next possible
+    /// time.
+    ///
+    /// This is useful to check which modules are actually plugged into a modular station.  The
+    /// request is only issued during data exchange.  Once the peripheral responded, a
+    /// [`PeripheralEvent::ConfigReadback`] is emitted and the configuration can be retrieved using
+    /// [`config_readback()`][`Peripheral::config_readback`].
+    ///
+    /// A buffer for the configuration must be attached using
+    /// [`with_config_readback_buffer()`][`Peripheral::with_config_readback_buffer`].  Without one,
+    /// the request is ignored and [`config_readback()`][`Peripheral::config_readback`] keeps
+    /// returning `None`.
+    pub fn request_config_readback(&mut self) {
+        if self.cfg_readback_buffer.is_empty() {
+            log::warn!(
+                "Peripheral #{} has no config readback buffer, ignoring readback request!",
+                self.address
+            );
+            return;
+        }
+        self.cfg_readback_needed = true;
+    }
+
+    /// Replace the user parameters of this peripheral.
+    ///
+    /// When the peripheral was already parameterized, it is parameterized again with the new
+    /// parameters (and configured again) at the next possible time.  This pauses data exchange
+    /// briefly, which is announced with a [`PeripheralEvent::Reparameterizing`].  The process
+    /// images are kept as they are.
+    pub fn set_user_parameters(&mut self, user_parameters: &'a [u8]) {
+        self.options.user_parameters = Some(user_parameters);
+        self.reparameterize = matches!(
+            self.state,
+            PeripheralState::WaitForConfig
+                | PeripheralState::ValidateConfig
+                | PeripheralState::PreDataExchange
+                | PeripheralState::DataExchange
+        );
+    }
+
+    /// Configuration that was last read back from the peripheral.
+    ///
+    /// Returns `None` while no configuration was read back yet or when no readback buffer is
+    /// attached.  Compare the result to
+    /// [`PeripheralOptions::config`] to check whether the peripheral is configured as expected.
+    pub fn config_readback(&self) -> Option<&[u8]> {
+        self.cfg_readback
+            .map(|length| &self.cfg_readback_buffer[..length])
+    }
 }
 
 impl<'a> Peripheral<'a> {
+    /// Remember that data was exchanged, for `DpMaster::on_data_exchanged()`.
+    #[inline]
+    pub(crate) fn mark_data_exchanged(&mut self) {
+        self.data_exchanged = true;
+    }
+
+    /// Whether data was exchanged since the last call, for `DpMaster::on_data_exchanged()`.
+    #[inline]
+    pub(crate) fn take_data_exchanged(&mut self) -> bool {
+        core::mem::take(&mut self.data_exchanged)
+    }
+
+    /// Remember that the current outputs must be sent for a committed output transaction.
+    #[inline]
+    pub(crate) fn request_output_commit(&mut self) {
+        self.output_commit_pending = true;
+    }
+
+    /// Whether the outputs of a committed output transaction were not sent yet.
+    #[inline]
+    pub(crate) fn output_commit_pending(&self) -> bool {
+        self.output_commit_pending
+    }
+
+    /// Note SYNC/FREEZE modes that were requested using a Global Control command.
+    ///
+    /// `mask` selects the mode flags that are affected by the command and `modes` holds their
+    /// new values.  The modes are verified with the next diagnostics of the peripheral.
+    pub(crate) fn expect_global_control_modes(
+        &mut self,
+        mask: DiagnosticFlags,
+        modes: DiagnosticFlags,
+    ) {
+        if !self.is_running() {
+            return;
+        }
+        self.gc_modes = (self.gc_modes - mask) | (modes & mask);
+        self.gc_mode_check |= mask;
+        self.diag_needed = true;
+    }
+
+    /// Warn about SYNC/FREEZE modes that the peripheral did not confirm in its diagnostics.
+    fn verify_global_control_modes(&mut self) {
+        let Some(diag) = self.diag.as_ref() else {
+            return;
+        };
+        let mismatch = (diag.flags ^ self.gc_modes) & self.gc_mode_check;
+        for (flag, mode) in [
+            (DiagnosticFlags::SYNC_MODE, "SYNC"),
+            (DiagnosticFlags::FREEZE_MODE, "FREEZE"),
+        ] {
+            if mismatch.contains(flag) {
+                let prefix = if self.gc_modes.contains(flag) {
+                    ""
+                } else {
+                    "UN"
+                };
+                log::warn!(
+                    "Peripheral #{} did not confirm {prefix}{mode} command!",
+                    self.address
+                );
+            }
+        }
+        self.gc_mode_check = DiagnosticFlags::empty();
+    }
+
     pub(crate) fn transmit_telegram<'b>(
         &mut self,
         now: crate::time::Instant,
@@ -345,26 +1139,27 @@ impl<'a> Peripheral<'a> {
         // We never expect to be called in `Stop` or even worse `Offline` operating states.
         debug_assert!(dp.operating_state.is_operate() || dp.operating_state.is_clear());
 
-        if self.state != PeripheralState::Offline && self.retry_count == 1 {
-            log::warn!("Resending a telegram to #{}...", self.address);
+        if self.reparameterize && self.retry_count == 0 {
+            self.reparameterize = false;
+            if self.state != PeripheralState::Offline {
+                log::info!("Peripheral #{} is parameterized again.", self.address);
+                self.state = PeripheralState::WaitForParam;
+                return Err((tx, Some(PeripheralEvent::Reparameterizing)));
+            }
         }
 
         let res = match self.state {
-            _ if self.retry_count > fdl.parameters().max_retry_limit => {
-                // Assume peripheral is now offline so the next step is sending SYNC messages to detect
-                // when it comes back.
-                log::warn!("Peripheral #{} stopped responding!", self.address);
-                self.state = PeripheralState::Offline;
-                Err((tx, Some(PeripheralEvent::Offline)))
-            }
+            PeripheralState::ParameterError => Err((tx, None)),
             PeripheralState::Offline => {
-                if self.retry_count == 0 {
-                    // Request diagnostics to see whether the peripheral responds.
-                    Ok(self.send_diagnostics_request(fdl, tx))
-                } else {
-                    // Don't retry when the peripheral may be offline.
-                    Err((tx, None))
-                }
+                // Request diagnostics to see whether the peripheral responds.  Don't retry when
+                // the peripheral may be offline.
+                Ok(self.send_diagnostics_request(fdl, tx).without_retries())
+            }
+            PeripheralState::WaitForParam
+                if self.prm_backoff_until.is_some_and(|until| now < until) =>
+            {
+                // Peripheral rejected our parameters repeatedly, slow down.
+                Err((tx, None))
             }
             PeripheralState::WaitForParam => {
                 if let Some(user_parameters) = self.options.user_parameters {
@@ -372,7 +1167,7 @@ impl<'a> Peripheral<'a> {
                     Ok(tx.send_data_telegram(
                         crate::fdl::DataTelegramHeader {
                             da: self.address,
-                            sa: fdl.parameters().address,
+                            sa: fdl.address(),
                             dsap: crate::consts::SAP_SLAVE_SET_PRM,
                             ssap: crate::consts::SAP_MASTER_MS0,
                             fc: crate::fdl::FunctionCode::new_srd_low(self.fcb),
@@ -387,13 +1182,20 @@ impl<'a> Peripheral<'a> {
                             if self.options.freeze_mode {
                                 buf[0] |= 0x10; // Freeze_Req
                             }
-                            if let Some((f1, f2)) = fdl.parameters().watchdog_factors {
+                            let watchdog_factors = match self.options.watchdog {
+                                Some(wdg) => crate::fdl::watchdog_factors_clamped(wdg),
+                                None => fdl.parameters().watchdog_factors,
+                            };
+                            if let Some((f1, f2)) = watchdog_factors {
                                 buf[0] |= 0x08; // WD_On
                                 buf[1] = f1;
                                 buf[2] = f2;
                             }
                             // Minimum T_sdr
-                            buf[3] = fdl.parameters().min_tsdr_bits;
+                            buf[3] = self
+                                .options
+                                .min_tsdr_bits
+                                .unwrap_or(fdl.parameters().min_tsdr_bits);
                             // Ident
                             buf[4..6].copy_from_slice(&self.options.ident_number.to_be_bytes());
                             // Groups
@@ -405,7 +1207,7 @@ impl<'a> Peripheral<'a> {
                 } else {
                     // When self.options.user_parameters is None, we need to wait before we can
                     // start with configuration.
-                    Err((tx, None))
+                    Err((tx, self.report_missing_configuration("user_parameters")))
                 }
             }
             PeripheralState::WaitForConfig => {
@@ -413,7 +1215,7 @@ impl<'a> Peripheral<'a> {
                     Ok(tx.send_data_telegram(
                         crate::fdl::DataTelegramHeader {
                             da: self.address,
-                            sa: fdl.parameters().address,
+                            sa: fdl.address(),
                             dsap: crate::consts::SAP_SLAVE_CHK_CFG,
                             ssap: crate::consts::SAP_MASTER_MS0,
                             fc: crate::fdl::FunctionCode::new_srd_low(self.fcb),
@@ -426,7 +1228,7 @@ impl<'a> Peripheral<'a> {
                 } else {
                     // When self.options.config is None, we need to wait before we can start with
                     // configuration.
-                    Err((tx, None))
+                    Err((tx, self.report_missing_configuration("config")))
                 }
             }
             PeripheralState::ValidateConfig => {
@@ -434,23 +1236,58 @@ impl<'a> Peripheral<'a> {
                 Ok(self.send_diagnostics_request(fdl, tx))
             }
             PeripheralState::DataExchange | PeripheralState::PreDataExchange => {
-                if self.diag_needed {
+                if let Some(interval) = self.diag_interval {
+                    let due = match self.last_diag {
+                        Some(last) => now - last >= interval,
+                        None => true,
+                    };
+                    if due {
+                        self.diag_needed = true;
+                    }
+                }
+
+                if self.dpv1.is_some_and(|req| req.is_awaiting_reply()) {
+                    // Resend the DPV1 telegram which was not answered.
+                    Ok(self.send_dpv1_telegram(fdl, tx))
+                } else if self.diag_needed {
                     Ok(self.send_diagnostics_request(fdl, tx))
+                } else if self.cfg_readback_needed {
+                    Ok(self.send_get_cfg_request(fdl, tx))
+                } else if self.dpv1.is_some() && self.dpv1_turn && !high_prio_only {
+                    Ok(self.send_dpv1_telegram(fdl, tx))
+                } else if self.retry_count == 0
+                    && self
+                        .dx_start
+                        .is_some_and(|start| now - start < self.options.min_slave_interval)
+                {
+                    // The peripheral cannot handle data exchange this often yet.
+                    Err((tx, None))
                 } else {
-                    #[cfg(feature = "debug-measure-roundtrip")]
-                    {
-                        self.tx_time = Some(now);
+                    // Alternate between DPV1 telegrams and data exchange so the outputs are
+                    // still updated while a DPV1 request is pending.
+                    self.dpv1_turn = true;
+                    self.output_commit_pending = false;
+                    self.tx_time = Some(now);
+                    if self.retry_count == 0 {
+                        self.dx_start = Some(now);
                     }
 
                     Ok(tx.send_data_telegram(
                         crate::fdl::DataTelegramHeader {
                             da: self.address,
-                            sa: fdl.parameters().address,
+                            sa: fdl.address(),
                             dsap: crate::consts::SAP_SLAVE_DATA_EXCHANGE,
                             ssap: crate::consts::SAP_MASTER_DATA_EXCHANGE,
                             fc: crate::fdl::FunctionCode::new_srd_high(self.fcb),
                         },
-                        self.pi_q.len(),
+                        // Peripherals supporting fail-safe mode receive empty data telegrams
+                        // in `Clear` state so they switch to their configured fail-safe
+                        // outputs.
+                        if dp.operating_state.is_clear() && self.options.fail_safe {
+                            0
+                        } else {
+                            self.pi_q.len()
+                        },
                         |buf| {
                             // Only write output process image in `Operate` state.  In `Clear`
                             // state, we leave the output process image all zeros.
@@ -473,6 +1310,54 @@ impl<'a> Peripheral<'a> {
         res
     }
 
+    /// Handle a request which was not answered, even after all retries by the FDL layer.
+    pub(crate) fn handle_timeout(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &crate::fdl::FdlActiveStation,
+    ) -> Option<PeripheralEvent> {
+        self.retry_count = 0;
+        if self.state == PeripheralState::Offline {
+            None
+        } else if self.tolerate_failure(now, fdl) {
+            // Only an intermittent failure, try again in the next cycle.
+            log::warn!(
+                "Peripheral #{} did not respond, tolerating intermittent failure.",
+                self.address
+            );
+            None
+        } else {
+            // Assume peripheral is now offline so the next step is sending SYNC messages
+            // to detect when it comes back.
+            log::warn!("Peripheral #{} stopped responding!", self.address);
+            self.state = PeripheralState::Offline;
+            // The peripheral will have forgotten about any pending DPV1 request.
+            if let Some(req) = self.dpv1.as_mut() {
+                req.step = crate::dp::dpv1::Dpv1Step::Request;
+            }
+            Some(PeripheralEvent::Offline)
+        }
+    }
+
+    /// Check whether exceeding the retry limit should be tolerated instead of going offline.
+    ///
+    /// This is the case for the first failure within the soft retry window.
+    fn tolerate_failure(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &crate::fdl::FdlActiveStation,
+    ) -> bool {
+        let window = fdl.parameters().soft_retry_window;
+        if self.state == PeripheralState::Offline || window == crate::time::Duration::ZERO {
+            return false;
+        }
+        if self.last_soft_failure.is_some_and(|t| now - t < window) {
+            return false;
+        }
+        self.last_soft_failure = Some(now);
+        true
+    }
+
     pub(crate) fn receive_reply(
         &mut self,
         now: crate::time::Instant,
@@ -481,10 +1366,18 @@ impl<'a> Peripheral<'a> {
         telegram: crate::fdl::Telegram,
     ) -> Option<PeripheralEvent> {
         match self.state {
+            // No requests are sent in this state.
+            PeripheralState::ParameterError => None,
             PeripheralState::Offline => {
                 // Diagnostics response
-                if self.handle_diagnostics_response(fdl, &telegram).is_some() {
+                if self
+                    .handle_diagnostics_response(now, fdl, &telegram)
+                    .is_some()
+                {
                     self.retry_count = 0;
+                    if let Err(event) = self.check_ident_number() {
+                        return event;
+                    }
                     self.state = PeripheralState::WaitForParam;
                     Some(PeripheralEvent::Online)
                 } else {
@@ -519,10 +1412,10 @@ impl<'a> Peripheral<'a> {
                 let address = self.address;
                 self.retry_count = 0;
                 let (new_state, event) =
-                    if let Some(diag) = self.handle_diagnostics_response(fdl, &telegram) {
+                    if let Some(diag) = self.handle_diagnostics_response(now, fdl, &telegram) {
                         if diag.flags.contains(DiagnosticFlags::PARAMETER_FAULT) {
                             log::warn!("Peripheral #{} reports a parameter fault!", address);
-                            // TODO: Going to `Offline` here will just end in a loop.
+                            // Repeated attempts are slowed down by handle_parameter_fault().
                             (
                                 PeripheralState::Offline,
                                 Some(PeripheralEvent::ParameterError),
@@ -532,12 +1425,7 @@ impl<'a> Peripheral<'a> {
                             // TODO: Going to `Offline` here will just end in a loop.
                             (PeripheralState::Offline, Some(PeripheralEvent::ConfigError))
                         } else if diag.flags.contains(DiagnosticFlags::PARAMETER_REQUIRED) {
-                            log::warn!(
-                            "Peripheral #{} wants parameters after completing setup?! Retrying...",
-                            address
-                        );
-                            // TODO: Report an event here?
-                            (PeripheralState::WaitForParam, None)
+                            self.handle_parameter_request(now)
                         } else if !diag.flags.contains(DiagnosticFlags::STATION_NOT_READY) {
                             log::info!("Peripheral #{} becomes ready for data exchange.", address);
                             (
@@ -551,17 +1439,46 @@ impl<'a> Peripheral<'a> {
                         (PeripheralState::ValidateConfig, None)
                     };
                 self.state = new_state;
-                event
+                // After a new setup, the peripheral is in neither SYNC nor FREEZE mode.
+                self.gc_modes = DiagnosticFlags::empty();
+                self.gc_mode_check = DiagnosticFlags::empty();
+                // An ident number mismatch takes precedence over all other diagnostics.
+                if let Err(event) = self.check_ident_number() {
+                    return event;
+                }
+                if event == Some(PeripheralEvent::ParameterError) {
+                    self.handle_parameter_fault(now)
+                } else {
+                    event
+                }
+            }
+            PeripheralState::DataExchange | PeripheralState::PreDataExchange
+                if self.dpv1.is_some_and(|req| req.is_awaiting_reply()) =>
+            {
+                self.retry_count = 0;
+                self.fcb.cycle();
+                self.handle_dpv1_reply(&telegram)
             }
             PeripheralState::DataExchange | PeripheralState::PreDataExchange => {
                 if self.diag_needed {
-                    if self.handle_diagnostics_response(fdl, &telegram).is_some() {
+                    if self
+                        .handle_diagnostics_response(now, fdl, &telegram)
+                        .is_some()
+                    {
                         self.retry_count = 0;
                         self.diag_needed = false;
+                        if let Err(event) = self.check_ident_number() {
+                            return event;
+                        }
+                        self.verify_global_control_modes();
                         Some(PeripheralEvent::Diagnostics)
                     } else {
                         None
                     }
+                } else if self.cfg_readback_needed {
+                    self.retry_count = 0;
+                    self.cfg_readback_needed = false;
+                    self.handle_get_cfg_response(&telegram)
                 } else {
                     let event = match telegram {
                         crate::fdl::Telegram::Data(t) => {
@@ -600,6 +1517,9 @@ impl<'a> Peripheral<'a> {
                                 if t.pdu.len() == self.pi_i.len() {
                                     self.pi_i.copy_from_slice(&t.pdu);
                                     self.state = PeripheralState::DataExchange;
+                                    self.last_data_exchange = Some(now);
+                                    self.prm_fault_count = 0;
+                                    self.prm_backoff_until = None;
                                     Some(PeripheralEvent::DataExchanged)
                                 } else {
                                     log::warn!(
@@ -623,13 +1543,19 @@ impl<'a> Peripheral<'a> {
                                 None
                             } else {
                                 self.state = PeripheralState::DataExchange;
+                                self.last_data_exchange = Some(now);
+                                self.prm_fault_count = 0;
+                                self.prm_backoff_until = None;
                                 Some(PeripheralEvent::DataExchanged)
                             }
                         }
                         crate::fdl::Telegram::Token(_) => unreachable!(),
                     };
-                    #[cfg(feature = "debug-measure-roundtrip")]
-                    if let Some(tx_time) = self.tx_time {
+                    if let (Some(PeripheralEvent::DataExchanged), Some(tx_time)) =
+                        (&event, self.tx_time.take())
+                    {
+                        self.last_cycle_time = Some(now - tx_time);
+                        #[cfg(feature = "debug-measure-roundtrip")]
                         log::debug!(
                             "Data-Exchange Roundtrip Time for #{}: {} us",
                             self.address,
@@ -652,7 +1578,7 @@ impl<'a> Peripheral<'a> {
         tx.send_data_telegram(
             crate::fdl::DataTelegramHeader {
                 da: self.address,
-                sa: master.parameters().address,
+                sa: master.address(),
                 dsap: crate::consts::SAP_SLAVE_DIAGNOSIS,
                 ssap: crate::consts::SAP_MASTER_MS0,
                 fc: crate::fdl::FunctionCode::new_srd_low(self.fcb),
@@ -662,8 +1588,238 @@ impl<'a> Peripheral<'a> {
         )
     }
 
+    fn send_get_cfg_request(
+        &mut self,
+        master: &crate::fdl::FdlActiveStation,
+        tx: crate::fdl::TelegramTx,
+    ) -> crate::fdl::TelegramTxResponse {
+        tx.send_data_telegram(
+            crate::fdl::DataTelegramHeader {
+                da: self.address,
+                sa: master.address(),
+                dsap: crate::consts::SAP_SLAVE_GET_CFG,
+                ssap: crate::consts::SAP_MASTER_MS0,
+                fc: crate::fdl::FunctionCode::new_srd_low(self.fcb),
+            },
+            0,
+            |_buf| (),
+        )
+    }
+
+    fn handle_get_cfg_response(
+        &mut self,
+        telegram: &crate::fdl::Telegram,
+    ) -> Option<PeripheralEvent> {
+        let t = match telegram {
+            crate::fdl::Telegram::Data(t) if t.h.ssap == crate::consts::SAP_SLAVE_GET_CFG => t,
+            _ => {
+                log::warn!(
+                    "Unexpected response to Get_Cfg by #{}: {telegram:?}",
+                    self.address
+                );
+                return None;
+            }
+        };
+        self.fcb.cycle();
+
+        let length = t.pdu.len().min(self.cfg_readback_buffer.len());
+        if length < t.pdu.len() {
+            log::warn!(
+                "Config of #{} does not fit the readback buffer ({} > {} bytes)!",
+                self.address,
+                t.pdu.len(),
+                length
+            );
+        }
+        self.cfg_readback_buffer[..length].copy_from_slice(&t.pdu[..length]);
+        self.cfg_readback = Some(length);
+        log::debug!("Config readback (#{}): {:?}", self.address, t.pdu);
+        Some(PeripheralEvent::ConfigReadback)
+    }
+
+    fn send_dpv1_telegram(
+        &mut self,
+        master: &crate::fdl::FdlActiveStation,
+        tx: crate::fdl::TelegramTx,
+    ) -> crate::fdl::TelegramTxResponse {
+        use crate::dp::dpv1::Dpv1Step;
+
+        self.dpv1_turn = false;
+        let req = self.dpv1.as_mut().unwrap();
+        let header = crate::fdl::DataTelegramHeader {
+            da: self.address,
+            sa: master.address(),
+            dsap: crate::consts::SAP_SLAVE_DPV1_MS1,
+            ssap: crate::consts::SAP_MASTER_MS1,
+            fc: crate::fdl::FunctionCode::new_srd_low(self.fcb),
+        };
+        match req.step {
+            Dpv1Step::Request | Dpv1Step::AwaitAck => {
+                req.step = Dpv1Step::AwaitAck;
+                let req = *req;
+                let write_length = if req.function == crate::dp::dpv1::FUNCTION_WRITE {
+                    usize::from(req.length)
+                } else {
+                    0
+                };
+                tx.send_data_telegram(header, 4 + write_length, |buf| {
+                    buf[..4].copy_from_slice(&[req.function, req.slot, req.index, req.length]);
+                    buf[4..].copy_from_slice(&self.dpv1_buffer[..write_length]);
+                })
+            }
+            Dpv1Step::Poll | Dpv1Step::AwaitResponse => {
+                // Poll for the response using an empty request
+                req.step = Dpv1Step::AwaitResponse;
+                tx.send_data_telegram(header, 0, |_buf| ())
+            }
+        }
+    }
+
+    fn handle_dpv1_reply(&mut self, telegram: &crate::fdl::Telegram) -> Option<PeripheralEvent> {
+        let req = self.dpv1.as_mut().unwrap();
+        let result = match telegram {
+            // The response is not available yet, poll again later.
+            crate::fdl::Telegram::ShortConfirmation(_) => {
+                req.step = crate::dp::dpv1::Dpv1Step::Poll;
+                return None;
+            }
+            crate::fdl::Telegram::Data(t) => match t.is_response() {
+                Some(crate::fdl::ResponseStatus::Ok | crate::fdl::ResponseStatus::DataLow)
+                    if t.pdu.is_empty() =>
+                {
+                    req.step = crate::dp::dpv1::Dpv1Step::Poll;
+                    return None;
+                }
+                Some(crate::fdl::ResponseStatus::SapNotEnabled) => {
+                    Err(crate::dp::Dpv1Error::NotSupported)
+                }
+                Some(crate::fdl::ResponseStatus::DataHigh) => {
+                    self.diag_needed = true;
+                    req.parse_response(t.pdu).map(|data| data.len())
+                }
+                _ => req.parse_response(t.pdu).map(|data| data.len()),
+            },
+            crate::fdl::Telegram::Token(_) => unreachable!(),
+        };
+
+        // Copy the received data into the DPV1 buffer
+        if let (Ok(length), crate::fdl::Telegram::Data(t)) = (result, telegram) {
+            self.dpv1_buffer[..length].copy_from_slice(&t.pdu[4..4 + length]);
+        }
+        if let Err(e) = result {
+            log::debug!("DPV1 request to #{} failed: {}", self.address, e);
+        }
+        self.dpv1 = None;
+        self.dpv1_response = Some(result);
+        Some(PeripheralEvent::Dpv1Response)
+    }
+
+    /// Check the ident number from the last diagnostics against the configured one.
+    ///
+    /// On mismatch, the peripheral is moved back to `Offline` so it is not parameterized.  The
+    /// `IdentMismatch` event is only returned the first time the mismatch is detected.
+    fn check_ident_number(&mut self) -> Result<(), Option<PeripheralEvent>> {
+        let got = match self.diag.as_ref() {
+            Some(diag) => diag.ident_number,
+            None => return Ok(()),
+        };
+        let expected = self.options.ident_number;
+        if got == expected {
+            self.ident_mismatch = false;
+            return Ok(());
+        }
+
+        self.state = PeripheralState::Offline;
+        if self.ident_mismatch {
+            Err(None)
+        } else {
+            log::warn!(
+                "Peripheral #{} reports ident number 0x{:04x}, expected 0x{:04x}!",
+                self.address,
+                got,
+                expected
+            );
+            self.ident_mismatch = true;
+            Err(Some(PeripheralEvent::IdentMismatch { expected, got }))
+        }
+    }
+
+    /// Report that the peripheral is stuck because `field` of its options is `None`.
+    ///
+    /// Only the first time is reported.
+    fn report_missing_configuration(&mut self, field: &str) -> Option<PeripheralEvent> {
+        if self.missing_configuration {
+            return None;
+        }
+        log::warn!(
+            "Peripheral #{} has no {} and will not enter data exchange!",
+            self.address,
+            field
+        );
+        self.missing_configuration = true;
+        Some(PeripheralEvent::MissingConfiguration)
+    }
+
+    /// Track consecutive parameter rejections and back off from re-parameterization.
+    ///
+    /// Only the first rejection in a series is reported as an event.
+    fn handle_parameter_fault(&mut self, now: crate::time::Instant) -> Option<PeripheralEvent> {
+        self.prm_fault_count = self.prm_fault_count.saturating_add(1);
+
+        if self.prm_fault_count >= PRM_FAULT_BACKOFF_THRESHOLD {
+            let exponent = u32::from(self.prm_fault_count - PRM_FAULT_BACKOFF_THRESHOLD).min(16);
+            let backoff = (PRM_FAULT_BACKOFF_MIN << exponent).min(PRM_FAULT_BACKOFF_MAX);
+            log::debug!(
+                "Peripheral #{} rejected parameters {} times, retrying in {}.",
+                self.address,
+                self.prm_fault_count,
+                backoff
+            );
+            self.prm_backoff_until = Some(now + backoff);
+        }
+
+        if self.prm_fault_count == 1 {
+            Some(PeripheralEvent::ParameterError)
+        } else {
+            None
+        }
+    }
+
+    /// Track parameter requests after setup and stop when the peripheral is stuck in a loop.
+    fn handle_parameter_request(
+        &mut self,
+        now: crate::time::Instant,
+    ) -> (PeripheralState, Option<PeripheralEvent>) {
+        let (count, start) = match self.prm_requests {
+            Some((count, start)) if now - start < PRM_LOOP_WINDOW => {
+                (count.saturating_add(1), start)
+            }
+            _ => (1, now),
+        };
+        self.prm_requests = Some((count, start));
+
+        if count >= PRM_LOOP_THRESHOLD {
+            log::warn!(
+                "Peripheral #{} requested parameters {} times after setup, giving up until reset!",
+                self.address,
+                count
+            );
+            (
+                PeripheralState::ParameterError,
+                Some(PeripheralEvent::ParameterLoop),
+            )
+        } else {
+            log::warn!(
+                "Peripheral #{} wants parameters after completing setup?! Retrying...",
+                self.address
+            );
+            (PeripheralState::WaitForParam, None)
+        }
+    }
+
     fn handle_diagnostics_response(
         &mut self,
+        now: crate::time::Instant,
         master: &crate::fdl::FdlActiveStation,
         telegram: &crate::fdl::Telegram,
     ) -> Option<&DiagnosticsInfo> {
@@ -725,6 +1881,7 @@ impl<'a> Peripheral<'a> {
             self.fcb.cycle();
 
             self.diag = Some(diag);
+            self.last_diag = Some(now);
             self.diag.as_ref()
         } else {
             // TODO: How to deal with this properly?
@@ -736,3 +1893,114 @@ impl<'a> Peripheral<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn io_lengths() {
+        let mut options = PeripheralOptions::default();
+        assert_eq!(options.io_lengths(), None);
+        options.config = Some(&[0x00, 0x20, 0x10, 0x51, 0xf1]);
+        assert_eq!(options.io_lengths(), Some((1 + 4 + 4, 1 + 4)));
+        // Special format: 2 bytes outputs; 20 words inputs, 12 bytes outputs, 1 byte manufacturer
+        // specific data
+        options.config = Some(&[0x40, 0x01, 0xc1, 0x0b, 0x53, 0x42]);
+        assert_eq!(options.io_lengths(), Some((40, 2 + 12)));
+        // Truncated
+        options.config = Some(&[0x10, 0xc0, 0x01]);
+        assert_eq!(options.io_lengths(), None);
+    }
+
+    #[test]
+    fn module_io() {
+        let mut options = PeripheralOptions::default();
+        assert_eq!(options.module_ranges(0), None);
+        options.config = Some(&[0x00, 0x20, 0x10, 0x51, 0xc1, 0x0b, 0x53, 0x42]);
+        assert_eq!(options.module_ranges(0), Some((0..0, 0..0)));
+        assert_eq!(options.module_ranges(1), Some((0..0, 0..1)));
+        assert_eq!(options.module_ranges(3), Some((1..5, 1..1)));
+        assert_eq!(options.module_ranges(4), Some((5..45, 1..13)));
+        assert_eq!(options.module_ranges(5), None);
+
+        let mut pi_i = [0u8; 45];
+        let mut pi_q = [0u8; 13];
+        pi_i[1..5].copy_from_slice(&[1, 2, 3, 4]);
+        let mut peripheral = Peripheral::new(7, options, &mut pi_i[..], &mut pi_q[..]);
+        assert_eq!(peripheral.module_input(3), &[1, 2, 3, 4]);
+        peripheral.module_output_mut(1)[0] = 0x55;
+        assert_eq!(peripheral.module_output(1), &[0x55]);
+        assert_eq!(peripheral.pi_q()[0], 0x55);
+    }
+
+    #[test]
+    fn typed_process_image_access() {
+        let mut pi_i = [0x12, 0x34, 0xff, 0xfe, 0x00, 0x01];
+        let mut pi_q = [0u8; 6];
+        let mut peripheral = Peripheral::new(7, Default::default(), &mut pi_i[..], &mut pi_q[..]);
+        assert_eq!(peripheral.pi_i_u16(0), 0x1234);
+        assert_eq!(peripheral.pi_i_i16(2), -2);
+        assert_eq!(peripheral.pi_i_u32(2), 0xfffe0001);
+        assert_eq!(peripheral.pi_i_i32(0), 0x1234fffe);
+
+        peripheral.pi_q_set_u16(0, 0x1234);
+        peripheral.pi_q_set_i32(2, -2);
+        assert_eq!(peripheral.pi_q(), &[0x12, 0x34, 0xff, 0xff, 0xff, 0xfe]);
+        peripheral.pi_q_set_i16(4, 1);
+        peripheral.pi_q_set_u32(0, 0xdeadbeef);
+        assert_eq!(peripheral.pi_q(), &[0xde, 0xad, 0xbe, 0xef, 0x00, 0x01]);
+    }
+
+    #[test]
+    #[should_panic = "2 bytes at offset 5 are out of range for a process image of 6 bytes"]
+    fn typed_process_image_out_of_range() {
+        let peripheral = Peripheral::new(7, Default::default(), vec![0u8; 6], Vec::new());
+        peripheral.pi_i_u16(5);
+    }
+
+    #[test]
+    #[should_panic = "Peripheral #7 has no module 1"]
+    fn module_io_out_of_range() {
+        let options = PeripheralOptions {
+            config: Some(&[0x10]),
+            ..Default::default()
+        };
+        let peripheral = Peripheral::new(7, options, vec![0u8; 1], Vec::new());
+        peripheral.module_input(1);
+    }
+
+    #[test]
+    fn reset_keeps_critical() {
+        let mut peripheral = Peripheral::new(7, Default::default(), Vec::new(), Vec::new());
+        peripheral.set_critical(false);
+        peripheral.reset();
+        assert!(!peripheral.is_critical());
+        peripheral.reset_address(8);
+        assert!(!peripheral.is_critical());
+        assert_eq!(peripheral.address(), 8);
+    }
+
+    #[test]
+    fn diagnostics_helpers() {
+        let ext_diag = crate::dp::ExtendedDiagnostics::default();
+        let mut diag = PeripheralDiagnostics {
+            flags: DiagnosticFlags::empty(),
+            ident_number: 0x1337,
+            master_address: None,
+            extended_diagnostics: &ext_diag,
+        };
+        assert!(!diag.needs_parameterization());
+        assert!(!diag.has_extended_diagnostics());
+
+        diag.flags = DiagnosticFlags::STATION_NOT_READY | DiagnosticFlags::PARAMETER_REQUIRED;
+        assert!(diag.needs_parameterization());
+
+        diag.flags = DiagnosticFlags::PARAMETER_FAULT;
+        assert!(diag.needs_parameterization());
+
+        diag.flags = DiagnosticFlags::EXT_DIAG | DiagnosticFlags::STATUS_DIAGNOSTICS;
+        assert!(!diag.needs_parameterization());
+        assert!(diag.has_extended_diagnostics());
+    }
+}
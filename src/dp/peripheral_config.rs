@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+
+/// Owned configuration of a peripheral
+///
+/// Unlike [`PeripheralOptions`][`crate::dp::PeripheralOptions`], this type owns all its data.
+/// With the `serde` feature, it can be serialized and deserialized.  This allows loading the
+/// configuration generated by `gsdtool config-wizard --format json` at runtime:
+///
+/// ```no_run
+/// # fn load_config() -> profirust::dp::PeripheralConfig { unimplemented!() }
+/// # let mut dp_master = profirust::dp::DpMaster::new(vec![]);
+/// // e.g. deserialized from a JSON file using `serde_json`
+/// let config = load_config();
+/// let options = config.options(profirust::Baudrate::B19200).unwrap();
+/// let (inputs, outputs) = options.io_lengths().unwrap();
+/// let handle = dp_master.add(profirust::dp::Peripheral::new(
+///     7, options, vec![0u8; inputs], vec![0u8; outputs],
+/// ));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PeripheralConfig {
+    /// Ident number to ensure the peripheral matches the GSD file
+    pub ident_number: u16,
+    /// UserPrm constructed from the GSD file
+    pub user_parameters: Vec<u8>,
+    /// Configuration constructed from the GSD file
+    pub config: Vec<u8>,
+    /// Maximum response time (Tsdr) of this peripheral for each supported baudrate
+    pub max_tsdr: BTreeMap<crate::Baudrate, u16>,
+    /// Whether this peripheral supports fail-safe mode
+    pub fail_safe: bool,
+    /// Minimum time between two data exchange cycles with this peripheral
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub min_slave_interval: crate::time::Duration,
+}
+
+impl PeripheralConfig {
+    /// Build the [`PeripheralOptions`][`crate::dp::PeripheralOptions`] for a bus running at the
+    /// given baudrate.
+    ///
+    /// Returns `None` when the peripheral does not support this baudrate.
+    pub fn options(&self, baudrate: crate::Baudrate) -> Option<crate::dp::PeripheralOptions<'_>> {
+        let max_tsdr = *self.max_tsdr.get(&baudrate)?;
+        Some(crate::dp::PeripheralOptions {
+            ident_number: self.ident_number,
+            max_tsdr,
+            fail_safe: self.fail_safe,
+            min_slave_interval: self.min_slave_interval,
+            user_parameters: Some(&self.user_parameters),
+            config: Some(&self.config),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_for_baudrate() {
+        let config = PeripheralConfig {
+            ident_number: 0x4711,
+            user_parameters: vec![0x00, 0x0a],
+            config: vec![0x21, 0x13],
+            max_tsdr: [
+                (crate::Baudrate::B19200, 15),
+                (crate::Baudrate::B1500000, 25),
+            ]
+            .into_iter()
+            .collect(),
+            fail_safe: true,
+            min_slave_interval: crate::time::Duration::from_millis(6),
+        };
+
+        let options = config.options(crate::Baudrate::B1500000).unwrap();
+        assert_eq!(options.ident_number, 0x4711);
+        assert_eq!(options.max_tsdr, 25);
+        assert!(options.fail_safe);
+        assert_eq!(
+            options.min_slave_interval,
+            crate::time::Duration::from_millis(6)
+        );
+        assert_eq!(options.user_parameters, Some(&[0x00, 0x0a][..]));
+        assert_eq!(options.config, Some(&[0x21, 0x13][..]));
+        assert_eq!(options.io_lengths(), Some((4, 2)));
+
+        assert_eq!(
+            config.options(crate::Baudrate::B19200).unwrap().max_tsdr,
+            15
+        );
+        assert!(config.options(crate::Baudrate::B12000000).is_none());
+    }
+}
@@ -5,13 +5,18 @@ use core::fmt;
 #[derive(Default)]
 pub struct PeripheralStorage<'a> {
     inner: Option<Peripheral<'a>>,
+    generation: u16,
 }
 
 /// Handle that can be used to obtain a peripheral from the DP master.
+///
+/// Handles of removed peripherals stay invalid, even when a new peripheral is added at the same
+/// address.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct PeripheralHandle {
     index: u8,
     address: u8,
+    generation: u16,
 }
 
 impl PeripheralHandle {
@@ -27,8 +32,25 @@ impl fmt::Display for PeripheralHandle {
     }
 }
 
+/// Error when adding a peripheral to a fixed-size storage which is already full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError {
+    /// Number of peripherals the storage can hold.
+    pub capacity: usize,
+}
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "peripheral storage is full (capacity {})", self.capacity)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CapacityError {}
+
 pub struct PeripheralSet<'a> {
     peripherals: managed::ManagedSlice<'a, PeripheralStorage<'a>>,
+    next_generation: u16,
 }
 
 impl<'a> PeripheralSet<'a> {
@@ -38,6 +60,7 @@ impl<'a> PeripheralSet<'a> {
     {
         Self {
             peripherals: storage.into(),
+            next_generation: 0,
         }
     }
 
@@ -46,29 +69,51 @@ impl<'a> PeripheralSet<'a> {
     /// # Panics
     /// This function panics if the storage is fixed-size (not a `Vec`) and is full.
     pub fn add(&mut self, peripheral: Peripheral<'a>) -> PeripheralHandle {
+        match self.try_add(peripheral) {
+            Ok(handle) => handle,
+            Err(_) => panic!("Adding peripheral to full PeripheralSet"),
+        }
+    }
+
+    /// Add a peripheral to the set, and return its handle.
+    ///
+    /// Returns a [`CapacityError`] if the storage is fixed-size (not a `Vec`) and is full.
+    pub fn try_add(
+        &mut self,
+        peripheral: Peripheral<'a>,
+    ) -> Result<PeripheralHandle, CapacityError> {
+        let generation = self.next_generation;
         for (index, slot) in self.peripherals.iter_mut().enumerate() {
             if slot.inner.is_none() {
                 let address = peripheral.address();
                 slot.inner = Some(peripheral);
-                return PeripheralHandle {
+                slot.generation = generation;
+                self.next_generation = generation.wrapping_add(1);
+                return Ok(PeripheralHandle {
                     index: u8::try_from(index).unwrap(),
                     address,
-                };
+                    generation,
+                });
             }
         }
 
         match &mut self.peripherals {
-            managed::ManagedSlice::Borrowed(_) => panic!("Adding peripheral to full PeripheralSet"),
+            managed::ManagedSlice::Borrowed(peripherals) => Err(CapacityError {
+                capacity: peripherals.len(),
+            }),
             #[cfg(any(feature = "std", feature = "alloc"))]
             managed::ManagedSlice::Owned(peripherals) => {
                 let address = peripheral.address();
                 peripherals.push(PeripheralStorage {
                     inner: Some(peripheral),
+                    generation,
                 });
-                PeripheralHandle {
+                self.next_generation = generation.wrapping_add(1);
+                Ok(PeripheralHandle {
                     index: (peripherals.len() - 1).try_into().unwrap(),
                     address,
-                }
+                    generation,
+                })
             }
         }
     }
@@ -76,24 +121,41 @@ impl<'a> PeripheralSet<'a> {
     /// Get a peripheral from the set by its handle, as mutable.
     ///
     /// # Panics
-    /// This function may panic if the handle does not belong to this peripheral set.
+    /// This function may panic if the handle does not belong to this peripheral set.  It also
+    /// panics if the peripheral was removed.
     pub fn get_mut(&mut self, handle: PeripheralHandle) -> &mut Peripheral<'a> {
-        self.peripherals[usize::from(handle.index)]
-            .inner
-            .as_mut()
-            .expect("Handle does not refer to a valid peripheral")
+        let slot = &mut self.peripherals[usize::from(handle.index)];
+        match slot.inner.as_mut() {
+            Some(p) if slot.generation == handle.generation => p,
+            _ => panic!("{handle} was removed from the peripheral set"),
+        }
+    }
+
+    /// Remove a peripheral from the set and return it.
+    ///
+    /// The storage slot is freed and can be reused by the next [`add()`][`PeripheralSet::add`].
+    /// The handle is invalid afterwards.  Returns `None` if the peripheral was already removed.
+    pub fn remove(&mut self, handle: PeripheralHandle) -> Option<Peripheral<'a>> {
+        let slot = self.peripherals.get_mut(usize::from(handle.index))?;
+        match &slot.inner {
+            Some(_) if slot.generation == handle.generation => slot.inner.take(),
+            _ => None,
+        }
     }
 
+    /// Iterate over all peripherals in the set, in the order of their storage slots.
     pub fn iter_mut(&mut self) -> impl Iterator<Item = (PeripheralHandle, &mut Peripheral<'a>)> {
         self.peripherals
             .iter_mut()
             .enumerate()
-            .filter_map(|(i, p)| {
-                p.inner.as_mut().map(|p| {
+            .filter_map(|(i, slot)| {
+                let generation = slot.generation;
+                slot.inner.as_mut().map(|p| {
                     (
                         PeripheralHandle {
                             index: u8::try_from(i).unwrap(),
                             address: p.address(),
+                            generation,
                         },
                         p,
                     )
@@ -101,13 +163,15 @@ impl<'a> PeripheralSet<'a> {
             })
     }
 
+    /// Iterate over all peripherals in the set, in the order of their storage slots.
     pub fn iter(&self) -> impl Iterator<Item = (PeripheralHandle, &Peripheral<'a>)> {
-        self.peripherals.iter().enumerate().filter_map(|(i, p)| {
-            p.inner.as_ref().map(|p| {
+        self.peripherals.iter().enumerate().filter_map(|(i, slot)| {
+            slot.inner.as_ref().map(|p| {
                 (
                     PeripheralHandle {
                         index: u8::try_from(i).unwrap(),
                         address: p.address(),
+                        generation: slot.generation,
                     },
                     p,
                 )
@@ -123,12 +187,14 @@ impl<'a> PeripheralSet<'a> {
             .iter_mut()
             .enumerate()
             .skip(usize::from(index))
-            .find_map(|(i, p)| {
-                p.inner.as_mut().map(|p| {
+            .find_map(|(i, slot)| {
+                let generation = slot.generation;
+                slot.inner.as_mut().map(|p| {
                     (
                         PeripheralHandle {
                             index: u8::try_from(i).unwrap(),
                             address: p.address(),
+                            generation,
                         },
                         p,
                     )
@@ -151,6 +217,115 @@ impl<'a> PeripheralSet<'a> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn iter_stable_across_add() {
+        let mut set = PeripheralSet::new(Vec::new());
+        let h1 = set.add(Peripheral::new(
+            5,
+            Default::default(),
+            &mut [][..],
+            &mut [][..],
+        ));
+        let h2 = set.add(Peripheral::new(
+            3,
+            Default::default(),
+            &mut [][..],
+            &mut [][..],
+        ));
+
+        let handles: Vec<_> = set.iter().map(|(h, _)| h).collect();
+        assert_eq!(handles, &[h1, h2]);
+
+        let h3 = set.add(Peripheral::new(
+            4,
+            Default::default(),
+            &mut [][..],
+            &mut [][..],
+        ));
+
+        let handles: Vec<_> = set.iter().map(|(h, _)| h).collect();
+        assert_eq!(handles, &[h1, h2, h3]);
+        let handles: Vec<_> = set.iter_mut().map(|(h, _)| h).collect();
+        assert_eq!(handles, &[h1, h2, h3]);
+
+        for (h, p) in set.iter() {
+            assert_eq!(h.address(), p.address());
+        }
+        assert_eq!(set.get_mut(h2).address(), 3);
+    }
+
+    #[test]
+    fn remove_frees_slot() {
+        let mut set = PeripheralSet::new(Vec::new());
+        let h1 = set.add(Peripheral::new(
+            5,
+            Default::default(),
+            &mut [][..],
+            &mut [][..],
+        ));
+        let h2 = set.add(Peripheral::new(
+            3,
+            Default::default(),
+            &mut [][..],
+            &mut [][..],
+        ));
+
+        assert_eq!(set.remove(h1).unwrap().address(), 5);
+        assert!(set.remove(h1).is_none());
+        let handles: Vec<_> = set.iter().map(|(h, _)| h).collect();
+        assert_eq!(handles, &[h2]);
+
+        // The freed slot is reused, but the old handle stays invalid
+        let h3 = set.add(Peripheral::new(
+            4,
+            Default::default(),
+            &mut [][..],
+            &mut [][..],
+        ));
+        assert_eq!(set.peripherals.len(), 2);
+        assert!(set.remove(h1).is_none());
+        assert_eq!(set.get_mut(h3).address(), 4);
+    }
+
+    #[test]
+    fn stale_handle_same_address() {
+        let mut set = PeripheralSet::new(Vec::new());
+        let h1 = set.add(Peripheral::new(
+            5,
+            Default::default(),
+            &mut [][..],
+            &mut [][..],
+        ));
+        set.remove(h1);
+
+        // A new peripheral at the same address reuses the slot but gets a new handle
+        let h2 = set.add(Peripheral::new(
+            5,
+            Default::default(),
+            &mut [][..],
+            &mut [][..],
+        ));
+        assert_ne!(h1, h2);
+        assert!(set.remove(h1).is_none());
+        assert_eq!(set.get_mut(h2).address(), 5);
+        let handles: Vec<_> = set.iter().map(|(h, _)| h).collect();
+        assert_eq!(handles, &[h2]);
+    }
+
+    #[test]
+    #[should_panic = "Station 5 was removed"]
+    fn get_removed_peripheral() {
+        let mut set = PeripheralSet::new(Vec::new());
+        let h = set.add(Peripheral::new(
+            5,
+            Default::default(),
+            &mut [][..],
+            &mut [][..],
+        ));
+        set.remove(h);
+        set.get_mut(h);
+    }
+
     #[test]
     fn cycle_index_api() {
         let buffer = [
@@ -159,18 +334,22 @@ mod tests {
             PeripheralStorage::default(),
             PeripheralStorage {
                 inner: Some(Peripheral::default()),
+                ..Default::default()
             },
             PeripheralStorage::default(),
             PeripheralStorage::default(),
             PeripheralStorage {
                 inner: Some(Peripheral::default()),
+                ..Default::default()
             },
             PeripheralStorage {
                 inner: Some(Peripheral::default()),
+                ..Default::default()
             },
             PeripheralStorage::default(),
             PeripheralStorage {
                 inner: Some(Peripheral::default()),
+                ..Default::default()
             },
             PeripheralStorage::default(),
             PeripheralStorage::default(),
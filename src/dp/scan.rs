@@ -32,66 +32,67 @@ impl DpScanner {
     pub fn take_last_event(&mut self) -> Option<DpScanEvent> {
         self.pending_event.take()
     }
+}
 
-    fn parse_diag_response(
-        &self,
-        telegram: crate::fdl::Telegram,
-        address: crate::Address,
-    ) -> Option<crate::dp::DiagnosticsInfo> {
-        if let crate::fdl::Telegram::Data(t) = telegram {
-            if t.h.dsap != crate::consts::SAP_MASTER_MS0 {
-                log::warn!("Diagnostics response by #{} to wrong SAP: {t:?}", address);
-                return None;
-            }
-            if t.h.ssap != crate::consts::SAP_SLAVE_DIAGNOSIS {
-                log::warn!("Diagnostics response by #{} from wrong SAP: {t:?}", address);
-                return None;
-            }
-            if t.pdu.len() < 6 {
-                log::warn!("Diagnostics response by #{} is too short: {t:?}", address);
-                return None;
-            }
-
-            let master_address = if t.pdu[3] == 255 {
-                None
-            } else {
-                Some(t.pdu[3])
-            };
+fn parse_diag_response(
+    telegram: crate::fdl::Telegram,
+    address: crate::Address,
+) -> Option<crate::dp::DiagnosticsInfo> {
+    if let crate::fdl::Telegram::Data(t) = telegram {
+        if t.h.dsap != crate::consts::SAP_MASTER_MS0 {
+            log::warn!("Diagnostics response by #{} to wrong SAP: {t:?}", address);
+            return None;
+        }
+        if t.h.ssap != crate::consts::SAP_SLAVE_DIAGNOSIS {
+            log::warn!("Diagnostics response by #{} from wrong SAP: {t:?}", address);
+            return None;
+        }
+        if t.pdu.len() < 6 {
+            log::warn!("Diagnostics response by #{} is too short: {t:?}", address);
+            return None;
+        }
 
-            let mut diag = crate::dp::DiagnosticsInfo {
-                flags: crate::dp::DiagnosticFlags::from_bits_retain(u16::from_le_bytes(
-                    t.pdu[0..2].try_into().unwrap(),
-                )),
-                master_address,
-                ident_number: u16::from_be_bytes(t.pdu[4..6].try_into().unwrap()),
-            };
+        let master_address = if t.pdu[3] == 255 {
+            None
+        } else {
+            Some(t.pdu[3])
+        };
 
-            if !diag
-                .flags
-                .contains(crate::dp::DiagnosticFlags::PERMANENT_BIT)
-            {
-                log::warn!("Inconsistent diagnostics for peripheral #{}!", address);
-            }
-            // we don't need the permanent bit anymore now
-            diag.flags.remove(crate::dp::DiagnosticFlags::PERMANENT_BIT);
+        let mut diag = crate::dp::DiagnosticsInfo {
+            flags: crate::dp::DiagnosticFlags::from_bits_retain(u16::from_le_bytes(
+                t.pdu[0..2].try_into().unwrap(),
+            )),
+            master_address,
+            ident_number: u16::from_be_bytes(t.pdu[4..6].try_into().unwrap()),
+        };
 
-            log::debug!("Peripheral Diagnostics (#{}): {:?}", address, diag);
+        if !diag
+            .flags
+            .contains(crate::dp::DiagnosticFlags::PERMANENT_BIT)
+        {
+            log::warn!("Inconsistent diagnostics for peripheral #{}!", address);
+        }
+        // we don't need the permanent bit anymore now
+        diag.flags.remove(crate::dp::DiagnosticFlags::PERMANENT_BIT);
 
-            if diag.flags.contains(crate::dp::DiagnosticFlags::EXT_DIAG) {
-                log::debug!("Extended Diagnostics (#{}): {:?}", address, &t.pdu[6..]);
-            }
+        log::debug!("Peripheral Diagnostics (#{}): {:?}", address, diag);
 
-            Some(diag)
-        } else {
-            log::warn!(
-                "Unexpected diagnostics response for #{}: {telegram:?}",
-                address
-            );
-            None
+        if diag.flags.contains(crate::dp::DiagnosticFlags::EXT_DIAG) {
+            log::debug!("Extended Diagnostics (#{}): {:?}", address, &t.pdu[6..]);
         }
+
+        Some(diag)
+    } else {
+        log::warn!(
+            "Unexpected diagnostics response for #{}: {telegram:?}",
+            address
+        );
+        None
     }
 }
 
+crate::fdl::impl_any_application!(DpScanner, DpScanner);
+
 impl crate::fdl::FdlApplication for DpScanner {
     fn transmit_telegram(
         &mut self,
@@ -100,7 +101,7 @@ impl crate::fdl::FdlApplication for DpScanner {
         tx: crate::fdl::TelegramTx,
         high_prio_only: bool,
     ) -> Option<crate::fdl::TelegramTxResponse> {
-        let this_station = fdl.parameters().address;
+        let this_station = fdl.address();
         let address = self.cursor;
 
         if self.current_address_done {
@@ -112,7 +113,8 @@ impl crate::fdl::FdlApplication for DpScanner {
             }
             None
         } else {
-            Some(tx.send_data_telegram(
+            // Absent stations are expected, so don't waste time on retries.
+            let tx_res = tx.send_data_telegram(
                 crate::fdl::DataTelegramHeader {
                     da: address,
                     sa: this_station,
@@ -122,7 +124,8 @@ impl crate::fdl::FdlApplication for DpScanner {
                 },
                 0,
                 |_buf| (),
-            ))
+            );
+            Some(tx_res.without_retries())
         }
     }
 
@@ -136,7 +139,7 @@ impl crate::fdl::FdlApplication for DpScanner {
         let station_unknown = !self.stations.get(usize::from(address)).unwrap();
         self.current_address_done = true;
 
-        let event = if let Some(diag) = self.parse_diag_response(telegram, address) {
+        let event = if let Some(diag) = parse_diag_response(telegram, address) {
             let desc = DpPeripheralDescription {
                 address,
                 ident: diag.ident_number,
@@ -177,3 +180,258 @@ impl crate::fdl::FdlApplication for DpScanner {
         }
     }
 }
+
+/// Identity of a station found by a [`BusScan`]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ScannedStation {
+    pub address: crate::Address,
+    pub ident: u16,
+    /// Master which has locked the station, if any
+    pub master_address: Option<crate::Address>,
+    /// Diagnostic flags reported by the station
+    pub flags: crate::dp::DiagnosticFlags,
+}
+
+impl ScannedStation {
+    /// Whether the station needs parameters before it can enter data exchange.
+    pub fn needs_parameters(&self) -> bool {
+        self.flags
+            .contains(crate::dp::DiagnosticFlags::PARAMETER_REQUIRED)
+    }
+}
+
+impl core::fmt::Display for ScannedStation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "address {}: ident 0x{:04X}", self.address, self.ident)?;
+        if self.needs_parameters() {
+            write!(f, ", needs parameters")?;
+        } else if let Some(master) = self.master_address {
+            write!(f, ", locked by master #{master}")?;
+        }
+        Ok(())
+    }
+}
+
+/// One-shot scan of all addresses on the bus
+///
+/// The scan requests diagnostics from every address once (including the default address 126 of
+/// peripherals which were not assigned an address yet) and collects the identity of all
+/// responding stations.  The scan is low priority traffic, so it pauses while the FDL layer
+/// only permits high priority telegrams.  It completes its cycle only after the last address was scanned, so the
+/// whole bus is scanned as fast as the token hold time permits.  Use
+/// [`is_done()`][`BusScan::is_done`] to check whether the results are complete.
+///
+/// `BusScan` can run alongside a [`DpMaster`][`crate::dp::DpMaster`] using
+/// [`FdlActiveStation::poll_multi()`][`crate::fdl::FdlActiveStation::poll_multi`].
+#[cfg(feature = "std")]
+pub struct BusScan {
+    cursor: crate::Address,
+    current_address_done: bool,
+    done: bool,
+    stations: Vec<ScannedStation>,
+}
+
+#[cfg(feature = "std")]
+impl BusScan {
+    pub fn new() -> Self {
+        Self {
+            cursor: 0,
+            current_address_done: false,
+            done: false,
+            stations: Vec::new(),
+        }
+    }
+
+    /// Whether all addresses were scanned.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Stations found so far, ordered by address.
+    pub fn stations(&self) -> &[ScannedStation] {
+        &self.stations
+    }
+
+    /// Consume the scan and return the stations that were found.
+    pub fn into_stations(self) -> Vec<ScannedStation> {
+        self.stations
+    }
+
+    fn advance(&mut self, this_station: crate::Address) {
+        self.current_address_done = false;
+        self.cursor += 1;
+        if self.cursor == this_station {
+            self.cursor += 1;
+        }
+        if self.cursor > 126 {
+            log::debug!("Bus scan found {} stations.", self.stations.len());
+            self.done = true;
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for BusScan {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+crate::fdl::impl_any_application!(BusScan, BusScan);
+
+#[cfg(feature = "std")]
+impl crate::fdl::FdlApplication for BusScan {
+    fn transmit_telegram(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &crate::fdl::FdlActiveStation,
+        tx: crate::fdl::TelegramTx,
+        high_prio_only: bool,
+    ) -> Option<crate::fdl::TelegramTxResponse> {
+        if high_prio_only {
+            return None;
+        }
+
+        let this_station = fdl.address();
+        if self.cursor == this_station {
+            self.advance(this_station);
+        }
+        if self.current_address_done {
+            self.advance(this_station);
+        }
+        if self.done {
+            return None;
+        }
+
+        // Absent stations are expected, so don't waste time on retries.
+        let tx_res = tx.send_data_telegram(
+            crate::fdl::DataTelegramHeader {
+                da: self.cursor,
+                sa: this_station,
+                dsap: crate::consts::SAP_SLAVE_DIAGNOSIS,
+                ssap: crate::consts::SAP_MASTER_MS0,
+                fc: crate::fdl::FunctionCode::new_srd_low(crate::fdl::FrameCountBit::First),
+            },
+            0,
+            |_buf| (),
+        );
+        Some(tx_res.without_retries())
+    }
+
+    fn receive_reply(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &crate::fdl::FdlActiveStation,
+        address: u8,
+        telegram: crate::fdl::Telegram,
+    ) {
+        self.current_address_done = true;
+        if let Some(diag) = parse_diag_response(telegram, address) {
+            let station = ScannedStation {
+                address,
+                ident: diag.ident_number,
+                master_address: diag.master_address,
+                flags: diag.flags,
+            };
+            log::debug!("Bus scan found station: {station}");
+            self.stations.push(station);
+        }
+    }
+
+    fn handle_timeout(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &crate::fdl::FdlActiveStation,
+        address: u8,
+    ) {
+        self.current_address_done = true;
+        log::trace!("Timeout for address #{address}.");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bus_scan() {
+        crate::test_utils::prepare_test_logger();
+        let fdl = crate::fdl::FdlActiveStation::new(
+            crate::fdl::ParametersBuilder::new(2, crate::Baudrate::B19200).build(),
+        );
+        let now = crate::time::Instant::ZERO;
+        let mut scan = BusScan::new();
+        let mut addresses = Vec::new();
+
+        // Nothing is scanned while only high priority telegrams are permitted
+        let mut buf = [0u8; 256];
+        assert!(crate::fdl::FdlApplication::transmit_telegram(
+            &mut scan,
+            now,
+            &fdl,
+            crate::fdl::TelegramTx::new(&mut buf),
+            true,
+        )
+        .is_none());
+        assert!(!scan.is_done());
+
+        loop {
+            let mut buf = [0u8; 256];
+            let Some(_) = crate::fdl::FdlApplication::transmit_telegram(
+                &mut scan,
+                now,
+                &fdl,
+                crate::fdl::TelegramTx::new(&mut buf),
+                false,
+            ) else {
+                break;
+            };
+            let request = match crate::fdl::Telegram::deserialize(&buf) {
+                Some(Ok((crate::fdl::Telegram::Data(t), _))) => t,
+                t => panic!("Bus scan sent unexpected telegram: {t:?}"),
+            };
+            let address = request.h.da;
+            addresses.push(address);
+
+            if address != 7 {
+                crate::fdl::FdlApplication::handle_timeout(&mut scan, now, &fdl, address);
+                continue;
+            }
+
+            let flags = crate::dp::DiagnosticFlags::PERMANENT_BIT
+                | crate::dp::DiagnosticFlags::STATION_NOT_READY
+                | crate::dp::DiagnosticFlags::PARAMETER_REQUIRED;
+            let mut pdu = [0u8; 6];
+            pdu[0..2].copy_from_slice(&flags.bits().to_le_bytes());
+            pdu[3] = 0xff;
+            pdu[4..6].copy_from_slice(&0x0a3fu16.to_be_bytes());
+            let reply = crate::fdl::Telegram::Data(crate::fdl::DataTelegram {
+                h: crate::fdl::DataTelegramHeader {
+                    da: request.h.sa,
+                    sa: address,
+                    dsap: request.h.ssap,
+                    ssap: request.h.dsap,
+                    fc: crate::fdl::FunctionCode::Response {
+                        state: crate::fdl::ResponseState::Slave,
+                        status: crate::fdl::ResponseStatus::DataLow,
+                    },
+                },
+                pdu: &pdu,
+            });
+            crate::fdl::FdlApplication::receive_reply(&mut scan, now, &fdl, address, reply);
+        }
+
+        assert!(scan.is_done());
+        let expected: Vec<_> = (0..=126).filter(|a| *a != 2).collect();
+        assert_eq!(addresses, expected);
+
+        assert_eq!(scan.stations().len(), 1);
+        let station = &scan.stations()[0];
+        assert!(station.needs_parameters());
+        assert_eq!(
+            station.to_string(),
+            "address 7: ident 0x0A3F, needs parameters"
+        );
+    }
+}
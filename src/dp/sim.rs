@@ -0,0 +1,484 @@
+//! Simulated DP peripherals
+//!
+//! Available with the `phy-simulator` feature.
+//!
+//! The [`SimulatedSlave`] emulates a DP peripheral on the bus of a
+//! [`SimulatorPhy`][`crate::phy::SimulatorPhy`].  This allows testing application logic against
+//! a fake bus before touching real hardware.
+//!
+//! # Example
+//! ```
+//! use profirust::{Baudrate, dp, fdl, phy};
+//! let baud = Baudrate::B19200;
+//!
+//! let mut dp_master = dp::DpMaster::new(vec![]);
+//! let handle = dp_master.add(dp::Peripheral::new(
+//!     8,
+//!     dp::PeripheralOptions {
+//!         ident_number: 0x4711,
+//!         user_parameters: Some(&[0x00]),
+//!         config: Some(&[0x21, 0x11]),
+//!         ..Default::default()
+//!     },
+//!     vec![0x00; 2],
+//!     vec![0x00; 2],
+//! ));
+//! dp_master.enter_operate();
+//!
+//! let mut phy = phy::SimulatorPhy::new(baud, "phy#master");
+//! let mut slave = dp::sim::SimulatedSlave::new(&phy, 8, 0x4711, 2, 2);
+//! slave.set_inputs(&[0x12, 0x34]);
+//!
+//! let mut fdl = fdl::FdlActiveStation::new(fdl::ParametersBuilder::new(2, baud).build());
+//! fdl.set_online();
+//!
+//! let mut now = profirust::time::Instant::ZERO;
+//! while !dp_master.get_mut(handle).is_running() {
+//!     phy.set_bus_time(now);
+//!     fdl.poll(now, &mut phy, &mut dp_master);
+//!     slave.poll(now);
+//!     now += profirust::time::Duration::from_micros(100);
+//! }
+//! ```
+
+/// Name of the PHY of the simulated slave at `address`.
+///
+/// The simulator needs `'static` names, so the names are only allocated once per address.
+fn phy_name(address: crate::Address) -> &'static str {
+    static NAMES: std::sync::OnceLock<Vec<&'static str>> = std::sync::OnceLock::new();
+    NAMES.get_or_init(|| {
+        (0..=u8::MAX)
+            .map(|a| &*format!("slave#{a}").leak())
+            .collect()
+    })[usize::from(address)]
+}
+
+/// DP slave which answers the requests of a DP master on a simulated bus.
+///
+/// The slave handles Set_Prm, Chk_Cfg, diagnostics, and data exchange requests as well as FDL
+/// status requests.  Requests for other SAPs and broadcasts (like Global Control) are ignored.
+/// Data exchange requests are only answered once the slave was parameterized and configured
+/// successfully.
+///
+/// The slave must be polled regularly using [`SimulatedSlave::poll()`], just like the FDL
+/// active stations on the same bus.
+#[derive(Debug)]
+pub struct SimulatedSlave {
+    phy: crate::phy::SimulatorPhy,
+    address: crate::Address,
+    ident_number: u16,
+    /// Configuration which the master must send, `None` accepts any configuration
+    config: Option<Vec<u8>>,
+    inputs: Vec<u8>,
+    outputs: Vec<u8>,
+    user_parameters: Option<Vec<u8>>,
+    extended_diagnostics: Vec<u8>,
+    /// Whether the master still needs to fetch changed diagnostics
+    diagnostics_pending: bool,
+    data_exchange_count: usize,
+    prm_fault: bool,
+    cfg_fault: bool,
+    configured: bool,
+    master_address: Option<crate::Address>,
+    /// Serialized response and the earliest time at which it may be sent
+    response: Option<(crate::time::Instant, Vec<u8>)>,
+}
+
+impl SimulatedSlave {
+    /// Connect a new slave to the bus of `phy`.
+    ///
+    /// The slave has `input_len` bytes of inputs and `output_len` bytes of outputs.  It accepts
+    /// any configuration unless one is set with [`SimulatedSlave::with_config()`].
+    pub fn new(
+        phy: &crate::phy::SimulatorPhy,
+        address: crate::Address,
+        ident_number: u16,
+        input_len: usize,
+        output_len: usize,
+    ) -> Self {
+        Self {
+            phy: phy.duplicate(phy_name(address)),
+            address,
+            ident_number,
+            config: None,
+            inputs: vec![0x00; input_len],
+            outputs: vec![0x00; output_len],
+            user_parameters: None,
+            extended_diagnostics: Vec::new(),
+            diagnostics_pending: false,
+            data_exchange_count: 0,
+            prm_fault: false,
+            cfg_fault: false,
+            configured: false,
+            master_address: None,
+            response: None,
+        }
+    }
+
+    /// Only accept the given configuration from the master.
+    ///
+    /// Any other configuration is rejected with a configuration fault.
+    pub fn with_config(mut self, config: &[u8]) -> Self {
+        self.config = Some(config.to_vec());
+        self
+    }
+
+    /// Station address of the slave.
+    #[inline(always)]
+    pub fn address(&self) -> crate::Address {
+        self.address
+    }
+
+    /// Whether the slave was parameterized and configured successfully.
+    pub fn is_ready(&self) -> bool {
+        self.configured && !self.prm_fault && !self.cfg_fault
+    }
+
+    /// Inputs which are sent to the master during data exchange.
+    #[inline(always)]
+    pub fn inputs_mut(&mut self) -> &mut [u8] {
+        &mut self.inputs
+    }
+
+    /// Set the inputs which are sent to the master during data exchange.
+    ///
+    /// # Panics
+    /// Panics if `inputs` does not have the input length of this slave.
+    pub fn set_inputs(&mut self, inputs: &[u8]) {
+        self.inputs.copy_from_slice(inputs);
+    }
+
+    /// Outputs received with the last data exchange request.
+    #[inline(always)]
+    pub fn outputs(&self) -> &[u8] {
+        &self.outputs
+    }
+
+    /// User parameters received with the last Set_Prm request.
+    #[inline(always)]
+    pub fn user_parameters(&self) -> Option<&[u8]> {
+        self.user_parameters.as_deref()
+    }
+
+    /// Number of data exchange requests which were answered.
+    #[inline(always)]
+    pub fn data_exchange_count(&self) -> usize {
+        self.data_exchange_count
+    }
+
+    /// Report the given extended diagnostics to the master.
+    ///
+    /// The slave signals the change in its next data exchange response so the master requests
+    /// diagnostics.  Pass an empty slice to clear the extended diagnostics again.
+    pub fn set_extended_diagnostics(&mut self, data: &[u8]) {
+        self.extended_diagnostics = data.to_vec();
+        self.diagnostics_pending = true;
+    }
+
+    /// Receive requests from the bus and transmit the responses.
+    pub fn poll(&mut self, now: crate::time::Instant) {
+        use crate::phy::ProfibusPhy;
+
+        if self.phy.poll_transmission(now) {
+            return;
+        }
+
+        if let Some((due, data)) = self.response.take() {
+            if now < due {
+                self.response = Some((due, data));
+            } else {
+                self.phy.transmit_data(now, |buffer| {
+                    buffer[..data.len()].copy_from_slice(&data);
+                    (data.len(), ())
+                });
+            }
+            return;
+        }
+
+        let address = self.address;
+        while let Some(request) = self.phy.receive_telegram(now, |t| match t {
+            crate::fdl::Telegram::Data(t) if t.h.da == address => {
+                Some((t.h.clone(), t.pdu.to_vec()))
+            }
+            _ => None,
+        }) {
+            let Some((h, pdu)) = request else {
+                continue;
+            };
+            if let Some(data) = self.respond(&crate::fdl::DataTelegram { h, pdu: &pdu }) {
+                // Leave the minimum station delay before responding.
+                let due = now + self.phy.baudrate().bits_to_time(11);
+                self.response = Some((due, data));
+                break;
+            }
+        }
+    }
+
+    fn respond(&mut self, t: &crate::fdl::DataTelegram) -> Option<Vec<u8>> {
+        let mut buffer = [0u8; 256];
+        let tx = crate::fdl::TelegramTx::new(&mut buffer);
+
+        if t.is_fdl_status_request().is_some() {
+            let length = tx
+                .send_fdl_status_response(
+                    t.h.sa,
+                    self.address,
+                    crate::fdl::ResponseState::Slave,
+                    crate::fdl::ResponseStatus::Ok,
+                )
+                .bytes_sent();
+            return Some(buffer[..length].to_vec());
+        }
+
+        let mut status = crate::fdl::ResponseStatus::DataLow;
+        let mut pdu = Vec::new();
+        match t.h.dsap {
+            crate::consts::SAP_SLAVE_DIAGNOSIS => {
+                let mut flags = crate::dp::DiagnosticFlags::PERMANENT_BIT;
+                flags.set(
+                    crate::dp::DiagnosticFlags::STATION_NOT_READY,
+                    !self.is_ready(),
+                );
+                flags.set(crate::dp::DiagnosticFlags::PARAMETER_FAULT, self.prm_fault);
+                flags.set(
+                    crate::dp::DiagnosticFlags::CONFIGURATION_FAULT,
+                    self.cfg_fault,
+                );
+                flags.set(
+                    crate::dp::DiagnosticFlags::PARAMETER_REQUIRED,
+                    self.user_parameters.is_none(),
+                );
+                flags.set(
+                    crate::dp::DiagnosticFlags::EXT_DIAG,
+                    !self.extended_diagnostics.is_empty(),
+                );
+                pdu.extend_from_slice(&flags.bits().to_le_bytes());
+                pdu.push(0x00);
+                pdu.push(self.master_address.unwrap_or(0xff));
+                pdu.extend_from_slice(&self.ident_number.to_be_bytes());
+                pdu.extend_from_slice(&self.extended_diagnostics);
+                self.diagnostics_pending = false;
+            }
+            crate::consts::SAP_SLAVE_SET_PRM => {
+                // Station status, watchdog, min. TSDR, ident number, and group ident
+                if t.pdu.len() < 7 {
+                    log::debug!(
+                        "Simulated slave #{} received truncated parameters",
+                        self.address
+                    );
+                    self.prm_fault = true;
+                } else {
+                    let ident_number = u16::from_be_bytes([t.pdu[4], t.pdu[5]]);
+                    self.prm_fault = ident_number != self.ident_number;
+                    self.user_parameters = Some(t.pdu[7..].to_vec());
+                }
+                self.master_address = Some(t.h.sa);
+                self.configured = false;
+                let length = tx.send_short_confirmation().bytes_sent();
+                return Some(buffer[..length].to_vec());
+            }
+            crate::consts::SAP_SLAVE_CHK_CFG => {
+                self.cfg_fault = self.config.as_ref().is_some_and(|c| t.pdu != c.as_slice());
+                self.configured = true;
+                let length = tx.send_short_confirmation().bytes_sent();
+                return Some(buffer[..length].to_vec());
+            }
+            crate::consts::SAP_SLAVE_DATA_EXCHANGE if self.is_ready() => {
+                if t.pdu.len() != self.outputs.len() {
+                    log::debug!(
+                        "Simulated slave #{} received {} bytes of outputs instead of {}",
+                        self.address,
+                        t.pdu.len(),
+                        self.outputs.len()
+                    );
+                    return None;
+                }
+                self.outputs.copy_from_slice(t.pdu);
+                self.data_exchange_count += 1;
+                if self.diagnostics_pending {
+                    status = crate::fdl::ResponseStatus::DataHigh;
+                } else if self.inputs.is_empty() {
+                    // Output-only slaves acknowledge with a short confirmation
+                    let length = tx.send_short_confirmation().bytes_sent();
+                    return Some(buffer[..length].to_vec());
+                }
+                pdu.extend_from_slice(&self.inputs);
+            }
+            sap => {
+                log::debug!(
+                    "Simulated slave #{} ignores request to SAP {sap:?}",
+                    self.address
+                );
+                return None;
+            }
+        }
+
+        let length = tx
+            .send_data_telegram(
+                crate::fdl::DataTelegramHeader {
+                    da: t.h.sa,
+                    sa: self.address,
+                    dsap: t.h.ssap,
+                    ssap: t.h.dsap,
+                    fc: crate::fdl::FunctionCode::Response {
+                        state: crate::fdl::ResponseState::Slave,
+                        status,
+                    },
+                },
+                pdu.len(),
+                |buf| buf.copy_from_slice(&pdu),
+            )
+            .bytes_sent();
+        Some(buffer[..length].to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulated_slave_extended_diagnostics() {
+        crate::test_utils::prepare_test_logger();
+        let baud = crate::Baudrate::B19200;
+
+        let mut dp_master = crate::dp::DpMaster::new(vec![]);
+        let handle = dp_master.add(
+            crate::dp::Peripheral::new(
+                8,
+                crate::dp::PeripheralOptions {
+                    ident_number: 0x4711,
+                    user_parameters: Some(&[0x00]),
+                    config: Some(&[0x10]),
+                    ..Default::default()
+                },
+                vec![0x00; 1],
+                vec![],
+            )
+            .with_diag_buffer(vec![0x00; 16]),
+        );
+        dp_master.enter_operate();
+
+        let mut bus = crate::test_utils::dp::SimulatedBus::new(baud);
+        let mut slave = SimulatedSlave::new(&bus.phy, 8, 0x4711, 1, 0).with_config(&[0x10]);
+        slave.set_inputs(&[0x5a]);
+
+        bus.run(
+            &mut dp_master,
+            &mut slave,
+            crate::time::Duration::from_millis(500),
+        );
+        let peripheral = dp_master.get_mut(handle);
+        assert!(peripheral.is_running());
+        assert_eq!(peripheral.pi_i(), [0x5a]);
+        assert!(peripheral
+            .last_diagnostics()
+            .unwrap()
+            .extended_diagnostics
+            .raw()
+            .is_empty());
+
+        // Identifier-related diagnostics for module 0
+        slave.set_extended_diagnostics(&[0x42, 0x01]);
+        bus.run(
+            &mut dp_master,
+            &mut slave,
+            crate::time::Duration::from_millis(100),
+        );
+        let diag = dp_master.get_mut(handle).last_diagnostics().unwrap();
+        assert!(diag.flags.contains(crate::dp::DiagnosticFlags::EXT_DIAG));
+        assert_eq!(diag.extended_diagnostics.raw(), [0x42, 0x01]);
+        assert!(dp_master.get_mut(handle).is_running());
+    }
+
+    #[test]
+    fn simulated_slave_output_only() {
+        crate::test_utils::prepare_test_logger();
+        let baud = crate::Baudrate::B19200;
+
+        let mut dp_master = crate::dp::DpMaster::new(vec![]);
+        let handle = dp_master.add(
+            crate::dp::Peripheral::new(
+                8,
+                crate::dp::PeripheralOptions {
+                    ident_number: 0x4711,
+                    user_parameters: Some(&[0x00]),
+                    config: Some(&[0x21]),
+                    ..Default::default()
+                },
+                vec![],
+                vec![0x00; 2],
+            )
+            .with_diag_buffer(vec![0x00; 16]),
+        );
+        dp_master.enter_operate();
+
+        let mut bus = crate::test_utils::dp::SimulatedBus::new(baud);
+        let mut slave = SimulatedSlave::new(&bus.phy, 8, 0x4711, 0, 2).with_config(&[0x21]);
+
+        bus.run(
+            &mut dp_master,
+            &mut slave,
+            crate::time::Duration::from_millis(500),
+        );
+        let peripheral = dp_master.get_mut(handle);
+        assert!(peripheral.is_running());
+        assert!(peripheral.pi_i().is_empty());
+        peripheral.pi_q_mut().copy_from_slice(&[0xde, 0xad]);
+        let count = slave.data_exchange_count();
+
+        bus.run(
+            &mut dp_master,
+            &mut slave,
+            crate::time::Duration::from_millis(100),
+        );
+        assert!(slave.data_exchange_count() > count);
+        assert_eq!(slave.outputs(), [0xde, 0xad]);
+
+        // Diagnostics are signalled using a response without data
+        slave.set_extended_diagnostics(&[0x42, 0x01]);
+        bus.run(
+            &mut dp_master,
+            &mut slave,
+            crate::time::Duration::from_millis(100),
+        );
+        let diag = dp_master.get_mut(handle).last_diagnostics().unwrap();
+        assert_eq!(diag.extended_diagnostics.raw(), [0x42, 0x01]);
+        assert!(dp_master.get_mut(handle).is_running());
+    }
+
+    #[test]
+    fn simulated_slave_truncated_parameters() {
+        crate::test_utils::prepare_test_logger();
+        let phy = crate::phy::SimulatorPhy::new(crate::Baudrate::B19200, "phy#master");
+        let mut slave = SimulatedSlave::new(&phy, 8, 0x4711, 1, 1);
+
+        let request = |dsap, pdu| crate::fdl::DataTelegram {
+            h: crate::fdl::DataTelegramHeader {
+                da: 8,
+                sa: 2,
+                dsap,
+                ssap: Some(62),
+                fc: crate::fdl::FunctionCode::new_srd_high(Default::default()),
+            },
+            pdu,
+        };
+
+        // Truncated parameters are acknowledged but lead to a parameter fault
+        let response = slave.respond(&request(crate::consts::SAP_SLAVE_SET_PRM, &[0x80, 0x01]));
+        assert_eq!(response.as_deref(), Some(&[0xe5][..]));
+
+        let response = slave
+            .respond(&request(crate::consts::SAP_SLAVE_DIAGNOSIS, &[]))
+            .unwrap();
+        let Some(Ok((crate::fdl::Telegram::Data(t), _))) =
+            crate::fdl::Telegram::deserialize(&response)
+        else {
+            panic!("no diagnostics response");
+        };
+        let flags =
+            crate::dp::DiagnosticFlags::from_bits_retain(u16::from_le_bytes([t.pdu[0], t.pdu[1]]));
+        assert!(flags.contains(crate::dp::DiagnosticFlags::PARAMETER_FAULT));
+        assert!(!slave.is_ready());
+    }
+}
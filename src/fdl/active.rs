@@ -13,6 +13,8 @@ pub enum ConnectivityState {
     /// The station is only passive.
     ///
     /// It will respond to requests, but it will not attempt to become part of the token ring.
+    /// FDL status requests are answered with "master not ready" and a token passed to this
+    /// station is never accepted.
     Passive,
     /// The station tries to become part of the token ring and then performs communication.
     Online,
@@ -44,9 +46,23 @@ enum GapState {
 
     /// A poll of the given address is scheduled next.
     DoPoll { current_address: crate::Address },
+
+    /// A poll of the given priority address is scheduled next.
+    ///
+    /// Priority addresses are polled at the start of each GAP polling cycle, before the
+    /// sequential scan.
+    DoPriorityPoll { current_address: crate::Address },
 }
 
 impl GapState {
+    pub fn poll_address(self) -> Option<crate::Address> {
+        match self {
+            GapState::Waiting { .. } => None,
+            GapState::DoPoll { current_address } => Some(current_address),
+            GapState::DoPriorityPoll { current_address } => Some(current_address),
+        }
+    }
+
     pub fn increment_wait(&mut self) {
         match self {
             GapState::Waiting {
@@ -73,7 +89,10 @@ enum ScheduleNext {
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 struct UseTokenData {
     pub token_time: crate::time::Instant,
-    pub first_app: Option<usize>,
+    pub first_app: Option<u8>,
+    pub first_background_app: Option<u8>,
+    /// Number of message cycles started during this token hold.
+    pub message_cycles: u8,
 }
 
 impl UseTokenData {
@@ -81,6 +100,38 @@ impl UseTokenData {
         Self {
             token_time,
             first_app: None,
+            first_background_app: None,
+            message_cycles: 0,
+        }
+    }
+}
+
+/// Request addressed to this station which is answered after the synchronization pause
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct PendingRequest {
+    source: crate::Address,
+    dsap: Option<u8>,
+    ssap: Option<u8>,
+    /// Who accepted the request, `None` to reject it
+    responder: Option<Responder>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Responder {
+    /// The request handler set with `FdlActiveStation::set_request_handler()`
+    Handler,
+    /// Application in the given slot, with its handle to detect when it was replaced
+    Application(usize, crate::fdl::ApplicationHandle),
+}
+
+/// Request handler registered with an [`FdlActiveStation`].
+struct RequestHandlerRef<'a>(Option<&'a mut dyn crate::fdl::RequestHandler>);
+
+impl core::fmt::Debug for RequestHandlerRef<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.0 {
+            Some(_) => f.write_str("Some(RequestHandler)"),
+            None => f.write_str("None"),
         }
     }
 }
@@ -88,19 +139,23 @@ impl UseTokenData {
 #[derive(Debug, PartialEq, Eq)]
 enum State {
     Offline,
-    PassiveIdle,
+    PassiveIdle {
+        status_request: Option<crate::Address>,
+        rejected_request: Option<PendingRequest>,
+    },
     ListenToken {
         status_request: Option<crate::Address>,
+        rejected_request: Option<PendingRequest>,
         collision_count: u8,
     },
     ActiveIdle {
         status_request: Option<crate::Address>,
+        app_request: Option<PendingRequest>,
         new_previous_station: Option<crate::Address>,
         collision_count: u8,
     },
     UseToken {
         data: UseTokenData,
-        first_cycle_done: bool,
     },
     ClaimToken {
         first: bool,
@@ -159,17 +214,30 @@ impl State {
     }
 
     fn transition_passive_idle(&mut self) {
-        debug_assert_state!(self, State::Offline { .. } | State::PassiveIdle { .. });
-        *self = State::PassiveIdle;
+        debug_assert_state!(
+            self,
+            State::Offline { .. }
+                | State::PassiveIdle { .. }
+                | State::ListenToken { .. }
+                | State::ActiveIdle { .. }
+        );
+        *self = State::PassiveIdle {
+            status_request: None,
+            rejected_request: None,
+        };
     }
 
     fn transition_listen_token(&mut self) {
         debug_assert_state!(
             self,
-            State::ListenToken { .. } | State::Offline { .. } | State::ActiveIdle { .. }
+            State::ListenToken { .. }
+                | State::Offline { .. }
+                | State::PassiveIdle { .. }
+                | State::ActiveIdle { .. }
         );
         *self = State::ListenToken {
             status_request: None,
+            rejected_request: None,
             collision_count: 0,
         };
     }
@@ -186,6 +254,7 @@ impl State {
         );
         *self = State::ActiveIdle {
             status_request: None,
+            app_request: None,
             new_previous_station: None,
             collision_count: 0,
         };
@@ -200,10 +269,7 @@ impl State {
                 | State::AwaitDataResponse { .. }
                 | State::ActiveIdle { .. }
         );
-        *self = State::UseToken {
-            data,
-            first_cycle_done: false,
-        };
+        *self = State::UseToken { data };
     }
 
     fn transition_claim_token(&mut self) {
@@ -251,6 +317,22 @@ impl State {
 /// Accessors for state-specific fields.  These accessors panic when trying to access a field
 /// from a different state.
 impl State {
+    fn get_passive_idle_status_request(&mut self) -> &mut Option<crate::Address> {
+        match self {
+            Self::PassiveIdle { status_request, .. } => status_request,
+            _ => unreachable!(),
+        }
+    }
+
+    fn get_passive_idle_rejected_request(&mut self) -> &mut Option<PendingRequest> {
+        match self {
+            Self::PassiveIdle {
+                rejected_request, ..
+            } => rejected_request,
+            _ => unreachable!(),
+        }
+    }
+
     fn get_listen_token_status_request(&mut self) -> &mut Option<crate::Address> {
         match self {
             Self::ListenToken { status_request, .. } => status_request,
@@ -258,6 +340,15 @@ impl State {
         }
     }
 
+    fn get_listen_token_rejected_request(&mut self) -> &mut Option<PendingRequest> {
+        match self {
+            Self::ListenToken {
+                rejected_request, ..
+            } => rejected_request,
+            _ => unreachable!(),
+        }
+    }
+
     fn get_listen_token_collision_count(&mut self) -> &mut u8 {
         match self {
             Self::ListenToken {
@@ -274,6 +365,13 @@ impl State {
         }
     }
 
+    fn get_active_idle_app_request(&mut self) -> &mut Option<PendingRequest> {
+        match self {
+            Self::ActiveIdle { app_request, .. } => app_request,
+            _ => unreachable!(),
+        }
+    }
+
     fn get_active_idle_new_previous_station(&mut self) -> &mut Option<crate::Address> {
         match self {
             Self::ActiveIdle {
@@ -300,15 +398,6 @@ impl State {
         }
     }
 
-    fn get_use_token_first_cycle_done(&mut self) -> &mut bool {
-        match self {
-            Self::UseToken {
-                first_cycle_done, ..
-            } => first_cycle_done,
-            _ => unreachable!(),
-        }
-    }
-
     fn get_claim_token_first(&mut self) -> &mut bool {
         match self {
             Self::ClaimToken { first, .. } => first,
@@ -359,8 +448,158 @@ impl State {
     }
 }
 
+/// Counters for received data which could not be decoded as a telegram
+///
+/// Checksum errors usually hint at signal integrity problems like missing termination or
+/// reflections while delimiter errors rather hint at framing problems.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RxErrorCounters {
+    /// Data with an invalid start delimiter
+    pub bad_start_delimiter: u32,
+    /// Telegrams without the end delimiter
+    pub bad_end_delimiter: u32,
+    /// Telegrams with a checksum mismatch
+    pub bad_checksum: u32,
+    /// Telegrams with inconsistent length information
+    pub length_mismatch: u32,
+    /// Telegrams with an invalid function code
+    pub bad_function_code: u32,
+    /// Overflows of the PHY receive buffer, each likely losing a telegram
+    pub overflows: u32,
+}
+
+impl RxErrorCounters {
+    /// Total number of receive errors
+    pub fn total(&self) -> u32 {
+        self.bad_start_delimiter
+            .wrapping_add(self.bad_end_delimiter)
+            .wrapping_add(self.bad_checksum)
+            .wrapping_add(self.length_mismatch)
+            .wrapping_add(self.bad_function_code)
+            .wrapping_add(self.overflows)
+    }
+
+    fn count(&mut self, error: crate::fdl::DeserializeError) {
+        let counter = match error {
+            crate::fdl::DeserializeError::BadStartDelimiter => &mut self.bad_start_delimiter,
+            crate::fdl::DeserializeError::BadEndDelimiter => &mut self.bad_end_delimiter,
+            crate::fdl::DeserializeError::BadChecksum => &mut self.bad_checksum,
+            crate::fdl::DeserializeError::LengthMismatch => &mut self.length_mismatch,
+            crate::fdl::DeserializeError::BadFunctionCode => &mut self.bad_function_code,
+        };
+        *counter = counter.wrapping_add(1);
+    }
+}
+
+/// Counters for line errors reported by the PHY
+///
+/// Unlike receive errors, these are detected by the UART itself.  Framing errors and break
+/// conditions usually mean that some station drives the line at the wrong time or that the bus
+/// is missing termination or bias resistors.  Only PHYs which can read the error counters of
+/// their UART report line errors (see [`ProfibusPhy::poll_line_errors()`]).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LineErrorCounters {
+    /// Characters without a valid stop bit
+    pub framing: u32,
+    /// Characters with wrong parity
+    pub parity: u32,
+    /// Break conditions on the line
+    pub breaks: u32,
+    /// Characters lost because they were not read in time
+    pub overruns: u32,
+}
+
+impl LineErrorCounters {
+    /// Total number of line errors
+    pub fn total(&self) -> u32 {
+        self.framing
+            .wrapping_add(self.parity)
+            .wrapping_add(self.breaks)
+            .wrapping_add(self.overruns)
+    }
+
+    /// Add the counts of `other` to these counters.
+    pub fn add(&mut self, other: LineErrorCounters) {
+        self.framing = self.framing.wrapping_add(other.framing);
+        self.parity = self.parity.wrapping_add(other.parity);
+        self.breaks = self.breaks.wrapping_add(other.breaks);
+        self.overruns = self.overruns.wrapping_add(other.overruns);
+    }
+}
+
+/// Bus health statistics of an FDL active station
+///
+/// The counters accumulate while the station is running and are reset when the station goes
+/// offline.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FdlStats {
+    /// Requests which were not answered within the slot time
+    pub timeouts: u32,
+    /// Token passes which had to be repeated because the next station did not start transmitting
+    pub token_retries: u32,
+    /// Token losses after which this station generated a new token
+    pub token_lost: u32,
+    /// Received data which could not be decoded
+    pub rx_errors: RxErrorCounters,
+    /// Line errors detected by the PHY
+    pub line_errors: LineErrorCounters,
+}
+
+impl FdlStats {
+    /// Total number of errors of all kinds
+    pub fn total(&self) -> u32 {
+        self.timeouts
+            .wrapping_add(self.token_retries)
+            .wrapping_add(self.token_lost)
+            .wrapping_add(self.rx_errors.total())
+            .wrapping_add(self.line_errors.total())
+    }
+}
+
+/// Summary of the bus health as seen by an FDL active station
+///
+/// Returned by [`FdlActiveStation::summary()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FdlSummary {
+    /// Connectivity state of the station
+    pub connectivity_state: ConnectivityState,
+    /// Whether the station is part of the token ring
+    pub in_ring: bool,
+    /// Measured duration of the last full token rotation
+    ///
+    /// `None` until the station received the token twice in a row while in the ring.
+    pub rotation_time: Option<crate::time::Duration>,
+    /// Target rotation time T<sub>TR</sub> from the parameters
+    pub target_rotation_time: crate::time::Duration,
+    /// Accumulated bus health statistics
+    pub stats: FdlStats,
+}
+
+/// Find the first (background) application at or after `start`, wrapping around.
+///
+/// Like [`ApplicationHandle`][`crate::fdl::ApplicationHandle`]s, the returned index is a `u8`.
+fn find_application(
+    apps: &dyn crate::fdl::ApplicationList,
+    start: usize,
+    background: bool,
+) -> Option<u8> {
+    let num_apps = apps.slots();
+    (0..num_apps)
+        .map(|i| (start + i) % num_apps)
+        .find(|i| apps.weight(*i).is_some_and(|w| (w == 0) == background))
+        .map(|i| u8::try_from(i).unwrap())
+}
+
+/// Whether moving on from application `current` to `next` passes (or reaches) application `first`.
+fn wraps_around(num_apps: usize, current: u8, next: u8, first: u8) -> bool {
+    let current = usize::from(current);
+    let distance =
+        |to: u8| (usize::from(to) % num_apps + num_apps - (current + 1) % num_apps) % num_apps;
+    distance(first) <= distance(next)
+}
+
 #[derive(Debug)]
-pub struct FdlActiveStation {
+pub struct FdlActiveStation<'a> {
     /// Parameters for the connected bus and this station
     p: crate::fdl::Parameters,
 
@@ -388,14 +627,62 @@ pub struct FdlActiveStation {
     /// Timestamp of the acquisition of the last token.
     last_token_time: crate::time::Instant,
 
+    /// Duration of the last token rotation in bits.
+    rotation_bits: Option<u32>,
+
+    /// Whether `last_token_time` is from the current ring membership, for measuring rotations.
+    measure_rotation: bool,
+
     /// Timestamp of the end of our token hold time.
     end_token_hold_time: crate::time::Instant,
 
     /// Index of the application that gets to transmit a telegram next.
-    next_application: usize,
+    next_application: u8,
+
+    /// Number of cycles the current application has completed in its turn.
+    application_turns: u8,
+
+    /// Handle of the application which transmitted the last request.
+    requesting_application: Option<crate::fdl::ApplicationHandle>,
+
+    /// Background application which is currently using the leftover token hold time.
+    background_application: Option<u8>,
+
+    /// Index of the background application that gets to use leftover token hold time next.
+    next_background_application: u8,
+
+    /// Bus health statistics
+    stats: FdlStats,
+
+    /// Whether any telegram was received since we last passed the token.
+    rx_since_token_pass: bool,
+
+    /// Number of consecutive token rotations without receiving any telegram.
+    silent_rotations: u8,
+
+    /// Whether this station is alone on an otherwise silent bus.
+    bus_idle: bool,
+
+    /// Whether the station should go offline once it no longer holds the token.
+    offline_requested: bool,
+
+    /// Whether the outstanding request may be retried when it is not answered.
+    request_retries_allowed: bool,
+
+    /// Address and number of retries of a request which is currently being retried.
+    pending_retry: Option<(u8, u8)>,
+
+    /// Whether the station was part of the token ring after the last poll.
+    was_in_ring: bool,
+
+    /// Whether the station joined the token ring and this was not yet taken.
+    joined_ring: bool,
+
+    /// Handler for requests which other stations send to us.
+    request_handler: RequestHandlerRef<'a>,
 }
 
-impl FdlActiveStation {
+impl<'a> FdlActiveStation<'a> {
     pub fn new(param: crate::fdl::Parameters) -> Self {
         param.debug_assert_consistency();
 
@@ -410,18 +697,64 @@ impl FdlActiveStation {
             last_bus_activity: None,
             pending_bytes: 0,
             last_token_time: crate::time::Instant::ZERO,
+            rotation_bits: None,
+            measure_rotation: false,
             end_token_hold_time: crate::time::Instant::ZERO,
             next_application: 0,
+            application_turns: 0,
+            requesting_application: None,
+            background_application: None,
+            next_background_application: 0,
+            stats: FdlStats::default(),
+            rx_since_token_pass: false,
+            silent_rotations: 0,
+            bus_idle: false,
+            offline_requested: false,
+            request_retries_allowed: false,
+            pending_retry: None,
+            was_in_ring: false,
+            joined_ring: false,
+            request_handler: RequestHandlerRef(None),
             p: param,
         }
     }
 
+    /// Return the bus health statistics of this station.
+    ///
+    /// The statistics are reset when the station goes offline.
+    #[inline(always)]
+    pub fn stats(&self) -> FdlStats {
+        self.stats
+    }
+
+    /// Summarize the bus health as seen by this station.
+    pub fn summary(&self) -> FdlSummary {
+        FdlSummary {
+            connectivity_state: self.connectivity_state,
+            in_ring: self.is_in_ring(),
+            rotation_time: self.rotation_bits.map(|bits| self.p.bits_to_time(bits)),
+            target_rotation_time: self.p.token_rotation_time(),
+            stats: self.stats,
+        }
+    }
+
+    /// Reset all bus health statistics to zero.
+    pub fn reset_stats(&mut self) {
+        self.stats = FdlStats::default();
+    }
+
     /// Return a reference to the parameters configured for this FDL active station.
     #[inline(always)]
     pub fn parameters(&self) -> &crate::fdl::Parameters {
         &self.p
     }
 
+    /// Return the address of this FDL active station.
+    #[inline(always)]
+    pub fn address(&self) -> crate::Address {
+        self.p.address
+    }
+
     #[inline(always)]
     pub fn connectivity_state(&self) -> ConnectivityState {
         self.connectivity_state
@@ -431,16 +764,12 @@ impl FdlActiveStation {
     pub fn set_state(&mut self, state: ConnectivityState) {
         log::info!("FDL active station entering state \"{:?}\"", state);
         self.connectivity_state = state;
+        self.offline_requested = false;
 
         if state == ConnectivityState::Offline {
             // If we are going offline, reset all internal state by recreating the FDL station.
             let parameters = core::mem::take(&mut self.p);
             *self = Self::new(parameters);
-        } else if state != ConnectivityState::Online {
-            todo!(
-                "ConnectivityState {:?} is not yet supported properly!",
-                state
-            );
         }
     }
 
@@ -452,6 +781,18 @@ impl FdlActiveStation {
         self.set_state(ConnectivityState::Offline)
     }
 
+    /// Enter the [`Offline`][`ConnectivityState::Offline`] connectivity state without losing the
+    /// token.
+    ///
+    /// Unlike [`set_offline()`][`FdlActiveStation::set_offline`], a token held by this station
+    /// is first passed on to the next station.  The station then goes offline during a later
+    /// poll.  This avoids the next station having to recover from a lost token.
+    #[inline]
+    pub fn request_offline(&mut self) {
+        log::info!("FDL active station going offline after passing on the token");
+        self.offline_requested = true;
+    }
+
     /// Enter the [`Passive`][`ConnectivityState::Passive`] connectivity state.
     ///
     /// This is equivalent to calling `.set_state(ConnectivityState::Passive)`.
@@ -468,6 +809,117 @@ impl FdlActiveStation {
         self.set_state(ConnectivityState::Online)
     }
 
+    /// Set the handler for SDA, SDN, and SRD requests which other stations send to this station.
+    ///
+    /// The handler gets to accept a request before the applications do, see
+    /// [`RequestHandler`][`crate::fdl::RequestHandler`].  It replaces a previously set handler.
+    pub fn set_request_handler(&mut self, handler: &'a mut dyn crate::fdl::RequestHandler) {
+        self.request_handler.0 = Some(handler);
+    }
+
+    /// Remove the request handler and return it.
+    ///
+    /// A request which the handler accepted but did not answer yet is left unanswered.
+    pub fn remove_request_handler(&mut self) -> Option<&'a mut dyn crate::fdl::RequestHandler> {
+        self.request_handler.0.take()
+    }
+
+    /// Returns `true` when no other station responded for the configured number of token
+    /// rotations.
+    ///
+    /// See [`ParametersBuilder::bus_idle_rotations()`][`crate::fdl::ParametersBuilder::bus_idle_rotations`].
+    #[inline(always)]
+    pub fn is_bus_idle(&self) -> bool {
+        self.bus_idle
+    }
+
+    /// Number of token rotations from the start of one GAP scan cycle to the next.
+    ///
+    /// This is the longest time it takes to discover a new active station in the GAP of this
+    /// station.  It depends on the current size of the GAP, so it changes when stations join or
+    /// leave the token ring.  See
+    /// [`ParametersBuilder::gap_wait_rotations()`][`crate::fdl::ParametersBuilder::gap_wait_rotations`].
+    pub fn gap_scan_period(&self) -> u32 {
+        let gap = (0..self.p.highest_station_address)
+            .filter(|a| self.is_in_gap(*a))
+            .count();
+        let priority = (0..self.p.highest_station_address)
+            .filter(|a| self.p.priority_gap_addresses & (1 << a) != 0 && self.is_in_gap(*a))
+            .count();
+        // The polls of the scan cycle, then one rotation for noticing the end of the GAP and the
+        // wait rotations, plus one because the wait only ends once the counter exceeds them.
+        u32::try_from(gap + priority).unwrap() + u32::from(self.p.gap_wait_rotations) + 2
+    }
+
+    /// Returns `true` once this station has listened to the bus long enough to know the token
+    /// ring.
+    ///
+    /// After going online, the station first listens to two token rotations before it attempts
+    /// to join the ring.  Until then, this returns `false`.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        self.token_ring.ready_for_ring()
+    }
+
+    /// Returns `true` once after this station joined the token ring.
+    ///
+    /// This can be used to only start applications (e.g. put the DP master into operate) once
+    /// the station actually takes part in the token ring.  The notification is reset when the
+    /// station leaves the ring, so it fires again on the next join.
+    pub fn take_joined_ring(&mut self) -> bool {
+        core::mem::replace(&mut self.joined_ring, false)
+    }
+
+    /// Kind of the station at `address`, as reported in its last response.
+    ///
+    /// Stations passing the token are in the ring.  For all other stations, this is the state
+    /// they reported in their last response, either to a request of this station (e.g. FDL status
+    /// requests during gap polling) or witnessed between other stations.  The state is forgotten
+    /// when a station leaves the ring.  Returns `None` for stations which were not seen yet and
+    /// for this station.
+    pub fn station_kind(&self, address: crate::Address) -> Option<crate::fdl::ResponseState> {
+        self.token_ring.station_state(address)
+    }
+
+    /// Export the token ring knowledge of this station for a later warm start.
+    ///
+    /// Returns `None` while the station is not [ready][`FdlActiveStation::is_ready`] yet, as there
+    /// is no verified list of active stations to export.
+    pub fn export_state(&self) -> Option<crate::fdl::WarmStartState> {
+        if !self.token_ring.ready_for_ring() {
+            return None;
+        }
+        Some(crate::fdl::WarmStartState::new(&self.p, &self.token_ring))
+    }
+
+    /// Import token ring knowledge previously exported using
+    /// [`export_state()`][`FdlActiveStation::export_state`].
+    ///
+    /// This allows the station to join the token ring right away after going online, instead of
+    /// first listening to two full token rotations.  The state is validated against the current
+    /// parameters.  When it is rejected, the station keeps its cold start state.
+    ///
+    /// The station must be offline when importing a state.
+    pub fn import_state(
+        &mut self,
+        state: &crate::fdl::WarmStartState,
+    ) -> Result<(), crate::fdl::WarmStartError> {
+        if self.connectivity_state != ConnectivityState::Offline {
+            return Err(crate::fdl::WarmStartError::NotOffline);
+        }
+        match state.restore_token_ring(&self.p) {
+            Ok(token_ring) => {
+                log::info!("Warm-starting FDL active station with {:?}", token_ring);
+                self.token_ring = token_ring;
+                Ok(())
+            }
+            Err(e) => {
+                log::warn!("Rejected warm start state ({e}), doing a cold start.");
+                Err(e)
+            }
+        }
+    }
+
     /// Returns `true` when this active stations believes to be in the token ring.
     pub fn is_in_ring(&self) -> bool {
         matches!(
@@ -482,6 +934,95 @@ impl FdlActiveStation {
         )
     }
 
+    /// Returns `true` when this active station currently holds the token.
+    ///
+    /// While holding the token, the station may transmit requests until the token hold time is
+    /// used up.  Applications are only asked to transmit telegrams while the station holds the
+    /// token.
+    #[inline]
+    pub fn holds_token(&self) -> bool {
+        self.state.have_token()
+    }
+
+    /// Earliest time at which calling [`poll()`][`FdlActiveStation::poll`] could do useful work.
+    ///
+    /// This allows sleeping between polls instead of polling the station continuously.  The
+    /// deadline only accounts for the timers of the active station (synchronization pause, slot
+    /// time, token lost timeout).  Data received from the bus must still lead to an immediate
+    /// poll.
+    ///
+    /// The deadline may lie in the past, in which case the station must be polled right away.
+    /// Returns `None` when the station is not waiting for any timer, e.g. while offline.
+    pub fn next_poll_deadline(&self, now: crate::time::Instant) -> Option<crate::time::Instant> {
+        if matches!(self.state, State::Offline)
+            || matches!(
+                self.state,
+                State::PassiveIdle {
+                    status_request: None,
+                    rejected_request: None,
+                }
+            )
+        {
+            // A pending connectivity state change is handled on the next poll.
+            return match (self.connectivity_state, &self.state) {
+                (ConnectivityState::Offline, _)
+                | (ConnectivityState::Passive, State::PassiveIdle { .. }) => None,
+                _ => Some(now),
+            };
+        }
+
+        // Without any known bus activity, the timers are only started on the next poll.
+        let Some(last_bus_activity) = self.last_bus_activity else {
+            return Some(now);
+        };
+
+        // Most timeouts expire once `now` is strictly after the calculated instant.
+        let tick = crate::time::Duration::from_micros(1);
+        let sync_pause = last_bus_activity + self.p.idle_time() + tick;
+        let slot_expired = last_bus_activity + self.p.slot_time() + tick;
+        let token_lost = last_bus_activity + self.p.token_lost_timeout();
+
+        let deadline = match &self.state {
+            State::Offline
+            | State::PassiveIdle {
+                status_request: None,
+                rejected_request: None,
+            } => unreachable!(),
+            State::PassiveIdle { .. }
+            | State::ListenToken {
+                status_request: Some(_),
+                ..
+            }
+            | State::ListenToken {
+                rejected_request: Some(_),
+                ..
+            }
+            | State::ActiveIdle {
+                status_request: Some(_),
+                ..
+            }
+            | State::ActiveIdle {
+                app_request: Some(_),
+                ..
+            } => sync_pause,
+            // Incomplete telegrams are discarded after a slot time of silence.
+            State::ListenToken { .. } | State::ActiveIdle { .. } if self.pending_bytes != 0 => {
+                slot_expired.min(token_lost)
+            }
+            State::ListenToken { .. } | State::ActiveIdle { .. } => token_lost,
+            State::PassToken { .. } if self.is_idle_self_token_pass() => {
+                last_bus_activity + self.p.bits_to_time(5 * u32::from(self.p.slot_bits)) + tick
+            }
+            State::ClaimToken { .. } | State::UseToken { .. } | State::PassToken { .. } => {
+                sync_pause
+            }
+            State::AwaitDataResponse { .. }
+            | State::AwaitStatusResponse { .. }
+            | State::CheckTokenPass { .. } => slot_expired,
+        };
+        Some(deadline.max(now))
+    }
+
     #[doc(hidden)]
     pub fn inspect_token_ring(&self) -> &crate::fdl::TokenRing {
         &self.token_ring
@@ -535,7 +1076,7 @@ macro_rules! return_if_done {
     };
 }
 
-impl FdlActiveStation {
+impl FdlActiveStation<'_> {
     /// Mark the bus as active at the current point in time.
     ///
     /// Sets the current time as last_bus_activity unless we have already deduced that bus activity
@@ -569,7 +1110,7 @@ impl FdlActiveStation {
     ///
     /// This synchronization pause is required before every transmission.
     fn wait_synchronization_pause(&mut self, now: crate::time::Instant) -> Option<PollDone> {
-        if now <= (*self.last_bus_activity.get_or_insert(now) + self.p.bits_to_time(33)) {
+        if now <= (*self.last_bus_activity.get_or_insert(now) + self.p.idle_time()) {
             Some(PollDone::waiting_for_delay())
         } else {
             None
@@ -588,27 +1129,169 @@ impl FdlActiveStation {
     }
 
     fn check_for_bus_activity(&mut self, now: crate::time::Instant, phy: &mut impl ProfibusPhy) {
+        let line_errors = phy.poll_line_errors(now);
+        if line_errors.total() != 0 {
+            log::debug!("Line errors detected on the bus: {line_errors:?}");
+            self.stats.line_errors.add(line_errors);
+        }
+        if phy.poll_rx_overflow() {
+            log::warn!("RX overflow, telegram likely lost!  Is the bus polled often enough?");
+            self.stats.rx_errors.overflows = self.stats.rx_errors.overflows.wrapping_add(1);
+        }
+
         let pending_bytes = phy.poll_pending_received_bytes(now);
         if pending_bytes > self.pending_bytes {
             self.mark_bus_activity(now);
             self.pending_bytes = pending_bytes;
+        } else if pending_bytes != 0 && self.check_slot_expired(now) {
+            // The sender went silent in the middle of a telegram.  Discard the fragment so it
+            // does not get mixed up with the next telegram.
+            log::debug!("Discarding {pending_bytes} bytes of an incomplete telegram.");
+            phy.receive_data(now, |buffer| (buffer.len(), ()));
+            self.pending_bytes = 0;
+        }
+    }
+
+    /// Check whether `telegram` is an SDA or SRD request to us which must be rejected.
+    fn rejected_request(&self, telegram: &crate::fdl::DataTelegram) -> Option<PendingRequest> {
+        match telegram.h.fc {
+            crate::fdl::FunctionCode::Request { req, .. }
+                if telegram.h.da == self.p.address
+                    && req.priority().is_some()
+                    && req.expects_reply() =>
+            {
+                Some(PendingRequest {
+                    source: telegram.h.sa,
+                    dsap: telegram.h.dsap,
+                    ssap: telegram.h.ssap,
+                    responder: None,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Reject a request to a SAP which we do not serve.
+    fn transmit_rejection(
+        &self,
+        tx: crate::fdl::TelegramTx,
+        request: PendingRequest,
+        state: crate::fdl::ResponseState,
+    ) -> crate::fdl::TelegramTxResponse {
+        tx.send_data_telegram(
+            crate::fdl::DataTelegramHeader {
+                da: request.source,
+                sa: self.p.address,
+                dsap: request.ssap,
+                ssap: request.dsap,
+                fc: crate::fdl::FunctionCode::Response {
+                    state,
+                    status: crate::fdl::ResponseStatus::SapNotEnabled,
+                },
+            },
+            0,
+            |_| (),
+        )
+    }
+
+    /// Record the station state reported in a response telegram.
+    fn witness_response(&mut self, telegram: &crate::fdl::Telegram) {
+        if let crate::fdl::Telegram::Data(crate::fdl::DataTelegram {
+            h:
+                crate::fdl::DataTelegramHeader {
+                    sa,
+                    fc: crate::fdl::FunctionCode::Response { state, .. },
+                    ..
+                },
+            ..
+        }) = telegram
+        {
+            self.token_ring.record_station_state(*sa, *state);
         }
     }
 
     /// Mark receival of a telegram.
     fn mark_rx(&mut self, now: crate::time::Instant) {
         self.pending_bytes = 0;
+        self.rx_since_token_pass = true;
         self.mark_bus_activity(now);
     }
 
+    /// Whether the next token pass goes to ourselves while the bus is idle.
+    fn is_idle_self_token_pass(&self) -> bool {
+        self.bus_idle && self.token_ring.next_station() == self.p.address
+    }
+
+    /// Wait before passing the token to ourselves while the bus is idle.
+    ///
+    /// The pause of 5 slot times stays below the token lost timeout of any station (at least 6
+    /// slot times), so stations joining the bus do not generate a token of their own.
+    fn wait_bus_idle_pause(&mut self, now: crate::time::Instant) -> Option<PollDone> {
+        let last_bus_activity = *self.last_bus_activity.get_or_insert(now);
+        if self.is_idle_self_token_pass()
+            && now <= last_bus_activity + self.p.bits_to_time(5 * u32::from(self.p.slot_bits))
+        {
+            Some(PollDone::waiting_for_delay())
+        } else {
+            None
+        }
+    }
+
+    /// Track token rotations without any response to detect an idle bus.
+    ///
+    /// Called whenever we pass the token.
+    fn update_bus_idle(&mut self) {
+        if self.p.bus_idle_rotations == 0 {
+            return;
+        }
+
+        if self.rx_since_token_pass || self.token_ring.next_station() != self.p.address {
+            if self.bus_idle {
+                log::info!("Other stations are responding again, leaving bus idle mode.");
+            }
+            self.silent_rotations = 0;
+            self.bus_idle = false;
+        } else {
+            self.silent_rotations = self.silent_rotations.saturating_add(1);
+            if !self.bus_idle && self.silent_rotations >= self.p.bus_idle_rotations {
+                log::info!(
+                    "No station responded for {} token rotations, entering bus idle mode.",
+                    self.silent_rotations
+                );
+                self.bus_idle = true;
+            }
+        }
+        self.rx_since_token_pass = false;
+    }
+
+    /// Count a receive error reported by the PHY and drop it from the result.
+    fn count_rx_error<R>(
+        &mut self,
+        received: Option<Result<R, crate::fdl::DeserializeError>>,
+    ) -> Option<R> {
+        match received {
+            Some(Ok(r)) => Some(r),
+            Some(Err(e)) => {
+                log::trace!("Discarded invalid received data: {e}");
+                // The PHY dropped the whole receive buffer.
+                self.pending_bytes = 0;
+                self.stats.rx_errors.count(e);
+                None
+            }
+            None => None,
+        }
+    }
+
     /// Check whether the time to respond has passed without initiation of a response.
     fn check_slot_expired(&mut self, now: crate::time::Instant) -> bool {
         // We have two situations:
         // 1. Either the slot expires without any repsonse activity at all
         // 2. Or we received some bytes, but not a full telegram
         let last_bus_activity = *self.last_bus_activity.get_or_insert(now);
+        // Saturating math ensures a timer that went backwards never looks like an expired slot.
+        let elapsed = now.saturating_duration_since(last_bus_activity);
         if self.pending_bytes == 0 {
-            now > (last_bus_activity + self.p.slot_time())
+            elapsed > self.p.slot_time()
         } else {
             // TODO: Technically, no inter-character delay is allowed at all but we are in a rough
             // spot here.  The peripheral will most likely continue transmitting data in a short
@@ -620,12 +1303,12 @@ impl FdlActiveStation {
             // have longer delays between consecutive characters there.  For example, sometimes
             // data is received in chunks of 32 bytes.  This obviously looks like a large
             // inter-character delay that we need to be robust against.
-            now > (last_bus_activity + self.p.slot_time())
+            elapsed > self.p.slot_time()
         }
     }
 }
 
-impl FdlActiveStation {
+impl FdlActiveStation<'_> {
     #[must_use = "poll done marker"]
     fn handle_lost_token(
         &mut self,
@@ -635,9 +1318,10 @@ impl FdlActiveStation {
         // If we do not know of any previous bus activity, conservatively assume that the last
         // activity was just now and start counting from here...
         let last_bus_activity = *self.last_bus_activity.get_or_insert(now);
-        if (now - last_bus_activity) >= self.p.token_lost_timeout() {
+        if now.saturating_duration_since(last_bus_activity) >= self.p.token_lost_timeout() {
             if self.token_ring.ready_for_ring() {
                 log::warn!("Token lost! Generating a new one.");
+                self.stats.token_lost = self.stats.token_lost.wrapping_add(1);
             } else {
                 log::info!("Generating new token due to silent bus.");
             }
@@ -649,6 +1333,30 @@ impl FdlActiveStation {
         }
     }
 
+    fn is_in_gap(&self, address: crate::Address) -> bool {
+        let next_station = self.token_ring.next_station();
+        if address >= self.p.highest_station_address || address == self.p.address {
+            false
+        } else if next_station > self.p.address {
+            address > self.p.address && address < next_station
+        } else {
+            // Wrap-around GAP case (or we are alone on the bus)
+            address > self.p.address || address < next_station
+        }
+    }
+
+    fn next_priority_gap_poll(&self, current_address: Option<crate::Address>) -> GapState {
+        let start = current_address.map(|a| a + 1).unwrap_or(0);
+        let next_priority = (start..self.p.highest_station_address)
+            .find(|a| self.p.priority_gap_addresses & (1 << a) != 0 && self.is_in_gap(*a));
+
+        match next_priority {
+            Some(current_address) => GapState::DoPriorityPoll { current_address },
+            // Priority addresses done, continue with the sequential scan.
+            None => self.next_gap_poll(self.p.address),
+        }
+    }
+
     fn next_gap_poll(&self, current_address: crate::Address) -> GapState {
         let next_station = self.token_ring.next_station();
         let next_address = if current_address == (self.p.highest_station_address - 1) {
@@ -657,7 +1365,10 @@ impl FdlActiveStation {
             current_address + 1
         };
 
-        if next_address >= next_station && next_station > self.p.address {
+        if next_address == self.p.address {
+            // We went around the whole address range without finding another station.
+            GapState::Waiting { rotation_count: 0 }
+        } else if next_address >= next_station && next_station > self.p.address {
             // We have reached the end of the GAP, enter waiting state.
             GapState::Waiting { rotation_count: 0 }
         } else if next_address >= next_station
@@ -675,8 +1386,88 @@ impl FdlActiveStation {
 }
 
 /// State Machine of the FDL active station
-impl FdlActiveStation {
+impl FdlActiveStation<'_> {
     #[must_use = "poll done marker"]
+    fn do_passive_idle<'a, PHY: ProfibusPhy>(
+        &mut self,
+        now: crate::time::Instant,
+        phy: &mut PHY,
+    ) -> PollDone {
+        debug_assert_state!(self.state, State::PassiveIdle { .. });
+
+        // Handle pending response to a telegram request we received.  We never want to join the
+        // ring, so we always report that we are not ready.
+        if let Some(status_request_source) = *self.state.get_passive_idle_status_request() {
+            return_if_done!(self.wait_synchronization_pause(now));
+
+            let tx_res = phy
+                .transmit_telegram(now, |tx| {
+                    Some(tx.send_fdl_status_response(
+                        status_request_source,
+                        self.p.address,
+                        crate::fdl::ResponseState::MasterNotReady,
+                        crate::fdl::ResponseStatus::Ok,
+                    ))
+                })
+                .unwrap();
+
+            *self.state.get_passive_idle_status_request() = None;
+            return self.mark_tx(now, tx_res.bytes_sent());
+        }
+
+        if let Some(request) = *self.state.get_passive_idle_rejected_request() {
+            return_if_done!(self.wait_synchronization_pause(now));
+
+            let tx_res = phy
+                .transmit_telegram(now, |tx| {
+                    Some(self.transmit_rejection(
+                        tx,
+                        request,
+                        crate::fdl::ResponseState::MasterNotReady,
+                    ))
+                })
+                .unwrap();
+
+            *self.state.get_passive_idle_rejected_request() = None;
+            return self.mark_tx(now, tx_res.bytes_sent());
+        }
+
+        // Handle received telegrams.  Token telegrams are only witnessed, even when they are
+        // addressed to us.
+        let received = phy.try_receive_all_telegrams(now, |telegram, is_last_telegram| {
+            self.mark_rx(now);
+            self.witness_response(&telegram);
+
+            match telegram {
+                crate::fdl::Telegram::Token(token_telegram) => {
+                    self.token_ring
+                        .witness_token_pass(token_telegram.sa, token_telegram.da);
+                    PollDone::waiting_for_bus()
+                }
+                crate::fdl::Telegram::Data(data_telegram)
+                    if data_telegram.is_fdl_status_request().is_some()
+                        && data_telegram.h.da == self.p.address
+                        && is_last_telegram =>
+                {
+                    *self.state.get_passive_idle_status_request() = Some(data_telegram.h.sa);
+                    PollDone::waiting_for_delay()
+                }
+                crate::fdl::Telegram::Data(data_telegram) if is_last_telegram => {
+                    match self.rejected_request(&data_telegram) {
+                        Some(request) => {
+                            *self.state.get_passive_idle_rejected_request() = Some(request);
+                            PollDone::waiting_for_delay()
+                        }
+                        None => PollDone::waiting_for_bus(),
+                    }
+                }
+                _ => PollDone::waiting_for_bus(),
+            }
+        });
+        self.count_rx_error(received)
+            .unwrap_or(PollDone::waiting_for_bus())
+    }
+
     fn do_listen_token<'a, PHY: ProfibusPhy>(
         &mut self,
         now: crate::time::Instant,
@@ -719,8 +1510,25 @@ impl FdlActiveStation {
             return self.mark_tx(now, tx_res.bytes_sent());
         }
 
+        if let Some(request) = *self.state.get_listen_token_rejected_request() {
+            return_if_done!(self.wait_synchronization_pause(now));
+
+            let tx_res = phy
+                .transmit_telegram(now, |tx| {
+                    Some(self.transmit_rejection(
+                        tx,
+                        request,
+                        crate::fdl::ResponseState::MasterNotReady,
+                    ))
+                })
+                .unwrap();
+
+            *self.state.get_listen_token_rejected_request() = None;
+            return self.mark_tx(now, tx_res.bytes_sent());
+        }
+
         // Handle received telegrams
-        phy.receive_all_telegrams(now, |telegram, is_last_telegram| {
+        let received = phy.try_receive_all_telegrams(now, |telegram, is_last_telegram| {
             self.mark_rx(now);
 
             // This unusual construct is needed to catch situations where multiple telegrams are
@@ -750,6 +1558,8 @@ impl FdlActiveStation {
                 return PollDone::waiting_for_bus();
             }
 
+            self.witness_response(&telegram);
+
             match telegram {
                 // Handle witnessing a token telegram
                 crate::fdl::Telegram::Token(token_telegram) => {
@@ -769,9 +1579,22 @@ impl FdlActiveStation {
                         PollDone::waiting_for_bus()
                     }
                 }
+
+                // Reject all other requests sent to us
+                crate::fdl::Telegram::Data(data_telegram) if is_last_telegram => {
+                    match self.rejected_request(&data_telegram) {
+                        Some(request) => {
+                            *self.state.get_listen_token_rejected_request() = Some(request);
+                            PollDone::waiting_for_delay()
+                        }
+                        None => PollDone::waiting_for_bus(),
+                    }
+                }
                 _ => PollDone::waiting_for_bus(),
             }
-        }).unwrap_or(PollDone::waiting_for_bus())
+        });
+        self.count_rx_error(received)
+            .unwrap_or(PollDone::waiting_for_bus())
     }
 
     fn handle_telegram(
@@ -779,6 +1602,7 @@ impl FdlActiveStation {
         now: crate::time::Instant,
         telegram: crate::fdl::Telegram,
         is_last_telegram: bool,
+        apps: &mut dyn crate::fdl::ApplicationList,
     ) -> PollDone {
         // This unusual construct is needed to catch situations where multiple telegrams are
         // received at once and the first one leads us to go back to ListenToken due to collision.
@@ -788,6 +1612,8 @@ impl FdlActiveStation {
 
         debug_assert_state!(self.state, State::ActiveIdle { .. });
 
+        self.witness_response(&telegram);
+
         match telegram {
             // Handle any token telegrams
             crate::fdl::Telegram::Token(token_telegram) => {
@@ -858,6 +1684,49 @@ impl FdlActiveStation {
                 *self.state.get_active_idle_status_request() = Some(data_telegram.h.sa);
                 PollDone::waiting_for_delay()
             }
+
+            // Hand other requests sent to us to the applications
+            crate::fdl::Telegram::Data(data_telegram)
+                if data_telegram.h.da == self.p.address && is_last_telegram =>
+            {
+                let crate::fdl::FunctionCode::Request { req, .. } = data_telegram.h.fc else {
+                    return PollDone::waiting_for_bus();
+                };
+                // Only SDA, SDN, and SRD requests are passed on
+                if req.priority().is_none() {
+                    return PollDone::waiting_for_bus();
+                }
+
+                // The request handler gets the request first, then the applications
+                let handler = self.request_handler.0.take();
+                let responder = match handler {
+                    Some(handler) => {
+                        let accepted = handler.receive_request(now, self, &data_telegram);
+                        self.request_handler.0 = Some(handler);
+                        accepted.then_some(Responder::Handler)
+                    }
+                    None => None,
+                };
+                let responder = responder.or_else(|| {
+                    (0..apps.slots()).find_map(|index| {
+                        let (handle, app) = apps.get_dyn_mut(index)?;
+                        app.receive_request(now, self, &data_telegram)
+                            .then_some(Responder::Application(index, handle))
+                    })
+                });
+
+                if req.expects_reply() {
+                    *self.state.get_active_idle_app_request() = Some(PendingRequest {
+                        source: data_telegram.h.sa,
+                        dsap: data_telegram.h.dsap,
+                        ssap: data_telegram.h.ssap,
+                        responder,
+                    });
+                    PollDone::waiting_for_delay()
+                } else {
+                    PollDone::waiting_for_bus()
+                }
+            }
             _ => PollDone::waiting_for_bus(),
         }
     }
@@ -867,6 +1736,7 @@ impl FdlActiveStation {
         &mut self,
         now: crate::time::Instant,
         phy: &mut PHY,
+        apps: &mut dyn crate::fdl::ApplicationList,
     ) -> PollDone {
         debug_assert_state!(self.state, State::ActiveIdle { .. });
 
@@ -891,12 +1761,44 @@ impl FdlActiveStation {
             return self.mark_tx(now, tx_res.bytes_sent());
         }
 
-        phy.receive_all_telegrams(now, |telegram, is_last_telegram| {
+        // Handle pending response to a request for an application
+        if let Some(request) = *self.state.get_active_idle_app_request() {
+            return_if_done!(self.wait_synchronization_pause(now));
+
+            *self.state.get_active_idle_app_request() = None;
+            let tx_res = phy.transmit_telegram(now, |tx| match request.responder {
+                Some(Responder::Handler) => {
+                    let handler = self.request_handler.0.take()?;
+                    let tx_res = handler.transmit_response(now, self, request.source, tx);
+                    self.request_handler.0 = Some(handler);
+                    tx_res
+                }
+                // Skip the response if the application was removed in the meantime
+                Some(Responder::Application(index, handle)) => match apps.get_dyn_mut(index) {
+                    Some((h, app)) if h == handle => {
+                        app.transmit_response(now, self, request.source, tx)
+                    }
+                    _ => None,
+                },
+                // No application accepted the request
+                None => Some(self.transmit_rejection(
+                    tx,
+                    request,
+                    crate::fdl::ResponseState::MasterInRing,
+                )),
+            });
+            if let Some(tx_res) = tx_res {
+                return self.mark_tx(now, tx_res.bytes_sent());
+            }
+        }
+
+        let received = phy.try_receive_all_telegrams(now, |telegram, is_last_telegram| {
             self.mark_rx(now);
 
-            self.handle_telegram(now, telegram, is_last_telegram)
-        })
-        .unwrap_or(PollDone::waiting_for_bus())
+            self.handle_telegram(now, telegram, is_last_telegram, apps)
+        });
+        self.count_rx_error(received)
+            .unwrap_or(PollDone::waiting_for_bus())
     }
 
     #[must_use = "poll done marker"]
@@ -942,23 +1844,105 @@ impl FdlActiveStation {
             res
         }) {
             if let Some(addr) = tx_res.expects_reply() {
+                match self.pending_retry {
+                    Some((a, _)) if a == addr => log::warn!("Resending a telegram to #{addr}..."),
+                    // The application gave up on the request which was being retried.
+                    Some(_) => self.pending_retry = None,
+                    None => (),
+                }
+                self.request_retries_allowed = tx_res.allows_retries();
                 let data = *self.state.get_use_token_data();
                 self.state.transition_await_data_response(addr, data);
             }
             Some(self.mark_tx(now, tx_res.bytes_sent()))
         } else {
+            // The application completed its cycle, so it also gave up on any pending retry.
+            self.pending_retry = None;
             None
         }
     }
 
-    fn schedule_next_application(&mut self, num_apps: usize) -> ScheduleNext {
+    /// Index of the application which is currently transmitting.
+    fn current_application(&self) -> usize {
+        usize::from(self.background_application.unwrap_or(self.next_application))
+    }
+
+    /// Start using the leftover token hold time for background applications.
+    ///
+    /// Returns `false` when there is no time left or no background application.
+    fn start_background_applications(
+        &mut self,
+        now: crate::time::Instant,
+        apps: &dyn crate::fdl::ApplicationList,
+        high_prio_only: bool,
+    ) -> bool {
+        if high_prio_only || now >= self.end_token_hold_time {
+            return false;
+        }
+        let start = usize::from(self.next_background_application);
+        let Some(app) = find_application(apps, start, true) else {
+            return false;
+        };
+        let data = self.state.get_use_token_data();
+        data.first_app.get_or_insert(self.next_application);
+        data.first_background_app = Some(app);
+        self.background_application = Some(app);
+        true
+    }
+
+    fn schedule_next_application(
+        &mut self,
+        now: crate::time::Instant,
+        apps: &dyn crate::fdl::ApplicationList,
+        high_prio_only: bool,
+    ) -> ScheduleNext {
+        let num_apps = apps.slots();
+
+        if let Some(current) = self.background_application {
+            // Each background application gets one cycle of the leftover token hold time.
+            let first_background_app = self
+                .state
+                .get_use_token_data()
+                .first_background_app
+                .unwrap_or(current);
+            match find_application(apps, usize::from(current) + 1, true) {
+                Some(next)
+                    if !wraps_around(num_apps, current, next, first_background_app)
+                        && now < self.end_token_hold_time =>
+                {
+                    self.next_background_application = next;
+                    self.background_application = Some(next);
+                    return ScheduleNext::Scheduled;
+                }
+                Some(next) => self.next_background_application = next,
+                None => (),
+            }
+            self.background_application = None;
+            return ScheduleNext::CycleCompleted;
+        }
+
         let data = self.state.get_use_token_data();
         let first_app = *data.first_app.get_or_insert(self.next_application);
-        self.next_application = (self.next_application + 1) % num_apps;
-        if self.next_application == first_app {
-            ScheduleNext::CycleCompleted
-        } else {
+
+        // Applications with a higher weight complete multiple cycles in their turn.
+        let current = self.next_application;
+        self.application_turns += 1;
+        if self.application_turns < apps.weight(usize::from(current)).unwrap_or(0) {
+            return ScheduleNext::Scheduled;
+        }
+        self.application_turns = 0;
+
+        if let Some(next) = find_application(apps, usize::from(current) + 1, false) {
+            self.next_application = next;
+            if !wraps_around(num_apps, current, next, first_app) {
+                return ScheduleNext::Scheduled;
+            }
+        }
+
+        if self.start_background_applications(now, apps, high_prio_only) {
             ScheduleNext::Scheduled
+        } else {
+            ScheduleNext::CycleCompleted
         }
     }
 
@@ -967,19 +1951,39 @@ impl FdlActiveStation {
         &mut self,
         now: crate::time::Instant,
         phy: &mut PHY,
-        apps: &mut [&mut dyn FdlApplication],
+        apps: &mut dyn crate::fdl::ApplicationList,
         high_prio_only: bool,
     ) -> Option<PollDone> {
-        for _ in 0..apps.len() {
-            // TODO: Need to deal with the application list changing size.  Currently, this will lead
-            // to a panic or unexpected telegrams being forwarded to an application.
-            let current_app = &mut apps[self.next_application];
-            let res = self.app_transmit_telegram(now, phy, *current_app, high_prio_only);
-            return_if_done!(res);
+        if self.state.get_use_token_data().first_app.is_none() {
+            // Each token hold starts with the regular applications.  A background application
+            // which did not complete its cycle continues when there is leftover time again.
+            self.background_application = None;
+            match find_application(apps, usize::from(self.next_application), false) {
+                Some(app) if app != self.next_application => {
+                    self.next_application = app;
+                    self.application_turns = 0;
+                }
+                Some(_) => (),
+                None => {
+                    if !self.start_background_applications(now, apps, high_prio_only) {
+                        return None;
+                    }
+                }
+            }
+        }
+
+        loop {
+            // Applications may have been removed since the last poll.  Their slots are skipped.
+            if let Some((handle, app)) = apps.get_dyn_mut(self.current_application()) {
+                self.requesting_application = Some(handle);
+                return_if_done!(self.app_transmit_telegram(now, phy, app, high_prio_only));
+            }
 
             // The previous application claims its cycle is done, so the next application can take
             // over.
-            if self.schedule_next_application(apps.len()) == ScheduleNext::CycleCompleted {
+            if self.schedule_next_application(now, apps, high_prio_only)
+                == ScheduleNext::CycleCompleted
+            {
                 // All applications completed their cycle once since we got the token, now it's
                 // time to pass the token.
                 break;
@@ -993,16 +1997,29 @@ impl FdlActiveStation {
         &mut self,
         now: crate::time::Instant,
         phy: &mut PHY,
-        apps: &mut [&mut dyn FdlApplication],
+        apps: &mut dyn crate::fdl::ApplicationList,
     ) -> PollDone {
         debug_assert_state!(self.state, State::UseToken { .. });
 
+        if self.offline_requested {
+            // Pass on the token right away, without any further requests or GAP polling.
+            self.state
+                .transition_pass_token(false, PassTokenAttempt::First);
+            return PollDone::waiting_for_delay();
+        }
+
         let data = *self.state.get_use_token_data();
         if self.last_token_time != data.token_time {
             self.end_token_hold_time = self.last_token_time + self.p.token_rotation_time();
+            if self.measure_rotation {
+                let rotation = data.token_time - self.last_token_time;
+                self.rotation_bits =
+                    Some(u32::try_from(self.p.baudrate.time_to_bits(rotation)).unwrap_or(u32::MAX));
+            }
+            self.measure_rotation = true;
             self.last_token_time = data.token_time;
 
-            if let GapState::DoPoll { .. } = self.gap_state {
+            if self.gap_state.poll_address().is_some() {
                 // Subtract the gap poll time from the end_token_hold_time so we leave time for
                 // polling the gap.
                 self.end_token_hold_time -= self.p.bits_to_time(u32::from(self.p.slot_bits) + 100);
@@ -1011,12 +2028,13 @@ impl FdlActiveStation {
 
         return_if_done!(self.wait_synchronization_pause(now));
 
+        let data = self.state.get_use_token_data();
         if now < self.end_token_hold_time {
-            *self.state.get_use_token_first_cycle_done() = true;
+            data.message_cycles = data.message_cycles.saturating_add(1);
             return_if_done!(self.apps_transmit_telegram(now, phy, apps, false));
-        } else if !*self.state.get_use_token_first_cycle_done() {
-            // Do one high priority message cycle
-            *self.state.get_use_token_first_cycle_done() = true;
+        } else if data.message_cycles < self.p.min_message_cycles {
+            // Do high priority message cycles until the guaranteed minimum is reached
+            data.message_cycles += 1;
             return_if_done!(self.apps_transmit_telegram(now, phy, apps, true));
         }
 
@@ -1030,60 +2048,85 @@ impl FdlActiveStation {
         &mut self,
         now: crate::time::Instant,
         phy: &mut PHY,
-        apps: &mut [&mut dyn FdlApplication],
+        apps: &mut dyn crate::fdl::ApplicationList,
     ) -> PollDone {
         debug_assert_state!(self.state, State::AwaitDataResponse { .. });
 
         let address = *self.state.get_await_data_response_address();
         let data = *self.state.get_await_data_response_data();
 
-        // TODO: Need to deal with the application list changing size.  Currently, this will lead
-        // to a panic or unexpected telegrams being forwarded to an application.
-        let app = &mut apps[self.next_application];
+        // The application may have been removed (and its slot reused) since it sent the request.
+        // The reply or timeout is then dropped.
+        let mut app = match apps.get_dyn_mut(self.current_application()) {
+            Some((handle, app)) if Some(handle) == self.requesting_application => Some(app),
+            _ => None,
+        };
 
         // Here we conservatively only receive the first pending telegram because it is very
         // unlikely that some other station randomly stole our token.  If it did, we will notice in
         // the next poll cycle.
-        let reply_events: Result<Option<()>, PollDone> = phy
-            .receive_telegram(now, |telegram| {
-                self.mark_rx(now);
-
-                let is_valid_response = match &telegram {
-                    crate::fdl::Telegram::Token(_) => false,
-                    crate::fdl::Telegram::ShortConfirmation(_) => true,
-                    crate::fdl::Telegram::Data(t) => {
-                        t.h.sa == address && t.h.da == self.p.address && matches!(t.h.fc, crate::fdl::FunctionCode::Response { .. })
-                    },
-                };
+        let received = phy.try_receive_telegram(now, |telegram| {
+            self.mark_rx(now);
 
-                if is_valid_response {
-                    Ok(Some(app.receive_reply(now, self, address, telegram)))
-                } else {
-                    // When receiving a valid telegram that isn't a valid response, something went
-                    // wrong and we must go back to active idle state.
-                    log::warn!("Received unexpected telegram while waiting for reply from #{address}: {:?}", telegram);
-                    self.state.transition_active_idle();
-                    Err(PollDone::waiting_for_bus())
+            let is_valid_response = match &telegram {
+                crate::fdl::Telegram::Token(_) => false,
+                crate::fdl::Telegram::ShortConfirmation(_) => true,
+                crate::fdl::Telegram::Data(t) => {
+                    t.h.sa == address
+                        && t.h.da == self.p.address
+                        && matches!(t.h.fc, crate::fdl::FunctionCode::Response { .. })
                 }
-            })
-            .unwrap_or(Ok(None));
+            };
+
+            if is_valid_response {
+                self.witness_response(&telegram);
+                if let Some(app) = app.as_mut() {
+                    app.receive_reply(now, self, address, telegram);
+                }
+                Ok(Some(()))
+            } else {
+                // When receiving a valid telegram that isn't a valid response, something went
+                // wrong and we must go back to active idle state.
+                log::warn!(
+                    "Received unexpected telegram while waiting for reply from #{address}: {:?}",
+                    telegram
+                );
+                self.state.transition_active_idle();
+                Err(PollDone::waiting_for_bus())
+            }
+        });
+        let reply_events: Result<Option<()>, PollDone> =
+            self.count_rx_error(received).unwrap_or(Ok(None));
 
         match reply_events {
             Err(d) => {
                 return d.into();
             }
             Ok(Some(())) => {
+                self.pending_retry = None;
                 self.state.transition_use_token(data);
-                *self.state.get_use_token_first_cycle_done() = true;
                 return PollDone::waiting_for_delay();
             }
             Ok(None) => (),
         }
 
         if self.check_slot_expired(now) {
-            app.handle_timeout(now, self, address);
+            self.stats.timeouts = self.stats.timeouts.wrapping_add(1);
+            let retries = match self.pending_retry {
+                Some((a, retries)) if a == address => retries,
+                _ => 0,
+            };
+            if self.request_retries_allowed && retries < self.p.max_retry_limit {
+                // The application is asked to transmit again and is expected to repeat the
+                // request.
+                self.pending_retry = Some((address, retries + 1));
+            } else {
+                self.pending_retry = None;
+                if let Some(app) = app {
+                    app.handle_timeout(now, self, address);
+                }
+            }
             self.state.transition_use_token(data);
-            *self.state.get_use_token_first_cycle_done() = true;
 
             // Immediately evaluate UseToken state because the bus is free for immediate
             // transmission
@@ -1102,6 +2145,7 @@ impl FdlActiveStation {
         debug_assert_state!(self.state, State::PassToken { .. });
 
         return_if_done!(self.wait_synchronization_pause(now));
+        return_if_done!(self.wait_bus_idle_pause(now));
 
         if *self.state.get_pass_token_do_gap() {
             match &mut self.gap_state {
@@ -1111,7 +2155,7 @@ impl FdlActiveStation {
                     if *rotation_count > self.p.gap_wait_rotations {
                         // We're done waiting, do a poll now!
                         log::debug!("Starting next gap polling cycle!");
-                        self.gap_state = self.next_gap_poll(self.p.address);
+                        self.gap_state = self.next_priority_gap_poll(None);
                     } else {
                         *rotation_count += 1;
                     }
@@ -1120,9 +2164,13 @@ impl FdlActiveStation {
                     let current_address = *current_address;
                     self.gap_state = self.next_gap_poll(current_address);
                 }
+                GapState::DoPriorityPoll { current_address } => {
+                    let current_address = *current_address;
+                    self.gap_state = self.next_priority_gap_poll(Some(current_address));
+                }
             }
 
-            if let GapState::DoPoll { current_address } = self.gap_state {
+            if let Some(current_address) = self.gap_state.poll_address() {
                 let tx_res = phy
                     .transmit_telegram(now, |tx| {
                         Some(tx.send_fdl_status_request(current_address, self.p.address))
@@ -1144,6 +2192,7 @@ impl FdlActiveStation {
         self.token_ring
             .witness_token_pass(self.p.address, self.token_ring.next_station());
 
+        self.update_bus_idle();
         if self.token_ring.next_station() == self.p.address {
             self.state
                 .transition_use_token(UseTokenData::with_token_time(now));
@@ -1168,13 +2217,14 @@ impl FdlActiveStation {
         // Here we conservatively only receive the first pending telegram because it is very
         // unlikely that some other station randomly stole our token.  If it did, we will notice in
         // the next poll cycle.
-        let received = phy.receive_telegram(now, |telegram| {
+        let received = phy.try_receive_telegram(now, |telegram| {
             self.mark_rx(now);
 
             if let crate::fdl::Telegram::Data(telegram) = &telegram {
                 if telegram.h.sa == address && telegram.h.da == self.p.address {
                     if let crate::fdl::FunctionCode::Response { state, status } = telegram.h.fc {
                         log::trace!("Address #{address} responded");
+                        self.token_ring.record_station_state(address, state);
                         if status == crate::fdl::ResponseStatus::Ok
                             && matches!(state, crate::fdl::ResponseState::MasterWithoutToken | crate::fdl::ResponseState::MasterInRing) {
                             self.token_ring.set_next_station(address);
@@ -1191,7 +2241,7 @@ impl FdlActiveStation {
             PollDone::waiting_for_bus()
         });
 
-        if let Some(res) = received {
+        if let Some(res) = self.count_rx_error(received) {
             return res;
         }
 
@@ -1212,10 +2262,14 @@ impl FdlActiveStation {
         &mut self,
         now: crate::time::Instant,
         phy: &mut PHY,
+        apps: &mut dyn crate::fdl::ApplicationList,
     ) -> PollDone {
         debug_assert_state!(self.state, State::CheckTokenPass { .. });
 
         if self.check_slot_expired(now) {
+            if *self.state.get_check_token_pass_attempt() != PassTokenAttempt::Third {
+                self.stats.token_retries = self.stats.token_retries.wrapping_add(1);
+            }
             match *self.state.get_check_token_pass_attempt() {
                 PassTokenAttempt::First => {
                     log::warn!(
@@ -1251,7 +2305,7 @@ impl FdlActiveStation {
         }
 
         let mut first_in = true;
-        phy.receive_all_telegrams(now, |telegram, is_last_telegram| {
+        let received = phy.try_receive_all_telegrams(now, |telegram, is_last_telegram| {
             self.mark_rx(now);
 
             // Only check and transition to ActiveIdle on the first telegram.
@@ -1269,9 +2323,10 @@ impl FdlActiveStation {
 
                 first_in = false;
             }
-            self.handle_telegram(now, telegram, is_last_telegram)
-        })
-        .unwrap_or(PollDone::waiting_for_bus())
+            self.handle_telegram(now, telegram, is_last_telegram, apps)
+        });
+        self.count_rx_error(received)
+            .unwrap_or(PollDone::waiting_for_bus())
     }
 
     /// Poll the bus with a single active application.
@@ -1284,7 +2339,14 @@ impl FdlActiveStation {
         phy: &mut PHY,
         app: &mut dyn FdlApplication,
     ) {
-        let _result = self.poll_inner(now, phy, &mut [app]);
+        let _result = self.poll_inner(
+            now,
+            phy,
+            &mut crate::fdl::ApplicationSlice {
+                apps: &mut [app],
+                weights: &[],
+            },
+        );
     }
 
     /// Poll the bus with multiple active applications.
@@ -1300,13 +2362,57 @@ impl FdlActiveStation {
     /// the applications get to continue their cycles once the token is received again.
     ///
     /// **Warning**: The list of applications must not change unless the FdlActiveStation is
-    /// currently offline.  Changing the list may lead to unexpected behavior of applications or
-    /// panics.
+    /// currently offline.  Changing the list may lead to unexpected behavior of applications.  Use
+    /// [`poll_applications()`][`FdlActiveStation::poll_applications`] if applications need to be
+    /// added or removed at runtime.
     pub fn poll_multi<PHY: ProfibusPhy>(
         &mut self,
         now: crate::time::Instant,
         phy: &mut PHY,
         apps: &mut [&mut dyn FdlApplication],
+    ) {
+        let _result = self.poll_inner(
+            now,
+            phy,
+            &mut crate::fdl::ApplicationSlice { apps, weights: &[] },
+        );
+    }
+
+    /// Poll the bus with multiple active applications with different scheduling weights.
+    ///
+    /// This works like [`poll_multi()`][`FdlActiveStation::poll_multi`] but `weights[i]` sets how
+    /// many cycles `apps[i]` may complete in its turn before the next application is activated.
+    /// Applications without an entry in `weights` have a weight of 1.
+    ///
+    /// Applications with a weight of 0 are background applications.  They are only activated
+    /// when all other applications completed their turn and token hold time is left.  This is
+    /// useful for running a bus scanner next to a DP master without slowing down the DP cycle.
+    ///
+    /// **Warning**: The list of applications must not change unless the FdlActiveStation is
+    /// currently offline, just like for [`poll_multi()`][`FdlActiveStation::poll_multi`].
+    pub fn poll_weighted<PHY: ProfibusPhy>(
+        &mut self,
+        now: crate::time::Instant,
+        phy: &mut PHY,
+        apps: &mut [&mut dyn FdlApplication],
+        weights: &[u8],
+    ) {
+        let _result = self.poll_inner(
+            now,
+            phy,
+            &mut crate::fdl::ApplicationSlice { apps, weights },
+        );
+    }
+
+    /// Poll the bus with the applications from an [`ApplicationSet`][`crate::fdl::ApplicationSet`].
+    ///
+    /// This works like [`poll_weighted()`][`FdlActiveStation::poll_weighted`] with the weights
+    /// configured in the set.  Applications can be added to or removed from the set at any time.
+    pub fn poll_applications<PHY: ProfibusPhy>(
+        &mut self,
+        now: crate::time::Instant,
+        phy: &mut PHY,
+        apps: &mut crate::fdl::ApplicationSet,
     ) {
         let _result = self.poll_inner(now, phy, apps);
     }
@@ -1315,8 +2421,51 @@ impl FdlActiveStation {
         &mut self,
         now: crate::time::Instant,
         phy: &mut PHY,
-        apps: &mut [&mut dyn FdlApplication],
+        apps: &mut dyn crate::fdl::ApplicationList,
+    ) -> PollDone {
+        let result = self.poll_state_machine(now, phy, apps);
+
+        let in_ring = self.is_in_ring();
+        if in_ring && !self.was_in_ring {
+            log::info!("FDL active station joined the token ring.");
+            self.joined_ring = true;
+        } else if !in_ring && self.was_in_ring {
+            self.joined_ring = false;
+            self.rotation_bits = None;
+            self.measure_rotation = false;
+        }
+        self.was_in_ring = in_ring;
+
+        result
+    }
+
+    fn poll_state_machine<PHY: ProfibusPhy>(
+        &mut self,
+        now: crate::time::Instant,
+        phy: &mut PHY,
+        apps: &mut dyn crate::fdl::ApplicationList,
     ) -> PollDone {
+        if self.offline_requested {
+            // Go offline once the token was passed on.  When we are alone in the ring, there is
+            // nobody to pass it to.
+            let alone = self.token_ring.next_station() == self.p.address;
+            match &self.state {
+                State::Offline
+                | State::PassiveIdle { .. }
+                | State::ListenToken { .. }
+                | State::ActiveIdle { .. }
+                | State::ClaimToken { .. } => {
+                    self.set_offline();
+                    return PollDone::offline();
+                }
+                State::PassToken { .. } if alone => {
+                    self.set_offline();
+                    return PollDone::offline();
+                }
+                _ => (),
+            }
+        }
+
         // Handle connectivity_state changes
         match self.connectivity_state {
             ConnectivityState::Offline => {
@@ -1325,20 +2474,20 @@ impl FdlActiveStation {
                 return PollDone::offline().into();
             }
             ConnectivityState::Passive => {
-                // TODO: Check if these are all the states from which we can transition to passive
-                // idle
+                // While holding the token, we first finish the current token cycle and only
+                // leave the ring afterwards.
                 match &self.state {
                     State::ActiveIdle { .. } | State::ListenToken { .. } | State::Offline => {
                         self.state.transition_passive_idle();
                     }
-                    State::PassiveIdle => (),
+                    State::PassiveIdle { .. } => (),
                     s => {
                         log::debug!("Can't transition from \"{s:?}\" to PassiveIdle");
                     }
                 }
             }
             ConnectivityState::Online => {
-                if matches!(self.state, State::Offline | State::PassiveIdle) {
+                if matches!(self.state, State::Offline | State::PassiveIdle { .. }) {
                     self.state.transition_listen_token();
                 }
             }
@@ -1354,15 +2503,15 @@ impl FdlActiveStation {
 
         match &self.state {
             State::Offline { .. } => unreachable!(),
+            State::PassiveIdle { .. } => self.do_passive_idle(now, phy).into(),
             State::ListenToken { .. } => self.do_listen_token(now, phy).into(),
             State::ClaimToken { .. } => self.do_claim_token(now, phy).into(),
             State::UseToken { .. } => self.do_use_token(now, phy, apps).into(),
             State::AwaitDataResponse { .. } => self.do_await_data_response(now, phy, apps).into(),
             State::PassToken { .. } => self.do_pass_token(now, phy).into(),
-            State::CheckTokenPass { .. } => self.do_check_token_pass(now, phy).into(),
-            State::ActiveIdle { .. } => self.do_active_idle(now, phy).into(),
+            State::CheckTokenPass { .. } => self.do_check_token_pass(now, phy, apps).into(),
+            State::ActiveIdle { .. } => self.do_active_idle(now, phy, apps).into(),
             State::AwaitStatusResponse { .. } => self.do_await_status_response(now, phy).into(),
-            s => todo!("Active station state {s:?} not implemented yet!"),
         }
     }
 }
@@ -1376,7 +2525,55 @@ mod tests {
     fn fdl_active_station_struct_size() {
         let size = std::mem::size_of::<FdlActiveStation>();
         println!("FDL active station struct is {size} bytes large.");
-        assert!(size <= 256);
+        assert!(size <= 288);
+    }
+
+    /// Only polling at the reported deadlines must lead to the same bus communication as
+    /// continuous polling.
+    #[test]
+    fn poll_at_deadline() {
+        crate::test_utils::prepare_test_logger();
+
+        let run = |sleep: bool| {
+            let mut phy = crate::phy::SimulatorPhy::new(crate::Baudrate::B19200, "phy");
+            let mut monitor = phy.duplicate("monitor");
+            let mut fdl = FdlActiveStation::new(Default::default());
+            crate::test_utils::set_active_addr(fdl.address());
+            fdl.set_online();
+
+            let mut telegrams = Vec::new();
+            let mut polls = 0;
+            let mut now = crate::time::Instant::ZERO;
+            while now.total_millis() < 500 {
+                fdl.poll(now, &mut phy, &mut ());
+                polls += 1;
+
+                let step = now + crate::time::Duration::from_micros(100);
+                now = if sleep {
+                    // Bus activity of our own station is always covered by the deadline.
+                    fdl.next_poll_deadline(now).unwrap().max(step)
+                } else {
+                    step
+                };
+                phy.set_bus_time(now);
+                crate::test_utils::set_log_timestamp(now);
+
+                while let Some(t) = monitor.receive_telegram(now, |t| {
+                    format!("{:?}", t.clone_with_pdu_buffer(&mut [0u8; 256]))
+                }) {
+                    telegrams.push(t);
+                }
+            }
+            (telegrams, polls)
+        };
+
+        let (busy_telegrams, busy_polls) = run(false);
+        let (sleep_telegrams, sleep_polls) = run(true);
+        assert!(busy_telegrams.len() > 10);
+        let common = sleep_telegrams.len().min(busy_telegrams.len());
+        assert!(common > 10);
+        assert_eq!(sleep_telegrams[..common], busy_telegrams[..common]);
+        assert!(sleep_polls * 10 < busy_polls);
     }
 
     #[test]
@@ -1386,7 +2583,7 @@ mod tests {
         let mut phy = crate::phy::SimulatorPhy::new(crate::Baudrate::B19200, "phy");
         let mut fdl = FdlActiveStation::new(Default::default());
 
-        crate::test_utils::set_active_addr(fdl.parameters().address);
+        crate::test_utils::set_active_addr(fdl.address());
 
         fdl.set_online();
         fdl.set_offline();
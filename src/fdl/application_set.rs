@@ -0,0 +1,366 @@
+use crate::fdl::FdlApplication;
+use core::fmt;
+
+/// An application that can be stored in an [`ApplicationSet`].
+///
+/// The applications which are part of `profirust` are stored by value so they can be accessed
+/// again using [`ApplicationSet::get_mut()`].  Other applications are stored by reference.
+///
+/// Convert an application into this type using `From`/`Into` and get it back using
+/// [`downcast()`][`Application::downcast`].
+pub struct Application<'a>(pub(crate) ApplicationKind<'a>);
+
+/// Storage for the different application types.
+///
+/// This is crate-private so new application types can be added without breaking users.  The
+/// conversions for the types of other layers live next to those types.
+pub(crate) enum ApplicationKind<'a> {
+    DpMaster(crate::dp::DpMaster<'a>),
+    DpScanner(crate::dp::scan::DpScanner),
+    LiveList(crate::fdl::live_list::LiveList),
+    #[cfg(feature = "std")]
+    BusScan(crate::dp::scan::BusScan),
+    Other(&'a mut dyn FdlApplication),
+}
+
+impl<'a> Application<'a> {
+    /// Get back the application of type `A`, or `Err(self)` if it is of a different type.
+    #[allow(clippy::result_large_err)]
+    pub fn downcast<A: AnyApplication<'a>>(self) -> Result<A, Self> {
+        A::downcast(self)
+    }
+
+    fn as_dyn_mut(&mut self) -> &mut dyn FdlApplication {
+        match &mut self.0 {
+            ApplicationKind::DpMaster(a) => a,
+            ApplicationKind::DpScanner(a) => a,
+            ApplicationKind::LiveList(a) => a,
+            #[cfg(feature = "std")]
+            ApplicationKind::BusScan(a) => a,
+            ApplicationKind::Other(a) => &mut **a,
+        }
+    }
+}
+
+/// Application types that can be retrieved from an [`ApplicationSet`].
+pub trait AnyApplication<'a>: Sized {
+    fn downcast_ref<'c>(application: &'c Application<'a>) -> Option<&'c Self>;
+    fn downcast_mut<'c>(application: &'c mut Application<'a>) -> Option<&'c mut Self>;
+    #[allow(clippy::result_large_err)]
+    fn downcast(application: Application<'a>) -> Result<Self, Application<'a>>;
+}
+
+macro_rules! impl_any_application {
+    ($variant:ident, $type:ty) => {
+        impl<'a> crate::fdl::AnyApplication<'a> for $type {
+            fn downcast_ref<'c>(application: &'c crate::fdl::Application<'a>) -> Option<&'c Self> {
+                match &application.0 {
+                    crate::fdl::ApplicationKind::$variant(a) => Some(a),
+                    _ => None,
+                }
+            }
+
+            fn downcast_mut<'c>(
+                application: &'c mut crate::fdl::Application<'a>,
+            ) -> Option<&'c mut Self> {
+                match &mut application.0 {
+                    crate::fdl::ApplicationKind::$variant(a) => Some(a),
+                    _ => None,
+                }
+            }
+
+            fn downcast(
+                application: crate::fdl::Application<'a>,
+            ) -> Result<Self, crate::fdl::Application<'a>> {
+                match application.0 {
+                    crate::fdl::ApplicationKind::$variant(a) => Ok(a),
+                    other => Err(crate::fdl::Application(other)),
+                }
+            }
+        }
+
+        impl<'a> From<$type> for crate::fdl::Application<'a> {
+            fn from(application: $type) -> Self {
+                crate::fdl::Application(crate::fdl::ApplicationKind::$variant(application))
+            }
+        }
+    };
+}
+pub(crate) use impl_any_application;
+
+impl_any_application!(LiveList, crate::fdl::live_list::LiveList);
+
+impl<'a> From<&'a mut dyn FdlApplication> for Application<'a> {
+    fn from(application: &'a mut dyn FdlApplication) -> Self {
+        Application(ApplicationKind::Other(application))
+    }
+}
+
+struct ApplicationEntry<'a> {
+    application: Application<'a>,
+    weight: u8,
+    generation: u16,
+}
+
+/// Storage type that can hold one application.
+#[derive(Default)]
+pub struct ApplicationStorage<'a> {
+    inner: Option<ApplicationEntry<'a>>,
+}
+
+/// Handle that can be used to obtain an application from an [`ApplicationSet`].
+///
+/// Handles stay valid when other applications are added or removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ApplicationHandle {
+    index: u8,
+    generation: u16,
+}
+
+impl fmt::Display for ApplicationHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Application {}", self.index)
+    }
+}
+
+/// Collection of applications for
+/// [`FdlActiveStation::poll_applications()`][`crate::fdl::FdlActiveStation::poll_applications`].
+///
+/// Unlike the slice passed to
+/// [`poll_multi()`][`crate::fdl::FdlActiveStation::poll_multi`], applications can be added to and
+/// removed from the set at any time.  The FDL active station keeps track of the applications
+/// using their handles, so a reply is never forwarded to the wrong application.
+///
+/// Like for the [`DpMaster`][`crate::dp::DpMaster`], the storage can either be fixed-size (slice
+/// or array) or, if `alloc`/`std` is available, a `Vec<>` that will grow as needed.
+pub struct ApplicationSet<'a> {
+    applications: managed::ManagedSlice<'a, ApplicationStorage<'a>>,
+    next_generation: u16,
+}
+
+impl<'a> ApplicationSet<'a> {
+    pub fn new<S>(storage: S) -> Self
+    where
+        S: Into<managed::ManagedSlice<'a, ApplicationStorage<'a>>>,
+    {
+        Self {
+            applications: storage.into(),
+            next_generation: 0,
+        }
+    }
+
+    /// Add an application to the set with a scheduling weight of 1, and return its handle.
+    ///
+    /// # Panics
+    /// This function panics if the storage is fixed-size (not a `Vec`) and is full.
+    pub fn add<A: Into<Application<'a>>>(&mut self, application: A) -> ApplicationHandle {
+        let generation = self.next_generation;
+        self.next_generation = self.next_generation.wrapping_add(1);
+        let entry = ApplicationEntry {
+            application: application.into(),
+            weight: 1,
+            generation,
+        };
+
+        for (index, slot) in self.applications.iter_mut().enumerate() {
+            if slot.inner.is_none() {
+                slot.inner = Some(entry);
+                return ApplicationHandle {
+                    index: u8::try_from(index).unwrap(),
+                    generation,
+                };
+            }
+        }
+
+        match &mut self.applications {
+            managed::ManagedSlice::Borrowed(_) => {
+                panic!("Adding application to full ApplicationSet")
+            }
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            managed::ManagedSlice::Owned(applications) => {
+                applications.push(ApplicationStorage { inner: Some(entry) });
+                ApplicationHandle {
+                    index: (applications.len() - 1).try_into().unwrap(),
+                    generation,
+                }
+            }
+        }
+    }
+
+    /// Set the scheduling weight of an application.
+    ///
+    /// See [`FdlActiveStation::poll_weighted()`][`crate::fdl::FdlActiveStation::poll_weighted`]
+    /// for the meaning of the weight.
+    ///
+    /// # Panics
+    /// This function panics if the application was removed.
+    pub fn set_weight(&mut self, handle: ApplicationHandle, weight: u8) {
+        self.entry_mut(handle).weight = weight;
+    }
+
+    /// Get an application from the set by its handle.
+    ///
+    /// # Panics
+    /// This function panics if the application was removed or is not of type `A`.
+    pub fn get<A: AnyApplication<'a>>(&self, handle: ApplicationHandle) -> &A {
+        let entry = match self.applications[usize::from(handle.index)].inner.as_ref() {
+            Some(e) if e.generation == handle.generation => e,
+            _ => panic!("{handle} was removed from the application set"),
+        };
+        match A::downcast_ref(&entry.application) {
+            Some(a) => a,
+            None => panic!("{handle} is not a {}", core::any::type_name::<A>()),
+        }
+    }
+
+    /// Get an application from the set by its handle, as mutable.
+    ///
+    /// # Panics
+    /// This function panics if the application was removed or is not of type `A`.
+    pub fn get_mut<A: AnyApplication<'a>>(&mut self, handle: ApplicationHandle) -> &mut A {
+        match A::downcast_mut(&mut self.entry_mut(handle).application) {
+            Some(a) => a,
+            None => panic!("{handle} is not a {}", core::any::type_name::<A>()),
+        }
+    }
+
+    /// Remove an application from the set and return it.
+    ///
+    /// The storage slot is freed and can be reused by the next [`add()`][`ApplicationSet::add`].
+    /// The handle is invalid afterwards.  Returns `None` if the application was already removed.
+    pub fn remove(&mut self, handle: ApplicationHandle) -> Option<Application<'a>> {
+        let slot = self.applications.get_mut(usize::from(handle.index))?;
+        match &slot.inner {
+            Some(e) if e.generation == handle.generation => {
+                slot.inner.take().map(|e| e.application)
+            }
+            _ => None,
+        }
+    }
+
+    fn entry_mut(&mut self, handle: ApplicationHandle) -> &mut ApplicationEntry<'a> {
+        match self.applications[usize::from(handle.index)].inner.as_mut() {
+            Some(e) if e.generation == handle.generation => e,
+            _ => panic!("{handle} was removed from the application set"),
+        }
+    }
+}
+
+/// List of applications as seen by the scheduler of the FDL active station.
+///
+/// Slots can be empty.  The handle of an application identifies it even when the slot is reused.
+pub(crate) trait ApplicationList {
+    /// Number of slots, including empty ones.
+    fn slots(&self) -> usize;
+
+    /// Scheduling weight of the application in the given slot, or `None` if the slot is empty.
+    fn weight(&self, index: usize) -> Option<u8>;
+
+    fn get_dyn_mut(&mut self, index: usize)
+        -> Option<(ApplicationHandle, &mut dyn FdlApplication)>;
+}
+
+impl ApplicationList for ApplicationSet<'_> {
+    fn slots(&self) -> usize {
+        self.applications.len()
+    }
+
+    fn weight(&self, index: usize) -> Option<u8> {
+        self.applications
+            .get(index)?
+            .inner
+            .as_ref()
+            .map(|e| e.weight)
+    }
+
+    fn get_dyn_mut(
+        &mut self,
+        index: usize,
+    ) -> Option<(ApplicationHandle, &mut dyn FdlApplication)> {
+        let entry = self.applications.get_mut(index)?.inner.as_mut()?;
+        let handle = ApplicationHandle {
+            index: u8::try_from(index).unwrap(),
+            generation: entry.generation,
+        };
+        Some((handle, entry.application.as_dyn_mut()))
+    }
+}
+
+/// Applications passed as a slice with optional scheduling weights.
+pub(crate) struct ApplicationSlice<'s, 'a> {
+    pub apps: &'s mut [&'a mut dyn FdlApplication],
+    pub weights: &'s [u8],
+}
+
+impl ApplicationList for ApplicationSlice<'_, '_> {
+    fn slots(&self) -> usize {
+        self.apps.len()
+    }
+
+    fn weight(&self, index: usize) -> Option<u8> {
+        if index < self.apps.len() {
+            Some(self.weights.get(index).copied().unwrap_or(1))
+        } else {
+            None
+        }
+    }
+
+    fn get_dyn_mut(
+        &mut self,
+        index: usize,
+    ) -> Option<(ApplicationHandle, &mut dyn FdlApplication)> {
+        let app = self.apps.get_mut(index)?;
+        let handle = ApplicationHandle {
+            index: u8::try_from(index).unwrap(),
+            generation: 0,
+        };
+        Some((handle, &mut **app))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handles_stable_across_remove() {
+        let mut set = ApplicationSet::new(Vec::new());
+        let h1 = set.add(crate::dp::scan::DpScanner::new());
+        let h2 = set.add(crate::fdl::live_list::LiveList::new());
+        set.set_weight(h2, 0);
+
+        let removed = set.remove(h1).unwrap();
+        let removed = removed
+            .downcast::<crate::fdl::live_list::LiveList>()
+            .unwrap_err();
+        assert!(removed.downcast::<crate::dp::scan::DpScanner>().is_ok());
+        assert!(set.remove(h1).is_none());
+        assert_eq!(set.weight(1), Some(0));
+
+        // The freed slot is reused, but the old handle stays invalid
+        let h3 = set.add(crate::dp::DpMaster::new(Vec::new()));
+        assert_eq!(set.slots(), 2);
+        assert_ne!(h1, h3);
+        assert!(set.remove(h1).is_none());
+        assert_eq!(set.get_dyn_mut(0).unwrap().0, h3);
+        set.get_mut::<crate::dp::DpMaster>(h3).enter_operate();
+        set.get::<crate::fdl::live_list::LiveList>(h2);
+    }
+
+    #[test]
+    #[should_panic = "Application 0 was removed"]
+    fn get_removed_application() {
+        let mut set = ApplicationSet::new(Vec::new());
+        let h = set.add(crate::dp::scan::DpScanner::new());
+        set.remove(h);
+        set.get_mut::<crate::dp::scan::DpScanner>(h);
+    }
+
+    #[test]
+    #[should_panic = "is not a"]
+    fn get_wrong_type() {
+        let mut set = ApplicationSet::new(Vec::new());
+        let h = set.add(crate::dp::scan::DpScanner::new());
+        set.get_mut::<crate::fdl::live_list::LiveList>(h);
+    }
+}
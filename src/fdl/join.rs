@@ -0,0 +1,176 @@
+use crate::fdl::{
+    DataTelegram, FdlActiveStation, FdlApplication, Telegram, TelegramTx, TelegramTxResponse,
+};
+
+/// Which of the two joined applications
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    First,
+    Second,
+}
+
+/// Two applications combined into one, see [`join()`].
+pub struct Join<A, B> {
+    first: A,
+    second: B,
+    /// Whether the second application is currently running its cycle
+    second_active: bool,
+    /// Application which is waiting for a reply from the given address
+    requester: Option<(Side, u8)>,
+    /// Application which accepted the last request sent to us
+    responder: Option<Side>,
+}
+
+/// Combine two applications into a single [`FdlApplication`].
+///
+/// In each cycle, the first application runs its cycle and then the second application runs its
+/// cycle.  Replies and timeouts are passed to the application which sent the request.  Requests
+/// from other stations are offered to the first application before the second one.
+///
+/// The joined applications can be accessed using [`Join::first_mut()`] and
+/// [`Join::second_mut()`].
+///
+/// ```no_run
+/// # use profirust::{fdl, dp, Baudrate};
+/// # let mut phy = profirust::phy::SimulatorPhy::new(Baudrate::B19200, "phy");
+/// # let mut fdl = fdl::FdlActiveStation::new(fdl::ParametersBuilder::new(2, Baudrate::B19200).build());
+/// let mut app = fdl::join(dp::DpMaster::new(vec![]), fdl::live_list::LiveList::new());
+/// loop {
+///     let now = profirust::time::Instant::now();
+///     fdl.poll(now, &mut phy, &mut app);
+///     let dp_master = app.first_mut();
+///     // ...
+/// }
+/// ```
+pub fn join<A: FdlApplication, B: FdlApplication>(first: A, second: B) -> Join<A, B> {
+    Join {
+        first,
+        second,
+        second_active: false,
+        requester: None,
+        responder: None,
+    }
+}
+
+impl<A, B> Join<A, B> {
+    /// Access the first application.
+    #[inline]
+    pub fn first(&self) -> &A {
+        &self.first
+    }
+
+    /// Access the first application, as mutable.
+    #[inline]
+    pub fn first_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Access the second application.
+    #[inline]
+    pub fn second(&self) -> &B {
+        &self.second
+    }
+
+    /// Access the second application, as mutable.
+    #[inline]
+    pub fn second_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Split the combination into the two applications again.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+
+    /// Take the application which is waiting for a reply from `addr`.
+    fn take_requester(&mut self, addr: u8) -> Option<Side> {
+        match self.requester.take() {
+            Some((side, a)) if a == addr => Some(side),
+            _ => {
+                log::warn!("Dropping reply from #{addr} which no joined application requested");
+                None
+            }
+        }
+    }
+}
+
+impl<A: FdlApplication, B: FdlApplication> FdlApplication for Join<A, B> {
+    fn transmit_telegram(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &FdlActiveStation,
+        mut tx: TelegramTx,
+        high_prio_only: bool,
+    ) -> Option<TelegramTxResponse> {
+        if !self.second_active {
+            if let Some(res) = self
+                .first
+                .transmit_telegram(now, fdl, tx.reborrow(), high_prio_only)
+            {
+                self.requester = res.expects_reply().map(|addr| (Side::First, addr));
+                return Some(res);
+            }
+            self.second_active = true;
+        }
+
+        if let Some(res) = self.second.transmit_telegram(now, fdl, tx, high_prio_only) {
+            self.requester = res.expects_reply().map(|addr| (Side::Second, addr));
+            return Some(res);
+        }
+
+        // Both applications completed their cycle.
+        self.second_active = false;
+        None
+    }
+
+    fn receive_reply(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &FdlActiveStation,
+        addr: u8,
+        telegram: Telegram,
+    ) {
+        match self.take_requester(addr) {
+            Some(Side::First) => self.first.receive_reply(now, fdl, addr, telegram),
+            Some(Side::Second) => self.second.receive_reply(now, fdl, addr, telegram),
+            None => (),
+        }
+    }
+
+    fn handle_timeout(&mut self, now: crate::time::Instant, fdl: &FdlActiveStation, addr: u8) {
+        match self.take_requester(addr) {
+            Some(Side::First) => self.first.handle_timeout(now, fdl, addr),
+            Some(Side::Second) => self.second.handle_timeout(now, fdl, addr),
+            None => (),
+        }
+    }
+
+    fn receive_request(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &FdlActiveStation,
+        telegram: &DataTelegram,
+    ) -> bool {
+        self.responder = if self.first.receive_request(now, fdl, telegram) {
+            Some(Side::First)
+        } else if self.second.receive_request(now, fdl, telegram) {
+            Some(Side::Second)
+        } else {
+            None
+        };
+        self.responder.is_some()
+    }
+
+    fn transmit_response(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &FdlActiveStation,
+        addr: u8,
+        tx: TelegramTx,
+    ) -> Option<TelegramTxResponse> {
+        match self.responder.take()? {
+            Side::First => self.first.transmit_response(now, fdl, addr, tx),
+            Side::Second => self.second.transmit_response(now, fdl, addr, tx),
+        }
+    }
+}
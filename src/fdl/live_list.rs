@@ -10,6 +10,42 @@ pub enum StationEvent {
     Lost(crate::Address),
 }
 
+/// Copyable snapshot of the stations in a [`LiveList`]
+///
+/// Snapshots can be compared to detect stations appearing or disappearing between two points in
+/// time, without allocating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LiveListSnapshot {
+    stations: bitvec::BitArr!(for 128),
+}
+
+impl LiveListSnapshot {
+    /// Whether the station with the given address was live.
+    pub fn contains(&self, address: crate::Address) -> bool {
+        self.stations
+            .get(usize::from(address))
+            .map(|b| *b)
+            .unwrap_or(false)
+    }
+
+    /// Stations that are live in this snapshot but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        Self {
+            stations: self.stations & !other.stations,
+        }
+    }
+
+    /// Whether no station is live in this snapshot.
+    pub fn is_empty(&self) -> bool {
+        self.stations.not_any()
+    }
+
+    /// Iterate over the addresses of all live stations in this snapshot.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = crate::Address> + '_ {
+        self.stations.iter_ones().map(|a| u8::try_from(a).unwrap())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LiveList {
     stations: bitvec::BitArr!(for 128),
@@ -32,6 +68,13 @@ impl LiveList {
         self.stations.iter_ones().map(|a| u8::try_from(a).unwrap())
     }
 
+    /// Take a snapshot of the currently live stations.
+    pub fn snapshot(&self) -> LiveListSnapshot {
+        LiveListSnapshot {
+            stations: self.stations,
+        }
+    }
+
     pub fn take_last_event(&mut self) -> Option<StationEvent> {
         self.pending_event.take()
     }
@@ -45,7 +88,7 @@ impl crate::fdl::FdlApplication for LiveList {
         tx: super::TelegramTx,
         high_prio_only: bool,
     ) -> Option<super::TelegramTxResponse> {
-        let this_station = fdl.parameters().address;
+        let this_station = fdl.address();
         let address = self.cursor;
 
         if self.current_address_done {
@@ -57,7 +100,11 @@ impl crate::fdl::FdlApplication for LiveList {
             }
             None
         } else {
-            Some(tx.send_fdl_status_request(address, this_station))
+            // Absent stations are expected, so don't waste time on retries.
+            Some(
+                tx.send_fdl_status_request(address, this_station)
+                    .without_retries(),
+            )
         }
     }
 
@@ -109,3 +156,33 @@ impl crate::fdl::FdlApplication for LiveList {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_difference() {
+        let mut live_list = LiveList::new();
+        live_list.stations.set(3, true);
+        live_list.stations.set(7, true);
+        let before = live_list.snapshot();
+
+        live_list.stations.set(7, false);
+        live_list.stations.set(42, true);
+        let after = live_list.snapshot();
+
+        assert_ne!(before, after);
+        let copy = before;
+        assert_eq!(before, copy);
+        assert!(before.contains(7));
+        assert!(!after.contains(7));
+        assert!(!after.contains(200));
+
+        let appeared: Vec<_> = after.difference(&before).iter().collect();
+        assert_eq!(appeared, &[42]);
+        let disappeared: Vec<_> = before.difference(&after).iter().collect();
+        assert_eq!(disappeared, &[7]);
+        assert!(after.difference(&after).is_empty());
+    }
+}
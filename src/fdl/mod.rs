@@ -5,23 +5,40 @@
 //!
 //! You can also find the representations of PROFIBUS telegrams and related data structures here.
 mod active;
+mod application_set;
+mod join;
 pub mod live_list;
 mod parameters;
 mod telegram;
 mod token_ring;
+pub mod tunnel;
+mod warm_start;
 
 #[cfg(test)]
 mod test_active;
 
-pub use active::{ConnectivityState, FdlActiveStation};
-pub use parameters::{Parameters, ParametersBuilder};
+pub use active::{
+    ConnectivityState, FdlActiveStation, FdlStats, FdlSummary, LineErrorCounters, RxErrorCounters,
+};
+pub(crate) use application_set::{
+    impl_any_application, ApplicationKind, ApplicationList, ApplicationSlice,
+};
+pub use application_set::{
+    AnyApplication, Application, ApplicationHandle, ApplicationSet, ApplicationStorage,
+};
+pub use join::{join, Join};
+pub(crate) use parameters::{watchdog_factors, watchdog_factors_clamped};
+pub use parameters::{ParameterError, Parameters, ParametersBuilder};
+pub use telegram::{DeserializeError, Priority};
 pub(crate) use token_ring::TokenRing;
+pub use warm_start::{WarmStartError, WarmStartState};
 
 // Hide these for now until they get a cleaner interface
 #[doc(hidden)]
 pub use telegram::{
     DataTelegram, DataTelegramHeader, FrameCountBit, FunctionCode, RequestType, ResponseState,
     ResponseStatus, ShortConfirmation, Telegram, TelegramTx, TelegramTxResponse, TokenTelegram,
+    TooSmall,
 };
 
 /// The interface for application layer components.
@@ -31,12 +48,23 @@ pub trait FdlApplication {
     /// Possibly transmit a telegram.
     ///
     /// The FDL layer will know whether a reply is expected based on the telegram that is sent.  If
-    /// a reply is received, `receive_reply()` will be called to handle it.  If no reply is
-    /// received in Tsl time, `transmit_telegram()` is called again.  It should then retry
-    /// transmission according to the retry count configured in `fdl.parameters().max_retry_limit`.
+    /// a reply is received, `receive_reply()` will be called to handle it.
+    ///
+    /// If no reply is received in Tsl time, the FDL layer retries the request up to
+    /// `fdl.parameters().max_retry_limit` times: `transmit_telegram()` is called again and must
+    /// then repeat the same request.  Only when the last retry is not answered either,
+    /// `handle_timeout()` is called.  Applications must therefore not count retries themselves.
+    /// Requests which should not be retried can be marked using
+    /// [`TelegramTxResponse::without_retries()`].
     ///
     /// When `transmit_telegram()` returns `None`, the FDL master will interpret this as end of
     /// cycle and will pass on the token.
+    ///
+    /// `high_prio_only` is set when the token hold time is already used up and the station only
+    /// performs its guaranteed `min_message_cycles`.  Applications should then only send high
+    /// priority requests (see [`Priority`]) and return `None` to defer anything else to the next
+    /// token visit.  The priority can be chosen explicitly using
+    /// [`TelegramTx::send_data_telegram_with_priority()`].
     fn transmit_telegram(
         &mut self,
         now: crate::time::Instant,
@@ -55,7 +83,77 @@ pub trait FdlApplication {
     );
 
     /// Handle a timeout while waiting for a reply from the given address.
+    ///
+    /// This is only called once all retries of the request were unanswered.
     fn handle_timeout(&mut self, now: crate::time::Instant, fdl: &FdlActiveStation, addr: u8);
+
+    /// Accept a request which another station sent to this station.
+    ///
+    /// This works like [`RequestHandler::receive_request()`], but is only called when the
+    /// request handler set with
+    /// [`FdlActiveStation::set_request_handler()`] did not accept the request.  Only the first
+    /// application accepting a request gets to answer it using
+    /// [`transmit_response()`][`FdlApplication::transmit_response`].
+    fn receive_request(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &FdlActiveStation,
+        telegram: &DataTelegram,
+    ) -> bool {
+        false
+    }
+
+    /// Transmit the response to a request accepted in
+    /// [`receive_request()`][`FdlApplication::receive_request`].
+    ///
+    /// See [`RequestHandler::transmit_response()`].
+    fn transmit_response(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &FdlActiveStation,
+        addr: u8,
+        tx: TelegramTx,
+    ) -> Option<TelegramTxResponse> {
+        None
+    }
+}
+
+/// Handler for requests which other stations send to this station.
+///
+/// Register it using [`FdlActiveStation::set_request_handler()`] to serve SAPs of this station,
+/// e.g. to expose diagnostics to a class 2 master.
+pub trait RequestHandler {
+    /// Accept a request which another station sent to this station.
+    ///
+    /// This is called for SDA, SDN, and SRD requests addressed to this station while it is in
+    /// the token ring.  FDL status requests are always answered by the FDL layer itself.  Return
+    /// `true` to accept the request and answer it using
+    /// [`transmit_response()`][`RequestHandler::transmit_response`], so check the SAPs of
+    /// `telegram` before accepting it.  Requests which neither the handler nor an application
+    /// accepts are rejected with [`ResponseStatus::SapNotEnabled`].
+    ///
+    /// The handler must remember everything it needs for the response as `telegram` is not
+    /// available anymore later.
+    fn receive_request(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &FdlActiveStation,
+        telegram: &DataTelegram,
+    ) -> bool;
+
+    /// Transmit the response to a request accepted in
+    /// [`receive_request()`][`RequestHandler::receive_request`].
+    ///
+    /// `addr` is the station which sent the request.  SRD requests must be answered with a data
+    /// response and SDA requests with a short confirmation.  Returning `None` leaves the request
+    /// unanswered.  This is not called for SDN requests as they do not expect a response.
+    fn transmit_response(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &FdlActiveStation,
+        addr: u8,
+        tx: TelegramTx,
+    ) -> Option<TelegramTxResponse>;
 }
 
 // A sort of placeholder when no application is used.
@@ -14,7 +14,8 @@
 /// let master_address = 2;
 /// let param = fdl::ParametersBuilder::new(master_address, profirust::Baudrate::B19200)
 ///     .slot_bits(300)
-///     .build_verified(&dp_master);
+///     .build_verified(&dp_master)
+///     .unwrap();
 /// ```
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[non_exhaustive]
@@ -37,10 +38,25 @@ pub struct Parameters {
     pub highest_station_address: u8,
     /// Maximum number of retries when no answer was received
     pub max_retry_limit: u8,
+    /// Window in which exceeding the retry limit only once is tolerated
+    ///
+    /// Zero means peripherals go offline as soon as the retry limit is exceeded.
+    pub soft_retry_window: crate::time::Duration,
     /// min T<sub>SDR</sub>: Minimum delay before anyone is allowed to respond to a telegram
     pub min_tsdr_bits: u8,
     /// Watchdog timeout for peripherals monitoring the DP master
     pub watchdog_factors: Option<(u8, u8)>,
+    /// Addresses which are polled first in each GAP scan cycle
+    ///
+    /// Bitmask where bit `n` corresponds to station address `n`.
+    pub priority_gap_addresses: u128,
+    /// Number of token rotations without any response after which the bus is considered idle
+    ///
+    /// Zero disables bus idle detection.
+    pub bus_idle_rotations: u8,
+    /// Minimum number of message cycles per token hold, even when the target rotation time is
+    /// exceeded
+    pub min_message_cycles: u8,
 }
 
 impl Default for Parameters {
@@ -64,10 +80,18 @@ impl Default for Parameters {
             // sane default as retries should not be necessary at all on a bus that is set up
             // correctly.
             max_retry_limit: 1,
+            // Peripherals go offline immediately by default.
+            soft_retry_window: crate::time::Duration::ZERO,
             // No watchdog by default.
             //
             // TODO: Is this what we want?  Found 6250 x HSA recommended elsewhere.
             watchdog_factors: None,
+            // No priority addresses by default, the GAP is scanned sequentially.
+            priority_gap_addresses: 0,
+            // The token is passed at full rate even when no other station responds.
+            bus_idle_rotations: 0,
+            // The standard guarantees one high priority message cycle per token hold.
+            min_message_cycles: 1,
         }
     }
 }
@@ -97,13 +121,21 @@ fn min_slot_bits(baudrate: crate::Baudrate) -> u16 {
     }
 }
 
+/// Convert a watchdog timeout into the two watchdog factors of the Set_Prm telegram.
+///
+/// Returns `None` for a zero timeout (= no watchdog) and `Some(Err(()))` when the timeout cannot
+/// be represented.
 #[inline]
-fn watchdog_factors(dur: crate::time::Duration) -> Option<Result<(u8, u8), ()>> {
+pub(crate) fn watchdog_factors(dur: crate::time::Duration) -> Option<Result<(u8, u8), ()>> {
     // TODO: Support the different watchdog time bases in some way?
     Some(dur)
         .filter(|dur| *dur != crate::time::Duration::ZERO)
         .map(|dur| {
             let timeout_10ms: u32 = (dur.total_millis() / 10).try_into().or(Err(()))?;
+            if timeout_10ms == 0 {
+                // Timeout is too small
+                return Err(());
+            }
 
             for f1 in 1..256 {
                 let f2 = (timeout_10ms + f1 - 1) / f1;
@@ -118,8 +150,25 @@ fn watchdog_factors(dur: crate::time::Duration) -> Option<Result<(u8, u8), ()>>
         })
 }
 
+/// Like [`watchdog_factors()`], but clamps timeouts which cannot be represented to the closest
+/// possible value (10 ms to 650.25 s).
+#[inline]
+pub(crate) fn watchdog_factors_clamped(dur: crate::time::Duration) -> Option<(u8, u8)> {
+    watchdog_factors(dur).map(|factors| {
+        factors.unwrap_or(if dur < crate::time::Duration::from_millis(10) {
+            (1, 1)
+        } else {
+            (255, 255)
+        })
+    })
+}
+
 /// Builder for the parameters of an FDL active station
-pub struct ParametersBuilder(Parameters);
+pub struct ParametersBuilder {
+    params: Parameters,
+    /// Expected number of active stations from [`ParametersBuilder::recommended_gap_wait()`]
+    gap_wait_active_stations: Option<u8>,
+}
 
 impl ParametersBuilder {
     /// Start building parameters for an FDL active station with the given `address`.
@@ -129,12 +178,16 @@ impl ParametersBuilder {
     #[inline]
     pub fn new(address: u8, baudrate: crate::Baudrate) -> Self {
         assert!(address <= 125);
-        Self(Parameters {
+        let params = Parameters {
             address,
             baudrate,
             slot_bits: min_slot_bits(baudrate),
             ..Default::default()
-        })
+        };
+        Self {
+            params,
+            gap_wait_active_stations: None,
+        }
     }
 
     /// Configure non-standard T<sub>SL</sub> (slot time in bits)
@@ -154,11 +207,32 @@ impl ParametersBuilder {
     /// | 12000000 | 1000 |
     #[inline]
     pub fn slot_bits(&mut self, slot_bits: u16) -> &mut Self {
-        self.0.slot_bits = slot_bits;
-        assert!(slot_bits >= min_slot_bits(self.0.baudrate));
+        self.params.slot_bits = slot_bits;
+        assert!(slot_bits >= min_slot_bits(self.params.baudrate));
         self
     }
 
+    /// Derive T<sub>SL</sub> (slot time in bits) from the peripherals of a DP master.
+    ///
+    /// The slot time is set to the largest max T<sub>SDR</sub> of all peripherals plus a safety
+    /// margin of 25% and the 15 bits which `build_verified()` requires.  It is never set below the
+    /// minimum slot time for the baudrate (see [`ParametersBuilder::slot_bits()`]).
+    ///
+    /// Peripherals added to the DP master later are not taken into account.
+    #[inline]
+    pub fn auto_slot_bits(&mut self, dp_master: &crate::dp::DpMaster) -> &mut Self {
+        let max_tsdr = dp_master
+            .iter()
+            .map(|(_, peripheral)| peripheral.options().max_tsdr)
+            .max()
+            .unwrap_or(0);
+        let slot_bits = max_tsdr
+            .saturating_add(max_tsdr / 4)
+            .saturating_add(15)
+            .max(min_slot_bits(self.params.baudrate));
+        self.slot_bits(slot_bits)
+    }
+
     /// Set the highest projected (active) station address.
     ///
     /// The HSA is used when scanning for other FDL active station who want to participate on the bus.
@@ -169,8 +243,8 @@ impl ParametersBuilder {
     /// optimize recovery time after a master drops from the bus.
     #[inline]
     pub fn highest_station_address(&mut self, hsa: u8) -> &mut Self {
-        assert!(hsa > self.0.address && hsa <= 126);
-        self.0.highest_station_address = hsa;
+        assert!(hsa > self.params.address && hsa <= 126);
+        self.params.highest_station_address = hsa;
         // TODO: We probably shouldn't override an explicitly set value here...
         self.token_rotation_bits(u32::from(hsa) * 5000);
         self
@@ -185,7 +259,7 @@ impl ParametersBuilder {
     /// communication.  Defaults to 32436.
     pub fn token_rotation_bits(&mut self, ttr: u32) -> &mut Self {
         assert!(ttr >= 256 && ttr <= 16_777_960);
-        self.0.token_rotation_bits = ttr;
+        self.params.token_rotation_bits = ttr;
         self
     }
 
@@ -195,9 +269,80 @@ impl ParametersBuilder {
     ///
     /// This factor is the wait time between scan cycles.  A low value means stations are found
     /// very quickly but the tradeoff is a higher average cycle time.
+    ///
+    /// During a scan cycle, one address of the GAP is polled per token rotation, which costs up to
+    /// one slot time each.  On a busy ring this slows down all other communication while a low
+    /// scan rate means new stations take a long time to appear.  See
+    /// [`ParametersBuilder::recommended_gap_wait()`] for a sensible choice and
+    /// [`FdlActiveStation::gap_scan_period()`][`crate::fdl::FdlActiveStation::gap_scan_period`]
+    /// for the resulting scan period.
     pub fn gap_wait_rotations(&mut self, gap_wait: u8) -> &mut Self {
         assert!(gap_wait >= 1 && gap_wait <= 100);
-        self.0.gap_wait_rotations = gap_wait;
+        self.params.gap_wait_rotations = gap_wait;
+        self.gap_wait_active_stations = None;
+        self
+    }
+
+    /// Set the GAP wait rotations based on the expected number of active stations.
+    ///
+    /// The addresses up to the HSA are split into one GAP per active station, so each station
+    /// only has about `HSA / active_stations` addresses to scan.  The wait is chosen to be as long
+    /// as a scan cycle.  This means at most every second token rotation includes a GAP poll, while
+    /// new stations are still found in roughly the same time regardless of the ring size.
+    ///
+    /// The GAP wait is calculated in [`ParametersBuilder::build()`] from the final HSA, so the
+    /// order of the builder calls does not matter.  A later call to
+    /// [`ParametersBuilder::gap_wait_rotations()`] overrides the recommendation.
+    pub fn recommended_gap_wait(&mut self, active_stations: u8) -> &mut Self {
+        self.gap_wait_active_stations = Some(active_stations);
+        self
+    }
+
+    /// Set addresses which should be polled first in each GAP scan cycle.
+    ///
+    /// Normally the GAP is scanned sequentially, which means that a station far away from this
+    /// one takes a long time to be discovered.  The priority addresses (for example those of the
+    /// configured peripherals) are polled at the start of each GAP scan cycle, ahead of the
+    /// sequential scan.  Addresses outside the GAP of this station are ignored.
+    pub fn priority_gap_addresses(&mut self, addresses: &[u8]) -> &mut Self {
+        self.params.priority_gap_addresses = 0;
+        for address in addresses.iter().copied() {
+            assert!(address <= 125);
+            self.params.priority_gap_addresses |= 1 << address;
+        }
+        self
+    }
+
+    /// Set after how many token rotations without any response the bus is considered idle.
+    ///
+    /// A station that is alone on the bus keeps passing the token to itself.  Once no other
+    /// station responded for this many token rotations, the station enters bus idle mode (see
+    /// [`FdlActiveStation::is_bus_idle()`][`crate::fdl::FdlActiveStation::is_bus_idle`]) and
+    /// slows down passing the token to itself.  The bus is still kept busy often enough that
+    /// stations joining later do not consider the token lost.  Bus idle mode ends as soon as any
+    /// station responds again.
+    ///
+    /// Default is zero, meaning bus idle detection is disabled.
+    pub fn bus_idle_rotations(&mut self, rotations: u8) -> &mut Self {
+        self.params.bus_idle_rotations = rotations;
+        self
+    }
+
+    /// Set the minimum number of message cycles per token hold.
+    ///
+    /// When the token arrives late (the target rotation time is already exceeded), a station is
+    /// normally only allowed a single message cycle before it must pass the token on.  On a busy
+    /// bus with multiple masters, this can starve the DP cycle.  With this setting, the station
+    /// may perform up to `cycles` message cycles per token hold even when it is over time.  Message
+    /// cycles performed before the token hold time ran out also count towards this minimum.
+    ///
+    /// Note that this prolongs the token rotation for all other stations on the bus.
+    ///
+    /// Default value is 1, meaning one message cycle is always allowed.
+    #[inline]
+    pub fn min_message_cycles(&mut self, cycles: u8) -> &mut Self {
+        assert!(cycles >= 1);
+        self.params.min_message_cycles = cycles;
         self
     }
 
@@ -214,7 +359,21 @@ impl ParametersBuilder {
     #[inline]
     pub fn max_retry_limit(&mut self, max_retry_limit: u8) -> &mut Self {
         assert!(max_retry_limit >= 1 && max_retry_limit <= 15);
-        self.0.max_retry_limit = max_retry_limit;
+        self.params.max_retry_limit = max_retry_limit;
+        self
+    }
+
+    /// Set the window in which a peripheral may exceed the retry limit once without going offline.
+    ///
+    /// When the retry limit is exceeded for the first time within this window, the peripheral is
+    /// only skipped for the current cycle.  Only a second failure within the window makes it go
+    /// offline.  This reduces churn of offline/online events for peripherals which miss a
+    /// response occasionally but recover immediately.
+    ///
+    /// Default is zero, meaning peripherals go offline as soon as the retry limit is exceeded.
+    #[inline]
+    pub fn soft_retry_window(&mut self, window: crate::time::Duration) -> &mut Self {
+        self.params.soft_retry_window = window;
         self
     }
 
@@ -225,7 +384,7 @@ impl ParametersBuilder {
     #[inline]
     pub fn min_tsdr(&mut self, min_tsdr_bits: u8) -> &mut Self {
         assert!(min_tsdr_bits >= 11);
-        self.0.min_tsdr_bits = min_tsdr_bits;
+        self.params.min_tsdr_bits = min_tsdr_bits;
         self
     }
 
@@ -235,64 +394,438 @@ impl ParametersBuilder {
     pub fn watchdog_timeout(&mut self, wdg: crate::time::Duration) -> &mut Self {
         assert!(wdg >= crate::time::Duration::from_millis(10));
         assert!(wdg <= crate::time::Duration::from_secs(650));
-        self.0.watchdog_factors = watchdog_factors(wdg).transpose().unwrap();
+        self.params.watchdog_factors = watchdog_factors(wdg).transpose().unwrap();
         self
     }
 
     /// Build the parameters struct.
     #[inline]
     pub fn build(&self) -> Parameters {
-        self.0.clone()
+        let mut params = self.params.clone();
+        if let Some(active_stations) = self.gap_wait_active_stations {
+            params.gap_wait_rotations = params
+                .highest_station_address
+                .div_ceil(active_stations.max(1))
+                .clamp(1, 100);
+        }
+        params
     }
 
     /// Build the parameters struct and verify it against the given DP master.
     ///
     /// This ensures that, for example, the selected T<sub>SL</sub> is greater than the max Tsdr of
-    /// all peripherals currently tracked by the DP master.
+    /// all peripherals currently tracked by the DP master.  The first violated constraint is
+    /// returned as a [`ParameterError`].
     #[inline]
-    pub fn build_verified(&self, dp_master: &crate::dp::DpMaster) -> Parameters {
+    pub fn build_verified(
+        &self,
+        dp_master: &crate::dp::DpMaster,
+    ) -> Result<Parameters, ParameterError> {
+        if u16::from(self.params.min_tsdr_bits) >= self.params.slot_bits {
+            return Err(ParameterError::MinTsdrTooLarge {
+                min_tsdr_bits: self.params.min_tsdr_bits,
+                slot_bits: self.params.slot_bits,
+            });
+        }
+
         for (_, peripheral) in dp_master.iter() {
-            assert!(
-                peripheral.options().max_tsdr + 15 <= self.0.slot_bits,
-                "max Tsdr of peripheral #{} too large for slot time",
-                peripheral.address(),
-            );
+            if peripheral.address() == self.params.address {
+                return Err(ParameterError::AddressConflict {
+                    address: self.params.address,
+                });
+            }
+            if let Some(min_tsdr_bits) = peripheral.options().min_tsdr_bits {
+                if u16::from(min_tsdr_bits) >= self.params.slot_bits {
+                    return Err(ParameterError::MinTsdrTooLarge {
+                        min_tsdr_bits,
+                        slot_bits: self.params.slot_bits,
+                    });
+                }
+            }
+            let max_tsdr = peripheral.options().max_tsdr;
+            if max_tsdr.saturating_add(15) > self.params.slot_bits {
+                return Err(ParameterError::SlotTimeTooShort {
+                    address: peripheral.address(),
+                    max_tsdr,
+                    slot_bits: self.params.slot_bits,
+                });
+            }
+        }
+
+        // One cycle with all peripherals must fit into the target rotation time.  Answering
+        // peripherals respond within their max Tsdr, so the slot time is not needed here.
+        let min_token_rotation_bits = self.params.estimate_cycle_bits(dp_master);
+        if self.params.token_rotation_bits < min_token_rotation_bits {
+            return Err(ParameterError::TokenRotationTooShort {
+                token_rotation_bits: self.params.token_rotation_bits,
+                min: min_token_rotation_bits,
+            });
+        }
+
+        Ok(self.build())
+    }
+}
+
+/// Violated constraint found by [`ParametersBuilder::build_verified()`]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ParameterError {
+    /// The slot time is too short for the max T<sub>SDR</sub> of a peripheral.
+    SlotTimeTooShort {
+        address: crate::Address,
+        max_tsdr: u16,
+        slot_bits: u16,
+    },
+    /// The target rotation time is too short to communicate with all peripherals.
+    ///
+    /// The minimum is the
+    /// [estimated cycle time][`Parameters::estimate_cycle_time`] of the DP master, in bits.
+    TokenRotationTooShort { token_rotation_bits: u32, min: u32 },
+    /// The min T<sub>SDR</sub> is not shorter than the slot time.
+    MinTsdrTooLarge { min_tsdr_bits: u8, slot_bits: u16 },
+    /// A peripheral has the same address as this station.
+    AddressConflict { address: crate::Address },
+}
+
+impl core::fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ParameterError::SlotTimeTooShort {
+                address,
+                max_tsdr,
+                slot_bits,
+            } => write!(
+                f,
+                "slot time of {slot_bits} bits is too short for max Tsdr of {max_tsdr} bits of peripheral #{address}"
+            ),
+            ParameterError::TokenRotationTooShort {
+                token_rotation_bits,
+                min,
+            } => write!(
+                f,
+                "token rotation time of {token_rotation_bits} bits is shorter than the minimum of {min} bits"
+            ),
+            ParameterError::MinTsdrTooLarge {
+                min_tsdr_bits,
+                slot_bits,
+            } => write!(
+                f,
+                "min Tsdr of {min_tsdr_bits} bits is not shorter than the slot time of {slot_bits} bits"
+            ),
+            ParameterError::AddressConflict { address } => write!(
+                f,
+                "peripheral #{address} has the same address as this station"
+            ),
         }
-        self.0.clone()
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for ParameterError {}
+
 impl Parameters {
+    /// Convert a number of bit times (t<sub>BIT</sub>) at the configured baudrate to a duration.
+    ///
+    /// All FDL timing parameters are specified in bit times.  One bit time is 1 / baudrate.
     pub fn bits_to_time(&self, bits: u32) -> crate::time::Duration {
         self.baudrate.bits_to_time(bits)
     }
 
     /// T<sub>SL</sub> (slot time) converted to duration
+    ///
+    /// Maximum time to wait for the first character of a response after a request was sent,
+    /// calculated as `slot_bits` * t<sub>BIT</sub>.
     pub fn slot_time(&self) -> crate::time::Duration {
         self.bits_to_time(u32::from(self.slot_bits))
     }
 
     /// min T<sub>SDR</sub> (minimum time before responding) converted to duration
+    ///
+    /// Calculated as `min_tsdr_bits` * t<sub>BIT</sub>.
     pub fn min_tsdr_time(&self) -> crate::time::Duration {
         self.bits_to_time(u32::from(self.min_tsdr_bits))
     }
 
+    /// T<sub>SYN</sub> (synchronization pause) converted to duration
+    ///
+    /// The bus must be idle for this long before the station transmits a request or passes the
+    /// token.  Calculated as 33 * t<sub>BIT</sub>.
+    pub fn idle_time(&self) -> crate::time::Duration {
+        self.bits_to_time(self.idle_bits())
+    }
+
+    /// [`idle_time()`][`Parameters::idle_time`] in bits.
+    fn idle_bits(&self) -> u32 {
+        33
+    }
+
     /// Timeout after which the token is considered lost.
     ///
-    /// Calculated as 6 * T<sub>SL</sub> + 2 * Addr * T<sub>SL</sub>.
+    /// Calculated as (6 + 2 * Addr) * T<sub>SL</sub>, so stations with lower addresses claim
+    /// the token first.
     pub fn token_lost_timeout(&self) -> crate::time::Duration {
         let timeout_bits = u32::from(self.slot_bits) * (6 + 2 * u32::from(self.address));
         self.bits_to_time(timeout_bits)
     }
 
-    /// T<sub>TR</sub> (projected token rotation time)
+    /// T<sub>TR</sub> (target token rotation time) converted to duration
+    ///
+    /// Calculated as `token_rotation_bits` * t<sub>BIT</sub>.  The station only holds the token
+    /// for as long as the last rotation was shorter than this.
     pub fn token_rotation_time(&self) -> crate::time::Duration {
         self.bits_to_time(self.token_rotation_bits)
     }
 
+    /// Worst-case bus time used by polling one GAP address.
+    ///
+    /// At most one GAP address is polled per token visit, right before passing the token.  The
+    /// poll takes the synchronization pause, the FDL status request (6 characters of 11 bits), a
+    /// full slot time, and the status response (again 6 characters) of a station which only
+    /// answers at the very end of the slot time: (33 + 66 + 66) * t<sub>BIT</sub> +
+    /// T<sub>SL</sub>.
+    pub fn gap_poll_budget(&self) -> crate::time::Duration {
+        self.bits_to_time(self.idle_bits() + 2 * 6 * 11 + u32::from(self.slot_bits))
+    }
+
     /// Watchdog timeout
     pub fn watchdog_timeout(&self) -> Option<crate::time::Duration> {
         self.watchdog_factors
             .map(|(f1, f2)| crate::time::Duration::from_millis(u64::from(f1) * u64::from(f2) * 10))
     }
+
+    /// Estimate the worst-case duration of one cycle with all peripherals of a DP master.
+    ///
+    /// For each peripheral, the transmission times of the data exchange request and response,
+    /// the max T<sub>SDR</sub> of the peripheral, and the sync pause before the request are summed
+    /// up.  The token pass to the next station is included as well.  Retries and acyclic
+    /// communication are not taken into account.
+    ///
+    /// On a bus with multiple active stations, multiply the estimate by the number of masters to
+    /// get the token rotation time.
+    pub fn estimate_cycle_time(&self, dp_master: &crate::dp::DpMaster) -> crate::time::Duration {
+        self.bits_to_time(self.estimate_cycle_bits(dp_master))
+    }
+
+    /// [`estimate_cycle_time()`][`Parameters::estimate_cycle_time`] in bits.
+    fn estimate_cycle_bits(&self, dp_master: &crate::dp::DpMaster) -> u32 {
+        // Sync pause before each request
+        let sync_bits = usize::try_from(self.idle_bits()).unwrap();
+        const BITS_PER_BYTE: usize = 11;
+        let header = crate::fdl::DataTelegramHeader {
+            da: 0,
+            sa: 0,
+            dsap: None,
+            ssap: None,
+            fc: crate::fdl::FunctionCode::new_srd_high(Default::default()),
+        };
+
+        let mut bits = 0;
+        for (_, peripheral) in dp_master.iter() {
+            let request = header.serialized_len(peripheral.pi_q().len());
+            let response = header.serialized_len(peripheral.pi_i().len());
+            let tsdr = peripheral.options().max_tsdr.max(self.min_tsdr_bits.into());
+            bits += sync_bits + (request + response) * BITS_PER_BYTE + usize::from(tsdr);
+        }
+        // Token telegram
+        bits += sync_bits + 3 * BITS_PER_BYTE;
+
+        u32::try_from(bits).unwrap_or(u32::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_slot_bits() {
+        let mut dp_master = crate::dp::DpMaster::new(Vec::new());
+        let params = ParametersBuilder::new(2, crate::Baudrate::B1500000)
+            .auto_slot_bits(&dp_master)
+            .build();
+        assert_eq!(params.slot_bits, 300);
+
+        for (address, max_tsdr) in [(8, 150), (9, 800), (10, 60)] {
+            let options = crate::dp::PeripheralOptions {
+                max_tsdr,
+                ..Default::default()
+            };
+            dp_master.add(crate::dp::Peripheral::new(
+                address,
+                options,
+                Vec::new(),
+                Vec::new(),
+            ));
+        }
+        let params = ParametersBuilder::new(2, crate::Baudrate::B1500000)
+            .auto_slot_bits(&dp_master)
+            .build_verified(&dp_master)
+            .unwrap();
+        assert_eq!(params.slot_bits, 1015);
+    }
+
+    #[test]
+    fn recommended_gap_wait() {
+        let gap_wait = |hsa, active_stations| {
+            ParametersBuilder::new(2, crate::Baudrate::B19200)
+                .highest_station_address(hsa)
+                .recommended_gap_wait(active_stations)
+                .build()
+                .gap_wait_rotations
+        };
+        assert_eq!(gap_wait(126, 0), 100);
+        assert_eq!(gap_wait(126, 1), 100);
+        assert_eq!(gap_wait(126, 4), 32);
+        assert_eq!(gap_wait(16, 3), 6);
+        assert_eq!(gap_wait(16, 100), 1);
+
+        // The HSA may also be set afterwards
+        let params = ParametersBuilder::new(2, crate::Baudrate::B19200)
+            .recommended_gap_wait(3)
+            .highest_station_address(16)
+            .build();
+        assert_eq!(params.gap_wait_rotations, 6);
+
+        // An explicit GAP wait overrides the recommendation
+        let params = ParametersBuilder::new(2, crate::Baudrate::B19200)
+            .recommended_gap_wait(3)
+            .gap_wait_rotations(42)
+            .build();
+        assert_eq!(params.gap_wait_rotations, 42);
+    }
+
+    #[test]
+    fn timing_helpers() {
+        let params = ParametersBuilder::new(2, crate::Baudrate::B1500000)
+            .slot_bits(300)
+            .token_rotation_bits(15000)
+            .build();
+        let us = crate::time::Duration::from_micros;
+        assert_eq!(params.bits_to_time(3), us(2));
+        assert_eq!(params.slot_time(), us(200));
+        assert_eq!(params.idle_time(), us(22));
+        assert_eq!(params.token_lost_timeout(), us(2000));
+        assert_eq!(params.token_rotation_time(), us(10000));
+        assert_eq!(params.gap_poll_budget(), us(310));
+    }
+
+    #[test]
+    fn build_verified_errors() {
+        let mut dp_master = crate::dp::DpMaster::new(Vec::new());
+        let options = crate::dp::PeripheralOptions {
+            max_tsdr: 290,
+            ..Default::default()
+        };
+        dp_master.add(crate::dp::Peripheral::new(
+            8,
+            options,
+            Vec::new(),
+            Vec::new(),
+        ));
+
+        assert_eq!(
+            ParametersBuilder::new(2, crate::Baudrate::B19200)
+                .slot_bits(300)
+                .build_verified(&dp_master),
+            Err(ParameterError::SlotTimeTooShort {
+                address: 8,
+                max_tsdr: 290,
+                slot_bits: 300
+            })
+        );
+        assert_eq!(
+            ParametersBuilder::new(2, crate::Baudrate::B19200)
+                .slot_bits(400)
+                .token_rotation_bits(500)
+                .build_verified(&dp_master),
+            // 33 bits sync pause, 6 bytes request, 290 bits Tsdr, 6 bytes response, token pass
+            Err(ParameterError::TokenRotationTooShort {
+                token_rotation_bits: 500,
+                min: 33 + 66 + 290 + 66 + 66
+            })
+        );
+        assert_eq!(
+            ParametersBuilder::new(2, crate::Baudrate::B19200)
+                .slot_bits(100)
+                .min_tsdr(100)
+                .build_verified(&crate::dp::DpMaster::new(Vec::new())),
+            Err(ParameterError::MinTsdrTooLarge {
+                min_tsdr_bits: 100,
+                slot_bits: 100
+            })
+        );
+        assert_eq!(
+            ParametersBuilder::new(8, crate::Baudrate::B19200)
+                .slot_bits(400)
+                .build_verified(&dp_master),
+            Err(ParameterError::AddressConflict { address: 8 })
+        );
+        let mut dp_master_min_tsdr = crate::dp::DpMaster::new(Vec::new());
+        dp_master_min_tsdr.add(crate::dp::Peripheral::new(
+            8,
+            crate::dp::PeripheralOptions {
+                min_tsdr_bits: Some(200),
+                ..Default::default()
+            },
+            Vec::new(),
+            Vec::new(),
+        ));
+        assert_eq!(
+            ParametersBuilder::new(2, crate::Baudrate::B19200)
+                .slot_bits(200)
+                .build_verified(&dp_master_min_tsdr),
+            Err(ParameterError::MinTsdrTooLarge {
+                min_tsdr_bits: 200,
+                slot_bits: 200
+            })
+        );
+        assert!(ParametersBuilder::new(2, crate::Baudrate::B19200)
+            .slot_bits(400)
+            .build_verified(&dp_master)
+            .is_ok());
+    }
+
+    #[test]
+    fn build_verified_large_slot_time() {
+        let mut dp_master = crate::dp::DpMaster::new(Vec::new());
+        for address in 8..16 {
+            dp_master.add(crate::dp::Peripheral::new(
+                address,
+                Default::default(),
+                vec![0u8; 4],
+                vec![0u8; 4],
+            ));
+        }
+        // Answering peripherals never use the full slot time, so the default TTR suffices.
+        assert!(ParametersBuilder::new(2, crate::Baudrate::B19200)
+            .slot_bits(4000)
+            .build_verified(&dp_master)
+            .is_ok());
+    }
+
+    #[test]
+    fn estimate_cycle_time() {
+        let params = ParametersBuilder::new(2, crate::Baudrate::B19200).build();
+        let mut dp_master = crate::dp::DpMaster::new(Vec::new());
+        // Only the token pass
+        assert_eq!(
+            params.estimate_cycle_time(&dp_master),
+            params.bits_to_time(66)
+        );
+
+        let options = crate::dp::PeripheralOptions {
+            max_tsdr: 60,
+            ..Default::default()
+        };
+        dp_master.add(crate::dp::Peripheral::new(
+            8,
+            options,
+            vec![0u8; 4],
+            vec![0u8; 2],
+        ));
+        // 33 bits sync pause, 11 bytes request, 60 bits Tsdr, 13 bytes response
+        assert_eq!(
+            params.estimate_cycle_time(&dp_master),
+            params.bits_to_time(66 + 33 + 121 + 60 + 143)
+        );
+    }
 }
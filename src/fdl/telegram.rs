@@ -66,6 +66,42 @@ impl RequestType {
             Self::LsapStatus => true,
         }
     }
+
+    /// Priority of this request, if it is an SDA, SDN, or SRD request.
+    pub fn priority(self) -> Option<Priority> {
+        match self {
+            Self::SdaLow | Self::SdnLow | Self::SrdLow => Some(Priority::Low),
+            Self::SdaHigh | Self::SdnHigh | Self::SrdHigh => Some(Priority::High),
+            _ => None,
+        }
+    }
+
+    /// The same request with the given priority.
+    ///
+    /// Returns `None` for requests which do not carry a priority.
+    pub fn with_priority(self, priority: Priority) -> Option<RequestType> {
+        match (self, priority) {
+            (Self::SdaLow | Self::SdaHigh, Priority::Low) => Some(Self::SdaLow),
+            (Self::SdaLow | Self::SdaHigh, Priority::High) => Some(Self::SdaHigh),
+            (Self::SdnLow | Self::SdnHigh, Priority::Low) => Some(Self::SdnLow),
+            (Self::SdnLow | Self::SdnHigh, Priority::High) => Some(Self::SdnHigh),
+            (Self::SrdLow | Self::SrdHigh, Priority::Low) => Some(Self::SrdLow),
+            (Self::SrdLow | Self::SrdHigh, Priority::High) => Some(Self::SrdHigh),
+            _ => None,
+        }
+    }
+}
+
+/// Priority of an SDA, SDN, or SRD request
+///
+/// When the token hold time is used up, an active station only sends high priority requests.  See
+/// [`FdlApplication::transmit_telegram()`][`crate::fdl::FdlApplication::transmit_telegram`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Priority {
+    /// Low priority, only sent while token hold time remains
+    Low,
+    /// High priority
+    High,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -246,6 +282,13 @@ impl FunctionCode {
             req: RequestType::SrdHigh,
         }
     }
+
+    pub fn new_srd(fcb: FrameCountBit, priority: Priority) -> Self {
+        match priority {
+            Priority::Low => Self::new_srd_low(fcb),
+            Priority::High => Self::new_srd_high(fcb),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -263,6 +306,21 @@ pub struct DataTelegramHeader {
 }
 
 impl DataTelegramHeader {
+    /// Length of the serialized telegram with a PDU of the given length.
+    pub(crate) fn serialized_len(&self, pdu_len: usize) -> usize {
+        let length_byte =
+            pdu_len + usize::from(self.dsap.is_some()) + usize::from(self.ssap.is_some()) + 3;
+
+        match length_byte {
+            // SD1: start delimiter, DA, SA, FC, FCS, ED
+            3 => 6,
+            // SD3: start delimiter, 11 bytes content, FCS, ED
+            11 => 14,
+            // SD2: start delimiter, 2x length, start delimiter, content, FCS, ED
+            _ => length_byte + 6,
+        }
+    }
+
     pub fn serialize<F>(&self, buffer: &mut [u8], pdu_len: usize, write_pdu: F) -> usize
     where
         F: FnOnce(&mut [u8]),
@@ -333,7 +391,7 @@ pub struct DataTelegram<'a> {
 }
 
 impl<'a> DataTelegram<'a> {
-    pub fn deserialize(mut buffer: &'a [u8]) -> Option<Result<(Self, usize), ()>> {
+    pub fn deserialize(mut buffer: &'a [u8]) -> Option<Result<(Self, usize), DeserializeError>> {
         if buffer.len() < 6 {
             return None;
         }
@@ -346,17 +404,17 @@ impl<'a> DataTelegram<'a> {
                 buffer = &buffer[3..];
                 if l1 != l2 {
                     log::debug!("Length info mismatch: {} != {}", l1, l2);
-                    return Some(Err(()));
+                    return Some(Err(DeserializeError::LengthMismatch));
                 } else if l1 < 3 {
                     log::debug!("Length is too short: {}", l1);
-                    return Some(Err(()));
+                    return Some(Err(DeserializeError::LengthMismatch));
                 }
                 (l1 - 3, usize::from(l1) + 6)
             }
             crate::consts::SD3 => (8, 14),
             s => {
                 log::debug!("Unknown start delimiter 0x{s:02x}");
-                return Some(Err(()));
+                return Some(Err(DeserializeError::BadStartDelimiter));
             }
         };
         let mut length = usize::from(length);
@@ -385,7 +443,7 @@ impl<'a> DataTelegram<'a> {
             Ok(fc) => fc,
             Err(_) => {
                 log::debug!("Unparseable function code");
-                return Some(Err(()));
+                return Some(Err(DeserializeError::BadFunctionCode));
             }
         };
 
@@ -395,7 +453,7 @@ impl<'a> DataTelegram<'a> {
             let dsap = buffer[0];
             if length < 1 {
                 log::debug!("Length {} but DSAP expected", length);
-                return Some(Err(()));
+                return Some(Err(DeserializeError::LengthMismatch));
             }
             length -= 1;
             buffer = &buffer[1..];
@@ -407,7 +465,7 @@ impl<'a> DataTelegram<'a> {
             let ssap = buffer[0];
             if length < 1 {
                 log::debug!("Length {} but SSAP expected", length);
-                return Some(Err(()));
+                return Some(Err(DeserializeError::LengthMismatch));
             }
             length -= 1;
             buffer = &buffer[1..];
@@ -426,12 +484,12 @@ impl<'a> DataTelegram<'a> {
 
         if checksum_received != checksum_calculated {
             log::debug!("Checksum mismatch");
-            return Some(Err(()));
+            return Some(Err(DeserializeError::BadChecksum));
         }
 
         if buffer[length + 1] != crate::consts::ED {
             log::debug!("No end delimiter");
-            return Some(Err(()));
+            return Some(Err(DeserializeError::BadEndDelimiter));
         }
 
         Some(Ok((
@@ -503,7 +561,7 @@ impl TokenTelegram {
         3
     }
 
-    pub fn deserialize(buffer: &[u8]) -> Option<Result<(Self, usize), ()>> {
+    pub fn deserialize(buffer: &[u8]) -> Option<Result<(Self, usize), DeserializeError>> {
         if buffer.len() < 3 {
             return None;
         }
@@ -527,6 +585,43 @@ impl ShortConfirmation {
     }
 }
 
+/// Error when a buffer is too small to hold a serialized telegram
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TooSmall;
+
+impl core::fmt::Display for TooSmall {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "buffer too small for telegram")
+    }
+}
+
+/// Reason why received data could not be decoded as a telegram
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DeserializeError {
+    /// The first byte is not a known start delimiter.
+    BadStartDelimiter,
+    /// The telegram does not end with the end delimiter.
+    BadEndDelimiter,
+    /// The checksum does not match the telegram contents.
+    BadChecksum,
+    /// The length bytes are inconsistent or too short for the telegram contents.
+    LengthMismatch,
+    /// The function code byte is invalid.
+    BadFunctionCode,
+}
+
+impl core::fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DeserializeError::BadStartDelimiter => write!(f, "invalid start delimiter"),
+            DeserializeError::BadEndDelimiter => write!(f, "missing end delimiter"),
+            DeserializeError::BadChecksum => write!(f, "checksum mismatch"),
+            DeserializeError::LengthMismatch => write!(f, "inconsistent telegram length"),
+            DeserializeError::BadFunctionCode => write!(f, "invalid function code"),
+        }
+    }
+}
+
 /// Representation of a decoded telegram
 #[derive(PartialEq, Eq, Clone)]
 pub enum Telegram<'a> {
@@ -564,7 +659,7 @@ impl From<ShortConfirmation> for Telegram<'_> {
 }
 
 impl<'a> Telegram<'a> {
-    pub fn deserialize(buffer: &'a [u8]) -> Option<Result<(Self, usize), ()>> {
+    pub fn deserialize(buffer: &'a [u8]) -> Option<Result<(Self, usize), DeserializeError>> {
         if buffer.len() == 0 {
             return None;
         }
@@ -577,10 +672,36 @@ impl<'a> Telegram<'a> {
             crate::consts::SD1 | crate::consts::SD2 | crate::consts::SD3 => {
                 DataTelegram::deserialize(buffer).map(|v| v.map(|(v, s)| (v.into(), s)))
             }
-            _ => Some(Err(())),
+            _ => Some(Err(DeserializeError::BadStartDelimiter)),
         }
     }
 
+    /// Serialize this telegram into the given buffer.
+    ///
+    /// Returns the number of bytes written or [`TooSmall`] when the telegram does not fit into
+    /// the buffer.  The serialized telegram can be parsed again using
+    /// [`Telegram::deserialize()`].
+    pub fn serialize(&self, buffer: &mut [u8]) -> Result<usize, TooSmall> {
+        let length = match self {
+            Telegram::Data(t) => t.h.serialized_len(t.pdu.len()),
+            Telegram::Token(_) => 3,
+            Telegram::ShortConfirmation(_) => 1,
+        };
+        if buffer.len() < length {
+            return Err(TooSmall);
+        }
+
+        let written = match self {
+            Telegram::Data(t) => {
+                t.h.serialize(buffer, t.pdu.len(), |buf| buf.copy_from_slice(t.pdu))
+            }
+            Telegram::Token(t) => t.serialize(buffer),
+            Telegram::ShortConfirmation(t) => t.serialize(buffer),
+        };
+        debug_assert_eq!(written, length);
+        Ok(written)
+    }
+
     pub fn source_address(&self) -> Option<u8> {
         match self {
             Telegram::Data(t) => Some(t.h.sa),
@@ -615,6 +736,7 @@ pub struct TelegramTx<'a> {
 pub struct TelegramTxResponse {
     bytes_sent: usize,
     expects_reply: Option<u8>,
+    retry: bool,
 }
 
 impl<'a> TelegramTx<'a> {
@@ -622,6 +744,11 @@ impl<'a> TelegramTx<'a> {
         Self { buf }
     }
 
+    /// Borrow the transmit buffer again for passing it on to another application.
+    pub(crate) fn reborrow(&mut self) -> TelegramTx<'_> {
+        TelegramTx { buf: self.buf }
+    }
+
     pub fn send_token_telegram(self, da: u8, sa: u8) -> TelegramTxResponse {
         let token_telegram = TokenTelegram::new(da, sa);
         TelegramTxResponse::new(token_telegram.serialize(self.buf), None)
@@ -654,6 +781,24 @@ impl<'a> TelegramTx<'a> {
         )
     }
 
+    /// Send a data telegram with an explicitly chosen priority.
+    ///
+    /// The priority of the request in `header` is replaced with `priority`.  Returns `None`
+    /// without sending anything if `header` is not an SDA, SDN, or SRD request.
+    pub fn send_data_telegram_with_priority<F: FnOnce(&mut [u8])>(
+        self,
+        mut header: DataTelegramHeader,
+        priority: Priority,
+        pdu_len: usize,
+        write_pdu: F,
+    ) -> Option<TelegramTxResponse> {
+        match &mut header.fc {
+            FunctionCode::Request { req, .. } => *req = req.with_priority(priority)?,
+            FunctionCode::Response { .. } => return None,
+        }
+        Some(self.send_data_telegram(header, pdu_len, write_pdu))
+    }
+
     pub fn send_fdl_status_request(self, da: u8, sa: u8) -> TelegramTxResponse {
         self.send_data_telegram(
             DataTelegramHeader {
@@ -697,14 +842,27 @@ impl TelegramTxResponse {
         Self {
             bytes_sent,
             expects_reply,
+            retry: true,
         }
     }
+
+    /// Don't retry this request when it is not answered.
+    ///
+    /// The FDL layer then reports the first timeout to the application right away.  This is
+    /// useful for probing stations which are likely absent.
+    pub fn without_retries(mut self) -> Self {
+        self.retry = false;
+        self
+    }
     pub fn bytes_sent(self) -> usize {
         self.bytes_sent
     }
     pub fn expects_reply(self) -> Option<u8> {
         self.expects_reply
     }
+    pub fn allows_retries(self) -> bool {
+        self.retry
+    }
 }
 
 #[cfg(test)]
@@ -816,6 +974,16 @@ mod tests {
             assert_eq!(res.pdu, pdu);
             assert_eq!(res_len, length);
 
+            // Serializing the whole telegram must lead to the same result.
+            let mut buffer2 = [0u8; 256];
+            let telegram = Telegram::Data(res);
+            assert_eq!(telegram.serialize(&mut buffer2), Ok(length));
+            assert_eq!(buffer2[..length], buffer[..length]);
+            assert_eq!(
+                telegram.serialize(&mut buffer2[..length - 1]),
+                Err(TooSmall)
+            );
+
             // Now attempt parsing the telegram partially to ensure this also always works.
             for i in 0..length {
                 println!("Trying partial parse at {i}/{length}...");
@@ -825,6 +993,100 @@ mod tests {
         }
     }
 
+    #[test]
+    fn telegram_deserialize_errors() {
+        let mut buffer = [0u8; 256];
+        let length = TelegramTx::new(&mut buffer)
+            .send_fdl_status_request(34, 2)
+            .bytes_sent();
+        let valid = &buffer[..length];
+        assert!(matches!(Telegram::deserialize(valid), Some(Ok(_))));
+
+        let corrupted = |index: usize, value: u8| {
+            let mut data = valid.to_vec();
+            data[index] = value;
+            Telegram::deserialize(&data).map(|r| r.map(|_| ()))
+        };
+        assert_eq!(
+            corrupted(0, 0x42),
+            Some(Err(DeserializeError::BadStartDelimiter))
+        );
+        assert_eq!(
+            corrupted(length - 2, valid[length - 2].wrapping_add(1)),
+            Some(Err(DeserializeError::BadChecksum))
+        );
+        assert_eq!(
+            corrupted(length - 1, 0x00),
+            Some(Err(DeserializeError::BadEndDelimiter))
+        );
+
+        // Variable length telegram with differing length bytes
+        let length = TelegramTx::new(&mut buffer)
+            .send_data_telegram(
+                DataTelegramHeader {
+                    da: 34,
+                    sa: 2,
+                    dsap: None,
+                    ssap: None,
+                    fc: FunctionCode::new_srd_low(FrameCountBit::Inactive),
+                },
+                3,
+                |buf| buf.copy_from_slice(&[0x12, 0x34, 0x56]),
+            )
+            .bytes_sent();
+        assert!(matches!(
+            Telegram::deserialize(&buffer[..length]),
+            Some(Ok(_))
+        ));
+        buffer[2] += 1;
+        assert_eq!(
+            Telegram::deserialize(&buffer[..length]).map(|r| r.map(|_| ())),
+            Some(Err(DeserializeError::LengthMismatch))
+        );
+    }
+
+    #[test]
+    fn telegram_serialize_roundtrip() {
+        let pdu = [0x12, 0x34, 0x56];
+        let telegrams = [
+            Telegram::Token(TokenTelegram::new(34, 2)),
+            Telegram::ShortConfirmation(ShortConfirmation),
+            Telegram::Data(DataTelegram {
+                h: DataTelegramHeader {
+                    da: 34,
+                    sa: 2,
+                    dsap: None,
+                    ssap: None,
+                    fc: FunctionCode::Request {
+                        fcb: FrameCountBit::Inactive,
+                        req: RequestType::FdlStatus,
+                    },
+                },
+                pdu: &[],
+            }),
+            Telegram::Data(DataTelegram {
+                h: DataTelegramHeader {
+                    da: 34,
+                    sa: 2,
+                    dsap: crate::consts::SAP_SLAVE_DIAGNOSIS,
+                    ssap: crate::consts::SAP_MASTER_MS0,
+                    fc: FunctionCode::new_srd_high(FrameCountBit::First),
+                },
+                pdu: &pdu,
+            }),
+        ];
+
+        for telegram in telegrams.iter() {
+            let mut buffer = [0u8; 256];
+            let length = telegram.serialize(&mut buffer).unwrap();
+            let (telegram2, length2) = Telegram::deserialize(&buffer[..length]).unwrap().unwrap();
+            assert_eq!(&telegram2, telegram);
+            assert_eq!(length2, length);
+
+            assert_eq!(telegram.serialize(&mut buffer[..length - 1]), Err(TooSmall));
+        }
+    }
+
     /// Special-case to ensure we are definitely testing the SD1 telegram as well.
     ///
     /// This helps me sleep at night...
@@ -857,6 +1119,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn data_telegram_with_priority() {
+        for priority in [Priority::Low, Priority::High] {
+            let mut buffer = [0u8; 256];
+            let length = TelegramTx::new(&mut buffer)
+                .send_data_telegram_with_priority(
+                    DataTelegramHeader {
+                        da: 13,
+                        sa: 14,
+                        dsap: Some(61),
+                        ssap: Some(62),
+                        fc: FunctionCode::new_srd_low(FrameCountBit::First),
+                    },
+                    priority,
+                    2,
+                    |buf| buf.copy_from_slice(&[0xca, 0xfe]),
+                )
+                .unwrap()
+                .bytes_sent();
+            let (telegram, _) = Telegram::deserialize(&buffer[..length]).unwrap().unwrap();
+            let Telegram::Data(telegram) = telegram else {
+                panic!("not a data telegram: {telegram:?}");
+            };
+            assert_eq!(
+                telegram.h.fc,
+                FunctionCode::new_srd(FrameCountBit::First, priority)
+            );
+            assert_eq!(telegram.pdu, &[0xca, 0xfe]);
+        }
+
+        let mut buffer = [0u8; 256];
+        assert!(TelegramTx::new(&mut buffer)
+            .send_data_telegram_with_priority(
+                DataTelegramHeader {
+                    da: 13,
+                    sa: 14,
+                    dsap: None,
+                    ssap: None,
+                    fc: FunctionCode::Request {
+                        fcb: FrameCountBit::Inactive,
+                        req: RequestType::FdlStatus,
+                    },
+                },
+                Priority::High,
+                0,
+                |_| (),
+            )
+            .is_none());
+
+        assert_eq!(
+            RequestType::SdnHigh.with_priority(Priority::Low),
+            Some(RequestType::SdnLow)
+        );
+        assert_eq!(RequestType::SdaLow.priority(), Some(Priority::Low));
+        assert_eq!(RequestType::FdlStatus.with_priority(Priority::High), None);
+        assert_eq!(RequestType::Ident.priority(), None);
+    }
+
     proptest! {
         #[test]
         fn function_code_proptest(fc in any::<FunctionCode>()) {
@@ -2,21 +2,22 @@ use crate::fdl;
 use crate::phy;
 use crate::phy::ProfibusPhy;
 
-struct FdlActiveUnderTest {
+struct FdlActiveUnderTest<'a> {
     control_addr: u8,
     timestep: crate::time::Duration,
     pub phy_control: phy::SimulatorPhy,
     phy_active: phy::SimulatorPhy,
-    pub active_station: fdl::FdlActiveStation,
+    pub active_station: fdl::FdlActiveStation<'a>,
+    pub app: Box<dyn fdl::FdlApplication>,
 }
 
-impl Default for FdlActiveUnderTest {
+impl Default for FdlActiveUnderTest<'_> {
     fn default() -> Self {
         Self::new(7)
     }
 }
 
-impl FdlActiveUnderTest {
+impl FdlActiveUnderTest<'_> {
     pub fn new(addr: crate::Address) -> Self {
         let baud = crate::Baudrate::B19200;
         let control_addr = 15;
@@ -32,7 +33,7 @@ impl FdlActiveUnderTest {
                 .build(),
         );
 
-        crate::test_utils::set_active_addr(active_station.parameters().address);
+        crate::test_utils::set_active_addr(active_station.address());
         active_station.set_online();
 
         Self {
@@ -41,6 +42,7 @@ impl FdlActiveUnderTest {
             phy_control,
             phy_active,
             active_station,
+            app: Box::new(()),
         }
     }
 
@@ -53,9 +55,12 @@ impl FdlActiveUnderTest {
     }
 
     pub fn do_fdl_active_station_cycle(&mut self) {
-        crate::test_utils::set_active_addr(self.active_station.parameters().address);
-        self.active_station
-            .poll(self.phy_control.bus_time(), &mut self.phy_active, &mut ());
+        crate::test_utils::set_active_addr(self.active_station.address());
+        self.active_station.poll(
+            self.phy_control.bus_time(),
+            &mut self.phy_active,
+            &mut *self.app,
+        );
         crate::test_utils::set_active_addr(self.control_addr);
     }
 
@@ -73,8 +78,9 @@ impl FdlActiveUnderTest {
         crate::test_utils::set_active_addr(self.control_addr);
         for now in self.phy_control.iter_until_matching(self.timestep, f) {
             crate::test_utils::set_log_timestamp(now);
-            crate::test_utils::set_active_addr(self.active_station.parameters().address);
-            self.active_station.poll(now, &mut self.phy_active, &mut ());
+            crate::test_utils::set_active_addr(self.active_station.address());
+            self.active_station
+                .poll(now, &mut self.phy_active, &mut *self.app);
             crate::test_utils::set_active_addr(self.control_addr);
         }
         self.phy_control.bus_time() - start
@@ -93,8 +99,9 @@ impl FdlActiveUnderTest {
             true
         }) {
             crate::test_utils::set_log_timestamp(now);
-            crate::test_utils::set_active_addr(self.active_station.parameters().address);
-            self.active_station.poll(now, &mut self.phy_active, &mut ());
+            crate::test_utils::set_active_addr(self.active_station.address());
+            self.active_station
+                .poll(now, &mut self.phy_active, &mut *self.app);
             crate::test_utils::set_active_addr(self.control_addr);
         }
         (self.phy_control.bus_time() - start, res)
@@ -202,6 +209,52 @@ impl FdlActiveUnderTest {
     }
 }
 
+/// Test that an active station passes on the token before going offline when requested.
+#[test]
+fn request_offline_passes_token() {
+    crate::test_utils::prepare_test_logger();
+    let mut fdl_ut = FdlActiveUnderTest::default();
+    fdl_ut.prepare_two_station_ring();
+    fdl_ut.wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }));
+
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| Some(tx.send_token_telegram(7, 15)));
+    fdl_ut.wait_transmission();
+
+    // The token is held right now, so it must be passed on immediately.
+    assert!(fdl_ut.active_station.holds_token());
+    fdl_ut.active_station.request_offline();
+    let time =
+        fdl_ut.assert_next_telegram(fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }));
+    assert!(time < fdl_ut.bits_to_time(100));
+    assert!(fdl_ut.active_station.connectivity_state().is_online());
+
+    // Once the next station is using the token, we go offline.
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| Some(tx.send_fdl_status_request(8, 15)));
+    fdl_ut.wait_transmission();
+    fdl_ut.do_timestep();
+    assert!(fdl_ut.active_station.connectivity_state().is_offline());
+    assert!(!fdl_ut.active_station.is_in_ring());
+}
+
+/// Test that a station alone on the bus goes offline directly when requested.
+#[test]
+fn request_offline_alone() {
+    crate::test_utils::prepare_test_logger();
+    let mut fdl_ut = FdlActiveUnderTest::default();
+    let addr = fdl_ut.fdl_param().address;
+    fdl_ut.wait_for_matching(|t| {
+        t == fdl::Telegram::Token(fdl::TokenTelegram { da: addr, sa: addr })
+    });
+
+    fdl_ut.active_station.request_offline();
+    for _ in 0..100 {
+        fdl_ut.do_timestep();
+    }
+    assert!(fdl_ut.active_station.connectivity_state().is_offline());
+}
+
 /// Test that an active station sends a claimed token twice before doing anything else.
 #[test]
 fn new_token_is_sent_twice() {
@@ -619,6 +672,8 @@ fn active_station_resends_token() {
     fdl_ut.wait_transmission();
 
     fdl_ut.wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }));
+
+    assert_eq!(fdl_ut.active_station.stats().token_retries, 2);
 }
 
 /// Test that an active station forwards the token to the next station when when the previous next
@@ -705,8 +760,68 @@ fn active_station_replies_after_token_pass() {
     }));
 }
 
+/// Test that an active station records the kind of stations from their responses.
+#[test]
+fn active_station_records_station_kinds() {
+    crate::test_utils::prepare_test_logger();
+    let mut fdl_ut = FdlActiveUnderTest::new(7);
+
+    fdl_ut.prepare_two_station_ring();
+
+    fdl_ut.wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }));
+
+    // Witness a response from a station to another master
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| Some(tx.send_fdl_status_request(20, 15)));
+    fdl_ut.wait_transmission();
+    fdl_ut.advance_bus_time_min_tsdr();
+    fdl_ut.transmit_telegram(|tx| {
+        Some(tx.send_fdl_status_response(
+            15,
+            20,
+            fdl::ResponseState::MasterNotReady,
+            fdl::ResponseStatus::Ok,
+        ))
+    });
+    fdl_ut.wait_transmission();
+
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| Some(tx.send_token_telegram(7, 15)));
+    fdl_ut.wait_transmission();
+
+    // Answer the gap poll as a slave
+    fdl_ut.wait_for_matching(|t| {
+        if let fdl::Telegram::Data(data_telegram) = t {
+            data_telegram.is_fdl_status_request().is_some() && data_telegram.h.da == 9
+        } else {
+            false
+        }
+    });
+    fdl_ut.advance_bus_time_min_tsdr();
+    fdl_ut.transmit_telegram(|tx| {
+        Some(tx.send_fdl_status_response(7, 9, fdl::ResponseState::Slave, fdl::ResponseStatus::Ok))
+    });
+    fdl_ut.wait_transmission();
+
+    fdl_ut.wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }));
+
+    let station = &fdl_ut.active_station;
+    assert_eq!(station.station_kind(9), Some(fdl::ResponseState::Slave));
+    assert_eq!(
+        station.station_kind(20),
+        Some(fdl::ResponseState::MasterNotReady)
+    );
+    // Stations passing the token are in the ring
+    assert_eq!(
+        station.station_kind(15),
+        Some(fdl::ResponseState::MasterInRing)
+    );
+    assert_eq!(station.station_kind(7), None);
+    assert_eq!(station.station_kind(30), None);
+    assert_eq!(station.station_kind(200), None);
+}
+
 /// Test that an active station responds to unknown requests.
-#[ignore = "not yet implemented"]
 #[test]
 fn active_station_responds_unknown() {
     crate::test_utils::prepare_test_logger();
@@ -747,6 +862,320 @@ fn active_station_responds_unknown() {
     }));
 }
 
+/// Application answering SRD requests to SAP 50 with the reversed request data
+#[derive(Default)]
+struct ReverseResponder {
+    request: Option<(Option<u8>, Vec<u8>)>,
+}
+
+impl fdl::FdlApplication for ReverseResponder {
+    fn transmit_telegram(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &fdl::FdlActiveStation,
+        tx: fdl::TelegramTx,
+        high_prio_only: bool,
+    ) -> Option<fdl::TelegramTxResponse> {
+        None
+    }
+
+    fn receive_reply(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &fdl::FdlActiveStation,
+        addr: u8,
+        telegram: fdl::Telegram,
+    ) {
+    }
+
+    fn handle_timeout(&mut self, now: crate::time::Instant, fdl: &fdl::FdlActiveStation, addr: u8) {
+    }
+
+    fn receive_request(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &fdl::FdlActiveStation,
+        telegram: &fdl::DataTelegram,
+    ) -> bool {
+        fdl::RequestHandler::receive_request(self, now, fdl, telegram)
+    }
+
+    fn transmit_response(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &fdl::FdlActiveStation,
+        addr: u8,
+        tx: fdl::TelegramTx,
+    ) -> Option<fdl::TelegramTxResponse> {
+        fdl::RequestHandler::transmit_response(self, now, fdl, addr, tx)
+    }
+}
+
+impl fdl::RequestHandler for ReverseResponder {
+    fn receive_request(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &fdl::FdlActiveStation,
+        telegram: &fdl::DataTelegram,
+    ) -> bool {
+        if telegram.h.dsap != Some(50) {
+            return false;
+        }
+        self.request = Some((
+            telegram.h.ssap,
+            telegram.pdu.iter().rev().copied().collect(),
+        ));
+        true
+    }
+
+    fn transmit_response(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &fdl::FdlActiveStation,
+        addr: u8,
+        tx: fdl::TelegramTx,
+    ) -> Option<fdl::TelegramTxResponse> {
+        let (ssap, pdu) = self.request.take()?;
+        Some(tx.send_data_telegram(
+            fdl::DataTelegramHeader {
+                da: addr,
+                sa: fdl.address(),
+                dsap: ssap,
+                ssap: Some(50),
+                fc: fdl::FunctionCode::Response {
+                    state: fdl::ResponseState::MasterInRing,
+                    status: fdl::ResponseStatus::DataLow,
+                },
+            },
+            pdu.len(),
+            |buf| buf.copy_from_slice(&pdu),
+        ))
+    }
+}
+
+/// Test that an active station lets applications answer requests sent to it.
+#[test]
+fn active_station_answers_application_requests() {
+    crate::test_utils::prepare_test_logger();
+    let mut fdl_ut = FdlActiveUnderTest::new(7);
+    fdl_ut.app = Box::new(ReverseResponder::default());
+
+    fdl_ut.prepare_two_station_ring();
+
+    fdl_ut.wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }));
+
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| {
+        Some(tx.send_data_telegram(
+            fdl::DataTelegramHeader {
+                da: 7,
+                sa: 15,
+                dsap: Some(50),
+                ssap: Some(40),
+                fc: crate::fdl::FunctionCode::new_srd_low(Default::default()),
+            },
+            3,
+            |buf| buf.copy_from_slice(&[0x01, 0x02, 0x03]),
+        ))
+    });
+    fdl_ut.wait_transmission();
+
+    fdl_ut.assert_next_telegram(fdl::Telegram::Data(fdl::DataTelegram {
+        h: fdl::DataTelegramHeader {
+            da: 15,
+            sa: 7,
+            dsap: Some(40),
+            ssap: Some(50),
+            fc: fdl::FunctionCode::Response {
+                state: fdl::ResponseState::MasterInRing,
+                status: fdl::ResponseStatus::DataLow,
+            },
+        },
+        pdu: &[0x03, 0x02, 0x01],
+    }));
+
+    // Requests to other SAPs are still rejected
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| {
+        Some(tx.send_data_telegram(
+            fdl::DataTelegramHeader {
+                da: 7,
+                sa: 15,
+                dsap: Some(51),
+                ssap: Some(40),
+                fc: crate::fdl::FunctionCode::new_srd_low(Default::default()),
+            },
+            0,
+            |_buf| (),
+        ))
+    });
+    fdl_ut.wait_transmission();
+
+    fdl_ut.assert_next_telegram(fdl::Telegram::Data(fdl::DataTelegram {
+        h: fdl::DataTelegramHeader {
+            da: 15,
+            sa: 7,
+            dsap: Some(40),
+            ssap: Some(51),
+            fc: fdl::FunctionCode::Response {
+                state: fdl::ResponseState::MasterInRing,
+                status: fdl::ResponseStatus::SapNotEnabled,
+            },
+        },
+        pdu: &[],
+    }));
+}
+
+/// Test that the request handler answers requests sent to the station.
+#[test]
+fn active_station_answers_with_request_handler() {
+    crate::test_utils::prepare_test_logger();
+    let mut handler = ReverseResponder::default();
+    let mut fdl_ut = FdlActiveUnderTest::new(7);
+    fdl_ut.active_station.set_request_handler(&mut handler);
+
+    fdl_ut.prepare_two_station_ring();
+
+    fdl_ut.wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }));
+
+    for handler_set in [true, false] {
+        fdl_ut.advance_bus_time_sync_pause();
+        fdl_ut.transmit_telegram(|tx| {
+            Some(tx.send_data_telegram(
+                fdl::DataTelegramHeader {
+                    da: 7,
+                    sa: 15,
+                    dsap: Some(50),
+                    ssap: Some(40),
+                    fc: crate::fdl::FunctionCode::new_srd_low(Default::default()),
+                },
+                2,
+                |buf| buf.copy_from_slice(&[0x01, 0x02]),
+            ))
+        });
+        fdl_ut.wait_transmission();
+
+        let (status, pdu): (_, &[u8]) = if handler_set {
+            (fdl::ResponseStatus::DataLow, &[0x02, 0x01])
+        } else {
+            (fdl::ResponseStatus::SapNotEnabled, &[])
+        };
+        fdl_ut.assert_next_telegram(fdl::Telegram::Data(fdl::DataTelegram {
+            h: fdl::DataTelegramHeader {
+                da: 15,
+                sa: 7,
+                dsap: Some(40),
+                ssap: Some(50),
+                fc: fdl::FunctionCode::Response {
+                    state: fdl::ResponseState::MasterInRing,
+                    status,
+                },
+            },
+            pdu,
+        }));
+
+        assert_eq!(
+            fdl_ut.active_station.remove_request_handler().is_some(),
+            handler_set
+        );
+    }
+}
+
+/// Test that no response is sent when the application which accepted a request was replaced.
+#[test]
+fn active_station_skips_response_of_removed_application() {
+    crate::test_utils::prepare_test_logger();
+    let mut fdl_ut = FdlActiveUnderTest::new(7);
+    fdl_ut.app = Box::new(ReverseResponder::default());
+
+    fdl_ut.prepare_two_station_ring();
+
+    fdl_ut.wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }));
+
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| {
+        Some(tx.send_data_telegram(
+            fdl::DataTelegramHeader {
+                da: 7,
+                sa: 15,
+                dsap: Some(50),
+                ssap: Some(40),
+                fc: crate::fdl::FunctionCode::new_srd_low(Default::default()),
+            },
+            3,
+            |buf| buf.copy_from_slice(&[0x01, 0x02, 0x03]),
+        ))
+    });
+    fdl_ut.wait_transmission();
+    fdl_ut.do_fdl_active_station_cycle();
+
+    // Another application now lives in the same slot.  It would answer if it were asked.
+    let mut apps = fdl::ApplicationSet::new(Vec::new());
+    let mut removed = ReverseResponder::default();
+    let handle = apps.add(&mut removed as &mut dyn fdl::FdlApplication);
+    apps.remove(handle);
+    let mut other = ReverseResponder {
+        request: Some((Some(40), vec![0x04])),
+    };
+    apps.add(&mut other as &mut dyn fdl::FdlApplication);
+
+    let timeout = fdl_ut.now() + fdl_ut.bits_to_time(200);
+    while fdl_ut.now() < timeout {
+        fdl_ut.advance_bus_time_bits(1);
+        let now = fdl_ut.now();
+        fdl_ut
+            .active_station
+            .poll_applications(now, &mut fdl_ut.phy_active, &mut apps);
+        assert_eq!(fdl_ut.phy_control.poll_pending_received_bytes(now), 0);
+    }
+    assert!(other.request.is_some());
+}
+
+/// Test that a station which is not in the ring rejects requests to SAPs it does not serve.
+#[rstest::rstest]
+fn station_outside_ring_rejects_requests(#[values(false, true)] passive: bool) {
+    crate::test_utils::prepare_test_logger();
+    let mut fdl_ut = FdlActiveUnderTest::default();
+    if passive {
+        fdl_ut.active_station.set_passive();
+    }
+
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| Some(tx.send_token_telegram(15, 15)));
+    fdl_ut.wait_transmission();
+
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| {
+        Some(tx.send_data_telegram(
+            fdl::DataTelegramHeader {
+                da: 7,
+                sa: 15,
+                dsap: crate::consts::SAP_SLAVE_DIAGNOSIS,
+                ssap: crate::consts::SAP_MASTER_MS0,
+                fc: crate::fdl::FunctionCode::new_srd_low(Default::default()),
+            },
+            0,
+            |_buf| (),
+        ))
+    });
+    fdl_ut.wait_transmission();
+
+    fdl_ut.assert_next_telegram(fdl::Telegram::Data(fdl::DataTelegram {
+        h: fdl::DataTelegramHeader {
+            da: 15,
+            sa: 7,
+            dsap: crate::consts::SAP_MASTER_MS0,
+            ssap: crate::consts::SAP_SLAVE_DIAGNOSIS,
+            fc: fdl::FunctionCode::Response {
+                state: fdl::ResponseState::MasterNotReady,
+                status: fdl::ResponseStatus::SapNotEnabled,
+            },
+        },
+        pdu: &[],
+    }));
+    assert!(!fdl_ut.active_station.is_in_ring());
+}
+
 /// Test that a token lost timeout is triggered in the active idle state as well
 #[test]
 fn active_idle_token_lost() {
@@ -910,9 +1339,89 @@ fn multimaster_smoke() {
     }
 }
 
+/// Test that a DP master exchanges data with a slave while sharing the bus with another master.
 #[test]
-fn active_station_receives_faulty_token_telegram() {
-    crate::test_utils::prepare_test_logger_with_warnings(vec![
+fn multimaster_dp_data_exchange() {
+    crate::test_utils::prepare_test_logger();
+    let baud = crate::Baudrate::B19200;
+
+    let phy = crate::phy::SimulatorPhy::new(baud, "phy#main");
+
+    let mut dp_master = crate::dp::DpMaster::new(vec![]);
+    let handle = dp_master.add(crate::dp::Peripheral::new(
+        8,
+        crate::dp::PeripheralOptions {
+            ident_number: 0x4711,
+            user_parameters: Some(&[0x00, 0x42]),
+            config: Some(&[0x21, 0x11]),
+            ..Default::default()
+        },
+        vec![0x00; 2],
+        vec![0x00; 2],
+    ));
+    dp_master
+        .get_mut(handle)
+        .pi_q_mut()
+        .copy_from_slice(&[0xca, 0xfe]);
+    dp_master.enter_operate();
+
+    let mut slave =
+        crate::dp::sim::SimulatedSlave::new(&phy, 8, 0x4711, 2, 2).with_config(&[0x21, 0x11]);
+    slave.set_inputs(&[0x12, 0x34]);
+
+    let mut masters: Vec<_> = [2, 7]
+        .into_iter()
+        .map(|addr| {
+            let phy = phy.duplicate(format!("phy#{addr}").leak());
+            let mut fdl = crate::fdl::FdlActiveStation::new(
+                crate::fdl::ParametersBuilder::new(addr, baud)
+                    .highest_station_address(16)
+                    .slot_bits(300)
+                    .build(),
+            );
+            crate::test_utils::set_active_addr(addr);
+            fdl.set_online();
+            (addr, phy, fdl)
+        })
+        .collect();
+
+    let start = crate::time::Instant::ZERO;
+    let mut now = start;
+    while (now - start) < crate::time::Duration::from_secs(3) {
+        crate::test_utils::set_log_timestamp(now);
+        phy.set_bus_time(now);
+
+        for (addr, phy, fdl) in masters.iter_mut() {
+            crate::test_utils::set_active_addr(*addr);
+            if *addr == 2 {
+                fdl.poll(now, phy, &mut dp_master);
+            } else {
+                fdl.poll(now, phy, &mut ());
+            }
+        }
+        slave.poll(now);
+
+        now += crate::time::Duration::from_micros(100);
+    }
+
+    for (addr, _, fdl) in masters.iter() {
+        let known_actives: Vec<_> = fdl.inspect_token_ring().iter_active_stations().collect();
+        assert_eq!(known_actives, [2, 7], "wrong LAS formed in station #{addr}");
+    }
+
+    assert!(slave.is_ready());
+    assert_eq!(slave.user_parameters(), Some(&[0x00, 0x42][..]));
+    assert!(slave.data_exchange_count() > 10);
+    assert_eq!(slave.outputs(), [0xca, 0xfe]);
+
+    let peripheral = dp_master.get_mut(handle);
+    assert!(peripheral.is_running());
+    assert_eq!(peripheral.pi_i(), [0x12, 0x34]);
+}
+
+#[test]
+fn active_station_receives_faulty_token_telegram() {
+    crate::test_utils::prepare_test_logger_with_warnings(vec![
         "Unexpected station #174 transmitting after token pass to #15",
         "Witnessed token pass from invalid address #174->#223, ignoring.",
     ]);
@@ -979,3 +1488,1115 @@ fn slot_time_timing() {
         "Slot time was {time} instead of {slot_time} (that's {bits_over} too many T_bit)!"
     );
 }
+
+/// Test that priority addresses are polled first in each GAP polling cycle.
+#[test]
+fn priority_gap_addresses() {
+    crate::test_utils::prepare_test_logger();
+    let mut fdl_ut = FdlActiveUnderTest::new(7);
+    fdl_ut.active_station = fdl::FdlActiveStation::new(
+        crate::fdl::ParametersBuilder::new(7, crate::Baudrate::B19200)
+            .highest_station_address(16)
+            .slot_bits(300)
+            .gap_wait_rotations(1)
+            .priority_gap_addresses(&[12, 3, 7, 11, 20])
+            .build(),
+    );
+    fdl_ut.active_station.set_online();
+    fdl_ut.prepare_two_station_ring();
+
+    let mut polled = vec![];
+    while polled.len() < 16 {
+        let mut pdu = [0u8; 256];
+        let (_, t) = fdl_ut.wait_next_telegram(|t| Some(t.clone_with_pdu_buffer(&mut pdu)));
+        match t.unwrap() {
+            fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }) => {
+                fdl_ut.advance_bus_time_sync_pause();
+                fdl_ut.transmit_telegram(|tx| Some(tx.send_token_telegram(7, 15)));
+                fdl_ut.wait_transmission();
+            }
+            fdl::Telegram::Data(t) if t.is_fdl_status_request().is_some() => {
+                assert_eq!(t.h.sa, 7);
+                polled.push(t.h.da);
+            }
+            t => panic!("unexpected telegram {t:?}"),
+        }
+    }
+
+    // The first cycle was already scheduled sequentially.  The following cycle starts with the
+    // priority addresses inside the GAP (8 to 14) before scanning sequentially again.
+    assert_eq!(
+        polled,
+        &[8, 9, 10, 11, 12, 13, 14, 11, 12, 8, 9, 10, 11, 12, 13, 14]
+    );
+}
+
+/// Test that an active station ignores a truncated request and answers the next complete one.
+#[test]
+fn active_station_ignores_truncated_telegram() {
+    crate::test_utils::prepare_test_logger();
+    let mut fdl_ut = FdlActiveUnderTest::default();
+
+    fdl_ut.prepare_two_station_ring();
+
+    fdl_ut.wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }));
+
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| Some(tx.send_fdl_status_request(3, 15)));
+    fdl_ut.wait_transmission();
+
+    fdl_ut
+        .phy_control
+        .set_error_model(phy::simulator::ErrorModel {
+            truncate_probability: 1.0,
+            seed: 7,
+            ..Default::default()
+        });
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| Some(tx.send_fdl_status_request(7, 15)));
+    fdl_ut.wait_transmission();
+    fdl_ut
+        .phy_control
+        .set_error_model(phy::simulator::ErrorModel::default());
+
+    // The station must not respond to the partial telegram.
+    fdl_ut.assert_idle_bits(fdl_ut.fdl_param().slot_bits.into());
+
+    fdl_ut.transmit_telegram(|tx| Some(tx.send_fdl_status_request(7, 15)));
+    fdl_ut.wait_transmission();
+
+    fdl_ut.assert_next_telegram(fdl::Telegram::Data(fdl::DataTelegram {
+        h: fdl::DataTelegramHeader {
+            da: 15,
+            sa: 7,
+            dsap: None,
+            ssap: None,
+            fc: fdl::FunctionCode::Response {
+                state: fdl::ResponseState::MasterInRing,
+                status: fdl::ResponseStatus::Ok,
+            },
+        },
+        pdu: &[],
+    }));
+}
+
+/// Test that an active station recovers when the token passed to it was garbled.
+#[test]
+fn active_station_garbled_token() {
+    crate::test_utils::prepare_test_logger_with_warnings(vec!["Token lost! Generating a new one."]);
+    let mut fdl_ut = FdlActiveUnderTest::default();
+
+    fdl_ut.prepare_two_station_ring();
+
+    fdl_ut.wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }));
+
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| Some(tx.send_fdl_status_request(3, 15)));
+    fdl_ut.wait_transmission();
+
+    // Flip every bit of the token telegram so it cannot be decoded.
+    fdl_ut
+        .phy_control
+        .set_error_model(phy::simulator::ErrorModel {
+            bit_error_rate: 1.0,
+            ..Default::default()
+        });
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| Some(tx.send_token_telegram(7, 15)));
+    fdl_ut.wait_transmission();
+    fdl_ut
+        .phy_control
+        .set_error_model(phy::simulator::ErrorModel::default());
+
+    let time =
+        fdl_ut.assert_next_telegram(fdl::Telegram::Token(fdl::TokenTelegram { da: 7, sa: 7 }));
+    assert!(time > fdl_ut.fdl_param().token_lost_timeout());
+    assert_eq!(fdl_ut.active_station.stats().token_lost, 1);
+}
+
+/// Test that an active station counts received data which cannot be decoded.
+#[test]
+fn active_station_counts_rx_errors() {
+    crate::test_utils::prepare_test_logger();
+    let mut fdl_ut = FdlActiveUnderTest::default();
+
+    fdl_ut.prepare_two_station_ring();
+
+    fdl_ut.wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }));
+    assert_eq!(fdl_ut.active_station.stats().rx_errors.total(), 0);
+
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| Some(tx.send_fdl_status_request(3, 15)));
+    fdl_ut.wait_transmission();
+
+    // Status request with a broken checksum
+    fdl_ut.advance_bus_time_sync_pause();
+    let now = fdl_ut.now();
+    fdl_ut.phy_control.transmit_data(now, |buffer| {
+        let length = fdl::TelegramTx::new(buffer)
+            .send_fdl_status_request(7, 15)
+            .bytes_sent();
+        buffer[length - 2] ^= 0x55;
+        (length, ())
+    });
+    fdl_ut.wait_transmission();
+    fdl_ut.assert_idle_bits(fdl_ut.fdl_param().slot_bits.into());
+
+    // Garbage byte which is not a start delimiter
+    let now = fdl_ut.now();
+    fdl_ut.phy_control.transmit_data(now, |buffer| {
+        buffer[0] = 0x42;
+        (1, ())
+    });
+    fdl_ut.wait_transmission();
+    fdl_ut.assert_idle_bits(fdl_ut.fdl_param().slot_bits.into());
+
+    assert_eq!(
+        fdl_ut.active_station.stats().rx_errors,
+        fdl::RxErrorCounters {
+            bad_checksum: 1,
+            bad_start_delimiter: 1,
+            ..Default::default()
+        }
+    );
+
+    fdl_ut.active_station.reset_stats();
+    assert_eq!(fdl_ut.active_station.stats(), fdl::FdlStats::default());
+}
+
+/// Test that an active station counts line errors reported by the PHY.
+#[test]
+fn active_station_counts_line_errors() {
+    crate::test_utils::prepare_test_logger();
+    let mut fdl_ut = FdlActiveUnderTest::default();
+
+    fdl_ut.prepare_two_station_ring();
+
+    fdl_ut.wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }));
+    assert_eq!(fdl_ut.active_station.stats().line_errors.total(), 0);
+
+    fdl_ut
+        .phy_active
+        .inject_line_errors(fdl::LineErrorCounters {
+            framing: 3,
+            breaks: 1,
+            ..Default::default()
+        });
+    fdl_ut.do_timestep();
+    fdl_ut
+        .phy_active
+        .inject_line_errors(fdl::LineErrorCounters {
+            framing: 2,
+            ..Default::default()
+        });
+    fdl_ut.do_timestep();
+
+    assert_eq!(
+        fdl_ut.active_station.stats().line_errors,
+        fdl::LineErrorCounters {
+            framing: 5,
+            breaks: 1,
+            ..Default::default()
+        }
+    );
+    // Line errors are not mixed up with receive errors.
+    assert_eq!(fdl_ut.active_station.stats().rx_errors.total(), 0);
+}
+
+/// Test that overflows of the PHY receive buffer are reported.
+#[test]
+fn active_station_counts_rx_overflows() {
+    crate::test_utils::prepare_test_logger_with_warnings(vec![
+        "RX overflow, telegram likely lost!  Is the bus polled often enough?",
+    ]);
+    let mut fdl_ut = FdlActiveUnderTest::default();
+
+    fdl_ut.prepare_two_station_ring();
+
+    fdl_ut.wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }));
+    assert_eq!(fdl_ut.active_station.stats().rx_errors.total(), 0);
+
+    fdl_ut.phy_active.inject_rx_overflow();
+    fdl_ut.do_timestep();
+    fdl_ut.do_timestep();
+
+    assert_eq!(
+        fdl_ut.active_station.stats().rx_errors,
+        fdl::RxErrorCounters {
+            overflows: 1,
+            ..Default::default()
+        }
+    );
+}
+
+/// Test that requests which are not answered are counted as timeouts.
+#[test]
+fn active_station_counts_timeouts() {
+    crate::test_utils::prepare_test_logger();
+
+    #[derive(Default)]
+    struct RequestOnce {
+        sent: bool,
+        timed_out: bool,
+    }
+
+    impl fdl::FdlApplication for RequestOnce {
+        fn transmit_telegram(
+            &mut self,
+            _now: crate::time::Instant,
+            fdl: &fdl::FdlActiveStation,
+            tx: fdl::TelegramTx,
+            _high_prio_only: bool,
+        ) -> Option<fdl::TelegramTxResponse> {
+            if self.sent {
+                return None;
+            }
+            self.sent = true;
+            Some(
+                tx.send_fdl_status_request(20, fdl.address())
+                    .without_retries(),
+            )
+        }
+
+        fn receive_reply(
+            &mut self,
+            _now: crate::time::Instant,
+            _fdl: &fdl::FdlActiveStation,
+            _addr: u8,
+            _telegram: fdl::Telegram,
+        ) {
+            panic!("Absent station replied");
+        }
+
+        fn handle_timeout(
+            &mut self,
+            _now: crate::time::Instant,
+            _fdl: &fdl::FdlActiveStation,
+            addr: u8,
+        ) {
+            assert_eq!(addr, 20);
+            self.timed_out = true;
+        }
+    }
+
+    let baud = crate::Baudrate::B19200;
+    let mut phy = phy::SimulatorPhy::new(baud, "phy#ut");
+    let mut fdl = fdl::FdlActiveStation::new(fdl::ParametersBuilder::new(7, baud).build());
+    fdl.set_online();
+    let mut app = RequestOnce::default();
+
+    let mut now = crate::time::Instant::ZERO;
+    while !app.timed_out {
+        assert!(now < crate::time::Instant::ZERO + crate::time::Duration::from_secs(10));
+        phy.set_bus_time(now);
+        fdl.poll(now, &mut phy, &mut app);
+        now += crate::time::Duration::from_micros(100);
+    }
+
+    assert_eq!(fdl.stats().timeouts, 1);
+}
+
+/// Test that unanswered requests are retried by the FDL layer before reporting a timeout.
+#[test]
+fn active_station_retries_requests() {
+    crate::test_utils::prepare_test_logger_with_warnings(vec!["Resending a telegram to #20..."]);
+
+    #[derive(Default)]
+    struct RetriedRequest {
+        transmissions: usize,
+        timeouts: usize,
+    }
+
+    impl fdl::FdlApplication for RetriedRequest {
+        fn transmit_telegram(
+            &mut self,
+            _now: crate::time::Instant,
+            fdl: &fdl::FdlActiveStation,
+            tx: fdl::TelegramTx,
+            _high_prio_only: bool,
+        ) -> Option<fdl::TelegramTxResponse> {
+            if self.timeouts > 0 {
+                return None;
+            }
+            // Repeat the request until a timeout is reported.
+            self.transmissions += 1;
+            Some(tx.send_fdl_status_request(20, fdl.address()))
+        }
+
+        fn receive_reply(
+            &mut self,
+            _now: crate::time::Instant,
+            _fdl: &fdl::FdlActiveStation,
+            _addr: u8,
+            _telegram: fdl::Telegram,
+        ) {
+            panic!("Absent station replied");
+        }
+
+        fn handle_timeout(
+            &mut self,
+            _now: crate::time::Instant,
+            _fdl: &fdl::FdlActiveStation,
+            addr: u8,
+        ) {
+            assert_eq!(addr, 20);
+            self.timeouts += 1;
+        }
+    }
+
+    let baud = crate::Baudrate::B19200;
+    let mut phy = phy::SimulatorPhy::new(baud, "phy#ut");
+    let mut fdl = fdl::FdlActiveStation::new(
+        fdl::ParametersBuilder::new(7, baud)
+            .max_retry_limit(3)
+            .build(),
+    );
+    fdl.set_online();
+    let mut app = RetriedRequest::default();
+
+    let mut now = crate::time::Instant::ZERO;
+    while now < crate::time::Instant::ZERO + crate::time::Duration::from_secs(1) {
+        phy.set_bus_time(now);
+        fdl.poll(now, &mut phy, &mut app);
+        now += crate::time::Duration::from_micros(100);
+    }
+
+    // One initial transmission and three retries, then a single timeout is reported.
+    assert_eq!(app.transmissions, 4);
+    assert_eq!(app.timeouts, 1);
+    assert_eq!(fdl.stats().timeouts, 4);
+}
+
+/// Test that an active station keeps waiting for a reply which is received in chunks.
+///
+/// USB-serial adapters on non-realtime systems deliver data in chunks with larger gaps in between.
+/// The station must measure the slot time from the last received chunk and not time out early.
+#[test]
+fn active_station_receives_chunked_reply() {
+    crate::test_utils::prepare_test_logger();
+    let mut fdl_ut = FdlActiveUnderTest::new(7);
+
+    let slot_bits = u32::from(fdl_ut.fdl_param().slot_bits);
+    let inter_chunk_delay = fdl_ut.bits_to_time(slot_bits * 4 / 5);
+    fdl_ut.phy_active.set_rx_chunking(2, inter_chunk_delay);
+
+    fdl_ut.wait_for_matching(|t| {
+        if let fdl::Telegram::Data(data_telegram) = t {
+            data_telegram.is_fdl_status_request().is_some()
+                && data_telegram.h.da == 4
+                && data_telegram.h.sa == 7
+        } else {
+            false
+        }
+    });
+
+    fdl_ut.advance_bus_time_min_tsdr();
+    fdl_ut.transmit_telegram(|tx| {
+        Some(tx.send_fdl_status_response(
+            7,
+            4,
+            fdl::ResponseState::MasterWithoutToken,
+            fdl::ResponseStatus::Ok,
+        ))
+    });
+    fdl_ut.wait_transmission();
+
+    // The last chunk only arrives more than a slot time after the reply was transmitted.
+    let time =
+        fdl_ut.assert_next_telegram(fdl::Telegram::Token(fdl::TokenTelegram { da: 4, sa: 7 }));
+    assert!(time > fdl_ut.bits_to_time(slot_bits));
+}
+
+/// Test that a passive station responds to status requests but never joins the ring.
+#[test]
+fn passive_station() {
+    // The token ring was witnessed before going online, so the silent bus is a lost token.
+    crate::test_utils::prepare_test_logger_with_warnings(vec!["Token lost! Generating a new one."]);
+    let mut fdl_ut = FdlActiveUnderTest::default();
+    fdl_ut.active_station.set_passive();
+
+    // Let the control station hold the token for a while.
+    for _ in 0..3 {
+        fdl_ut.advance_bus_time_sync_pause();
+        fdl_ut.transmit_telegram(|tx| Some(tx.send_token_telegram(15, 15)));
+        fdl_ut.wait_transmission();
+    }
+
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| Some(tx.send_fdl_status_request(7, 15)));
+    fdl_ut.wait_transmission();
+
+    fdl_ut.assert_next_telegram(fdl::Telegram::Data(fdl::DataTelegram {
+        h: fdl::DataTelegramHeader {
+            da: 15,
+            sa: 7,
+            dsap: None,
+            ssap: None,
+            fc: fdl::FunctionCode::Response {
+                state: fdl::ResponseState::MasterNotReady,
+                status: fdl::ResponseStatus::Ok,
+            },
+        },
+        pdu: &[],
+    }));
+
+    // A token passed to us is not accepted.
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| Some(tx.send_token_telegram(7, 15)));
+    fdl_ut.wait_transmission();
+    fdl_ut.assert_idle_bits(fdl_ut.fdl_param().slot_bits.into());
+
+    // Even on a silent bus, the passive station never claims the token.
+    let token_lost_timeout = fdl_ut.fdl_param().token_lost_timeout();
+    fdl_ut.assert_idle_time(token_lost_timeout * 3);
+    assert!(!fdl_ut.active_station.is_in_ring());
+
+    // Going online lets the station join the bus as usual.
+    fdl_ut.active_station.set_online();
+    fdl_ut.wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 7, sa: 7 }));
+}
+
+/// Test that the GAP scan of a station which is alone on the bus never polls addresses at or
+/// above the HSA.
+#[test]
+fn gap_scan_bounded_by_hsa() {
+    crate::test_utils::prepare_test_logger();
+    let mut fdl_ut = FdlActiveUnderTest::new(7);
+    fdl_ut.active_station = fdl::FdlActiveStation::new(
+        crate::fdl::ParametersBuilder::new(7, crate::Baudrate::B19200)
+            .highest_station_address(10)
+            .slot_bits(300)
+            .gap_wait_rotations(1)
+            .build(),
+    );
+    fdl_ut.active_station.set_online();
+
+    let mut polled = vec![];
+    while polled.len() < 40 {
+        let mut pdu = [0u8; 256];
+        let (_, t) = fdl_ut.wait_next_telegram(|t| Some(t.clone_with_pdu_buffer(&mut pdu)));
+        match t.unwrap() {
+            fdl::Telegram::Token(fdl::TokenTelegram { da: 7, sa: 7 }) => (),
+            fdl::Telegram::Data(t) if t.is_fdl_status_request().is_some() => {
+                assert_eq!(t.h.sa, 7);
+                polled.push(t.h.da);
+            }
+            t => panic!("unexpected telegram {t:?}"),
+        }
+    }
+
+    // Only addresses below the HSA are polled and the station never polls itself.
+    assert!(polled.iter().all(|a| *a < 10 && *a != 7));
+    assert_eq!(
+        polled[..18],
+        [8, 9, 0, 1, 2, 3, 4, 5, 6, 8, 9, 0, 1, 2, 3, 4, 5, 6]
+    );
+}
+
+/// Test the bus health summary of a station alone on the bus.
+#[test]
+fn active_station_summary() {
+    crate::test_utils::prepare_test_logger();
+    let mut fdl_ut = FdlActiveUnderTest::new(7);
+
+    let summary = fdl_ut.active_station.summary();
+    assert!(!summary.in_ring);
+    assert_eq!(summary.rotation_time, None);
+
+    // Wait for the station to claim the token and then pass it to itself twice
+    for _ in 0..4 {
+        fdl_ut
+            .wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 7, sa: 7 }));
+    }
+
+    let summary = fdl_ut.active_station.summary();
+    assert_eq!(summary.connectivity_state, fdl::ConnectivityState::Online);
+    assert!(summary.in_ring);
+    assert_eq!(
+        summary.target_rotation_time,
+        fdl_ut.fdl_param().token_rotation_time()
+    );
+    let rotation_time = summary.rotation_time.unwrap();
+    assert!(rotation_time > crate::time::Duration::ZERO);
+    assert!(rotation_time < summary.target_rotation_time);
+    assert_eq!(summary.stats.total(), 0);
+
+    fdl_ut.active_station.set_offline();
+    assert_eq!(fdl_ut.active_station.summary().rotation_time, None);
+}
+
+/// Test that the reported GAP scan period matches the actual polling.
+#[test]
+fn gap_scan_period() {
+    crate::test_utils::prepare_test_logger();
+    let mut fdl_ut = FdlActiveUnderTest::new(7);
+    fdl_ut.active_station = fdl::FdlActiveStation::new(
+        crate::fdl::ParametersBuilder::new(7, crate::Baudrate::B19200)
+            .highest_station_address(10)
+            .slot_bits(300)
+            .gap_wait_rotations(3)
+            .priority_gap_addresses(&[2])
+            .build(),
+    );
+    fdl_ut.active_station.set_online();
+
+    // Count the token rotations between two polls of the first address in the sequential scan.
+    let mut rotations = None;
+    loop {
+        let (_, t) = fdl_ut.wait_next_telegram(|t| match t {
+            fdl::Telegram::Token(_) => Some(None),
+            fdl::Telegram::Data(t) if t.is_fdl_status_request().is_some() => Some(Some(t.h.da)),
+            t => panic!("unexpected telegram {t:?}"),
+        });
+        match (t.unwrap(), rotations) {
+            (Some(8), None) => rotations = Some(0),
+            (Some(8), Some(_)) => break,
+            (None, Some(r)) => rotations = Some(r + 1),
+            _ => (),
+        }
+    }
+
+    // 9 GAP addresses, 1 priority address, 3 wait rotations
+    assert_eq!(fdl_ut.active_station.gap_scan_period(), 15);
+    assert_eq!(rotations, Some(15));
+}
+
+/// Application which sends one request to an (absent) station per cycle.
+struct Requester<'a> {
+    address: u8,
+    sent: bool,
+    log: &'a core::cell::RefCell<Vec<u8>>,
+}
+
+impl fdl::FdlApplication for Requester<'_> {
+    fn transmit_telegram(
+        &mut self,
+        _now: crate::time::Instant,
+        fdl: &fdl::FdlActiveStation,
+        tx: fdl::TelegramTx,
+        _high_prio_only: bool,
+    ) -> Option<fdl::TelegramTxResponse> {
+        if self.sent {
+            self.sent = false;
+            return None;
+        }
+        assert!(fdl.holds_token());
+        self.sent = true;
+        self.log.borrow_mut().push(self.address);
+        Some(tx.send_fdl_status_request(self.address, fdl.address()))
+    }
+
+    fn receive_reply(
+        &mut self,
+        _now: crate::time::Instant,
+        _fdl: &fdl::FdlActiveStation,
+        _addr: u8,
+        _telegram: fdl::Telegram,
+    ) {
+        panic!("Absent station replied");
+    }
+
+    fn handle_timeout(
+        &mut self,
+        _now: crate::time::Instant,
+        _fdl: &fdl::FdlActiveStation,
+        addr: u8,
+    ) {
+        assert_eq!(addr, self.address);
+    }
+}
+
+/// Run two applications with the given scheduling weights and return the order of their cycles.
+fn run_weighted_applications(weights: &[u8], token_rotation_bits: u32) -> Vec<u8> {
+    let log = core::cell::RefCell::new(Vec::new());
+    let mut app1 = Requester {
+        address: 20,
+        sent: false,
+        log: &log,
+    };
+    let mut app2 = Requester {
+        address: 30,
+        sent: false,
+        log: &log,
+    };
+
+    let baud = crate::Baudrate::B19200;
+    let mut phy = phy::SimulatorPhy::new(baud, "phy#ut");
+    let mut fdl = fdl::FdlActiveStation::new(
+        fdl::ParametersBuilder::new(7, baud)
+            .token_rotation_bits(token_rotation_bits)
+            .build(),
+    );
+    fdl.set_online();
+
+    let mut now = crate::time::Instant::ZERO;
+    while now < crate::time::Instant::ZERO + crate::time::Duration::from_secs(1) {
+        phy.set_bus_time(now);
+        fdl.poll_weighted(now, &mut phy, &mut [&mut app1, &mut app2], weights);
+        now += crate::time::Duration::from_micros(100);
+    }
+
+    log.into_inner()
+}
+
+#[test]
+fn active_station_holds_token() {
+    crate::test_utils::prepare_test_logger();
+    let log = core::cell::RefCell::new(Vec::new());
+    let mut app = Requester {
+        address: 20,
+        sent: false,
+        log: &log,
+    };
+
+    let baud = crate::Baudrate::B19200;
+    let mut phy = phy::SimulatorPhy::new(baud, "phy#ut");
+    let mut fdl = fdl::FdlActiveStation::new(fdl::ParametersBuilder::new(7, baud).build());
+    assert!(!fdl.holds_token());
+    fdl.set_online();
+
+    // The requester checks that the token is held whenever it gets to transmit.
+    let mut held = Vec::new();
+    let mut now = crate::time::Instant::ZERO;
+    while now < crate::time::Instant::ZERO + crate::time::Duration::from_millis(200) {
+        phy.set_bus_time(now);
+        fdl.poll(now, &mut phy, &mut app);
+        held.push(fdl.holds_token());
+        now += crate::time::Duration::from_micros(100);
+    }
+
+    assert!(!log.borrow().is_empty());
+    // The token is released while passing it on, even to ourselves.
+    assert!(held.contains(&true));
+    assert!(held.contains(&false));
+}
+
+/// Application which sends a burst of requests to an (absent) station in each cycle.
+struct BurstRequester<'a> {
+    burst: u8,
+    sent: u8,
+    count: &'a core::cell::Cell<u8>,
+}
+
+impl fdl::FdlApplication for BurstRequester<'_> {
+    fn transmit_telegram(
+        &mut self,
+        _now: crate::time::Instant,
+        fdl: &fdl::FdlActiveStation,
+        tx: fdl::TelegramTx,
+        _high_prio_only: bool,
+    ) -> Option<fdl::TelegramTxResponse> {
+        if self.sent == self.burst {
+            self.sent = 0;
+            return None;
+        }
+        self.sent += 1;
+        self.count.set(self.count.get() + 1);
+        Some(
+            tx.send_fdl_status_request(20, fdl.address())
+                .without_retries(),
+        )
+    }
+
+    fn receive_reply(
+        &mut self,
+        _now: crate::time::Instant,
+        _fdl: &fdl::FdlActiveStation,
+        _addr: u8,
+        _telegram: fdl::Telegram,
+    ) {
+        panic!("Absent station replied");
+    }
+
+    fn handle_timeout(
+        &mut self,
+        _now: crate::time::Instant,
+        _fdl: &fdl::FdlActiveStation,
+        _addr: u8,
+    ) {
+    }
+}
+
+/// Run a burst requester and return the number of requests sent in each token hold.
+fn run_burst_requester(token_rotation_bits: u32, min_message_cycles: u8) -> Vec<u8> {
+    let count = core::cell::Cell::new(0);
+    let mut app = BurstRequester {
+        burst: 5,
+        sent: 0,
+        count: &count,
+    };
+
+    let baud = crate::Baudrate::B19200;
+    let mut phy = phy::SimulatorPhy::new(baud, "phy#ut");
+    let mut fdl = fdl::FdlActiveStation::new(
+        fdl::ParametersBuilder::new(7, baud)
+            .token_rotation_bits(token_rotation_bits)
+            .min_message_cycles(min_message_cycles)
+            .build(),
+    );
+    fdl.set_online();
+
+    let mut holds = Vec::new();
+    let mut held = false;
+    let mut now = crate::time::Instant::ZERO;
+    while now < crate::time::Instant::ZERO + crate::time::Duration::from_secs(1) {
+        phy.set_bus_time(now);
+        fdl.poll(now, &mut phy, &mut app);
+        // The token is also released briefly for GAP polling, so the token hold is only
+        // considered complete once some requests were sent.
+        if held && !fdl.holds_token() && count.get() != 0 {
+            holds.push(count.replace(0));
+        }
+        held = fdl.holds_token();
+        now += crate::time::Duration::from_micros(100);
+    }
+
+    holds
+}
+
+#[test]
+fn active_station_min_message_cycles() {
+    crate::test_utils::prepare_test_logger();
+
+    // With enough token hold time, the whole burst is sent.
+    let holds = run_burst_requester(20000, 1);
+    assert!(holds.len() > 5);
+    assert!(holds.iter().skip(1).all(|c| *c == 5), "{holds:?}");
+
+    // When over time, only a single high priority message cycle is allowed by default.
+    let holds = run_burst_requester(256, 1);
+    assert!(holds.len() > 5);
+    assert!(holds.iter().skip(1).all(|c| *c == 1), "{holds:?}");
+
+    // The minimum number of message cycles is guaranteed even when over time.  The burst of
+    // five requests is thus split across two token holds.
+    let holds = run_burst_requester(256, 3);
+    assert!(holds.len() > 5);
+    assert!(holds.chunks_exact(2).all(|c| c == [3, 2]), "{holds:?}");
+}
+
+#[test]
+fn active_station_joined_applications() {
+    crate::test_utils::prepare_test_logger();
+    let log = core::cell::RefCell::new(Vec::new());
+    let mut app = fdl::join(
+        Requester {
+            address: 20,
+            sent: false,
+            log: &log,
+        },
+        Requester {
+            address: 30,
+            sent: false,
+            log: &log,
+        },
+    );
+
+    let baud = crate::Baudrate::B19200;
+    let mut phy = phy::SimulatorPhy::new(baud, "phy#ut");
+    let mut fdl = fdl::FdlActiveStation::new(
+        fdl::ParametersBuilder::new(7, baud)
+            .token_rotation_bits(20000)
+            .build(),
+    );
+    fdl.set_online();
+
+    let mut now = crate::time::Instant::ZERO;
+    while now < crate::time::Instant::ZERO + crate::time::Duration::from_secs(1) {
+        phy.set_bus_time(now);
+        fdl.poll(now, &mut phy, &mut app);
+        now += crate::time::Duration::from_micros(100);
+    }
+
+    // Both applications get a cycle in each token rotation and the requesters check that the
+    // timeouts are routed to them.
+    let log = log.borrow();
+    assert!(log.len() > 10);
+    assert!(log.chunks_exact(2).all(|c| c == [20, 30]), "{log:?}");
+}
+
+#[test]
+fn active_station_weighted_scheduling() {
+    crate::test_utils::prepare_test_logger();
+
+    let log = run_weighted_applications(&[], 20000);
+    assert!(log.len() > 10);
+    assert!(log.chunks_exact(2).all(|c| c == [20, 30]), "{log:?}");
+
+    let log = run_weighted_applications(&[2, 1], 20000);
+    assert!(log.len() > 10);
+    assert!(log.chunks_exact(3).all(|c| c == [20, 20, 30]), "{log:?}");
+}
+
+#[test]
+fn active_station_background_application() {
+    crate::test_utils::prepare_test_logger();
+
+    // With enough token hold time, the background application gets a cycle after the regular
+    // application.
+    let log = run_weighted_applications(&[1, 0], 20000);
+    assert!(log.len() > 10);
+    assert!(log.chunks_exact(2).all(|c| c == [20, 30]), "{log:?}");
+
+    // Without leftover token hold time, the background application is never serviced.
+    let log = run_weighted_applications(&[1, 0], 256);
+    assert!(log.len() > 10);
+    assert!(log.iter().all(|a| *a == 20), "{log:?}");
+}
+
+#[test]
+fn active_station_application_set_remove() {
+    crate::test_utils::prepare_test_logger();
+
+    let log = core::cell::RefCell::new(Vec::new());
+    let mut app1 = Requester {
+        address: 20,
+        sent: false,
+        log: &log,
+    };
+    let mut app2 = Requester {
+        address: 30,
+        sent: false,
+        log: &log,
+    };
+    let mut app3 = Requester {
+        address: 40,
+        sent: false,
+        log: &log,
+    };
+
+    let mut apps = fdl::ApplicationSet::new(Vec::new());
+    let handle1 = apps.add(&mut app1 as &mut dyn fdl::FdlApplication);
+    apps.add(&mut app2 as &mut dyn fdl::FdlApplication);
+
+    let baud = crate::Baudrate::B19200;
+    let mut phy = phy::SimulatorPhy::new(baud, "phy#ut");
+    let mut fdl = fdl::FdlActiveStation::new(fdl::ParametersBuilder::new(7, baud).build());
+    fdl.set_online();
+
+    let mut now = crate::time::Instant::ZERO;
+    while log.borrow().is_empty() {
+        assert!(now < crate::time::Instant::ZERO + crate::time::Duration::from_secs(1));
+        phy.set_bus_time(now);
+        fdl.poll_applications(now, &mut phy, &mut apps);
+        now += crate::time::Duration::from_micros(100);
+    }
+
+    // Replace the first application while it is waiting for a reply.  The new application reuses
+    // the storage slot but must not receive the timeout for the old request.  It takes over the
+    // turn of the removed application.
+    assert!(apps.remove(handle1).is_some());
+    apps.add(&mut app3 as &mut dyn fdl::FdlApplication);
+
+    while now < crate::time::Instant::ZERO + crate::time::Duration::from_secs(1) {
+        phy.set_bus_time(now);
+        fdl.poll_applications(now, &mut phy, &mut apps);
+        now += crate::time::Duration::from_micros(100);
+    }
+
+    let log = log.into_inner();
+    assert!(log.len() > 10);
+    assert_eq!(log[0], 20);
+    assert!(log[1..].chunks_exact(2).all(|c| c == [40, 30]), "{log:?}");
+}
+
+/// Test that the active station does not consider the token lost when the time jumps backwards.
+#[test]
+fn active_station_time_jumps_backwards() {
+    crate::test_utils::prepare_test_logger();
+
+    let baud = crate::Baudrate::B19200;
+    let mut phy = phy::SimulatorPhy::new(baud, "phy#ut");
+    let mut fdl = fdl::FdlActiveStation::new(fdl::ParametersBuilder::new(7, baud).build());
+    fdl.set_online();
+
+    let start = crate::time::Instant::ZERO + crate::time::Duration::from_secs(10);
+    phy.set_bus_time(start);
+    fdl.poll(start, &mut phy, &mut ());
+
+    // A time far in the past must not look like a long silence on the bus.
+    let past = crate::time::Instant::ZERO;
+    phy.set_bus_time(past);
+    fdl.poll(past, &mut phy, &mut ());
+    assert!(!fdl.is_in_ring());
+
+    // Once the token lost timeout really expired, the station claims the token.
+    let later = start + fdl.parameters().token_lost_timeout();
+    phy.set_bus_time(later);
+    fdl.poll(later, &mut phy, &mut ());
+    assert!(fdl.is_in_ring());
+}
+
+/// Test that the active station reports readiness and joining of the token ring.
+#[test]
+fn active_station_joined_ring() {
+    crate::test_utils::prepare_test_logger();
+    let mut fdl_ut = FdlActiveUnderTest::default();
+
+    assert!(!fdl_ut.active_station.is_ready());
+    assert!(!fdl_ut.active_station.take_joined_ring());
+
+    fdl_ut.prepare_two_station_ring();
+    fdl_ut.wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }));
+
+    assert!(fdl_ut.active_station.is_ready());
+    assert!(fdl_ut.active_station.is_in_ring());
+    assert!(fdl_ut.active_station.take_joined_ring());
+    // The notification is only reported once.
+    assert!(!fdl_ut.active_station.take_joined_ring());
+
+    fdl_ut.active_station.set_offline();
+    assert!(!fdl_ut.active_station.is_ready());
+    assert!(!fdl_ut.active_station.take_joined_ring());
+}
+
+/// Test that an active station can warm-start using a previously exported state.
+#[test]
+fn active_station_warm_start() {
+    crate::test_utils::prepare_test_logger_with_warnings(vec![
+        "Rejected warm start state (warm start state belongs to a different station address), doing a cold start.",
+    ]);
+    let mut fdl_ut = FdlActiveUnderTest::default();
+    assert_eq!(fdl_ut.active_station.export_state(), None);
+
+    fdl_ut.prepare_two_station_ring();
+    fdl_ut.wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }));
+
+    let state = fdl_ut.active_station.export_state().unwrap();
+    assert_eq!(state.this_station(), 7);
+    assert_eq!(state.next_station(), 15);
+    assert_eq!(state.previous_station(), 15);
+    assert_eq!(state.iter_active_stations().collect::<Vec<_>>(), &[7, 15]);
+
+    // The state can only be imported while offline.
+    assert_eq!(
+        fdl_ut.active_station.import_state(&state),
+        Err(fdl::WarmStartError::NotOffline)
+    );
+
+    // Store and load the state again, detecting corruption.
+    let mut bytes = *state.as_bytes();
+    let state = fdl::WarmStartState::from_bytes(&bytes).unwrap();
+    bytes[10] ^= 0x01;
+    assert_eq!(
+        fdl::WarmStartState::from_bytes(&bytes),
+        Err(fdl::WarmStartError::ChecksumMismatch)
+    );
+    assert_eq!(
+        fdl::WarmStartState::from_bytes(&bytes[..8]),
+        Err(fdl::WarmStartError::InvalidLength)
+    );
+
+    // A station with different parameters rejects the state and does a cold start.
+    let mut other_station = fdl::FdlActiveStation::new(
+        crate::fdl::ParametersBuilder::new(8, crate::Baudrate::B19200)
+            .highest_station_address(16)
+            .build(),
+    );
+    assert_eq!(
+        other_station.import_state(&state),
+        Err(fdl::WarmStartError::AddressMismatch)
+    );
+    assert!(!other_station.is_ready());
+
+    fdl_ut.active_station.set_offline();
+    fdl_ut.active_station.import_state(&state).unwrap();
+    fdl_ut.active_station.set_online();
+    assert!(fdl_ut.active_station.is_ready());
+
+    // Without listening to any token rotations, the station is ready to receive the token.
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| Some(tx.send_fdl_status_request(7, 15)));
+    fdl_ut.wait_transmission();
+
+    fdl_ut.assert_next_telegram(fdl::Telegram::Data(fdl::DataTelegram {
+        h: fdl::DataTelegramHeader {
+            da: 15,
+            sa: 7,
+            dsap: None,
+            ssap: None,
+            fc: fdl::FunctionCode::Response {
+                state: fdl::ResponseState::MasterWithoutToken,
+                status: fdl::ResponseStatus::Ok,
+            },
+        },
+        pdu: &[],
+    }));
+
+    fdl_ut.advance_bus_time_sync_pause();
+    fdl_ut.transmit_telegram(|tx| Some(tx.send_token_telegram(7, 15)));
+    fdl_ut.wait_transmission();
+
+    fdl_ut.wait_for_matching(|t| t == fdl::Telegram::Token(fdl::TokenTelegram { da: 15, sa: 7 }));
+    assert!(fdl_ut.active_station.take_joined_ring());
+}
+
+#[test]
+fn active_station_bus_idle() {
+    crate::test_utils::prepare_test_logger();
+    let mut fdl_ut = FdlActiveUnderTest::new(7);
+    fdl_ut.active_station = fdl::FdlActiveStation::new(
+        crate::fdl::ParametersBuilder::new(7, crate::Baudrate::B19200)
+            .highest_station_address(16)
+            .slot_bits(300)
+            .bus_idle_rotations(3)
+            .build(),
+    );
+    fdl_ut.active_station.set_online();
+
+    // Returns the time until the next token pass and the address polled in between, if any.
+    let next_token_interval = |fdl_ut: &mut FdlActiveUnderTest| {
+        let mut interval = crate::time::Duration::ZERO;
+        let mut polled = None;
+        loop {
+            let mut pdu = [0u8; 256];
+            let (time, t) = fdl_ut.wait_next_telegram(|t| Some(t.clone_with_pdu_buffer(&mut pdu)));
+            interval += time;
+            match t.unwrap() {
+                fdl::Telegram::Token(fdl::TokenTelegram { da: 7, sa: 7 }) => {
+                    return (interval, polled);
+                }
+                fdl::Telegram::Data(t) if t.is_fdl_status_request().is_some() => {
+                    polled = Some(t.h.da);
+                }
+                t => panic!("unexpected telegram {t:?}"),
+            }
+        }
+    };
+
+    // Claim the token and do the first rotations at full rate.
+    fdl_ut.wait_for_matching(|t| matches!(t, fdl::Telegram::Token(_)));
+    next_token_interval(&mut fdl_ut);
+    for _ in 0..3 {
+        assert!(!fdl_ut.active_station.is_bus_idle());
+        next_token_interval(&mut fdl_ut);
+    }
+    assert!(fdl_ut.active_station.is_bus_idle());
+
+    // While idle, the token is passed to ourselves much slower but still within the token lost
+    // timeout of any station.
+    let slot_time = fdl_ut.bits_to_time(300);
+    let mut idle_intervals = 0;
+    for _ in 0..30 {
+        let (interval, polled) = next_token_interval(&mut fdl_ut);
+        if polled.is_none() {
+            assert!(interval >= slot_time * 5);
+            assert!(interval < slot_time * 6);
+            idle_intervals += 1;
+        }
+    }
+    assert!(idle_intervals > 0);
+
+    // A station responding to the GAP poll ends bus idle mode.
+    fdl_ut.wait_for_matching(|t| {
+        matches!(t, fdl::Telegram::Data(t) if t.is_fdl_status_request().is_some() && t.h.da == 15)
+    });
+    fdl_ut.advance_bus_time_min_tsdr();
+    fdl_ut.transmit_telegram(|tx| {
+        Some(tx.send_fdl_status_response(7, 15, fdl::ResponseState::Slave, fdl::ResponseStatus::Ok))
+    });
+    next_token_interval(&mut fdl_ut);
+    assert!(!fdl_ut.active_station.is_bus_idle());
+    let (interval, _) = next_token_interval(&mut fdl_ut);
+    assert!(interval < slot_time * 5);
+}
@@ -1,3 +1,5 @@
+use bitvec::field::BitField;
+
 /// Status of the `LAS` (List of Active Stations)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum LasState {
@@ -50,6 +52,14 @@ pub struct TokenRing {
     /// There is always a `previous_station`.  When no other active stations are known, we are our
     /// own `previous_station`, so PS==TS.
     previous_station: crate::Address,
+
+    /// State last reported by each station which is not in the `LAS`.
+    ///
+    /// Two bits per address: `0` for unknown, otherwise the [`ResponseState`] plus one.  Stations
+    /// in the `LAS` are in the ring, so `MasterInRing` is never stored.
+    ///
+    /// [`ResponseState`]: crate::fdl::ResponseState
+    station_states: bitvec::BitArr!(for 2 * 128, in u8),
 }
 
 impl TokenRing {
@@ -64,7 +74,22 @@ impl TokenRing {
             this_station: param.address,
             next_station: param.address,
             previous_station: param.address,
+            station_states: bitvec::array::BitArray::ZERO,
+        }
+    }
+
+    /// Restore a previously verified `LAS`, e.g. when warm-starting the station.
+    pub fn restore(
+        param: &crate::fdl::Parameters,
+        active_stations: impl Iterator<Item = crate::Address>,
+    ) -> Self {
+        let mut token_ring = Self::new(param);
+        for address in active_stations {
+            token_ring.active_stations.set(usize::from(address), true);
         }
+        token_ring.las_state = LasState::Valid;
+        token_ring.update_next_previous();
+        token_ring
     }
 
     pub fn iter_active_stations(
@@ -91,6 +116,49 @@ impl TokenRing {
         self.previous_station
     }
 
+    /// State of the station at `address`, as far as it is known.
+    ///
+    /// Stations in the `LAS` are reported as `MasterInRing`.  Returns `None` for this station.
+    pub fn station_state(&self, address: crate::Address) -> Option<crate::fdl::ResponseState> {
+        let a = usize::from(address);
+        if address == self.this_station || a >= self.active_stations.len() {
+            None
+        } else if self.active_stations[a] {
+            Some(crate::fdl::ResponseState::MasterInRing)
+        } else {
+            let bits: u8 = self.station_states[a * 2..a * 2 + 2].load_le();
+            bits.checked_sub(1)
+                .and_then(crate::fdl::ResponseState::from_u8)
+        }
+    }
+
+    /// Record the state a station reported in a response.
+    ///
+    /// `MasterInRing` is not recorded, the station is entered into the `LAS` once it passes the
+    /// token.
+    pub fn record_station_state(
+        &mut self,
+        address: crate::Address,
+        state: crate::fdl::ResponseState,
+    ) {
+        let a = usize::from(address);
+        if let Some(bits) = self.station_states.get_mut(a * 2..a * 2 + 2) {
+            match state {
+                crate::fdl::ResponseState::MasterInRing => bits.store_le(0u8),
+                state => bits.store_le(state as u8 + 1),
+            }
+        }
+    }
+
+    /// Remove the stations in `range` from the `LAS`, forgetting their state.
+    fn clear_active_stations(&mut self, range: core::ops::Range<usize>) {
+        for a in self.active_stations[range.clone()].iter_ones() {
+            let a = a + range.start;
+            self.station_states[a * 2..a * 2 + 2].fill(false);
+        }
+        self.active_stations[range].fill(false);
+    }
+
     fn verify_las_from_token_pass(&mut self, sa: crate::Address, da: crate::Address) -> bool {
         // SA station must be active
         if !self.active_stations[usize::from(sa)] {
@@ -123,10 +191,10 @@ impl TokenRing {
     fn update_las_from_token_pass(&mut self, sa: crate::Address, da: crate::Address) {
         // Clear the GAP from this token pass as it does not contain any known active stations.
         if da > sa {
-            self.active_stations[usize::from(sa)..usize::from(da)].fill(false);
+            self.clear_active_stations(usize::from(sa)..usize::from(da));
         } else {
-            self.active_stations[usize::from(sa)..].fill(false);
-            self.active_stations[..usize::from(da)].fill(false);
+            self.clear_active_stations(usize::from(sa)..self.active_stations.len());
+            self.clear_active_stations(0..usize::from(da));
         }
 
         // At this point, we only know that the source address is alive so we only enter it into
@@ -225,7 +293,8 @@ impl TokenRing {
     }
 
     pub fn remove_station(&mut self, address: crate::Address) {
-        self.active_stations.set(usize::from(address), false);
+        let a = usize::from(address);
+        self.clear_active_stations(a..a + 1);
         self.update_next_previous();
     }
 }
@@ -263,6 +332,43 @@ mod tests {
         dbg!(&token_ring);
     }
 
+    #[test]
+    fn test_station_states() {
+        use crate::fdl::ResponseState;
+
+        let mut token_ring = TokenRing::new(&crate::fdl::Parameters {
+            address: 7,
+            ..Default::default()
+        });
+        token_ring.claim_token();
+
+        token_ring.record_station_state(10, ResponseState::Slave);
+        token_ring.record_station_state(20, ResponseState::MasterWithoutToken);
+        assert_eq!(token_ring.station_state(10), Some(ResponseState::Slave));
+        assert_eq!(
+            token_ring.station_state(20),
+            Some(ResponseState::MasterWithoutToken)
+        );
+        assert_eq!(token_ring.station_state(7), None);
+        assert_eq!(token_ring.station_state(30), None);
+
+        // Station 20 joins the ring
+        token_ring.witness_token_pass(20, 7);
+        assert_eq!(
+            token_ring.station_state(20),
+            Some(ResponseState::MasterInRing)
+        );
+
+        // Station 20 drops out of the ring again and its old state is forgotten
+        token_ring.witness_token_pass(15, 7);
+        assert_eq!(token_ring.station_state(20), None);
+        assert_eq!(
+            token_ring.station_state(15),
+            Some(ResponseState::MasterInRing)
+        );
+        assert_eq!(token_ring.station_state(10), Some(ResponseState::Slave));
+    }
+
     #[test]
     fn test_las_initialization_bad_verification() {
         let mut token_ring = TokenRing::new(&crate::fdl::Parameters {
@@ -0,0 +1,410 @@
+//! Raw data telegrams on a service access point (SAP)
+//!
+//! The [`RawSapApplication`] allows running protocols other than PROFIBUS-DP (e.g. MPI, FMS, or
+//! programming channels) on top of the FDL layer.  It does not know anything about the payload
+//! semantics: Requests are sent as-is and replies are handed to a callback.
+//!
+//! To run it alongside a DP master, combine both applications using [`join()`][`crate::fdl::join`].
+
+/// Maximum length of the data in a single request.
+///
+/// This is the largest PDU of a data telegram which carries both a destination and a source SAP.
+pub const MAX_DATA_LEN: usize = 244;
+
+/// FDL service used for a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Service {
+    /// Send Data with Acknowledge
+    Sda,
+    /// Send Data with No acknowledge
+    Sdn,
+    /// Send and Request Data
+    Srd,
+}
+
+/// Outcome of a request, passed to the callback of the [`RawSapApplication`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RawSapEvent<'a> {
+    /// The remote station replied with data.
+    Reply {
+        address: crate::Address,
+        /// SAP of the remote station the reply was sent from
+        sap: Option<u8>,
+        data: &'a [u8],
+    },
+    /// The remote station acknowledged the request without sending data.
+    Acknowledged { address: crate::Address },
+    /// The remote station rejected the request.
+    Rejected {
+        address: crate::Address,
+        status: crate::fdl::ResponseStatus,
+    },
+    /// The remote station did not answer, even after all retries.
+    Timeout { address: crate::Address },
+}
+
+/// Reason why a request could not be queued
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TunnelError {
+    /// A previous request is still waiting to be sent or answered.
+    Busy,
+    /// The data does not fit into a single telegram, see [`MAX_DATA_LEN`].
+    TooLong,
+    /// The destination address is not a valid station address.
+    InvalidAddress,
+}
+
+impl core::fmt::Display for TunnelError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TunnelError::Busy => write!(f, "a previous request is still pending"),
+            TunnelError::TooLong => write!(f, "request data exceeds {MAX_DATA_LEN} bytes"),
+            TunnelError::InvalidAddress => write!(f, "invalid destination address"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TunnelError {}
+
+#[derive(Debug, Clone)]
+struct Request {
+    address: crate::Address,
+    dsap: Option<u8>,
+    service: Service,
+    priority: crate::fdl::Priority,
+    data: [u8; MAX_DATA_LEN],
+    len: usize,
+}
+
+/// FDL application sending raw data telegrams from a local SAP
+///
+/// Requests are queued using [`send()`][`Self::send`] and transmitted when this station holds
+/// the token.  Low priority requests are deferred while the token hold time is used up.  The
+/// outcome of each request is passed to the `callback` as a [`RawSapEvent`].
+///
+/// Only one request can be pending at a time.  The frame count bit is maintained for the station
+/// addressed last and restarts whenever a different station is addressed.
+///
+/// This application only acts as a requester.  Requests from other stations to the local SAP are
+/// not handled.
+///
+/// # Example
+/// ```no_run
+/// # use profirust::{fdl, Baudrate};
+/// # let mut phy = profirust::phy::SimulatorPhy::new(Baudrate::B19200, "phy");
+/// # let mut fdl = fdl::FdlActiveStation::new(fdl::ParametersBuilder::new(2, Baudrate::B19200).build());
+/// use fdl::tunnel::{RawSapApplication, RawSapEvent, Service};
+///
+/// let mut tunnel = RawSapApplication::new(0x32, |_now, event: RawSapEvent| {
+///     println!("{event:?}");
+/// });
+/// tunnel
+///     .send(3, Some(0x32), Service::Srd, fdl::Priority::Low, &[0x01, 0x02])
+///     .unwrap();
+///
+/// loop {
+///     let now = profirust::time::Instant::now();
+///     fdl.poll(now, &mut phy, &mut tunnel);
+/// }
+/// ```
+pub struct RawSapApplication<F> {
+    sap: u8,
+    callback: F,
+    request: Option<Request>,
+    fcb: (crate::Address, crate::fdl::FrameCountBit),
+}
+
+impl<F> RawSapApplication<F>
+where
+    F: FnMut(crate::time::Instant, RawSapEvent<'_>),
+{
+    /// Create an application sending from the local `sap`.
+    pub fn new(sap: u8, callback: F) -> Self {
+        Self {
+            sap,
+            callback,
+            request: None,
+            fcb: (0, crate::fdl::FrameCountBit::First),
+        }
+    }
+
+    /// The local SAP this application sends from.
+    #[inline]
+    pub fn sap(&self) -> u8 {
+        self.sap
+    }
+
+    /// Whether a request is still waiting to be sent or answered.
+    #[inline]
+    pub fn is_busy(&self) -> bool {
+        self.request.is_some()
+    }
+
+    /// Queue a request to the SAP `dsap` of the station at `address`.
+    ///
+    /// A `dsap` of `None` addresses the default SAP of the remote station.
+    pub fn send(
+        &mut self,
+        address: crate::Address,
+        dsap: Option<u8>,
+        service: Service,
+        priority: crate::fdl::Priority,
+        data: &[u8],
+    ) -> Result<(), TunnelError> {
+        if self.request.is_some() {
+            return Err(TunnelError::Busy);
+        }
+        if data.len() > MAX_DATA_LEN {
+            return Err(TunnelError::TooLong);
+        }
+        if address > 126 {
+            return Err(TunnelError::InvalidAddress);
+        }
+
+        let mut request = Request {
+            address,
+            dsap,
+            service,
+            priority,
+            data: [0u8; MAX_DATA_LEN],
+            len: data.len(),
+        };
+        request.data[..data.len()].copy_from_slice(data);
+        self.request = Some(request);
+        Ok(())
+    }
+
+    /// Drop the pending request, if any.
+    pub fn cancel(&mut self) {
+        self.request = None;
+    }
+
+    /// Take the pending request addressed to `addr` once it was answered or timed out.
+    fn take_request(&mut self, addr: u8) -> Option<Request> {
+        match self.request.take() {
+            Some(request) if request.address == addr => Some(request),
+            request => {
+                self.request = request;
+                log::warn!("Dropping reply from #{addr} which the SAP tunnel did not request");
+                None
+            }
+        }
+    }
+}
+
+impl<F> crate::fdl::FdlApplication for RawSapApplication<F>
+where
+    F: FnMut(crate::time::Instant, RawSapEvent<'_>),
+{
+    fn transmit_telegram(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &crate::fdl::FdlActiveStation,
+        tx: crate::fdl::TelegramTx,
+        high_prio_only: bool,
+    ) -> Option<crate::fdl::TelegramTxResponse> {
+        let request = self.request.as_ref()?;
+        if high_prio_only && request.priority == crate::fdl::Priority::Low {
+            return None;
+        }
+
+        if self.fcb.0 != request.address {
+            self.fcb = (request.address, crate::fdl::FrameCountBit::First);
+        }
+        let (req, fcb) = match request.service {
+            Service::Sda => (crate::fdl::RequestType::SdaLow, self.fcb.1),
+            Service::Sdn => (
+                crate::fdl::RequestType::SdnLow,
+                crate::fdl::FrameCountBit::Inactive,
+            ),
+            Service::Srd => (crate::fdl::RequestType::SrdLow, self.fcb.1),
+        };
+
+        let data = &request.data[..request.len];
+        let res = tx.send_data_telegram_with_priority(
+            crate::fdl::DataTelegramHeader {
+                da: request.address,
+                sa: fdl.address(),
+                dsap: request.dsap,
+                ssap: Some(self.sap),
+                fc: crate::fdl::FunctionCode::Request { fcb, req },
+            },
+            request.priority,
+            data.len(),
+            |buf| buf.copy_from_slice(data),
+        )?;
+
+        if request.service == Service::Sdn {
+            // No reply will follow, the request is done.
+            self.request = None;
+        }
+        Some(res)
+    }
+
+    fn receive_reply(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &crate::fdl::FdlActiveStation,
+        addr: u8,
+        telegram: crate::fdl::Telegram,
+    ) {
+        if self.take_request(addr).is_none() {
+            return;
+        }
+        self.fcb.1.cycle();
+
+        let event = match telegram {
+            crate::fdl::Telegram::ShortConfirmation(_) => {
+                RawSapEvent::Acknowledged { address: addr }
+            }
+            crate::fdl::Telegram::Data(crate::fdl::DataTelegram {
+                h:
+                    crate::fdl::DataTelegramHeader {
+                        ssap,
+                        fc: crate::fdl::FunctionCode::Response { status, .. },
+                        ..
+                    },
+                pdu,
+            }) => match status {
+                crate::fdl::ResponseStatus::Ok
+                | crate::fdl::ResponseStatus::DataLow
+                | crate::fdl::ResponseStatus::DataHigh => {
+                    if pdu.is_empty() {
+                        RawSapEvent::Acknowledged { address: addr }
+                    } else {
+                        RawSapEvent::Reply {
+                            address: addr,
+                            sap: ssap,
+                            data: pdu,
+                        }
+                    }
+                }
+                status => RawSapEvent::Rejected {
+                    address: addr,
+                    status,
+                },
+            },
+            telegram => {
+                log::warn!("Unexpected reply from #{addr} to SAP tunnel: {telegram:?}");
+                return;
+            }
+        };
+        (self.callback)(now, event);
+    }
+
+    fn handle_timeout(
+        &mut self,
+        now: crate::time::Instant,
+        fdl: &crate::fdl::FdlActiveStation,
+        addr: u8,
+    ) {
+        if self.take_request(addr).is_none() {
+            return;
+        }
+        // The remote station may have restarted, so begin counting frames anew.
+        self.fcb.1.reset();
+        (self.callback)(now, RawSapEvent::Timeout { address: addr });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_sap_tunnel() {
+        crate::test_utils::prepare_test_logger_with_warnings(vec!["Resending a telegram to #8..."]);
+        let mut phy = crate::phy::DryRunPhy::new(
+            |_now, telegram: &crate::fdl::Telegram, tx: crate::fdl::TelegramTx| match telegram {
+                crate::fdl::Telegram::Data(t) if t.h.da == 7 && t.h.dsap == Some(0x32) => {
+                    assert_eq!(t.h.ssap, Some(0x30));
+                    assert_eq!(t.pdu, &[0x01, 0x02]);
+                    Some(tx.send_data_telegram(
+                        crate::fdl::DataTelegramHeader {
+                            da: t.h.sa,
+                            sa: 7,
+                            dsap: t.h.ssap,
+                            ssap: t.h.dsap,
+                            fc: crate::fdl::FunctionCode::Response {
+                                state: crate::fdl::ResponseState::Slave,
+                                status: crate::fdl::ResponseStatus::DataLow,
+                            },
+                        },
+                        3,
+                        |buf| buf.copy_from_slice(&[0xaa, 0xbb, 0xcc]),
+                    ))
+                }
+                _ => None,
+            },
+        );
+        let mut fdl = crate::fdl::FdlActiveStation::new(
+            crate::fdl::ParametersBuilder::new(2, crate::Baudrate::B19200)
+                .highest_station_address(16)
+                .build(),
+        );
+
+        let events = std::cell::RefCell::new(Vec::new());
+        let mut tunnel = RawSapApplication::new(0x30, |_now, event: RawSapEvent| {
+            events.borrow_mut().push(match event {
+                RawSapEvent::Reply { address, sap, data } => {
+                    assert_eq!(sap, Some(0x32));
+                    (address, Some(data.to_vec()))
+                }
+                RawSapEvent::Timeout { address } => (address, None),
+                e => panic!("unexpected event {e:?}"),
+            })
+        });
+        fdl.set_online();
+
+        let mut now = crate::time::Instant::ZERO;
+        tunnel
+            .send(
+                7,
+                Some(0x32),
+                Service::Srd,
+                crate::fdl::Priority::High,
+                &[0x01, 0x02],
+            )
+            .unwrap();
+        assert_eq!(
+            tunnel.send(8, None, Service::Srd, crate::fdl::Priority::Low, &[]),
+            Err(TunnelError::Busy)
+        );
+        while tunnel.is_busy() {
+            fdl.poll(now, &mut phy, &mut tunnel);
+            now += crate::time::Duration::from_micros(100);
+            assert!(now < crate::time::Instant::ZERO + crate::time::Duration::from_secs(2));
+        }
+
+        tunnel
+            .send(
+                8,
+                Some(0x32),
+                Service::Sda,
+                crate::fdl::Priority::Low,
+                &[0x03],
+            )
+            .unwrap();
+        while tunnel.is_busy() {
+            fdl.poll(now, &mut phy, &mut tunnel);
+            now += crate::time::Duration::from_micros(100);
+            assert!(now < crate::time::Instant::ZERO + crate::time::Duration::from_secs(2));
+        }
+        assert_eq!(
+            *events.borrow(),
+            &[(7, Some(vec![0xaa, 0xbb, 0xcc])), (8, None)]
+        );
+        assert_eq!(
+            phy.transmitted_telegrams()
+                .filter_map(|(_, t)| match t {
+                    crate::fdl::Telegram::Data(t) if t.h.ssap == Some(0x30) => Some(t.h.fc),
+                    _ => None,
+                })
+                .next(),
+            Some(crate::fdl::FunctionCode::new_srd_high(
+                crate::fdl::FrameCountBit::First
+            ))
+        );
+    }
+}
@@ -0,0 +1,170 @@
+/// Version of the serialized [`WarmStartState`] layout
+const VERSION: u8 = 1;
+
+const OFFSET_VERSION: usize = 0;
+const OFFSET_THIS_STATION: usize = 1;
+const OFFSET_NEXT_STATION: usize = 2;
+const OFFSET_PREVIOUS_STATION: usize = 3;
+const OFFSET_BAUDRATE: usize = 4;
+const OFFSET_LAS: usize = 8;
+const OFFSET_CHECKSUM: usize = 24;
+
+/// Token ring knowledge of an FDL active station for a fast restart
+///
+/// Exported using [`FdlActiveStation::export_state()`][`crate::fdl::FdlActiveStation::export_state`]
+/// and imported again using
+/// [`FdlActiveStation::import_state()`][`crate::fdl::FdlActiveStation::import_state`].  It
+/// contains the list of active stations (LAS) along with the next and previous station.  The
+/// station can then join the token ring right away instead of listening to two full token
+/// rotations first.
+///
+/// The state can be stored in non-volatile memory using [`as_bytes()`][`Self::as_bytes`].  The
+/// serialized form is protected by a checksum that is verified in
+/// [`from_bytes()`][`Self::from_bytes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarmStartState {
+    bytes: [u8; WarmStartState::SIZE],
+}
+
+impl WarmStartState {
+    /// Size of the serialized state in bytes.
+    pub const SIZE: usize = 26;
+
+    pub(crate) fn new(param: &crate::fdl::Parameters, token_ring: &crate::fdl::TokenRing) -> Self {
+        let mut bytes = [0u8; Self::SIZE];
+        bytes[OFFSET_VERSION] = VERSION;
+        bytes[OFFSET_THIS_STATION] = token_ring.this_station();
+        bytes[OFFSET_NEXT_STATION] = token_ring.next_station();
+        bytes[OFFSET_PREVIOUS_STATION] = token_ring.previous_station();
+        bytes[OFFSET_BAUDRATE..OFFSET_LAS]
+            .copy_from_slice(&(param.baudrate.to_rate() as u32).to_le_bytes());
+        for address in token_ring.iter_active_stations() {
+            bytes[OFFSET_LAS + usize::from(address / 8)] |= 1 << (address % 8);
+        }
+        let checksum = fletcher16(&bytes[..OFFSET_CHECKSUM]);
+        bytes[OFFSET_CHECKSUM..].copy_from_slice(&checksum.to_le_bytes());
+        Self { bytes }
+    }
+
+    /// Load a state from its serialized form, verifying the checksum.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WarmStartError> {
+        let bytes: [u8; Self::SIZE] = bytes
+            .try_into()
+            .map_err(|_| WarmStartError::InvalidLength)?;
+        let checksum = u16::from_le_bytes([bytes[OFFSET_CHECKSUM], bytes[OFFSET_CHECKSUM + 1]]);
+        if checksum != fletcher16(&bytes[..OFFSET_CHECKSUM]) {
+            return Err(WarmStartError::ChecksumMismatch);
+        }
+        if bytes[OFFSET_VERSION] != VERSION {
+            return Err(WarmStartError::UnsupportedVersion);
+        }
+        Ok(Self { bytes })
+    }
+
+    /// Serialized form of this state, for storing it.
+    pub fn as_bytes(&self) -> &[u8; Self::SIZE] {
+        &self.bytes
+    }
+
+    /// Address of the station which exported this state.
+    pub fn this_station(&self) -> crate::Address {
+        self.bytes[OFFSET_THIS_STATION]
+    }
+
+    /// Station the token was passed on to.
+    pub fn next_station(&self) -> crate::Address {
+        self.bytes[OFFSET_NEXT_STATION]
+    }
+
+    /// Station the token was received from.
+    pub fn previous_station(&self) -> crate::Address {
+        self.bytes[OFFSET_PREVIOUS_STATION]
+    }
+
+    /// Iterate over the addresses of all stations in the list of active stations.
+    pub fn iter_active_stations(&self) -> impl Iterator<Item = crate::Address> + '_ {
+        (0..128u8).filter(|a| self.bytes[OFFSET_LAS + usize::from(a / 8)] & (1 << (a % 8)) != 0)
+    }
+
+    /// Check this state against the parameters of a station and rebuild its token ring.
+    pub(crate) fn restore_token_ring(
+        &self,
+        param: &crate::fdl::Parameters,
+    ) -> Result<crate::fdl::TokenRing, WarmStartError> {
+        if self.this_station() != param.address {
+            return Err(WarmStartError::AddressMismatch);
+        }
+        let rate = u32::from_le_bytes(self.bytes[OFFSET_BAUDRATE..OFFSET_LAS].try_into().unwrap());
+        if u64::from(rate) != param.baudrate.to_rate() {
+            return Err(WarmStartError::BaudrateMismatch);
+        }
+        if self.iter_active_stations().any(|a| a > 125) {
+            return Err(WarmStartError::InconsistentRing);
+        }
+
+        let token_ring = crate::fdl::TokenRing::restore(param, self.iter_active_stations());
+        if !self.iter_active_stations().any(|a| a == param.address)
+            || token_ring.next_station() != self.next_station()
+            || token_ring.previous_station() != self.previous_station()
+        {
+            return Err(WarmStartError::InconsistentRing);
+        }
+        Ok(token_ring)
+    }
+}
+
+/// Fletcher-16 checksum
+fn fletcher16(data: &[u8]) -> u16 {
+    let (mut sum1, mut sum2) = (0u16, 0u16);
+    for b in data {
+        sum1 = (sum1 + u16::from(*b)) % 255;
+        sum2 = (sum2 + sum1) % 255;
+    }
+    (sum2 << 8) | sum1
+}
+
+/// Reason why a [`WarmStartState`] was rejected
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum WarmStartError {
+    /// The serialized state does not have the expected length.
+    InvalidLength,
+    /// The checksum of the serialized state does not match its contents.
+    ChecksumMismatch,
+    /// The state was serialized using an unsupported layout version.
+    UnsupportedVersion,
+    /// The state was exported by a station with a different address.
+    AddressMismatch,
+    /// The state was exported by a station using a different baudrate.
+    BaudrateMismatch,
+    /// The list of active stations does not match the next and previous station.
+    InconsistentRing,
+    /// The state can only be imported while the station is offline.
+    NotOffline,
+}
+
+impl core::fmt::Display for WarmStartError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            WarmStartError::InvalidLength => write!(f, "warm start state has an invalid length"),
+            WarmStartError::ChecksumMismatch => write!(f, "warm start state checksum mismatch"),
+            WarmStartError::UnsupportedVersion => {
+                write!(f, "warm start state has an unsupported version")
+            }
+            WarmStartError::AddressMismatch => {
+                write!(f, "warm start state belongs to a different station address")
+            }
+            WarmStartError::BaudrateMismatch => {
+                write!(f, "warm start state was recorded at a different baudrate")
+            }
+            WarmStartError::InconsistentRing => {
+                write!(f, "warm start state contains an inconsistent token ring")
+            }
+            WarmStartError::NotOffline => {
+                write!(f, "warm start state can only be imported while offline")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for WarmStartError {}
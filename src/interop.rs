@@ -0,0 +1,231 @@
+//! Interoperability with other crates of the `profirust` project
+//!
+//! This module is available with the `gsd` feature.  It allows checking [`PeripheralOptions`]
+//! against the GSD file of a peripheral, as parsed by [`gsd_parser`].  This catches mistakes like
+//! copying the wrong configuration bytes during setup instead of finding out at runtime through a
+//! configuration fault.
+//!
+//! # Example
+//! ```no_run
+//! use profirust::dp;
+//!
+//! let gsd = gsd_parser::parse_from_file("wago0b69.gsd").unwrap();
+//! let options = dp::PeripheralOptions {
+//!     ident_number: 0x0b69,
+//!     config: Some(&[0x22, 0x13]),
+//!     ..Default::default()
+//! };
+//! options.validate_against(&gsd).unwrap();
+//! ```
+use crate::dp::PeripheralOptions;
+use gsd_parser::GenericStationDescription;
+
+/// Mismatch between [`PeripheralOptions`] and a GSD file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigMismatch {
+    /// The options do not contain any configuration.
+    NoConfig,
+    /// The ident number does not match the one from the GSD file.
+    IdentNumber { expected: u16, got: u16 },
+    /// The configuration bytes starting at `offset` do not match any of the available modules.
+    UnknownModule { offset: usize },
+    /// The configuration consists of more modules than the peripheral supports.
+    TooManyModules { count: usize, max: usize },
+    /// The total input length exceeds `Max_Input_Len`.
+    InputsTooLong { length: usize, max: usize },
+    /// The total output length exceeds `Max_Output_Len`.
+    OutputsTooLong { length: usize, max: usize },
+    /// The total I/O length exceeds `Max_Data_Len`.
+    DataTooLong { length: usize, max: usize },
+}
+
+impl core::fmt::Display for ConfigMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ConfigMismatch::NoConfig => write!(f, "no configuration given"),
+            ConfigMismatch::IdentNumber { expected, got } => write!(
+                f,
+                "ident number 0x{got:04x} does not match 0x{expected:04x} from the GSD file"
+            ),
+            ConfigMismatch::UnknownModule { offset } => write!(
+                f,
+                "configuration bytes at offset {offset} do not match any available module"
+            ),
+            ConfigMismatch::TooManyModules { count, max } => write!(
+                f,
+                "configuration contains {count} modules but at most {max} are supported"
+            ),
+            ConfigMismatch::InputsTooLong { length, max } => {
+                write!(f, "{length} input bytes exceed the maximum of {max} bytes")
+            }
+            ConfigMismatch::OutputsTooLong { length, max } => {
+                write!(f, "{length} output bytes exceed the maximum of {max} bytes")
+            }
+            ConfigMismatch::DataTooLong { length, max } => {
+                write!(f, "{length} I/O bytes exceed the maximum of {max} bytes")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigMismatch {}
+
+impl PeripheralOptions<'_> {
+    /// Check the ident number and configuration against the GSD file of the peripheral.
+    ///
+    /// The configuration must be a concatenation of the configuration bytes of the available
+    /// modules and the resulting I/O lengths must not exceed the limits from the GSD file.
+    pub fn validate_against(&self, gsd: &GenericStationDescription) -> Result<(), ConfigMismatch> {
+        if self.ident_number != gsd.ident_number {
+            return Err(ConfigMismatch::IdentNumber {
+                expected: gsd.ident_number,
+                got: self.ident_number,
+            });
+        }
+
+        let config = self.config.ok_or(ConfigMismatch::NoConfig)?;
+
+        // Find the smallest number of modules which make up the configuration.
+        // `module_count[i]` is the number of modules needed to reach offset `i`.
+        let mut module_count: Vec<Option<usize>> = vec![None; config.len() + 1];
+        module_count[0] = Some(0);
+        let mut furthest = 0;
+        for offset in 0..config.len() {
+            let Some(count) = module_count[offset] else {
+                continue;
+            };
+            furthest = offset;
+            for module in gsd.available_modules.iter() {
+                let end = offset + module.config.len();
+                if !module.config.is_empty()
+                    && config.get(offset..end) == Some(&module.config[..])
+                    && module_count[end].map(|c| count + 1 < c).unwrap_or(true)
+                {
+                    module_count[end] = Some(count + 1);
+                }
+            }
+        }
+        let count =
+            module_count[config.len()].ok_or(ConfigMismatch::UnknownModule { offset: furthest })?;
+        let max_modules = usize::from(gsd.max_modules);
+        if count > max_modules {
+            return Err(ConfigMismatch::TooManyModules {
+                count,
+                max: max_modules,
+            });
+        }
+
+        let (inputs, outputs) = self
+            .io_lengths()
+            .ok_or(ConfigMismatch::UnknownModule { offset: furthest })?;
+        let max_inputs = usize::from(gsd.max_input_length);
+        if inputs > max_inputs {
+            return Err(ConfigMismatch::InputsTooLong {
+                length: inputs,
+                max: max_inputs,
+            });
+        }
+        let max_outputs = usize::from(gsd.max_output_length);
+        if outputs > max_outputs {
+            return Err(ConfigMismatch::OutputsTooLong {
+                length: outputs,
+                max: max_outputs,
+            });
+        }
+        let max_data = usize::from(gsd.max_data_length);
+        if inputs + outputs > max_data {
+            return Err(ConfigMismatch::DataTooLong {
+                length: inputs + outputs,
+                max: max_data,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn gsd() -> GenericStationDescription {
+        let module = |name: &str, config: &[u8]| {
+            Arc::new(gsd_parser::Module {
+                name: name.to_owned(),
+                config: config.to_vec(),
+                ..Default::default()
+            })
+        };
+        GenericStationDescription {
+            ident_number: 0x1337,
+            modular_station: true,
+            max_modules: 3,
+            max_input_length: 8,
+            max_output_length: 4,
+            max_data_length: 10,
+            available_modules: vec![
+                module("4 DI", &[0x10]),
+                module("8 DO", &[0x20]),
+                module("32 DO", &[0x23]),
+                module("64 DI", &[0x80, 0x07]),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn validate_config() {
+        let gsd = gsd();
+        let options = |config| PeripheralOptions {
+            ident_number: 0x1337,
+            config: Some(config),
+            ..Default::default()
+        };
+
+        assert_eq!(options(&[0x10, 0x20, 0x20]).validate_against(&gsd), Ok(()));
+        assert_eq!(options(&[0x20, 0x80, 0x07]).validate_against(&gsd), Ok(()));
+        assert_eq!(
+            PeripheralOptions::default().validate_against(&gsd),
+            Err(ConfigMismatch::IdentNumber {
+                expected: 0x1337,
+                got: 0
+            })
+        );
+        assert_eq!(
+            PeripheralOptions {
+                ident_number: 0x1337,
+                ..Default::default()
+            }
+            .validate_against(&gsd),
+            Err(ConfigMismatch::NoConfig)
+        );
+        assert_eq!(
+            options(&[0x10, 0x20, 0x13]).validate_against(&gsd),
+            Err(ConfigMismatch::UnknownModule { offset: 2 })
+        );
+        assert_eq!(
+            options(&[0x10, 0x80]).validate_against(&gsd),
+            Err(ConfigMismatch::UnknownModule { offset: 1 })
+        );
+        assert_eq!(
+            options(&[0x10, 0x10, 0x10, 0x10]).validate_against(&gsd),
+            Err(ConfigMismatch::TooManyModules { count: 4, max: 3 })
+        );
+        assert_eq!(
+            options(&[0x10, 0x80, 0x07]).validate_against(&gsd),
+            Err(ConfigMismatch::InputsTooLong { length: 9, max: 8 })
+        );
+        assert_eq!(
+            options(&[0x23, 0x20]).validate_against(&gsd),
+            Err(ConfigMismatch::OutputsTooLong { length: 5, max: 4 })
+        );
+        assert_eq!(
+            options(&[0x80, 0x07, 0x23]).validate_against(&gsd),
+            Err(ConfigMismatch::DataTooLong {
+                length: 12,
+                max: 10
+            })
+        );
+    }
+}
@@ -7,6 +7,8 @@
 //!   token passing between multiple master stations.
 //! - The [`dp`] module implements the PROFIBUS-DP (Decentralized Peripherals) application layer.
 //!   This is where peripherals are managed and cyclic data exchange is facilitated.
+//! - The `trace` module (with the `trace` feature) can store bus traces as pcapng captures.
+//! - The `interop` module (with the `gsd` feature) checks peripheral settings against GSD files.
 //!
 //! # Example
 //! To successfully communicate with a peripheral, you need to initialize and parameterize all
@@ -45,6 +47,7 @@
 //!     fdl::ParametersBuilder::new(master_address, Baudrate::B19200)
 //!         .slot_bits(300)
 //!         .build_verified(&dp_master)
+//!         .unwrap()
 //! );
 //!
 //! // Initialize the PHY layer:
@@ -82,8 +85,12 @@
 mod consts;
 pub mod dp;
 pub mod fdl;
+#[cfg(feature = "gsd")]
+pub mod interop;
 pub mod phy;
 pub mod time;
+#[cfg(feature = "trace")]
+pub mod trace;
 
 #[cfg(all(test, feature = "std"))]
 pub mod test_utils;
@@ -93,7 +100,8 @@ pub mod test_utils;
 /// - PROFIBUS DP networks can run at any of the available baudrates given that all stations
 ///   support the selected speed.
 /// - PROFIBUS PA networks must use `B31250` (31.25 kbit/s).
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Baudrate {
     /// 9.6 kbit/s
@@ -138,6 +146,26 @@ impl Baudrate {
         }
     }
 
+    /// Find the baudrate for a numeric value in bit/s.
+    ///
+    /// Returns `None` when the rate is not one of the baudrates supported by PROFIBUS.
+    pub fn from_rate(rate: u64) -> Option<Self> {
+        match rate {
+            9600 => Some(Baudrate::B9600),
+            19200 => Some(Baudrate::B19200),
+            31250 => Some(Baudrate::B31250),
+            45450 => Some(Baudrate::B45450),
+            93750 => Some(Baudrate::B93750),
+            187500 => Some(Baudrate::B187500),
+            500000 => Some(Baudrate::B500000),
+            1500000 => Some(Baudrate::B1500000),
+            3000000 => Some(Baudrate::B3000000),
+            6000000 => Some(Baudrate::B6000000),
+            12000000 => Some(Baudrate::B12000000),
+            _ => None,
+        }
+    }
+
     /// At this baudrate, return how long a given number of bits take to transmit.
     pub fn bits_to_time(self, bits: u32) -> crate::time::Duration {
         crate::time::Duration::from_micros(u64::from(bits) * 1000000 / self.to_rate())
@@ -149,6 +177,69 @@ impl Baudrate {
     }
 }
 
+/// Error when parsing a [`Baudrate`] from a string
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseBaudrateError(());
+
+impl core::fmt::Display for ParseBaudrateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid or unsupported baudrate")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseBaudrateError {}
+
+impl core::str::FromStr for Baudrate {
+    type Err = ParseBaudrateError;
+
+    /// Parse a baudrate from a string.
+    ///
+    /// Both the plain rate in bit/s (`"19200"`) and a shorthand using a `k` or `M` suffix
+    /// (`"19.2k"`, `"1.5M"`) are accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (number, multiplier) = if let Some(number) = s.strip_suffix('k') {
+            (number, 1000)
+        } else if let Some(number) = s.strip_suffix('M') {
+            (number, 1000000)
+        } else {
+            (s, 1)
+        };
+
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if integer.is_empty() || !integer.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBaudrateError(()));
+        }
+        if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBaudrateError(()));
+        }
+
+        let integer: u64 = integer.parse().map_err(|_| ParseBaudrateError(()))?;
+        let mut rate = integer
+            .checked_mul(multiplier)
+            .ok_or(ParseBaudrateError(()))?;
+
+        if !fraction.is_empty() {
+            let divisor = u32::try_from(fraction.len())
+                .ok()
+                .and_then(|digits| 10u64.checked_pow(digits))
+                .ok_or(ParseBaudrateError(()))?;
+            let fraction: u64 = fraction.parse().map_err(|_| ParseBaudrateError(()))?;
+            let fraction = fraction
+                .checked_mul(multiplier)
+                .ok_or(ParseBaudrateError(()))?;
+            if fraction % divisor != 0 {
+                // Fractional bits per second are never valid.
+                return Err(ParseBaudrateError(()));
+            }
+            rate += fraction / divisor;
+        }
+
+        Baudrate::from_rate(rate).ok_or(ParseBaudrateError(()))
+    }
+}
+
 pub type Address = u8;
 
 #[inline(always)]
@@ -163,20 +254,72 @@ pub(crate) fn debug_assert_address(addr: Address) {
 #[cfg(test)]
 mod tests {
     #[test]
-    fn baudrate_time_conversions() {
-        let all_bauds = &[
-            crate::Baudrate::B9600,
-            crate::Baudrate::B19200,
-            crate::Baudrate::B31250,
-            crate::Baudrate::B45450,
-            crate::Baudrate::B93750,
-            crate::Baudrate::B187500,
-            crate::Baudrate::B500000,
-            crate::Baudrate::B1500000,
-            crate::Baudrate::B3000000,
-            crate::Baudrate::B6000000,
-            crate::Baudrate::B12000000,
+    fn baudrate_from_rate_roundtrip() {
+        for baud in ALL_BAUDS.iter().copied() {
+            assert_eq!(crate::Baudrate::from_rate(baud.to_rate()), Some(baud));
+        }
+        assert_eq!(crate::Baudrate::from_rate(57600), None);
+        assert_eq!(crate::Baudrate::from_rate(0), None);
+    }
+
+    #[test]
+    fn baudrate_from_str() {
+        for baud in ALL_BAUDS.iter().copied() {
+            assert_eq!(baud.to_rate().to_string().parse(), Ok(baud));
+        }
+
+        let cases = &[
+            ("9.6k", crate::Baudrate::B9600),
+            ("19.2k", crate::Baudrate::B19200),
+            ("31.25k", crate::Baudrate::B31250),
+            ("45.45k", crate::Baudrate::B45450),
+            ("93.75k", crate::Baudrate::B93750),
+            ("187.5k", crate::Baudrate::B187500),
+            ("500k", crate::Baudrate::B500000),
+            ("1.5M", crate::Baudrate::B1500000),
+            ("1500k", crate::Baudrate::B1500000),
+            ("3M", crate::Baudrate::B3000000),
+            ("6M", crate::Baudrate::B6000000),
+            ("12M", crate::Baudrate::B12000000),
+            (" 12M ", crate::Baudrate::B12000000),
         ];
+        for (s, baud) in cases.iter() {
+            assert_eq!(s.parse::<crate::Baudrate>(), Ok(*baud), "{s:?}");
+        }
+
+        for s in &[
+            "",
+            "57600",
+            "57.6k",
+            "k",
+            ".5M",
+            "1.5",
+            "1.5G",
+            "-9600",
+            "19.2.0k",
+            "19.20001k",
+        ] {
+            assert!(s.parse::<crate::Baudrate>().is_err(), "{s:?}");
+        }
+    }
+
+    const ALL_BAUDS: &[crate::Baudrate] = &[
+        crate::Baudrate::B9600,
+        crate::Baudrate::B19200,
+        crate::Baudrate::B31250,
+        crate::Baudrate::B45450,
+        crate::Baudrate::B93750,
+        crate::Baudrate::B187500,
+        crate::Baudrate::B500000,
+        crate::Baudrate::B1500000,
+        crate::Baudrate::B3000000,
+        crate::Baudrate::B6000000,
+        crate::Baudrate::B12000000,
+    ];
+
+    #[test]
+    fn baudrate_time_conversions() {
+        let all_bauds = ALL_BAUDS;
         let test_values = &[0, 1, 10, 100, 2000, 65536, u32::MAX];
 
         for baud in all_bauds.iter().copied() {
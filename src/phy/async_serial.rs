@@ -0,0 +1,248 @@
+use std::borrow::Cow;
+use std::io;
+
+#[derive(Debug)]
+enum PhyData<'a> {
+    Rx {
+        buffer: crate::phy::BufferHandle<'a>,
+        length: usize,
+    },
+    Tx {
+        buffer: crate::phy::BufferHandle<'a>,
+        length: usize,
+        cursor: usize,
+    },
+}
+
+impl PhyData<'_> {
+    pub fn make_rx(&mut self) {
+        if let PhyData::Tx { buffer, .. } = self {
+            let buffer = std::mem::replace(buffer, [].into());
+            *self = PhyData::Rx { buffer, length: 0 };
+        }
+    }
+}
+
+/// PHY implementation for serial ports using the `tokio` runtime
+///
+/// Available with the `phy-async` feature.
+///
+/// This PHY wraps a [`tokio_serial::SerialStream`] for applications which already live inside a
+/// `tokio` runtime.  The PHY itself still implements the synchronous
+/// [`ProfibusPhy`][`crate::phy::ProfibusPhy`] trait: All reads and writes are non-blocking and
+/// received data is collected in an internal buffer.  This means the
+/// [`FdlActiveStation::poll()`][`crate::fdl::FdlActiveStation::poll`] timing logic works
+/// unchanged.  The bus poll loop should be run from a dedicated (blocking) task, for example
+/// using `tokio::task::spawn_blocking()`.
+///
+/// The PHY must be constructed from within the runtime, as the serial stream registers with its
+/// reactor.
+///
+/// # Timing
+/// The same considerations as for [`SerialPortPhy`][`crate::phy::SerialPortPhy`] apply, because
+/// the communication path is the same.
+///
+/// Additionally, note that the FDL layer must observe a synchronization pause of 33 bit times of
+/// bus idle before each transmission.  This pause is measured from the time when the last data
+/// was received, as seen by the calls to `poll()`.  Waiting for
+/// [`readable()`][`AsyncProfibusPhy::readable`] only tells you that _new data_ arrived, it does
+/// not tell you when the bus went idle.  Thus, you must not only wake up the poll loop on
+/// incoming data, but also poll again after your regular T<sub>SLEEP</sub> interval.  Otherwise,
+/// the station will not get to transmit after the synchronization pause has passed.
+///
+/// Backpressure from the serial port (a full output queue) is handled by
+/// [`poll_transmission()`][`crate::phy::ProfibusPhy::poll_transmission`] reporting the
+/// transmission as ongoing until all data was handed to the OS and the output queue is empty.
+///
+/// # Example
+/// ```no_run
+/// use profirust::{Baudrate, fdl, dp, phy};
+/// const BAUDRATE: Baudrate = Baudrate::B500000;
+/// # let mut dp_master = dp::DpMaster::new(vec![]);
+///
+/// let mut fdl = fdl::FdlActiveStation::new(
+///     fdl::ParametersBuilder::new(0x02, BAUDRATE)
+///         // Increased slot time due to USB latency
+///         .slot_bits(4000)
+///         .build_verified(&dp_master)
+///         .unwrap()
+/// );
+///
+/// // Must be called from within a tokio runtime
+/// let mut phy = phy::AsyncProfibusPhy::new("/dev/ttyUSB0", fdl.parameters().baudrate);
+/// ```
+pub struct AsyncProfibusPhy {
+    stream: tokio_serial::SerialStream,
+    data: PhyData<'static>,
+    /// Whether the receive buffer overflowed since the last `poll_rx_overflow()`
+    rx_overflow: bool,
+}
+
+impl AsyncProfibusPhy {
+    /// Open the serial port at the given path.
+    ///
+    /// # Panics
+    /// This function panics when the serial port cannot be opened or configured.  It also panics
+    /// when called outside of a `tokio` runtime.
+    pub fn new<'a, P: Into<Cow<'a, str>>>(serial_port: P, baudrate: crate::Baudrate) -> Self {
+        let builder = tokio_serial::new(serial_port, u32::try_from(baudrate.to_rate()).unwrap())
+            .data_bits(tokio_serial::DataBits::Eight)
+            .flow_control(tokio_serial::FlowControl::None)
+            .parity(tokio_serial::Parity::Even)
+            .stop_bits(tokio_serial::StopBits::One);
+        let stream = tokio_serial::SerialStream::open(&builder).unwrap();
+        Self::from_stream(stream, baudrate)
+    }
+
+    /// Use an already opened serial stream.
+    ///
+    /// The stream must be configured for the given baudrate, 8 data bits, even parity, and one
+    /// stop bit.
+    ///
+    /// # Panics
+    /// This function panics if the baudrate of the stream does not match.
+    pub fn from_stream(stream: tokio_serial::SerialStream, baudrate: crate::Baudrate) -> Self {
+        use tokio_serial::SerialPort;
+
+        assert_eq!(
+            u64::from(stream.baud_rate().unwrap()),
+            baudrate.to_rate(),
+            "baudrate not configured correctly"
+        );
+
+        let buffer = crate::phy::BufferHandle::from(vec![0u8; 512]);
+
+        Self {
+            stream,
+            data: PhyData::Rx { buffer, length: 0 },
+            rx_overflow: false,
+        }
+    }
+
+    /// Wait until new data is available for reading.
+    ///
+    /// This can be used to wake up the bus poll loop early when data arrives.  Please read the
+    /// [timing considerations][`AsyncProfibusPhy#timing`] before relying on this.
+    pub async fn readable(&self) -> io::Result<()> {
+        self.stream.readable().await
+    }
+
+    fn write(stream: &mut tokio_serial::SerialStream, buffer: &[u8]) -> io::Result<usize> {
+        match stream.try_write(buffer) {
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(0),
+            res => res,
+        }
+    }
+
+    fn get_output_queue(&mut self) -> io::Result<usize> {
+        use tokio_serial::SerialPort;
+
+        Ok(usize::try_from(self.stream.bytes_to_write()?).unwrap())
+    }
+
+    fn read(stream: &mut tokio_serial::SerialStream, buffer: &mut [u8]) -> io::Result<usize> {
+        match stream.try_read(buffer) {
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(0),
+            res => res,
+        }
+    }
+}
+
+impl crate::phy::ProfibusPhy for AsyncProfibusPhy {
+    fn poll_rx_overflow(&mut self) -> bool {
+        core::mem::take(&mut self.rx_overflow)
+    }
+
+    fn poll_transmission(&mut self, _now: crate::time::Instant) -> bool {
+        if let PhyData::Tx {
+            buffer,
+            length,
+            cursor,
+        } = &mut self.data
+        {
+            if length != cursor {
+                // Need to submit more data.
+                let written = Self::write(&mut self.stream, &buffer[*cursor..*length]).unwrap();
+                debug_assert!(written <= *length - *cursor);
+                *cursor += written;
+                true
+            } else {
+                // Everything was submitted already.
+                let queued = self.get_output_queue().unwrap();
+                if queued == 0 {
+                    // All data was sent.
+                    self.data.make_rx();
+                    false
+                } else {
+                    // Still sending.
+                    true
+                }
+            }
+        } else {
+            false
+        }
+    }
+
+    fn transmit_data<F, R>(&mut self, _now: crate::time::Instant, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> (usize, R),
+    {
+        match &mut self.data {
+            PhyData::Tx { .. } => panic!("transmit_data() while already transmitting!"),
+            PhyData::Rx {
+                buffer,
+                length: receive_length,
+            } => {
+                if *receive_length != 0 {
+                    log::warn!(
+                        "{} bytes in the receive buffer and we go into transmission?",
+                        receive_length
+                    );
+                }
+                let (length, res) = f(&mut buffer[..]);
+                if length == 0 {
+                    // Don't transmit anything.
+                    return res;
+                }
+                let cursor = Self::write(&mut self.stream, &buffer[..length]).unwrap();
+                debug_assert!(cursor <= length);
+                let buffer = std::mem::replace(buffer, [].into());
+                self.data = PhyData::Tx {
+                    buffer,
+                    length,
+                    cursor,
+                };
+                res
+            }
+        }
+    }
+
+    fn receive_data<F, R>(&mut self, _now: crate::time::Instant, f: F) -> R
+    where
+        F: FnOnce(&[u8]) -> (usize, R),
+    {
+        match &mut self.data {
+            PhyData::Tx { .. } => panic!("receive_data() while transmitting!"),
+            PhyData::Rx { buffer, length } => {
+                *length += Self::read(&mut self.stream, &mut buffer[*length..]).unwrap();
+                if crate::phy::rx_buffer_overflowed(buffer, *length, || {
+                    use tokio_serial::SerialPort;
+                    self.stream.bytes_to_read().map_or(0, |n| n as usize)
+                }) {
+                    self.rx_overflow = true;
+                }
+                let (drop, res) = f(&buffer[..*length]);
+                match drop {
+                    0 => (),
+                    d if d == *length => *length = 0,
+                    d => {
+                        assert!(d < *length);
+                        buffer.copy_within(d..*length, 0);
+                        *length -= d;
+                    }
+                }
+                res
+            }
+        }
+    }
+}
@@ -0,0 +1,264 @@
+/// Responder providing canned replies for a [`DryRunPhy`]
+///
+/// This is implemented for all closures of the signature
+/// `FnMut(time::Instant, &Telegram, TelegramTx) -> Option<TelegramTxResponse>`.
+pub trait DryRunResponder {
+    /// Produce a reply to the `telegram` which was transmitted at time `now`.
+    ///
+    /// The reply is written using `tx`.  Return `None` to simulate that no station answered.
+    fn respond(
+        &mut self,
+        now: crate::time::Instant,
+        telegram: &crate::fdl::Telegram,
+        tx: crate::fdl::TelegramTx,
+    ) -> Option<crate::fdl::TelegramTxResponse>;
+}
+
+impl<F> DryRunResponder for F
+where
+    F: FnMut(
+        crate::time::Instant,
+        &crate::fdl::Telegram,
+        crate::fdl::TelegramTx,
+    ) -> Option<crate::fdl::TelegramTxResponse>,
+{
+    fn respond(
+        &mut self,
+        now: crate::time::Instant,
+        telegram: &crate::fdl::Telegram,
+        tx: crate::fdl::TelegramTx,
+    ) -> Option<crate::fdl::TelegramTxResponse> {
+        self(now, telegram, tx)
+    }
+}
+
+/// PHY which transmits nothing and answers with canned responses
+///
+/// The `DryRunPhy` is meant for validating a configuration before connecting to real hardware.
+/// Every telegram that the FDL layer attempts to transmit is logged and recorded instead of being
+/// sent.  A [`DryRunResponder`] is then asked for a reply which is handed back to the FDL layer
+/// as if it was received from the bus.
+///
+/// Unlike the [`SimulatorPhy`][`crate::phy::SimulatorPhy`], no bus timing is modelled:
+/// Transmissions complete immediately and replies are available right away.
+///
+/// Only the last [`DEFAULT_HISTORY_LEN`][`DryRunPhy::DEFAULT_HISTORY_LEN`] telegrams are kept so
+/// long dry runs don't grow without bounds.  Use
+/// [`with_history_len()`][`DryRunPhy::with_history_len`] to change this.
+///
+/// # Example
+/// ```
+/// use profirust::{fdl, phy};
+///
+/// let mut phy = phy::DryRunPhy::new(
+///     |_now, telegram: &fdl::Telegram, tx: fdl::TelegramTx| match telegram {
+///         // Pretend a passive station is present at address 7
+///         fdl::Telegram::Data(t) if t.h.da == 7 && t.is_fdl_status_request().is_some() => {
+///             Some(tx.send_fdl_status_response(
+///                 t.h.sa,
+///                 7,
+///                 fdl::ResponseState::Slave,
+///                 fdl::ResponseStatus::Ok,
+///             ))
+///         }
+///         _ => None,
+///     },
+/// );
+///
+/// // ... run the FDL active station and applications using `phy` ...
+///
+/// for (now, telegram) in phy.transmitted_telegrams() {
+///     println!("{now} {telegram:?}");
+/// }
+/// ```
+pub struct DryRunPhy<R> {
+    responder: R,
+    /// The most recently transmitted telegrams, oldest first
+    transmitted: std::collections::VecDeque<(crate::time::Instant, Vec<u8>)>,
+    history_len: usize,
+    rx_buffer: Vec<u8>,
+}
+
+impl<R> DryRunPhy<R>
+where
+    R: DryRunResponder,
+{
+    /// Number of transmitted telegrams which are kept by default
+    pub const DEFAULT_HISTORY_LEN: usize = 1024;
+
+    /// Create a dry run PHY which asks `responder` for replies to transmitted telegrams.
+    pub fn new(responder: R) -> Self {
+        Self {
+            responder,
+            transmitted: std::collections::VecDeque::new(),
+            history_len: Self::DEFAULT_HISTORY_LEN,
+            rx_buffer: Vec::new(),
+        }
+    }
+
+    /// Keep only the last `len` transmitted telegrams.
+    ///
+    /// Older telegrams are dropped once more than `len` were transmitted.  With a length of zero,
+    /// no telegrams are recorded at all.
+    pub fn with_history_len(mut self, len: usize) -> Self {
+        self.history_len = len;
+        self.transmitted.truncate(len);
+        self
+    }
+
+    /// Iterate over the recorded telegrams, oldest first, along with their timestamps.
+    pub fn transmitted_telegrams(
+        &self,
+    ) -> impl Iterator<Item = (crate::time::Instant, crate::fdl::Telegram<'_>)> + '_ {
+        self.transmitted
+            .iter()
+            .filter_map(
+                |(now, buffer)| match crate::fdl::Telegram::deserialize(buffer) {
+                    Some(Ok((telegram, _))) => Some((*now, telegram)),
+                    _ => None,
+                },
+            )
+    }
+
+    /// Forget all telegrams that were transmitted so far.
+    pub fn clear_transmitted(&mut self) {
+        self.transmitted.clear();
+    }
+
+    /// Access the responder, as mutable.
+    pub fn responder_mut(&mut self) -> &mut R {
+        &mut self.responder
+    }
+}
+
+impl<R> crate::phy::ProfibusPhy for DryRunPhy<R>
+where
+    R: DryRunResponder,
+{
+    fn poll_transmission(&mut self, _now: crate::time::Instant) -> bool {
+        false
+    }
+
+    fn transmit_data<F, RES>(&mut self, now: crate::time::Instant, f: F) -> RES
+    where
+        F: FnOnce(&mut [u8]) -> (usize, RES),
+    {
+        let mut buffer = vec![0u8; 256];
+        let (length, res) = f(&mut buffer);
+        buffer.truncate(length);
+        if length == 0 {
+            return res;
+        }
+
+        match crate::fdl::Telegram::deserialize(&buffer) {
+            Some(Ok((telegram, _))) => {
+                log::debug!("Dry run TX {:?}", telegram);
+
+                let mut reply = [0u8; 256];
+                let reply_length = self
+                    .responder
+                    .respond(now, &telegram, crate::fdl::TelegramTx::new(&mut reply))
+                    .map(|response| response.bytes_sent())
+                    .unwrap_or(0);
+                if reply_length != 0 {
+                    log::debug!("Dry run RX {:?}", &reply[..reply_length]);
+                    self.rx_buffer.extend_from_slice(&reply[..reply_length]);
+                }
+            }
+            _ => log::warn!("Dry run TX {:?} (invalid!)", buffer),
+        }
+
+        if self.history_len != 0 {
+            if self.transmitted.len() == self.history_len {
+                self.transmitted.pop_front();
+            }
+            self.transmitted.push_back((now, buffer));
+        }
+        res
+    }
+
+    fn receive_data<F, RES>(&mut self, _now: crate::time::Instant, f: F) -> RES
+    where
+        F: FnOnce(&[u8]) -> (usize, RES),
+    {
+        let (drop, res) = f(&self.rx_buffer);
+        assert!(
+            drop <= self.rx_buffer.len(),
+            "attempted to drop more pending bytes than available!"
+        );
+        self.rx_buffer.drain(..drop);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dry_run_live_list() {
+        crate::test_utils::prepare_test_logger();
+        let mut phy = DryRunPhy::new(
+            |_now, telegram: &crate::fdl::Telegram, tx: crate::fdl::TelegramTx| match telegram {
+                crate::fdl::Telegram::Data(t)
+                    if t.h.da == 7 && t.is_fdl_status_request().is_some() =>
+                {
+                    Some(tx.send_fdl_status_response(
+                        t.h.sa,
+                        7,
+                        crate::fdl::ResponseState::Slave,
+                        crate::fdl::ResponseStatus::Ok,
+                    ))
+                }
+                _ => None,
+            },
+        );
+        let mut fdl = crate::fdl::FdlActiveStation::new(
+            crate::fdl::ParametersBuilder::new(2, crate::Baudrate::B19200)
+                .highest_station_address(16)
+                .build(),
+        );
+        let mut live_list = crate::fdl::live_list::LiveList::new();
+        fdl.set_online();
+
+        let mut now = crate::time::Instant::ZERO;
+        while now < crate::time::Instant::ZERO + crate::time::Duration::from_secs(2) {
+            fdl.poll(now, &mut phy, &mut live_list);
+            now += crate::time::Duration::from_micros(100);
+        }
+
+        assert_eq!(live_list.iter_stations().collect::<Vec<_>>(), &[7]);
+        assert!(phy.transmitted_telegrams().any(|(_, t)| matches!(
+            t,
+            crate::fdl::Telegram::Data(t) if t.h.da == 7 && t.is_fdl_status_request().is_some()
+        )));
+
+        phy.clear_transmitted();
+        assert_eq!(phy.transmitted_telegrams().count(), 0);
+    }
+
+    #[test]
+    fn dry_run_history_len() {
+        crate::test_utils::prepare_test_logger();
+        let mut phy = DryRunPhy::new(
+            |_now, _telegram: &crate::fdl::Telegram, _tx: crate::fdl::TelegramTx| None,
+        )
+        .with_history_len(3);
+
+        for da in 0..5 {
+            let now = crate::time::Instant::ZERO + crate::time::Duration::from_millis(da.into());
+            crate::phy::ProfibusPhy::transmit_telegram(&mut phy, now, |tx| {
+                Some(tx.send_token_telegram(da, 2))
+            });
+        }
+
+        let destinations: Vec<_> = phy
+            .transmitted_telegrams()
+            .map(|(_, t)| match t {
+                crate::fdl::Telegram::Token(t) => t.da,
+                t => panic!("unexpected telegram {t:?}"),
+            })
+            .collect();
+        assert_eq!(destinations, [2, 3, 4]);
+    }
+}
@@ -40,6 +40,85 @@ impl PhyData<'_> {
     }
 }
 
+/// Low-latency flag of the serial driver (`ASYNC_LOW_LATENCY`)
+const ASYNC_LOW_LATENCY: libc::c_int = 1 << 13;
+
+/// Serial port information for the `TIOCGSERIAL`/`TIOCSSERIAL` ioctls (`struct serial_struct`)
+#[repr(C)]
+struct SerialStruct {
+    type_: libc::c_int,
+    line: libc::c_int,
+    port: libc::c_uint,
+    irq: libc::c_int,
+    flags: libc::c_int,
+    xmit_fifo_size: libc::c_int,
+    custom_divisor: libc::c_int,
+    baud_base: libc::c_int,
+    close_delay: libc::c_ushort,
+    io_type: libc::c_char,
+    reserved_char: [libc::c_char; 1],
+    hub6: libc::c_int,
+    closing_wait: libc::c_ushort,
+    closing_wait2: libc::c_ushort,
+    iomem_base: *mut libc::c_uchar,
+    iomem_reg_shift: libc::c_ushort,
+    port_high: libc::c_uint,
+    iomap_base: libc::c_ulong,
+}
+
+/// Interval for reading the error counters of the UART driver
+const ICOUNT_POLL_INTERVAL: crate::time::Duration = crate::time::Duration::from_millis(100);
+
+/// Interrupt counters for the `TIOCGICOUNT` ioctl (`struct serial_icounter_struct`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct SerialIcounterStruct {
+    cts: libc::c_int,
+    dsr: libc::c_int,
+    rng: libc::c_int,
+    dcd: libc::c_int,
+    rx: libc::c_int,
+    tx: libc::c_int,
+    frame: libc::c_int,
+    overrun: libc::c_int,
+    parity: libc::c_int,
+    brk: libc::c_int,
+    buf_overrun: libc::c_int,
+    reserved: [libc::c_int; 9],
+}
+
+/// RS-485 mode options for the [`LinuxRs485Phy`]
+///
+/// These options are passed to the kernel UART driver using the `TIOCSRS485` ioctl.  The driver
+/// then switches the direction of the RS-485 transceiver using the RTS line.  See the [Kernel
+/// Documentation][kernel-rs485] for details.
+///
+/// The default drives RTS high during transmission without any delays.
+///
+/// [kernel-rs485]: https://www.kernel.org/doc/html/latest/driver-api/serial/serial-rs485.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rs485Options {
+    /// Logical level of RTS while sending (`SER_RS485_RTS_ON_SEND`)
+    pub rts_on_send: bool,
+    /// Logical level of RTS after sending (`SER_RS485_RTS_AFTER_SEND`)
+    pub rts_after_send: bool,
+    /// Delay between setting RTS and the start of the transmission in milliseconds
+    pub delay_rts_before_send_ms: u32,
+    /// Delay between the end of the transmission and resetting RTS in milliseconds
+    pub delay_rts_after_send_ms: u32,
+}
+
+impl Default for Rs485Options {
+    fn default() -> Self {
+        Self {
+            rts_on_send: true,
+            rts_after_send: false,
+            delay_rts_before_send_ms: 0,
+            delay_rts_after_send_ms: 0,
+        }
+    }
+}
+
 /// Linux userspace PHY implementation for UART TTY devices
 ///
 /// Available with the `phy-linux` feature.
@@ -50,6 +129,10 @@ impl PhyData<'_> {
 /// For USB-RS485 converters, use [`crate::phy::SerialPortPhy`] (feature `phy-serial`) instead.
 ///
 /// # Considerations
+/// The PHY enables the low-latency mode of the serial driver when opening the TTY device.  This is
+/// especially important for USB-serial adapters which otherwise buffer received data for multiple
+/// milliseconds.  Use [`LinuxRs485Phy::set_low_latency()`] to turn it off again.
+///
 /// Due to the non-realtime nature of Linux, you must use this PHY implementation with care.  You
 /// may need to decrease the baudrate or lengthen timeouts (T<sub>SL</sub>) to get reliable
 /// communication.  You should also consider running your pogram at "real-time priority" to ensure
@@ -67,16 +150,36 @@ impl PhyData<'_> {
 ///         // Increase T_slot (slot time)
 ///         .slot_bits(1920)
 ///         .build_verified(&dp_master)
+///         .unwrap()
 /// );
 ///
 /// let mut phy = phy::LinuxRs485Phy::new("/dev/ttyS0", fdl.parameters().baudrate);
 /// ```
 ///
+/// When the transceiver needs a different RTS polarity or delays, use
+/// [`LinuxRs485Phy::with_rs485_options()`]:
+/// ```no_run
+/// use profirust::{Baudrate, phy};
+///
+/// let options = phy::Rs485Options {
+///     rts_on_send: false,
+///     rts_after_send: true,
+///     ..Default::default()
+/// };
+/// let mut phy = phy::LinuxRs485Phy::with_rs485_options("/dev/ttyS0", Baudrate::B19200, options);
+/// ```
+///
 /// [kernel-rs485]: https://www.kernel.org/doc/html/latest/driver-api/serial/serial-rs485.html
 #[derive(Debug)]
 pub struct LinuxRs485Phy {
     fd: RawFd,
     data: PhyData<'static>,
+    /// Last error counters of the UART driver, `None` when not supported.
+    icount: Option<SerialIcounterStruct>,
+    /// Time after which the error counters are read again
+    next_icount_poll: crate::time::Instant,
+    /// Whether the receive buffer overflowed since the last `poll_rx_overflow()`
+    rx_overflow: bool,
 }
 
 impl LinuxRs485Phy {
@@ -86,10 +189,23 @@ impl LinuxRs485Phy {
     /// communication (line settings via termios and RS-485 mode when available).
     #[inline]
     pub fn new<P: AsRef<Path>>(serial_port: P, baudrate: crate::Baudrate) -> Self {
-        Self::new_inner(&serial_port.as_ref(), baudrate)
+        Self::new_inner(&serial_port.as_ref(), baudrate, Rs485Options::default())
     }
 
-    fn new_inner(serial_port: &Path, baudrate: crate::Baudrate) -> Self {
+    /// Construct and initialize a new PHY device with custom RS-485 mode options
+    ///
+    /// This works like [`LinuxRs485Phy::new()`] but configures the kernel RS-485 mode with the
+    /// given RTS polarity and delays.
+    #[inline]
+    pub fn with_rs485_options<P: AsRef<Path>>(
+        serial_port: P,
+        baudrate: crate::Baudrate,
+        options: Rs485Options,
+    ) -> Self {
+        Self::new_inner(serial_port.as_ref(), baudrate, options)
+    }
+
+    fn new_inner(serial_port: &Path, baudrate: crate::Baudrate, options: Rs485Options) -> Self {
         // open serial port non-blocking
         let path = std::ffi::CString::new(serial_port.as_os_str().as_bytes()).unwrap();
         let fd = unsafe {
@@ -182,21 +298,69 @@ impl LinuxRs485Phy {
 
         let res = rs485::SerialRs485::new()
             .set_enabled(true)
-            .set_rts_on_send(true)
-            .set_rts_after_send(false)
+            .set_rts_on_send(options.rts_on_send)
+            .set_rts_after_send(options.rts_after_send)
+            .delay_rts_before_send_ms(options.delay_rts_before_send_ms)
+            .delay_rts_after_send_ms(options.delay_rts_after_send_ms)
             .set_rx_during_tx(false)
             .set_on_fd(fd);
         if let Err(e) = res {
             log::warn!("Could not configure RS485 mode: {}", e);
         }
 
+        if let Err(e) = Self::configure_low_latency(fd, true) {
+            log::warn!("Could not enable low-latency mode: {}", e);
+        }
+
         // TODO: Allow configuring this buffer?
         let buffer = crate::phy::BufferHandle::from(vec![0u8; 512]);
 
+        let icount = Self::get_icount(fd)
+            .inspect_err(|e| log::debug!("UART error counters are not available: {}", e))
+            .ok();
+
         Self {
             fd,
             data: PhyData::Rx { buffer, length: 0 },
+            icount,
+            next_icount_poll: crate::time::Instant::ZERO,
+            rx_overflow: false,
+        }
+    }
+
+    /// Enable or disable the low-latency mode of the serial driver.
+    ///
+    /// Low-latency mode is enabled by default.  It makes the driver pass received data on
+    /// immediately instead of buffering it.  For USB-serial adapters with an FTDI chip, this also
+    /// reduces the latency timer of the adapter to 1ms.
+    pub fn set_low_latency(&mut self, enabled: bool) -> io::Result<()> {
+        Self::configure_low_latency(self.fd, enabled)
+    }
+
+    fn configure_low_latency(fd: RawFd, enabled: bool) -> io::Result<()> {
+        let mut serial: SerialStruct = unsafe { core::mem::zeroed() };
+        if unsafe { libc::ioctl(fd, libc::TIOCGSERIAL, &mut serial) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        if enabled {
+            serial.flags |= ASYNC_LOW_LATENCY;
+        } else {
+            serial.flags &= !ASYNC_LOW_LATENCY;
+        }
+
+        if unsafe { libc::ioctl(fd, libc::TIOCSSERIAL, &serial) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn get_icount(fd: RawFd) -> io::Result<SerialIcounterStruct> {
+        let mut icount: SerialIcounterStruct = unsafe { core::mem::zeroed() };
+        if unsafe { libc::ioctl(fd, libc::TIOCGICOUNT, &mut icount) } < 0 {
+            return Err(io::Error::last_os_error());
         }
+        Ok(icount)
     }
 
     /// Wait/block until the current transmission completes.
@@ -232,6 +396,15 @@ impl LinuxRs485Phy {
         Ok(usize::try_from(arg).unwrap())
     }
 
+    /// Number of received bytes waiting in the input queue of the kernel.
+    fn pending(fd: RawFd) -> usize {
+        let mut arg: libc::c_int = 0;
+        if unsafe { libc::ioctl(fd, libc::FIONREAD, &mut arg) } < 0 {
+            return 0;
+        }
+        usize::try_from(arg).unwrap_or(0)
+    }
+
     fn read(fd: RawFd, buffer: &mut [u8]) -> io::Result<usize> {
         match unsafe { libc::read(fd, buffer.as_mut_ptr() as *mut c_void, buffer.len()) } {
             -1 => {
@@ -248,6 +421,39 @@ impl LinuxRs485Phy {
 }
 
 impl crate::phy::ProfibusPhy for LinuxRs485Phy {
+    fn poll_rx_overflow(&mut self) -> bool {
+        core::mem::take(&mut self.rx_overflow)
+    }
+
+    fn poll_line_errors(&mut self, now: crate::time::Instant) -> crate::fdl::LineErrorCounters {
+        let Some(last) = self.icount else {
+            return Default::default();
+        };
+        // Reading the counters needs a syscall, so don't do it on every poll.
+        if now < self.next_icount_poll {
+            return Default::default();
+        }
+        self.next_icount_poll = now + ICOUNT_POLL_INTERVAL;
+        let icount = match Self::get_icount(self.fd) {
+            Ok(icount) => icount,
+            Err(e) => {
+                log::warn!("Failed reading UART error counters: {}", e);
+                return Default::default();
+            }
+        };
+        self.icount = Some(icount);
+
+        // The kernel counters are free-running and wrap around.
+        let delta = |new: libc::c_int, old: libc::c_int| new.wrapping_sub(old) as u32;
+        crate::fdl::LineErrorCounters {
+            framing: delta(icount.frame, last.frame),
+            parity: delta(icount.parity, last.parity),
+            breaks: delta(icount.brk, last.brk),
+            overruns: delta(icount.overrun, last.overrun)
+                .wrapping_add(delta(icount.buf_overrun, last.buf_overrun)),
+        }
+    }
+
     fn poll_transmission(&mut self, _now: crate::time::Instant) -> bool {
         if let PhyData::Tx {
             buffer,
@@ -320,7 +526,9 @@ impl crate::phy::ProfibusPhy for LinuxRs485Phy {
             PhyData::Tx { .. } => panic!("receive_data() while transmitting!"),
             PhyData::Rx { buffer, length } => {
                 *length += Self::read(self.fd, &mut buffer[*length..]).unwrap();
-                debug_assert!(*length <= buffer.len());
+                if crate::phy::rx_buffer_overflowed(buffer, *length, || Self::pending(self.fd)) {
+                    self.rx_overflow = true;
+                }
                 let (drop, res) = f(&buffer[..*length]);
                 match drop {
                     0 => (),
@@ -5,6 +5,7 @@
 //! implementation.  Here is a list:
 //!
 //! - `phy-serial`: Platform-independent PHY implementation for serial port devices
+//! - `phy-async`: PHY implementation for serial port devices using the `tokio` runtime
 //! - `phy-linux`: Linux userspace PHY implementation for UART TTY devices
 //! - `phy-rp2040`: PHY implementation for UART of the RP2040
 //! - `phy-simulator`: Simulator PHY implementation for `profirust` testing with a simulated bus
@@ -12,13 +13,18 @@
 #[cfg(feature = "phy-linux")]
 mod linux;
 #[cfg(feature = "phy-linux")]
-pub use linux::LinuxRs485Phy;
+pub use linux::{LinuxRs485Phy, Rs485Options};
 
 #[cfg(feature = "phy-serial")]
 mod serial;
 #[cfg(feature = "phy-serial")]
 pub use serial::SerialPortPhy;
 
+#[cfg(feature = "phy-async")]
+mod async_serial;
+#[cfg(feature = "phy-async")]
+pub use async_serial::AsyncProfibusPhy;
+
 #[cfg(feature = "phy-simulator")]
 pub mod simulator;
 #[cfg(feature = "phy-simulator")]
@@ -29,9 +35,31 @@ mod rp2040;
 #[cfg(feature = "phy-rp2040")]
 pub use rp2040::Rp2040Phy;
 
+mod recorder;
+pub use recorder::{Direction, RecordingPhy, TelegramRecorder};
+
+#[cfg(feature = "std")]
+mod dry_run;
+#[cfg(feature = "std")]
+pub use dry_run::{DryRunPhy, DryRunResponder};
+
 /// Type alias for the message buffer used by some PHY implementations
 pub type BufferHandle<'a> = managed::ManagedSlice<'a, u8>;
 
+/// Check whether the receive buffer of a PHY overflowed after reading into it.
+///
+/// A full buffer alone is no overflow, a telegram may fill it exactly.  Only when `pending`
+/// reports further received bytes waiting in the OS which do not fit into the buffer anymore, it
+/// overflowed.
+pub(crate) fn rx_buffer_overflowed(
+    buffer: &[u8],
+    length: usize,
+    pending: impl FnOnce() -> usize,
+) -> bool {
+    debug_assert!(length <= buffer.len());
+    length == buffer.len() && pending() != 0
+}
+
 /// Generic abstraction for `profirust` PHY implementations
 pub trait ProfibusPhy {
     /// Poll an ongoing transmission.
@@ -122,19 +150,37 @@ pub trait ProfibusPhy {
     /// # Panics
     /// This function may panic when a transmission is ongoing.
     fn receive_telegram<F, R>(&mut self, now: crate::time::Instant, f: F) -> Option<R>
+    where
+        F: FnOnce(crate::fdl::Telegram) -> R,
+    {
+        self.try_receive_telegram(now, f).and_then(Result::ok)
+    }
+
+    /// Try receiving a telegram, reporting data that could not be decoded.
+    ///
+    /// Works like `receive_telegram()` but returns a [`DeserializeError`][crate::fdl::DeserializeError]
+    /// when the received data is not a valid telegram.  The invalid data is discarded.
+    ///
+    /// # Panics
+    /// This function may panic when a transmission is ongoing.
+    fn try_receive_telegram<F, R>(
+        &mut self,
+        now: crate::time::Instant,
+        f: F,
+    ) -> Option<Result<R, crate::fdl::DeserializeError>>
     where
         F: FnOnce(crate::fdl::Telegram) -> R,
     {
         self.receive_data(now, |buffer| {
             match crate::fdl::Telegram::deserialize(buffer) {
                 // Discard all received data on error.
-                Some(Err(_)) => (buffer.len(), None),
+                Some(Err(e)) => (buffer.len(), Some(Err(e))),
                 Some(Ok((telegram, length))) => {
                     log::trace!("PHY RX {:?}", telegram);
                     if length != buffer.len() {
                         log::trace!("Received more than one telegram at once!");
                     }
-                    (length, Some(f(telegram)))
+                    (length, Some(Ok(f(telegram))))
                 }
                 // Don't drop any bytes yet if the telegram isn't complete.
                 None => (0, None),
@@ -159,7 +205,27 @@ pub trait ProfibusPhy {
     ///
     /// # Panics
     /// This function may panic when a transmission is ongoing.
-    fn receive_all_telegrams<F, R>(&mut self, now: crate::time::Instant, mut f: F) -> Option<R>
+    fn receive_all_telegrams<F, R>(&mut self, now: crate::time::Instant, f: F) -> Option<R>
+    where
+        F: FnMut(crate::fdl::Telegram, bool) -> R,
+    {
+        self.try_receive_all_telegrams(now, f).and_then(Result::ok)
+    }
+
+    /// Try receiving all pending telegrams, reporting data that could not be decoded.
+    ///
+    /// Works like `receive_all_telegrams()` but returns a
+    /// [`DeserializeError`][crate::fdl::DeserializeError] when the final data in the receive
+    /// buffer is not a valid telegram.  The invalid data is discarded.  Valid telegrams before it
+    /// are still passed to `f()`.
+    ///
+    /// # Panics
+    /// This function may panic when a transmission is ongoing.
+    fn try_receive_all_telegrams<F, R>(
+        &mut self,
+        now: crate::time::Instant,
+        mut f: F,
+    ) -> Option<Result<R, crate::fdl::DeserializeError>>
     where
         F: FnMut(crate::fdl::Telegram, bool) -> R,
     {
@@ -169,12 +235,12 @@ pub trait ProfibusPhy {
             let (is_last, res) = self.receive_data(now, |buffer| {
                 match crate::fdl::Telegram::deserialize(buffer) {
                     // Discard all received data on error.
-                    Some(Err(_)) => (buffer.len(), (true, None)),
+                    Some(Err(e)) => (buffer.len(), (true, Some(Err(e)))),
                     Some(Ok((telegram, length))) => {
                         log::trace!("PHY RX {:?}", telegram);
                         let telegram_is_last = length == buffer.len();
                         let res = f(telegram, telegram_is_last);
-                        (length, (telegram_is_last, Some(res)))
+                        (length, (telegram_is_last, Some(Ok(res))))
                     }
                     // Don't drop any bytes yet if the telegram isn't complete.
                     None => (0, (true, None)),
@@ -200,4 +266,43 @@ pub trait ProfibusPhy {
     fn poll_pending_received_bytes(&mut self, now: crate::time::Instant) -> usize {
         self.receive_data(now, |buf| (0, buf.len()))
     }
+
+    /// Poll for the number of line errors which were detected by the UART since the last call.
+    ///
+    /// Implementations may read the error counters of their UART only from time to time when this
+    /// is expensive.  Errors are then reported by a later call.  PHY implementations which cannot
+    /// read the error status of their UART do not need to implement this method.  The default
+    /// implementation never reports any errors.
+    fn poll_line_errors(&mut self, now: crate::time::Instant) -> crate::fdl::LineErrorCounters {
+        Default::default()
+    }
+
+    /// Poll whether the receive buffer of the PHY overflowed since the last call.
+    ///
+    /// When the buffer overflows, received data is lost and the telegram in progress is most
+    /// likely corrupted.  This usually means that the bus is not polled often enough.  PHY
+    /// implementations which cannot detect an overflow do not need to implement this method.
+    fn poll_rx_overflow(&mut self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn rx_buffer_overflow() {
+        // A telegram which exactly fills the buffer
+        let mut buffer = [0u8; 6];
+        let telegram = [0x10, 0x02, 0x22, 0x00, 0x24, 0x16];
+        buffer.copy_from_slice(&telegram);
+        assert!(!super::rx_buffer_overflowed(&buffer, 6, || 0));
+
+        // More data is waiting which does not fit anymore
+        assert!(super::rx_buffer_overflowed(&buffer, 6, || 1));
+
+        // Space is left, so the pending data can be read on the next poll
+        assert!(!super::rx_buffer_overflowed(&buffer, 5, || {
+            unreachable!("only checked for full buffers")
+        }));
+    }
 }
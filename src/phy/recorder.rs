@@ -0,0 +1,224 @@
+/// Direction of a recorded telegram
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Telegram was transmitted by this station
+    Tx,
+    /// Telegram was received from the bus
+    Rx,
+}
+
+/// Recorder for all telegrams passing through a [`RecordingPhy`]
+///
+/// This is implemented for all closures of the signature
+/// `FnMut(time::Instant, Direction, &Telegram)`.
+pub trait TelegramRecorder {
+    /// Record a telegram that was transmitted or received at time `now`.
+    fn record(
+        &mut self,
+        now: crate::time::Instant,
+        direction: Direction,
+        telegram: &crate::fdl::Telegram,
+    );
+}
+
+impl<F> TelegramRecorder for F
+where
+    F: FnMut(crate::time::Instant, Direction, &crate::fdl::Telegram),
+{
+    fn record(
+        &mut self,
+        now: crate::time::Instant,
+        direction: Direction,
+        telegram: &crate::fdl::Telegram,
+    ) {
+        self(now, direction, telegram)
+    }
+}
+
+/// PHY wrapper which records a trace of all telegrams
+///
+/// The `RecordingPhy` wraps any other PHY implementation and passes each telegram that is
+/// transmitted or received to a [`TelegramRecorder`].  This allows capturing a bus trace with
+/// timestamps for offline analysis.
+///
+/// # Example
+/// ```
+/// use profirust::phy;
+/// # let inner_phy = phy::SimulatorPhy::new(profirust::Baudrate::B19200, "phy");
+///
+/// let mut recorder = |now, direction, telegram: &profirust::fdl::Telegram| {
+///     println!("{now} {direction:?} {telegram:?}");
+/// };
+/// let mut phy = phy::RecordingPhy::new(inner_phy, &mut recorder);
+/// ```
+pub struct RecordingPhy<PHY, R> {
+    phy: PHY,
+    recorder: R,
+}
+
+impl<PHY, R> RecordingPhy<PHY, R>
+where
+    PHY: crate::phy::ProfibusPhy,
+    R: TelegramRecorder,
+{
+    /// Wrap the given PHY and pass all telegrams to `recorder`.
+    pub fn new(phy: PHY, recorder: R) -> Self {
+        Self { phy, recorder }
+    }
+
+    /// Access the wrapped PHY.
+    pub fn inner(&self) -> &PHY {
+        &self.phy
+    }
+
+    /// Access the wrapped PHY, as mutable.
+    pub fn inner_mut(&mut self) -> &mut PHY {
+        &mut self.phy
+    }
+
+    /// Access the recorder, as mutable.
+    pub fn recorder_mut(&mut self) -> &mut R {
+        &mut self.recorder
+    }
+
+    /// Return the wrapped PHY and the recorder.
+    pub fn into_inner(self) -> (PHY, R) {
+        (self.phy, self.recorder)
+    }
+}
+
+impl<PHY, R> crate::phy::ProfibusPhy for RecordingPhy<PHY, R>
+where
+    PHY: crate::phy::ProfibusPhy,
+    R: TelegramRecorder,
+{
+    fn poll_transmission(&mut self, now: crate::time::Instant) -> bool {
+        self.phy.poll_transmission(now)
+    }
+
+    fn transmit_data<F, RES>(&mut self, now: crate::time::Instant, f: F) -> RES
+    where
+        F: FnOnce(&mut [u8]) -> (usize, RES),
+    {
+        self.phy.transmit_data(now, f)
+    }
+
+    fn transmit_telegram<F>(
+        &mut self,
+        now: crate::time::Instant,
+        f: F,
+    ) -> Option<crate::fdl::TelegramTxResponse>
+    where
+        F: FnOnce(crate::fdl::TelegramTx) -> Option<crate::fdl::TelegramTxResponse>,
+    {
+        let recorder = &mut self.recorder;
+        self.phy.transmit_data(now, |buffer| {
+            let ttx = crate::fdl::TelegramTx::new(buffer);
+            let response = f(ttx);
+            if let Some(response) = response {
+                let bytes_sent = response.bytes_sent();
+
+                if let Some(Ok((t, _))) = crate::fdl::Telegram::deserialize(buffer) {
+                    log::trace!("PHY TX {:?}", t);
+                    recorder.record(now, Direction::Tx, &t);
+                } else {
+                    log::trace!("PHY TX {:?} (invalid!)", &buffer[..bytes_sent]);
+                }
+
+                (bytes_sent, Some(response))
+            } else {
+                (0, None)
+            }
+        })
+    }
+
+    fn receive_data<F, RES>(&mut self, now: crate::time::Instant, f: F) -> RES
+    where
+        F: FnOnce(&[u8]) -> (usize, RES),
+    {
+        self.phy.receive_data(now, f)
+    }
+
+    fn try_receive_telegram<F, RES>(
+        &mut self,
+        now: crate::time::Instant,
+        f: F,
+    ) -> Option<Result<RES, crate::fdl::DeserializeError>>
+    where
+        F: FnOnce(crate::fdl::Telegram) -> RES,
+    {
+        let recorder = &mut self.recorder;
+        self.phy.try_receive_telegram(now, |telegram| {
+            recorder.record(now, Direction::Rx, &telegram);
+            f(telegram)
+        })
+    }
+
+    fn try_receive_all_telegrams<F, RES>(
+        &mut self,
+        now: crate::time::Instant,
+        mut f: F,
+    ) -> Option<Result<RES, crate::fdl::DeserializeError>>
+    where
+        F: FnMut(crate::fdl::Telegram, bool) -> RES,
+    {
+        let recorder = &mut self.recorder;
+        self.phy
+            .try_receive_all_telegrams(now, |telegram, is_last_telegram| {
+                recorder.record(now, Direction::Rx, &telegram);
+                f(telegram, is_last_telegram)
+            })
+    }
+
+    fn poll_pending_received_bytes(&mut self, now: crate::time::Instant) -> usize {
+        self.phy.poll_pending_received_bytes(now)
+    }
+
+    fn poll_line_errors(&mut self, now: crate::time::Instant) -> crate::fdl::LineErrorCounters {
+        self.phy.poll_line_errors(now)
+    }
+
+    fn poll_rx_overflow(&mut self) -> bool {
+        self.phy.poll_rx_overflow()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::phy::ProfibusPhy;
+
+    #[test]
+    fn record_tx_and_rx() {
+        let phy1 = crate::phy::SimulatorPhy::new(crate::Baudrate::B19200, "phy1");
+        let mut phy2 = phy1.duplicate("phy2");
+
+        let mut trace = vec![];
+        let mut recorder = |now, direction, telegram: &crate::fdl::Telegram| {
+            trace.push((now, direction, telegram.source_address()));
+        };
+        let mut phy1 = RecordingPhy::new(phy1, &mut recorder);
+
+        let mut now = crate::time::Instant::ZERO;
+        phy1.transmit_telegram(now, |tx| Some(tx.send_token_telegram(3, 2)));
+
+        now += crate::time::Duration::from_millis(10);
+        phy1.inner().set_bus_time(now);
+        assert!(!phy1.poll_transmission(now));
+        phy2.receive_telegram(now, |_| ()).unwrap();
+        phy2.transmit_telegram(now, |tx| Some(tx.send_token_telegram(2, 3)));
+
+        now += crate::time::Duration::from_millis(10);
+        phy1.inner().set_bus_time(now);
+        phy1.receive_all_telegrams(now, |_, _| ()).unwrap();
+
+        drop(phy1);
+        assert_eq!(
+            trace,
+            &[
+                (crate::time::Instant::ZERO, Direction::Tx, Some(2)),
+                (now, Direction::Rx, Some(3)),
+            ]
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use embedded_hal::digital::v2::OutputPin;
+use rp2040_hal::dma;
 use rp2040_hal::uart;
 
 use fugit::RateExtU32;
@@ -13,8 +14,8 @@ enum PhyData<'a> {
     Tx {
         buffer: crate::phy::BufferHandle<'a>,
         length: usize,
-        cursor: usize,
         start_tx: crate::time::Instant,
+        dma_started: bool,
     },
 }
 
@@ -41,10 +42,39 @@ impl PhyData<'_> {
     }
 }
 
+/// Transmit buffer as the read target of a DMA transfer
+///
+/// The transfer reads from the buffer of a `PhyData::Tx`.  This buffer stays in place until the
+/// transfer was completed, so the pointer remains valid.
+struct DmaTxBuffer {
+    ptr: *const u8,
+    length: usize,
+}
+
+impl dma::ReadTarget for DmaTxBuffer {
+    type ReceivedWord = u8;
+
+    fn rx_treq() -> Option<u8> {
+        None
+    }
+
+    fn rx_address_count(&self) -> (u32, u32) {
+        (self.ptr as u32, u32::try_from(self.length).unwrap())
+    }
+
+    fn rx_increment(&self) -> bool {
+        true
+    }
+}
+
 /// PHY implementation for the [RP2040] microcontroller's UART peripheral
 ///
 /// Available with the `phy-rp2040` feature.
 ///
+/// Telegrams are transmitted using a DMA channel.  This means the bus poll loop does not need to
+/// keep refilling the UART FIFO during long telegrams, which is important for meeting the slot
+/// timing at high baudrates.
+///
 /// [RP2040]: https://www.raspberrypi.com/documentation/microcontrollers/rp2040.html
 ///
 /// # Example
@@ -72,33 +102,67 @@ impl PhyData<'_> {
 /// // Pin to toggle the RS485 direction (transmit vs. receive)
 /// let dir_pin = pins.gpio15.into_push_pull_output();
 ///
+/// // DMA channel for transmitting data
+/// use rp2040_hal::dma::DMAExt;
+/// let dma = pac.DMA.split(&mut pac.RESETS);
+///
 /// let mut phy_buffer = [0u8; 256];
 /// let mut phy = phy::Rp2040Phy::new(
 ///     uart,
 ///     dir_pin,
+///     dma.ch0,
 ///     &clocks.peripheral_clock,
 ///     &mut phy_buffer[..],
 ///     BAUDRATE,
 /// )
 /// .unwrap();
 /// ```
-#[derive(Debug)]
-pub struct Rp2040Phy<'a, UART, DIR> {
-    uart: UART,
+pub struct Rp2040Phy<'a, UART, DIR, CH>
+where
+    UART: dma::WriteTarget<TransmittedWord = u8>,
+    CH: dma::SingleChannel,
+{
+    /// The UART peripheral, unless it is currently owned by a DMA transfer
+    uart: Option<UART>,
+    /// The DMA channel, unless it is currently owned by a DMA transfer
+    dma_channel: Option<CH>,
+    /// The ongoing DMA transfer
+    transfer: Option<dma::single_buffer::Transfer<CH, DmaTxBuffer, UART>>,
     dir_pin: DIR,
     data: PhyData<'a>,
     baudrate: crate::Baudrate,
 }
 
-impl<'a, D, P, DIR> Rp2040Phy<'a, uart::UartPeripheral<uart::Enabled, D, P>, DIR>
+// The DMA transfer does not implement `Debug`, so only show whether one is ongoing.
+impl<UART, DIR, CH> core::fmt::Debug for Rp2040Phy<'_, UART, DIR, CH>
+where
+    UART: dma::WriteTarget<TransmittedWord = u8> + core::fmt::Debug,
+    DIR: core::fmt::Debug,
+    CH: dma::SingleChannel + core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Rp2040Phy")
+            .field("uart", &self.uart)
+            .field("dma_channel", &self.dma_channel)
+            .field("transmitting", &self.transfer.is_some())
+            .field("dir_pin", &self.dir_pin)
+            .field("data", &self.data)
+            .field("baudrate", &self.baudrate)
+            .finish()
+    }
+}
+
+impl<'a, D, P, DIR, CH> Rp2040Phy<'a, uart::UartPeripheral<uart::Enabled, D, P>, DIR, CH>
 where
     D: uart::UartDevice,
     P: uart::ValidUartPinout<D>,
     DIR: OutputPin,
+    CH: dma::SingleChannel,
 {
     pub fn new(
         uart: uart::UartPeripheral<uart::Disabled, D, P>,
         mut dir_pin: DIR,
+        dma_channel: CH,
         per_clock: &rp2040_hal::clocks::PeripheralClock,
         buffer: impl Into<crate::phy::BufferHandle<'a>>,
         baudrate: crate::Baudrate,
@@ -117,7 +181,9 @@ where
         dir_pin.set_low().ok().unwrap();
 
         Ok(Self {
-            uart,
+            uart: Some(uart),
+            dma_channel: Some(dma_channel),
+            transfer: None,
             dir_pin,
             data: PhyData::Rx {
                 buffer: buffer.into(),
@@ -128,36 +194,66 @@ where
     }
 }
 
-impl<'a, D, P, DIR> crate::phy::ProfibusPhy
-    for Rp2040Phy<'a, uart::UartPeripheral<uart::Enabled, D, P>, DIR>
+impl<UART, DIR, CH> Drop for Rp2040Phy<'_, UART, DIR, CH>
+where
+    UART: dma::WriteTarget<TransmittedWord = u8>,
+    CH: dma::SingleChannel,
+{
+    fn drop(&mut self) {
+        // The DMA transfer must not outlive the buffer it reads from.
+        if let Some(transfer) = self.transfer.take() {
+            transfer.wait();
+        }
+    }
+}
+
+impl<'a, D, P, DIR, CH> crate::phy::ProfibusPhy
+    for Rp2040Phy<'a, uart::UartPeripheral<uart::Enabled, D, P>, DIR, CH>
 where
     D: uart::UartDevice,
     P: uart::ValidUartPinout<D>,
     DIR: OutputPin,
+    CH: dma::SingleChannel,
 {
     fn poll_transmission(&mut self, now: crate::time::Instant) -> bool {
         if let PhyData::Tx {
             buffer,
             length,
-            cursor,
             start_tx,
+            dma_started,
         } = &mut self.data
         {
             if now < *start_tx {
                 // We must still wait before beginning transmission (Tset).
                 true
-            } else if length != cursor {
-                let pending = &buffer[*cursor..*length];
-                let written = match self.uart.write_raw(pending) {
-                    Ok(b) => pending.len() - b.len(),
-                    Err(nb::Error::WouldBlock) => 0,
-                    Err(nb::Error::Other(_)) => unreachable!(),
+            } else if !*dma_started {
+                // Hand the data over to the DMA channel.
+                let source = DmaTxBuffer {
+                    ptr: buffer.as_ptr(),
+                    length: *length,
                 };
-                debug_assert!(written <= *length - *cursor);
-                *cursor += written;
+                let transfer = dma::single_buffer::Config::new(
+                    self.dma_channel.take().unwrap(),
+                    source,
+                    self.uart.take().unwrap(),
+                )
+                .start();
+                self.transfer = Some(transfer);
+                *dma_started = true;
+                true
+            } else if let Some(transfer) = self.transfer.take() {
+                if !transfer.is_done() {
+                    // DMA is still feeding the UART.
+                    self.transfer = Some(transfer);
+                } else {
+                    let (dma_channel, _, uart) = transfer.wait();
+                    self.dma_channel = Some(dma_channel);
+                    self.uart = Some(uart);
+                }
                 true
             } else {
-                let busy = self.uart.uart_is_busy();
+                // All data is in the UART, wait until it was shifted out completely.
+                let busy = self.uart.as_ref().unwrap().uart_is_busy();
                 if !busy {
                     self.data.make_rx();
                     self.dir_pin.set_low().ok().unwrap();
@@ -201,8 +297,8 @@ where
                 self.data = PhyData::Tx {
                     buffer,
                     length,
-                    cursor: 0,
                     start_tx: now + t_set,
+                    dma_started: false,
                 };
                 res
             }
@@ -216,7 +312,8 @@ where
         match &mut self.data {
             PhyData::Tx { .. } => panic!("receive_data() while transmitting!"),
             PhyData::Rx { buffer, length } => {
-                *length += match self.uart.read_raw(&mut buffer[*length..]) {
+                let uart = self.uart.as_mut().unwrap();
+                *length += match uart.read_raw(&mut buffer[*length..]) {
                     Ok(l) => l,
                     Err(nb::Error::WouldBlock) => 0,
                     Err(nb::Error::Other(_)) => {
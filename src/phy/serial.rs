@@ -11,6 +11,10 @@ enum PhyData<'a> {
         buffer: crate::phy::BufferHandle<'a>,
         length: usize,
         cursor: usize,
+        /// Time when writing data may start (after the driver-enable pre delay)
+        write_start: crate::time::Instant,
+        /// Time when the transmission is finished (after the driver-disable post delay)
+        done: Option<crate::time::Instant>,
     },
 }
 
@@ -93,6 +97,7 @@ impl PhyData<'_> {
 ///         // Increased slot time due to USB latency
 ///         .slot_bits(4000)
 ///         .build_verified(&dp_master)
+///         .unwrap()
 /// );
 ///
 /// let mut phy = phy::SerialPortPhy::new("/dev/ttyUSB0", fdl.parameters().baudrate);
@@ -100,9 +105,15 @@ impl PhyData<'_> {
 /// let sleep_time = std::time::Duration::from_micros(3500);
 /// ```
 pub struct SerialPortPhy {
-    port: Box<dyn serialport::SerialPort>,
+    port: Option<Box<dyn serialport::SerialPort>>,
+    serial_port: String,
+    baudrate: crate::Baudrate,
     data: PhyData<'static>,
     last_rx: Option<crate::time::Instant>,
+    tx_enable_pre_delay: u32,
+    tx_disable_post_delay: u32,
+    /// Whether the receive buffer overflowed since the last `poll_rx_overflow()`
+    rx_overflow: bool,
 }
 
 impl SerialPortPhy {
@@ -111,6 +122,57 @@ impl SerialPortPhy {
     }
 
     fn new_inner(serial_port: Cow<'_, str>, baudrate: crate::Baudrate) -> Self {
+        let port = Self::open(&serial_port, baudrate).unwrap();
+
+        let buffer = crate::phy::BufferHandle::from(vec![0u8; 512]);
+
+        Self {
+            port: Some(port),
+            serial_port: serial_port.into_owned(),
+            baudrate,
+            data: PhyData::Rx { buffer, length: 0 },
+            last_rx: None,
+            tx_enable_pre_delay: 0,
+            tx_disable_post_delay: 0,
+            rx_overflow: false,
+        }
+    }
+
+    /// Drive the RTS line as the RS-485 driver-enable signal, with the given delays.
+    ///
+    /// Some RS-485 adapters need a deliberate delay between enabling the line driver and the start
+    /// of the transmission, and between the end of the transmission and disabling the line driver.
+    /// Otherwise the first or last bits of a telegram get truncated.
+    ///
+    /// With this option, RTS is asserted before each transmission and data is only written after
+    /// `tx_enable_pre_delay` bit times.  After the last byte was sent, RTS is deasserted once
+    /// `tx_disable_post_delay` bit times have passed.  The transmission is only reported as done
+    /// after the post delay elapsed.
+    ///
+    /// Setting both delays to zero disables driving the RTS line (this is the default).
+    ///
+    /// Returns an error when the RTS line cannot be controlled on this serial port.
+    pub fn with_tx_delays(
+        mut self,
+        tx_enable_pre_delay: u32,
+        tx_disable_post_delay: u32,
+    ) -> io::Result<Self> {
+        self.tx_enable_pre_delay = tx_enable_pre_delay;
+        self.tx_disable_post_delay = tx_disable_post_delay;
+        if let Some(port) = self.port.as_deref_mut() {
+            port.write_request_to_send(false)?;
+        }
+        Ok(self)
+    }
+
+    fn direction_control(&self) -> bool {
+        self.tx_enable_pre_delay != 0 || self.tx_disable_post_delay != 0
+    }
+
+    fn open(
+        serial_port: &str,
+        baudrate: crate::Baudrate,
+    ) -> io::Result<Box<dyn serialport::SerialPort>> {
         use serialport::SerialPort;
 
         #[allow(unused_mut)]
@@ -119,25 +181,66 @@ impl SerialPortPhy {
             .flow_control(serialport::FlowControl::None)
             .parity(serialport::Parity::Even)
             .stop_bits(serialport::StopBits::One)
-            .open_native()
-            .unwrap();
+            .open_native()?;
 
-        assert_eq!(
-            u64::from(port.baud_rate().unwrap()),
-            baudrate.to_rate(),
-            "baudrate not configured correctly"
-        );
+        if u64::from(port.baud_rate()?) != baudrate.to_rate() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "baudrate not configured correctly",
+            ));
+        }
 
         #[cfg(target_os = "linux")]
-        serialport_low_latency::enable_low_latency(&mut port).unwrap();
+        serialport_low_latency::enable_low_latency(&mut port).map_err(io::Error::other)?;
 
-        let buffer = crate::phy::BufferHandle::from(vec![0u8; 512]);
+        Ok(Box::new(port))
+    }
 
-        Self {
-            port: Box::new(port),
-            data: PhyData::Rx { buffer, length: 0 },
-            last_rx: None,
+    /// Reopen the serial port after it was disconnected.
+    ///
+    /// When an I/O error occurs (for example because a USB-RS485 adapter was unplugged), the
+    /// `SerialPortPhy` closes the serial port and behaves as if the bus was silent: Nothing gets
+    /// transmitted and no data is received.  The application can then call `reopen()`
+    /// periodically to recover.  Once the port is open again, the FDL layer will rejoin the token
+    /// ring on its own, just like after a token loss.
+    ///
+    /// Reopening a port which is still connected is also permitted.  Any ongoing transmission
+    /// and all received data are discarded.
+    pub fn reopen(&mut self) -> io::Result<()> {
+        self.port = None;
+        self.reset_data();
+        let mut port = Self::open(&self.serial_port, self.baudrate)?;
+        if self.direction_control() {
+            port.write_request_to_send(false)?;
         }
+        self.port = Some(port);
+        log::info!("Reopened serial port {}.", self.serial_port);
+        Ok(())
+    }
+
+    /// Whether the serial port is currently open.
+    ///
+    /// Returns `false` after an I/O error closed the port.  See [`SerialPortPhy::reopen()`].
+    pub fn is_connected(&self) -> bool {
+        self.port.is_some()
+    }
+
+    fn reset_data(&mut self) {
+        self.data.make_rx();
+        if let PhyData::Rx { length, .. } = &mut self.data {
+            *length = 0;
+        }
+        self.last_rx = None;
+    }
+
+    fn disconnect(&mut self, e: io::Error) {
+        log::error!(
+            "I/O error on serial port {}, disconnecting: {}",
+            self.serial_port,
+            e
+        );
+        self.port = None;
+        self.reset_data();
     }
 
     fn write(port: &mut dyn serialport::SerialPort, buffer: &[u8]) -> io::Result<usize> {
@@ -145,12 +248,12 @@ impl SerialPortPhy {
         port.write(buffer)
     }
 
-    fn get_output_queue(&mut self) -> io::Result<usize> {
-        Ok(usize::try_from(self.port.bytes_to_write().unwrap()).unwrap())
+    fn get_output_queue(port: &mut dyn serialport::SerialPort) -> io::Result<usize> {
+        Ok(usize::try_from(port.bytes_to_write()?).unwrap())
     }
 
     fn read(port: &mut dyn serialport::SerialPort, buffer: &mut [u8]) -> io::Result<usize> {
-        let bytes_to_read = port.bytes_to_read().unwrap();
+        let bytes_to_read = port.bytes_to_read()?;
         if bytes_to_read == 0 {
             Ok(0)
         } else {
@@ -161,29 +264,70 @@ impl SerialPortPhy {
 }
 
 impl crate::phy::ProfibusPhy for SerialPortPhy {
-    fn poll_transmission(&mut self, _now: crate::time::Instant) -> bool {
+    fn poll_rx_overflow(&mut self) -> bool {
+        core::mem::take(&mut self.rx_overflow)
+    }
+
+    fn poll_transmission(&mut self, now: crate::time::Instant) -> bool {
+        let direction_control = self.direction_control();
+        let post_delay = self.baudrate.bits_to_time(self.tx_disable_post_delay);
+        let Some(port) = self.port.as_deref_mut() else {
+            return false;
+        };
         if let PhyData::Tx {
             buffer,
             length,
             cursor,
+            write_start,
+            done,
         } = &mut self.data
         {
-            if length != cursor {
-                // Need to submit more data.
-                let written = Self::write(&mut *self.port, &buffer[*cursor..*length]).unwrap();
-                debug_assert!(written <= *length - *cursor);
-                *cursor += written;
+            if now < *write_start {
+                // Still waiting for the driver-enable pre delay.
                 true
+            } else if length != cursor {
+                // Need to submit more data.
+                match Self::write(port, &buffer[*cursor..*length]) {
+                    Ok(written) => {
+                        debug_assert!(written <= *length - *cursor);
+                        *cursor += written;
+                        true
+                    }
+                    Err(e) => {
+                        self.disconnect(e);
+                        false
+                    }
+                }
+            } else if let Some(done) = *done {
+                // Waiting for the driver-disable post delay.
+                if now < done {
+                    return true;
+                }
+                if let Err(e) = port.write_request_to_send(false) {
+                    self.disconnect(e.into());
+                    return false;
+                }
+                self.data.make_rx();
+                false
             } else {
                 // Everything was submitted already.
-                let queued = self.get_output_queue().unwrap();
-                if queued == 0 {
-                    // All data was sent.
-                    self.data.make_rx();
-                    false
-                } else {
+                match Self::get_output_queue(port) {
+                    Ok(0) if direction_control => {
+                        // All data was sent, start the post delay.
+                        *done = Some(now + post_delay);
+                        self.poll_transmission(now)
+                    }
+                    Ok(0) => {
+                        // All data was sent.
+                        self.data.make_rx();
+                        false
+                    }
                     // Still sending.
-                    true
+                    Ok(_) => true,
+                    Err(e) => {
+                        self.disconnect(e);
+                        false
+                    }
                 }
             }
         } else {
@@ -227,14 +371,32 @@ impl crate::phy::ProfibusPhy for SerialPortPhy {
                     // Don't transmit anything.
                     return res;
                 }
-                let cursor = Self::write(&mut *self.port, &buffer[..length]).unwrap();
-                debug_assert!(cursor <= length);
-                let buffer = std::mem::replace(buffer, [].into());
-                self.data = PhyData::Tx {
-                    buffer,
-                    length,
-                    cursor,
+                let Some(port) = self.port.as_deref_mut() else {
+                    // Disconnected, the data is lost.
+                    return res;
+                };
+                let result = if self.tx_enable_pre_delay != 0 || self.tx_disable_post_delay != 0 {
+                    // Enable the line driver and defer writing until the pre delay has passed.
+                    port.write_request_to_send(true)
+                        .map(|_| 0)
+                        .map_err(io::Error::from)
+                } else {
+                    Self::write(port, &buffer[..length])
                 };
+                match result {
+                    Ok(cursor) => {
+                        debug_assert!(cursor <= length);
+                        let buffer = std::mem::replace(buffer, [].into());
+                        self.data = PhyData::Tx {
+                            buffer,
+                            length,
+                            cursor,
+                            write_start: now + self.baudrate.bits_to_time(self.tx_enable_pre_delay),
+                            done: None,
+                        };
+                    }
+                    Err(e) => self.disconnect(e),
+                }
                 res
             }
         }
@@ -244,15 +406,29 @@ impl crate::phy::ProfibusPhy for SerialPortPhy {
     where
         F: FnOnce(&[u8]) -> (usize, R),
     {
+        if let (Some(port), PhyData::Rx { buffer, length }) =
+            (self.port.as_deref_mut(), &mut self.data)
+        {
+            match Self::read(port, &mut buffer[*length..]) {
+                Ok(0) => (),
+                Ok(received) => {
+                    *length += received;
+                    self.last_rx = Some(now);
+                }
+                Err(e) => self.disconnect(e),
+            }
+        }
+
         match &mut self.data {
             PhyData::Tx { .. } => panic!("receive_data() while transmitting!"),
             PhyData::Rx { buffer, length } => {
-                let last_length = *length;
-                *length += Self::read(&mut *self.port, &mut buffer[*length..]).unwrap();
-                if last_length != *length {
-                    self.last_rx = Some(now);
+                if let Some(port) = self.port.as_deref() {
+                    if crate::phy::rx_buffer_overflowed(buffer, *length, || {
+                        port.bytes_to_read().map_or(0, |n| n as usize)
+                    }) {
+                        self.rx_overflow = true;
+                    }
                 }
-                debug_assert!(*length <= buffer.len());
                 let (drop, res) = f(&buffer[..*length]);
                 match drop {
                     0 => (),
@@ -8,6 +8,57 @@ struct CapturedTelegram {
     length: usize,
 }
 
+/// Model of line errors injected by the simulated bus
+///
+/// All probabilities are in the range `0.0..=1.0`.  The errors are generated from a
+/// pseudo-random number generator with a fixed `seed` so simulations stay reproducible.
+///
+/// Errors are applied to each transmission when it is put onto the bus, so all receivers see the
+/// same corrupted data.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ErrorModel {
+    /// Probability of each transmitted bit being flipped
+    pub bit_error_rate: f64,
+    /// Probability of a whole telegram getting lost
+    pub drop_probability: f64,
+    /// Probability of a telegram getting cut off after a random number of bytes
+    pub truncate_probability: f64,
+    /// Seed for the pseudo-random number generator
+    pub seed: u64,
+}
+
+impl ErrorModel {
+    /// Whether this model never injects any errors
+    pub fn is_error_free(&self) -> bool {
+        self.bit_error_rate <= 0.0
+            && self.drop_probability <= 0.0
+            && self.truncate_probability <= 0.0
+    }
+}
+
+/// Small pseudo-random number generator (SplitMix64) for deterministic error injection
+#[derive(Debug)]
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Random number in the range `0.0..1.0`
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn chance(&mut self, probability: f64) -> bool {
+        probability > 0.0 && self.next_f64() < probability
+    }
+}
+
 #[derive(Debug)]
 struct SimulatorBus {
     baudrate: crate::Baudrate,
@@ -16,6 +67,8 @@ struct SimulatorBus {
     bus_time: crate::time::Instant,
     /// Which master is currently holding the token.  We use this to verify correct timing.
     token_master: Option<u8>,
+    error_model: ErrorModel,
+    rng: Rng,
 }
 
 impl SimulatorBus {
@@ -26,6 +79,41 @@ impl SimulatorBus {
             stream: Vec::new(),
             bus_time: crate::time::Instant::ZERO,
             token_master: None,
+            error_model: ErrorModel::default(),
+            rng: Rng(0),
+        }
+    }
+
+    pub fn set_error_model(&mut self, error_model: ErrorModel) {
+        self.rng = Rng(error_model.seed);
+        self.error_model = error_model;
+    }
+
+    /// Apply the error model to a transmission
+    fn inject_errors(&mut self, name: &'static str, data: &mut Vec<u8>) {
+        if self.error_model.is_error_free() {
+            return;
+        }
+
+        if self.rng.chance(self.error_model.drop_probability) {
+            log::trace!("{:8} {}: (dropped)", self.bus_time.total_micros(), name);
+            data.clear();
+            return;
+        }
+
+        if data.len() > 1 && self.rng.chance(self.error_model.truncate_probability) {
+            // Keep at least one byte, a completely lost telegram is modelled by dropping.
+            let length =
+                1 + usize::try_from(self.rng.next_u64() % (data.len() as u64 - 1)).unwrap();
+            data.truncate(length);
+        }
+
+        for byte in data.iter_mut() {
+            for bit in 0..8 {
+                if self.rng.chance(self.error_model.bit_error_rate) {
+                    *byte ^= 1 << bit;
+                }
+            }
         }
     }
 
@@ -47,6 +135,40 @@ impl SimulatorBus {
         &self.stream[cursor..self.current_cursor()]
     }
 
+    /// Cursor up to which data has been delivered to a receiver which gets it in chunks.
+    ///
+    /// Chunk `n` of a telegram is delivered `n * inter_chunk_delay` after its last byte was
+    /// transmitted.  Data of later telegrams is only delivered once all earlier chunks were.
+    pub fn chunked_cursor(
+        &self,
+        cursor: usize,
+        chunk_size: usize,
+        inter_chunk_delay: crate::time::Duration,
+    ) -> usize {
+        let first = self
+            .telegrams
+            .partition_point(|t| t.index + t.length <= cursor);
+
+        let mut delivered = cursor;
+        for t in &self.telegrams[first..] {
+            let mut offset = 0;
+            let mut delay = crate::time::Duration::ZERO;
+            while offset < t.length {
+                let end = (offset + chunk_size).min(t.length);
+                let complete = t.timestamp
+                    + self.baudrate.bits_to_time(u32::try_from(end * 11).unwrap())
+                    + delay;
+                if self.bus_time < complete {
+                    return delivered;
+                }
+                delivered = delivered.max(t.index + end);
+                offset = end;
+                delay += inter_chunk_delay;
+            }
+        }
+        delivered
+    }
+
     pub fn is_active(&self) -> Option<&'static str> {
         if self.telegrams.len() == 0 {
             return None;
@@ -65,7 +187,8 @@ impl SimulatorBus {
         }
     }
 
-    pub fn enqueue_telegram(&mut self, name: &'static str, mut data: Vec<u8>) {
+    /// Put a transmission onto the bus and return the number of bytes that actually made it.
+    pub fn enqueue_telegram(&mut self, name: &'static str, mut data: Vec<u8>) -> usize {
         if let Some(active_sender) = self.is_active() {
             panic!(
                 "\"{}\" attempted transmission while \"{}\" is still sending!",
@@ -75,7 +198,7 @@ impl SimulatorBus {
 
         // Drop out early if nothing needs to be sent.
         if data.len() == 0 {
-            return;
+            return 0;
         }
 
         let sa = if let Some(Ok((decoded, length))) = crate::fdl::Telegram::deserialize(&data) {
@@ -141,6 +264,11 @@ impl SimulatorBus {
             }
         }
 
+        self.inject_errors(name, &mut data);
+        if data.len() == 0 {
+            return 0;
+        }
+
         if let Some(Ok(decoded)) = crate::fdl::Telegram::deserialize(&data) {
             log::trace!("{:8} {}: {:?}", self.bus_time.total_micros(), name, decoded);
         } else {
@@ -152,14 +280,16 @@ impl SimulatorBus {
             log::trace!("{:8} {}: {}", self.bus_time.total_micros(), name, data_fmt);
         }
 
+        let length = data.len();
         let telegram = CapturedTelegram {
             sender: name,
             timestamp: self.bus_time,
             index: self.stream.len(),
-            length: data.len(),
+            length,
         };
         self.stream.append(&mut data);
         self.telegrams.push(telegram);
+        length
     }
 
     pub fn print_log(&self) {
@@ -196,6 +326,9 @@ pub struct SimulatorPhy {
     bus: sync::Arc<sync::Mutex<SimulatorBus>>,
     cursor: usize,
     name: &'static str,
+    rx_chunking: Option<(usize, crate::time::Duration)>,
+    line_errors: crate::fdl::LineErrorCounters,
+    rx_overflow: bool,
 }
 
 impl SimulatorPhy {
@@ -204,6 +337,9 @@ impl SimulatorPhy {
             bus: sync::Arc::new(sync::Mutex::new(SimulatorBus::new(baudrate))),
             cursor: 0,
             name,
+            rx_chunking: None,
+            line_errors: Default::default(),
+            rx_overflow: false,
         }
     }
 
@@ -212,6 +348,9 @@ impl SimulatorPhy {
             bus: self.bus.clone(),
             cursor: 0,
             name,
+            rx_chunking: None,
+            line_errors: Default::default(),
+            rx_overflow: false,
         }
     }
 
@@ -223,10 +362,45 @@ impl SimulatorPhy {
         self.bus.lock().unwrap().bus_time += dur;
     }
 
+    pub fn baudrate(&self) -> crate::Baudrate {
+        self.bus.lock().unwrap().baudrate
+    }
+
     pub fn bus_time(&self) -> crate::time::Instant {
         self.bus.lock().unwrap().bus_time
     }
 
+    /// Inject line errors into all transmissions on the simulated bus.
+    ///
+    /// This affects all PHYs connected to the same bus.  Pass `ErrorModel::default()` to go back
+    /// to error-free transmission.
+    pub fn set_error_model(&self, error_model: ErrorModel) {
+        self.bus.lock().unwrap().set_error_model(error_model);
+    }
+
+    /// Deliver received data to this PHY in chunks of `chunk_size` bytes.
+    ///
+    /// Each further chunk of a telegram is delayed by another `inter_chunk_delay`.  This
+    /// reproduces the behavior of USB-serial adapters on non-realtime systems.  Other PHYs on the
+    /// same bus are not affected.
+    ///
+    /// # Panics
+    /// Panics if `chunk_size` is zero.
+    pub fn set_rx_chunking(&mut self, chunk_size: usize, inter_chunk_delay: crate::time::Duration) {
+        assert!(chunk_size > 0, "chunk size must not be zero");
+        self.rx_chunking = Some((chunk_size, inter_chunk_delay));
+    }
+
+    /// Report the given line errors on the next call to `poll_line_errors()` of this PHY.
+    pub fn inject_line_errors(&mut self, errors: crate::fdl::LineErrorCounters) {
+        self.line_errors.add(errors);
+    }
+
+    /// Report a receive buffer overflow on the next call to `poll_rx_overflow()` of this PHY.
+    pub fn inject_rx_overflow(&mut self) {
+        self.rx_overflow = true;
+    }
+
     pub fn print_bus_log(&self) {
         self.bus.lock().unwrap().print_log();
     }
@@ -270,8 +444,7 @@ impl crate::phy::ProfibusPhy for SimulatorPhy {
         let (length, res) = f(&mut buffer);
         buffer.truncate(length);
 
-        bus.enqueue_telegram(self.name, buffer);
-        self.cursor += length;
+        self.cursor += bus.enqueue_telegram(self.name, buffer);
 
         res
     }
@@ -288,7 +461,12 @@ impl crate::phy::ProfibusPhy for SimulatorPhy {
         }
 
         let bus = self.bus.lock().unwrap();
-        let pending = bus.pending_bytes(self.cursor);
+        let pending = if let Some((chunk_size, inter_chunk_delay)) = self.rx_chunking {
+            let end = bus.chunked_cursor(self.cursor, chunk_size, inter_chunk_delay);
+            &bus.stream[self.cursor..end]
+        } else {
+            bus.pending_bytes(self.cursor)
+        };
 
         let (drop, res) = f(pending);
         assert!(
@@ -300,6 +478,14 @@ impl crate::phy::ProfibusPhy for SimulatorPhy {
 
         res
     }
+
+    fn poll_line_errors(&mut self, now: crate::time::Instant) -> crate::fdl::LineErrorCounters {
+        core::mem::take(&mut self.line_errors)
+    }
+
+    fn poll_rx_overflow(&mut self) -> bool {
+        core::mem::take(&mut self.rx_overflow)
+    }
 }
 
 pub struct SimulationIterator<'a, F> {
@@ -387,4 +573,82 @@ mod tests {
 
         phy1.print_bus_log();
     }
+
+    #[test]
+    fn error_model() {
+        let mut phy1 = SimulatorPhy::new(crate::Baudrate::B19200, "phy1");
+        let mut phy2 = phy1.duplicate("phy2");
+        let data = &[0xde, 0xad, 0xbe, 0xef, 0x12, 0x34];
+
+        let mut now = crate::time::Instant::ZERO;
+        let mut transmit_and_receive = |phy1: &mut SimulatorPhy, phy2: &mut SimulatorPhy| {
+            phy1.transmit_data(now, |buf| {
+                buf[..data.len()].copy_from_slice(data);
+                (data.len(), ())
+            });
+            now += crate::time::Duration::from_millis(100);
+            phy1.set_bus_time(now);
+            assert!(!phy1.poll_transmission(now));
+            phy2.receive_data(now, |buf| (buf.len(), buf.to_vec()))
+        };
+
+        phy1.set_error_model(ErrorModel {
+            drop_probability: 1.0,
+            ..Default::default()
+        });
+        assert_eq!(transmit_and_receive(&mut phy1, &mut phy2), &[]);
+
+        phy1.set_error_model(ErrorModel {
+            truncate_probability: 1.0,
+            ..Default::default()
+        });
+        let received = transmit_and_receive(&mut phy1, &mut phy2);
+        assert!(received.len() > 0 && received.len() < data.len());
+        assert_eq!(received, &data[..received.len()]);
+
+        phy1.set_error_model(ErrorModel {
+            bit_error_rate: 1.0,
+            ..Default::default()
+        });
+        let inverted: Vec<u8> = data.iter().map(|b| !b).collect();
+        assert_eq!(transmit_and_receive(&mut phy1, &mut phy2), inverted);
+
+        phy1.set_error_model(ErrorModel::default());
+        assert_eq!(transmit_and_receive(&mut phy1, &mut phy2), data);
+
+        // The sender must not see its own transmissions.
+        assert_eq!(phy1.receive_data(now, |buf| (0, buf.len())), 0);
+    }
+
+    #[test]
+    fn rx_chunking() {
+        let mut phy1 = SimulatorPhy::new(crate::Baudrate::B19200, "phy1");
+        let mut phy2 = phy1.duplicate("phy2");
+        phy2.set_rx_chunking(4, crate::time::Duration::from_millis(5));
+        let data = &[0xde, 0xad, 0xbe, 0xef, 0x12, 0x34, 0x56, 0x78, 0x9a];
+
+        let start = crate::time::Instant::ZERO;
+        phy1.transmit_data(start, |buf| {
+            buf[..data.len()].copy_from_slice(data);
+            (data.len(), ())
+        });
+        let bytes = |n: u32| crate::Baudrate::B19200.bits_to_time(n * 11);
+        let mut received_at = |now: crate::time::Instant| {
+            phy1.set_bus_time(now);
+            phy2.receive_data(now, |buf| (0, buf.len()))
+        };
+
+        assert_eq!(received_at(start + bytes(3)), 0);
+        assert_eq!(received_at(start + bytes(4)), 4);
+        // The whole telegram was transmitted but the second chunk is still delayed.
+        assert_eq!(received_at(start + bytes(9)), 4);
+        assert_eq!(
+            received_at(start + bytes(8) + crate::time::Duration::from_millis(5)),
+            8
+        );
+        assert_eq!(
+            received_at(start + bytes(9) + crate::time::Duration::from_millis(10)),
+            9
+        );
+    }
 }
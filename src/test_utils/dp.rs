@@ -0,0 +1,331 @@
+//! DP master test fixtures
+
+/// Minimal DP slave which answers the requests of the DP master.
+#[derive(Debug, Default)]
+pub struct FakeSlave {
+    pub address: u8,
+    pub ident_number: u16,
+    /// Station status byte and groups from the last Set_Prm telegram
+    pub parameters: Option<(u8, u8)>,
+    /// Watchdog factors from the last Set_Prm telegram
+    pub watchdog_factors: (u8, u8),
+    /// Min Tsdr from the last Set_Prm telegram
+    pub min_tsdr_bits: u8,
+    /// User parameters from the last Set_Prm telegram
+    pub user_parameters: Vec<u8>,
+    pub configured: bool,
+    /// Configuration from the last Chk_Cfg telegram
+    pub config: Vec<u8>,
+    pub inputs: Vec<u8>,
+    /// Don't respond to any requests
+    pub offline: bool,
+    /// Outputs received with the last data exchange telegram
+    pub outputs: Vec<u8>,
+    pub data_exchange_count: usize,
+    pub diagnostics_count: usize,
+    /// Reject all parameters with a parameter fault
+    pub prm_fault: bool,
+    pub set_prm_count: usize,
+    /// Contents of the DPV1 data record at slot 1, index 2
+    pub dpv1_record: Vec<u8>,
+    /// DPV1 response which is returned on the next poll
+    pub dpv1_response: Option<Vec<u8>>,
+    /// Outputs which are actually applied, taking SYNC mode into account
+    pub applied_outputs: Vec<u8>,
+    /// SYNC and FREEZE modes entered using Global Control
+    pub sync_mode: bool,
+    pub freeze_mode: bool,
+    /// Don't react to Global Control commands
+    pub ignore_global_control: bool,
+    /// Lose parameters after each data exchange, like with a mismatched watchdog
+    pub lose_parameters: bool,
+}
+
+impl FakeSlave {
+    pub fn global_control(&mut self, command: u8, groups: u8) {
+        let own_groups = self.parameters.map(|(_, g)| g).unwrap_or(0);
+        if self.ignore_global_control
+            || !self.configured
+            || (groups != 0 && own_groups & groups == 0)
+        {
+            return;
+        }
+        if command & crate::consts::GC_SYNC != 0 {
+            // Each SYNC command latches the last received outputs
+            self.sync_mode = true;
+            self.applied_outputs = self.outputs.clone();
+        }
+        if command & crate::consts::GC_UNSYNC != 0 {
+            self.sync_mode = false;
+            self.applied_outputs = self.outputs.clone();
+        }
+        if command & crate::consts::GC_FREEZE != 0 {
+            self.freeze_mode = true;
+        }
+        if command & crate::consts::GC_UNFREEZE != 0 {
+            self.freeze_mode = false;
+        }
+    }
+
+    pub fn respond<'a>(
+        &mut self,
+        t: &crate::fdl::DataTelegram,
+        pdu_buffer: &'a mut [u8],
+    ) -> crate::fdl::Telegram<'a> {
+        let response = |pdu: &'a [u8]| {
+            crate::fdl::Telegram::Data(crate::fdl::DataTelegram {
+                h: crate::fdl::DataTelegramHeader {
+                    da: t.h.sa,
+                    sa: t.h.da,
+                    dsap: t.h.ssap,
+                    ssap: t.h.dsap,
+                    fc: crate::fdl::FunctionCode::Response {
+                        state: crate::fdl::ResponseState::Slave,
+                        status: crate::fdl::ResponseStatus::DataLow,
+                    },
+                },
+                pdu,
+            })
+        };
+
+        if t.is_fdl_status_request().is_some() {
+            return crate::fdl::Telegram::Data(crate::fdl::DataTelegram {
+                h: crate::fdl::DataTelegramHeader {
+                    da: t.h.sa,
+                    sa: t.h.da,
+                    dsap: None,
+                    ssap: None,
+                    fc: crate::fdl::FunctionCode::Response {
+                        state: crate::fdl::ResponseState::Slave,
+                        status: crate::fdl::ResponseStatus::Ok,
+                    },
+                },
+                pdu: &[],
+            });
+        }
+
+        match t.h.dsap {
+            crate::consts::SAP_SLAVE_SET_ADDRESS => {
+                assert_eq!(t.pdu[1..3], self.ident_number.to_be_bytes());
+                self.address = t.pdu[0];
+                crate::fdl::ShortConfirmation.into()
+            }
+            crate::consts::SAP_SLAVE_DIAGNOSIS => {
+                self.diagnostics_count += 1;
+                let flags = if self.prm_fault && self.configured {
+                    self.configured = false;
+                    crate::dp::DiagnosticFlags::PERMANENT_BIT
+                        | crate::dp::DiagnosticFlags::STATION_NOT_READY
+                        | crate::dp::DiagnosticFlags::PARAMETER_FAULT
+                } else if self.configured {
+                    let mut flags = crate::dp::DiagnosticFlags::PERMANENT_BIT;
+                    flags.set(crate::dp::DiagnosticFlags::SYNC_MODE, self.sync_mode);
+                    flags.set(crate::dp::DiagnosticFlags::FREEZE_MODE, self.freeze_mode);
+                    flags
+                } else {
+                    crate::dp::DiagnosticFlags::PERMANENT_BIT
+                        | crate::dp::DiagnosticFlags::STATION_NOT_READY
+                        | crate::dp::DiagnosticFlags::PARAMETER_REQUIRED
+                };
+                pdu_buffer[0..2].copy_from_slice(&flags.bits().to_le_bytes());
+                pdu_buffer[2] = 0x00;
+                pdu_buffer[3] = 0xff;
+                pdu_buffer[4..6].copy_from_slice(&self.ident_number.to_be_bytes());
+                response(&pdu_buffer[..6])
+            }
+            crate::consts::SAP_SLAVE_SET_PRM => {
+                self.parameters = Some((t.pdu[0], t.pdu[6]));
+                self.watchdog_factors = (t.pdu[1], t.pdu[2]);
+                self.min_tsdr_bits = t.pdu[3];
+                self.user_parameters = t.pdu[7..].to_vec();
+                self.set_prm_count += 1;
+                crate::fdl::ShortConfirmation.into()
+            }
+            crate::consts::SAP_SLAVE_CHK_CFG => {
+                self.configured = true;
+                self.config = t.pdu.to_vec();
+                crate::fdl::ShortConfirmation.into()
+            }
+            crate::consts::SAP_SLAVE_GET_CFG => {
+                let len = self.config.len();
+                pdu_buffer[..len].copy_from_slice(&self.config);
+                response(&pdu_buffer[..len])
+            }
+            crate::consts::SAP_SLAVE_DATA_EXCHANGE if !self.configured => {
+                crate::fdl::Telegram::Data(crate::fdl::DataTelegram {
+                    h: crate::fdl::DataTelegramHeader {
+                        da: t.h.sa,
+                        sa: t.h.da,
+                        dsap: t.h.ssap,
+                        ssap: t.h.dsap,
+                        fc: crate::fdl::FunctionCode::Response {
+                            state: crate::fdl::ResponseState::Slave,
+                            status: crate::fdl::ResponseStatus::SapNotEnabled,
+                        },
+                    },
+                    pdu: &[],
+                })
+            }
+            crate::consts::SAP_SLAVE_DATA_EXCHANGE => {
+                if self.lose_parameters {
+                    self.configured = false;
+                }
+                self.outputs = t.pdu.to_vec();
+                if !self.sync_mode {
+                    self.applied_outputs = self.outputs.clone();
+                }
+                self.data_exchange_count += 1;
+                let len = self.inputs.len();
+                pdu_buffer[..len].copy_from_slice(&self.inputs);
+                response(&pdu_buffer[..len])
+            }
+            crate::consts::SAP_SLAVE_DPV1_MS1 => {
+                if t.pdu.is_empty() {
+                    // Poll for the response
+                    return match self.dpv1_response.take() {
+                        Some(r) => {
+                            let len = r.len();
+                            pdu_buffer[..len].copy_from_slice(&r);
+                            response(&pdu_buffer[..len])
+                        }
+                        None => crate::fdl::ShortConfirmation.into(),
+                    };
+                }
+                let (function, slot, index, length) = (t.pdu[0], t.pdu[1], t.pdu[2], t.pdu[3]);
+                self.dpv1_response = Some(if (slot, index) != (1, 2) {
+                    // Error response: Invalid index
+                    vec![function | 0x80, 0x80, 0xb0, 0x00]
+                } else if function == 0x5e {
+                    let mut r = vec![function, slot, index, length];
+                    r.extend_from_slice(&self.dpv1_record[..usize::from(length)]);
+                    r
+                } else {
+                    self.dpv1_record = t.pdu[4..].to_vec();
+                    vec![function, slot, index, length]
+                });
+                crate::fdl::ShortConfirmation.into()
+            }
+            sap => panic!("FakeSlave got request to unexpected SAP {sap:?}"),
+        }
+    }
+}
+
+/// Add a peripheral at address #8 with one byte of inputs and outputs to the DP master.
+///
+/// Returns a [`FakeSlave`] answering for this peripheral with the ident number from `options`.
+pub fn add_fake_peripheral<'a>(
+    dp_master: &mut crate::dp::DpMaster<'a>,
+    options: crate::dp::PeripheralOptions<'a>,
+) -> (crate::dp::PeripheralHandle, FakeSlave) {
+    let slave = FakeSlave {
+        address: 8,
+        ident_number: options.ident_number,
+        inputs: vec![0x42],
+        ..Default::default()
+    };
+    let handle = dp_master.add(crate::dp::Peripheral::new(
+        8,
+        options,
+        vec![0u8; 1],
+        vec![0u8; 1],
+    ));
+    (handle, slave)
+}
+
+/// Run one transmission of the DP master and let the slave respond.
+///
+/// Returns the `(control_command, group_select)` when a Global Control telegram was sent.
+pub fn run_dp_master(
+    dp_master: &mut crate::dp::DpMaster,
+    slave: &mut FakeSlave,
+) -> Option<(u8, u8)> {
+    run_dp_master_at(dp_master, slave, crate::time::Instant::ZERO)
+}
+
+pub fn run_dp_master_at(
+    dp_master: &mut crate::dp::DpMaster,
+    slave: &mut FakeSlave,
+    now: crate::time::Instant,
+) -> Option<(u8, u8)> {
+    let fdl = crate::fdl::FdlActiveStation::new(Default::default());
+    run_dp_master_with_fdl(dp_master, slave, now, &fdl)
+}
+
+pub fn run_dp_master_with_fdl(
+    dp_master: &mut crate::dp::DpMaster,
+    slave: &mut FakeSlave,
+    now: crate::time::Instant,
+    fdl: &crate::fdl::FdlActiveStation,
+) -> Option<(u8, u8)> {
+    let mut buf = [0u8; 256];
+    crate::fdl::FdlApplication::transmit_telegram(
+        dp_master,
+        now,
+        fdl,
+        crate::fdl::TelegramTx::new(&mut buf),
+        false,
+    )?;
+    let telegram = match crate::fdl::Telegram::deserialize(&buf) {
+        Some(Ok((crate::fdl::Telegram::Data(t), _))) => t,
+        t => panic!("DP master sent unexpected telegram: {t:?}"),
+    };
+
+    if telegram.h.dsap == crate::consts::SAP_SLAVE_GLOBAL_CONTROL {
+        assert_eq!(telegram.h.da, 0x7f);
+        slave.global_control(telegram.pdu[0], telegram.pdu[1]);
+        return Some((telegram.pdu[0], telegram.pdu[1]));
+    }
+
+    if slave.offline || telegram.h.da != slave.address {
+        crate::fdl::FdlApplication::handle_timeout(dp_master, now, fdl, telegram.h.da);
+        return None;
+    }
+    let mut pdu_buffer = [0u8; 256];
+    let reply = slave.respond(&telegram, &mut pdu_buffer);
+    crate::fdl::FdlApplication::receive_reply(dp_master, now, fdl, telegram.h.da, reply);
+    None
+}
+
+/// Run `count` transmissions of the DP master using [`run_dp_master()`].
+pub fn run_dp_master_n(dp_master: &mut crate::dp::DpMaster, slave: &mut FakeSlave, count: usize) {
+    for _ in 0..count {
+        run_dp_master(dp_master, slave);
+    }
+}
+
+/// Simulated bus with an FDL active station at address #2 for running a DP master against
+/// [`SimulatedSlave`][`crate::dp::sim::SimulatedSlave`]s.
+pub struct SimulatedBus {
+    pub phy: crate::phy::SimulatorPhy,
+    pub fdl: crate::fdl::FdlActiveStation<'static>,
+    pub now: crate::time::Instant,
+}
+
+impl SimulatedBus {
+    pub fn new(baud: crate::Baudrate) -> Self {
+        let mut fdl =
+            crate::fdl::FdlActiveStation::new(crate::fdl::ParametersBuilder::new(2, baud).build());
+        fdl.set_online();
+        Self {
+            phy: crate::phy::SimulatorPhy::new(baud, "phy#master"),
+            fdl,
+            now: crate::time::Instant::ZERO,
+        }
+    }
+
+    /// Poll the DP master and the slave for `dur`, in steps of 100us.
+    pub fn run(
+        &mut self,
+        dp_master: &mut crate::dp::DpMaster,
+        slave: &mut crate::dp::sim::SimulatedSlave,
+        dur: crate::time::Duration,
+    ) {
+        let end = self.now + dur;
+        while self.now < end {
+            self.phy.set_bus_time(self.now);
+            self.fdl.poll(self.now, &mut self.phy, dp_master);
+            slave.poll(self.now);
+            self.now += crate::time::Duration::from_micros(100);
+        }
+    }
+}
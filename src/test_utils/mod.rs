@@ -1,5 +1,7 @@
 use std::cell::{Cell, RefCell};
 
+pub mod dp;
+
 std::thread_local! {
     static LOG_TIMESTAMP: Cell<i64> = Cell::new(0);
     static ACTIVE_ADDR: Cell<crate::Address> = Cell::new(0);
@@ -11,9 +11,18 @@
  *
  * - [Instant] is used to represent absolute time.
  * - [Duration] is used to represent relative time.
+ * - [Clock] is a source of the current time.
+ *
+ * profirust never reads the time by itself.  Instead, the current time is passed to every
+ * `poll()` call.  With `std`, [Instant::now()] or a [StdClock] can be used for this.  On
+ * `no_std` targets, the current time must be read from a hardware timer, for example by
+ * implementing [Clock] for it.
  *
  * [Instant]: struct.Instant.html
  * [Duration]: struct.Duration.html
+ * [Clock]: trait.Clock.html
+ * [StdClock]: struct.StdClock.html
+ * [Instant::now()]: struct.Instant.html#method.now
  */
 
 use core::{fmt, ops};
@@ -106,6 +115,30 @@ impl Instant {
     pub const fn total_micros(&self) -> i64 {
         self.micros
     }
+
+    /// Add a duration, returning `None` on overflow.
+    pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
+        let micros = i64::try_from(duration.total_micros()).ok()?;
+        self.micros.checked_add(micros).map(Instant::from_micros)
+    }
+
+    /// Subtract a duration, returning `None` on overflow.
+    pub fn checked_sub(&self, duration: Duration) -> Option<Instant> {
+        let micros = i64::try_from(duration.total_micros()).ok()?;
+        self.micros.checked_sub(micros).map(Instant::from_micros)
+    }
+
+    /// The duration that passed since `earlier`, or `None` if `earlier` is later than `self`.
+    pub fn checked_duration_since(&self, earlier: Instant) -> Option<Duration> {
+        let micros = self.micros.checked_sub(earlier.micros)?;
+        u64::try_from(micros).ok().map(Duration::from_micros)
+    }
+
+    /// The duration that passed since `earlier`, or zero if `earlier` is later than `self`.
+    pub fn saturating_duration_since(&self, earlier: Instant) -> Duration {
+        self.checked_duration_since(earlier)
+            .unwrap_or(Duration::ZERO)
+    }
 }
 
 #[cfg(feature = "std")]
@@ -177,6 +210,7 @@ impl ops::Sub<Instant> for Instant {
 
 /// A relative amount of time.
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Duration {
     micros: u64,
 }
@@ -226,6 +260,29 @@ impl Duration {
     pub const fn total_micros(&self) -> u64 {
         self.micros
     }
+
+    /// Add another duration, returning `None` on overflow.
+    pub const fn checked_add(&self, rhs: Duration) -> Option<Duration> {
+        match self.micros.checked_add(rhs.micros) {
+            Some(micros) => Some(Duration { micros }),
+            None => None,
+        }
+    }
+
+    /// Subtract another duration, returning `None` if `rhs` is longer than `self`.
+    pub const fn checked_sub(&self, rhs: Duration) -> Option<Duration> {
+        match self.micros.checked_sub(rhs.micros) {
+            Some(micros) => Some(Duration { micros }),
+            None => None,
+        }
+    }
+
+    /// Subtract another duration, returning zero if `rhs` is longer than `self`.
+    pub const fn saturating_sub(&self, rhs: Duration) -> Duration {
+        Duration {
+            micros: self.micros.saturating_sub(rhs.micros),
+        }
+    }
 }
 
 impl fmt::Display for Duration {
@@ -336,3 +393,134 @@ impl From<Duration> for ::core::time::Duration {
         ::core::time::Duration::from_micros(val.total_micros())
     }
 }
+
+/// A monotonic source of the current time
+///
+/// Any `Fn() -> Instant` closure is a clock as well, so a hardware timer can be used like this:
+///
+/// ```
+/// # struct Timer;
+/// # impl Timer { fn ticks_us(&self) -> u64 { 0 } }
+/// # let timer = Timer;
+/// use profirust::time::{Clock, Instant};
+///
+/// let clock = || Instant::from_micros(timer.ticks_us() as i64);
+/// let now = clock.now();
+/// ```
+pub trait Clock {
+    /// The current time.
+    fn now(&self) -> Instant;
+}
+
+impl<F: Fn() -> Instant> Clock for F {
+    fn now(&self) -> Instant {
+        self()
+    }
+}
+
+/// Monotonic [`Clock`] based on [`std::time::Instant`]
+///
+/// Unlike [`Instant::now()`], which reads the system time, this clock never jumps.  It starts at
+/// [`Instant::ZERO`] when it is created.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+pub struct StdClock {
+    start: ::std::time::Instant,
+}
+
+#[cfg(feature = "std")]
+impl StdClock {
+    pub fn new() -> Self {
+        Self {
+            start: ::std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for StdClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    fn now(&self) -> Instant {
+        Instant::from(self.start)
+    }
+}
+
+/// The RP2040 timer counts microseconds since boot.
+#[cfg(feature = "phy-rp2040")]
+impl Clock for rp2040_hal::Timer {
+    fn now(&self) -> Instant {
+        Instant::from_micros(i64::try_from(self.get_counter().ticks()).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instant_checked_arithmetic() {
+        let max = Instant::from_micros(i64::MAX);
+        let min = Instant::from_micros(i64::MIN);
+        let one = Duration::from_micros(1);
+
+        assert_eq!(max.checked_add(one), None);
+        assert_eq!(
+            (max - one).checked_add(one),
+            Some(Instant::from_micros(i64::MAX))
+        );
+        assert_eq!(
+            Instant::ZERO.checked_add(Duration::from_micros(u64::MAX)),
+            None
+        );
+        assert_eq!(min.checked_sub(one), None);
+        assert_eq!(
+            (min + one).checked_sub(one),
+            Some(Instant::from_micros(i64::MIN))
+        );
+    }
+
+    #[test]
+    fn instant_duration_since() {
+        let max = Instant::from_micros(i64::MAX);
+        let min = Instant::from_micros(i64::MIN);
+        let earlier = max - Duration::from_millis(5);
+
+        assert_eq!(
+            max.saturating_duration_since(earlier),
+            Duration::from_millis(5)
+        );
+        assert_eq!(earlier.saturating_duration_since(max), Duration::ZERO);
+        assert_eq!(earlier.checked_duration_since(max), None);
+        assert_eq!(max.checked_duration_since(min), None);
+        assert_eq!(min.saturating_duration_since(max), Duration::ZERO);
+    }
+
+    #[test]
+    fn duration_checked_arithmetic() {
+        let max = Duration::from_micros(u64::MAX);
+        let one = Duration::from_micros(1);
+
+        assert_eq!(max.checked_add(one), None);
+        assert_eq!((max - one).checked_add(one), Some(max));
+        assert_eq!(Duration::ZERO.checked_sub(one), None);
+        assert_eq!(Duration::ZERO.saturating_sub(one), Duration::ZERO);
+        assert_eq!(max.saturating_sub(one), max - one);
+    }
+
+    #[test]
+    fn clock() {
+        let clock = || Instant::from_millis(42);
+        assert_eq!(clock.now(), Instant::from_millis(42));
+
+        let clock = StdClock::new();
+        let first = clock.now();
+        assert!(first >= Instant::ZERO);
+        assert!(clock.now() >= first);
+    }
+}
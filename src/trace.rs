@@ -0,0 +1,190 @@
+//! Bus traces
+//!
+//! This module is available with the `trace` feature.  It contains the [`PcapWriter`] which
+//! stores telegrams recorded by a [`RecordingPhy`][`crate::phy::RecordingPhy`] as a pcapng
+//! capture.  Such captures can be opened with existing analyzer tools like Wireshark.
+//!
+//! # Example
+//! ```no_run
+//! use profirust::{phy, trace};
+//! # let inner_phy = phy::SimulatorPhy::new(profirust::Baudrate::B19200, "phy");
+//!
+//! let file = std::fs::File::create("profibus.pcapng").unwrap();
+//! let pcap_writer = trace::PcapWriter::new(std::io::BufWriter::new(file)).unwrap();
+//! let mut phy = phy::RecordingPhy::new(inner_phy, pcap_writer);
+//! ```
+use std::io;
+
+/// pcapng block types
+const BLOCK_SECTION_HEADER: u32 = 0x0a0d0d0a;
+const BLOCK_INTERFACE_DESCRIPTION: u32 = 0x00000001;
+const BLOCK_ENHANCED_PACKET: u32 = 0x00000006;
+
+/// `LINKTYPE_PROFIBUS_DL`: Telegrams from start delimiter to end delimiter
+const LINKTYPE_PROFIBUS_DL: u16 = 257;
+
+/// pcapng option codes
+const OPT_ENDOFOPT: u16 = 0;
+const OPT_IF_TSRESOL: u16 = 9;
+const OPT_EPB_FLAGS: u16 = 2;
+
+/// Writer for pcapng captures of PROFIBUS telegrams
+///
+/// Each telegram is stored with its raw on-wire bytes (link-type `PROFIBUS_DL`), its timestamp,
+/// and its direction.  Timestamps are taken from the [`Instant`][`crate::time::Instant`]s passed
+/// to the writer, with microsecond resolution.
+///
+/// The `PcapWriter` implements [`TelegramRecorder`][`crate::phy::TelegramRecorder`] so it can be
+/// used with a [`RecordingPhy`][`crate::phy::RecordingPhy`] directly.  In this case, write errors
+/// are logged and the telegram is dropped from the capture.
+pub struct PcapWriter<W: io::Write> {
+    writer: W,
+}
+
+impl<W: io::Write> PcapWriter<W> {
+    /// Start a new capture by writing the pcapng headers to `writer`.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        // Section Header Block
+        let mut block = Vec::new();
+        block.extend_from_slice(&0x1a2b3c4du32.to_le_bytes());
+        // Version 1.0
+        block.extend_from_slice(&1u16.to_le_bytes());
+        block.extend_from_slice(&0u16.to_le_bytes());
+        // Section length is not specified
+        block.extend_from_slice(&(-1i64).to_le_bytes());
+        write_block(&mut writer, BLOCK_SECTION_HEADER, &block)?;
+
+        // Interface Description Block
+        let mut block = Vec::new();
+        block.extend_from_slice(&LINKTYPE_PROFIBUS_DL.to_le_bytes());
+        block.extend_from_slice(&0u16.to_le_bytes());
+        // Snap length: No telegram is longer than 256 bytes
+        block.extend_from_slice(&256u32.to_le_bytes());
+        // Timestamps in microseconds
+        write_option(&mut block, OPT_IF_TSRESOL, &[6]);
+        write_option(&mut block, OPT_ENDOFOPT, &[]);
+        write_block(&mut writer, BLOCK_INTERFACE_DESCRIPTION, &block)?;
+
+        Ok(Self { writer })
+    }
+
+    /// Write a telegram to the capture.
+    pub fn write_telegram(
+        &mut self,
+        now: crate::time::Instant,
+        direction: crate::phy::Direction,
+        telegram: &crate::fdl::Telegram,
+    ) -> io::Result<()> {
+        let mut buffer = [0u8; 256];
+        let length = telegram
+            .serialize(&mut buffer)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+        let data = &buffer[..length];
+
+        let timestamp = u64::try_from(now.total_micros()).unwrap_or(0);
+        let length = u32::try_from(length).unwrap();
+
+        let mut block = Vec::with_capacity(data.len() + 40);
+        // Interface ID
+        block.extend_from_slice(&0u32.to_le_bytes());
+        block.extend_from_slice(&u32::try_from(timestamp >> 32).unwrap().to_le_bytes());
+        block.extend_from_slice(&u32::try_from(timestamp & 0xffffffff).unwrap().to_le_bytes());
+        // Captured and original length
+        block.extend_from_slice(&length.to_le_bytes());
+        block.extend_from_slice(&length.to_le_bytes());
+        block.extend_from_slice(data);
+        pad_to_32bit(&mut block);
+
+        let flags: u32 = match direction {
+            crate::phy::Direction::Rx => 0b01,
+            crate::phy::Direction::Tx => 0b10,
+        };
+        write_option(&mut block, OPT_EPB_FLAGS, &flags.to_le_bytes());
+        write_option(&mut block, OPT_ENDOFOPT, &[]);
+
+        write_block(&mut self.writer, BLOCK_ENHANCED_PACKET, &block)
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: io::Write> crate::phy::TelegramRecorder for PcapWriter<W> {
+    fn record(
+        &mut self,
+        now: crate::time::Instant,
+        direction: crate::phy::Direction,
+        telegram: &crate::fdl::Telegram,
+    ) {
+        if let Err(e) = self.write_telegram(now, direction, telegram) {
+            log::warn!("Failed writing telegram to capture: {e}");
+        }
+    }
+}
+
+fn pad_to_32bit(buffer: &mut Vec<u8>) {
+    buffer.resize(buffer.len().next_multiple_of(4), 0x00);
+}
+
+fn write_option(buffer: &mut Vec<u8>, code: u16, value: &[u8]) {
+    buffer.extend_from_slice(&code.to_le_bytes());
+    buffer.extend_from_slice(&u16::try_from(value.len()).unwrap().to_le_bytes());
+    buffer.extend_from_slice(value);
+    pad_to_32bit(buffer);
+}
+
+fn write_block<W: io::Write>(writer: &mut W, block_type: u32, body: &[u8]) -> io::Result<()> {
+    debug_assert_eq!(body.len() & 0x3, 0);
+    let total_length = u32::try_from(body.len() + 12).unwrap().to_le_bytes();
+    writer.write_all(&block_type.to_le_bytes())?;
+    writer.write_all(&total_length)?;
+    writer.write_all(body)?;
+    writer.write_all(&total_length)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pcapng_capture() {
+        let mut pcap = PcapWriter::new(Vec::new()).unwrap();
+        let headers_len = pcap.writer.len();
+        // SHB (28 bytes) + IDB (20 bytes + 12 bytes options)
+        assert_eq!(headers_len, 28 + 32);
+        assert_eq!(pcap.writer[0..4], BLOCK_SECTION_HEADER.to_le_bytes());
+        assert_eq!(
+            pcap.writer[28..32],
+            BLOCK_INTERFACE_DESCRIPTION.to_le_bytes()
+        );
+        assert_eq!(pcap.writer[36..38], LINKTYPE_PROFIBUS_DL.to_le_bytes());
+
+        let now = crate::time::Instant::from_micros(0x1_0000_0002i64);
+        let telegram = crate::fdl::Telegram::Token(crate::fdl::TokenTelegram::new(3, 2));
+        pcap.write_telegram(now, crate::phy::Direction::Tx, &telegram)
+            .unwrap();
+
+        let epb = &pcap.into_inner()[headers_len..];
+        // 28 bytes header + 4 bytes data (padded) + 12 bytes options + 4 bytes trailer
+        assert_eq!(epb.len(), 48);
+        assert_eq!(epb[0..4], BLOCK_ENHANCED_PACKET.to_le_bytes());
+        assert_eq!(epb[4..8], 48u32.to_le_bytes());
+        assert_eq!(epb[12..16], 1u32.to_le_bytes());
+        assert_eq!(epb[16..20], 2u32.to_le_bytes());
+        assert_eq!(epb[20..24], 3u32.to_le_bytes());
+        assert_eq!(epb[28..32], [crate::consts::SD4, 3, 2, 0]);
+        assert_eq!(
+            epb[32..40],
+            [0x02, 0x00, 0x04, 0x00, 0x02, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(epb[44..48], 48u32.to_le_bytes());
+    }
+}